@@ -1,12 +1,19 @@
 use crate::{data_model::CanisterData, CANISTER_DATA};
 use ic_cdk_macros::init;
-use shared_utils::canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs;
+use shared_utils::{
+    canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs,
+    common::utils::system_time::get_current_system_time_from_ic,
+};
 
 #[init]
 fn init(init_args: IndividualUserTemplateInitArgs) {
     CANISTER_DATA.with(|canister_data_ref_cell| {
         let mut data = canister_data_ref_cell.borrow_mut();
         init_impl(init_args, &mut data);
+
+        if data.profile.principal_id.is_some() {
+            data.account_created_at = Some(get_current_system_time_from_ic());
+        }
     });
 }
 
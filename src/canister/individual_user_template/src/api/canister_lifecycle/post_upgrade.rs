@@ -4,12 +4,23 @@ use ic_cdk_macros::post_upgrade;
 use ic_stable_structures::Memory;
 use std::borrow::BorrowMut;
 
-use crate::data_model::memory;
+use crate::data_model::{memory, CanisterData};
 
-use shared_utils::canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs;
+use shared_utils::{
+    canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs,
+    common::utils::system_time::get_current_system_time_from_ic,
+};
 
 use crate::{
-    api::hot_or_not_bet::reenqueue_timers_for_pending_bet_outcomes::reenqueue_timers_for_pending_bet_outcomes,
+    api::{
+        cdao::cdao_deployment::resume_unfinished_cdao_deployments,
+        hot_or_not_bet::reenqueue_timers_for_pending_bet_outcomes::reenqueue_timers_for_pending_bet_outcomes,
+    },
+    util::{
+        kv_storage_expiry::start_kv_storage_expiry_sweeper_timer_if_needed,
+        utility_token_ledger_mirror::start_utility_token_ledger_mirror_timer,
+        wallet_zero_balance_sweeper::start_wallet_zero_balance_sweeper_timer_if_needed,
+    },
     CANISTER_DATA,
 };
 
@@ -18,7 +29,102 @@ fn post_upgrade() {
     restore_data_from_stable_memory();
     save_upgrade_args_to_memory();
     migrate_excessive_tokens();
+    migrate_utility_token_transaction_history_to_stable_memory();
+    advance_token_balance_reconciliation_checkpoint();
+    advance_utility_token_supply_stats_checkpoint();
     reenqueue_timers_for_pending_bet_outcomes();
+    restart_utility_token_ledger_mirror_timer();
+    resume_unfinished_cdao_deployments();
+    backfill_account_creation_time();
+    migrate_airdrop_claims_to_stable_memory();
+    migrate_airdrop_campaign_claims_to_stable_memory();
+    migrate_received_utility_token_transfers_to_stable_memory();
+    repair_kv_storage_namespace_ids();
+    backfill_kv_storage_namespace_usage();
+    start_kv_storage_expiry_sweeper_timer_if_needed();
+    start_wallet_zero_balance_sweeper_timer_if_needed();
+}
+
+fn repair_kv_storage_namespace_ids() {
+    let repaired_namespaces = CANISTER_DATA
+        .with_borrow_mut(|canister_data| canister_data.app_storage.repair_namespace_ids());
+    if repaired_namespaces > 0 {
+        ic_cdk::println!("Repaired the ids of {repaired_namespaces} KV storage namespaces");
+    }
+}
+
+fn backfill_kv_storage_namespace_usage() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.app_storage.backfill_namespace_usage();
+    });
+}
+
+fn migrate_airdrop_claims_to_stable_memory() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let current_time = get_current_system_time_from_ic();
+        let CanisterData {
+            cdao_canisters,
+            airdrop_claims,
+            ..
+        } = canister_data;
+
+        for deployed_cdao_canisters in cdao_canisters.iter_mut() {
+            airdrop_claims.migrate_airdrop_info(
+                deployed_cdao_canisters.root,
+                &mut deployed_cdao_canisters.airdrop_info,
+                current_time,
+            );
+        }
+    });
+}
+
+fn migrate_airdrop_campaign_claims_to_stable_memory() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .airdrop_campaigns
+            .migrate_airdrop_info(get_current_system_time_from_ic());
+    });
+}
+
+fn migrate_received_utility_token_transfers_to_stable_memory() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .utility_token_transfers
+            .migrate_received_transfer_ids_to_stable_memory(get_current_system_time_from_ic());
+    });
+}
+
+/// Accounts created before their creation time was recorded are assumed to be as old as their
+/// first token event, which is usually the signup reward.
+fn backfill_account_creation_time() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        if canister_data.account_created_at.is_some()
+            || canister_data.profile.principal_id.is_none()
+        {
+            return;
+        }
+
+        let first_token_event_time = canister_data
+            .utility_token_transaction_history
+            .first_key_value()
+            .and_then(|(_, token_event)| token_event.get_timestamp());
+
+        canister_data.account_created_at =
+            Some(first_token_event_time.unwrap_or_else(get_current_system_time_from_ic));
+    });
+}
+
+fn restart_utility_token_ledger_mirror_timer() {
+    let is_mirroring_enabled = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .utility_token_ledger_mirror
+            .get_ledger_canister_id()
+            .is_some()
+    });
+
+    if is_mirroring_enabled {
+        start_utility_token_ledger_mirror_timer();
+    }
 }
 
 fn restore_data_from_stable_memory() {
@@ -69,11 +175,47 @@ fn save_upgrade_args_to_memory() {
     });
 }
 
-fn migrate_excessive_tokens(){
+fn migrate_excessive_tokens() {
     CANISTER_DATA.with(|canister_data_ref_cell| {
         let mut canister_data_ref_cell = canister_data_ref_cell.borrow_mut();
-        if canister_data_ref_cell.my_token_balance.utility_token_balance > 18_00_00_00_00_00_00_00_00_00 {
-            canister_data_ref_cell.my_token_balance.utility_token_balance = 1000;
+        if canister_data_ref_cell
+            .my_token_balance
+            .utility_token_balance
+            > 18_00_00_00_00_00_00_00_00_00
+        {
+            canister_data_ref_cell
+                .my_token_balance
+                .utility_token_balance = 1000;
         }
     });
-}
\ No newline at end of file
+}
+
+fn advance_token_balance_reconciliation_checkpoint() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.advance_token_balance_reconciliation_checkpoint();
+    });
+}
+
+fn advance_utility_token_supply_stats_checkpoint() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.advance_utility_token_supply_stats_checkpoint();
+    });
+}
+
+fn migrate_utility_token_transaction_history_to_stable_memory() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let legacy_utility_token_transaction_history = std::mem::take(
+            &mut canister_data
+                .my_token_balance
+                .utility_token_transaction_history,
+        );
+
+        legacy_utility_token_transaction_history
+            .into_iter()
+            .for_each(|(token_event_id, token_event)| {
+                canister_data
+                    .utility_token_transaction_history
+                    .insert(token_event_id, token_event);
+            });
+    });
+}
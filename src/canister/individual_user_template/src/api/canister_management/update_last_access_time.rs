@@ -1,6 +1,9 @@
 use ic_cdk::caller;
 use ic_cdk_macros::update;
-use shared_utils::common::utils::system_time::get_current_system_time_from_ic;
+use shared_utils::{
+    common::utils::system_time::get_current_system_time_from_ic,
+    types::utility_token_supply_stats::get_day_since_epoch,
+};
 
 use crate::{
     util::subnet_orchestrator::{self, SubnetOrchestrator},
@@ -21,7 +24,11 @@ fn update_last_access_time() -> Result<String, String> {
         canister_data
             .session_type
             .ok_or(String::from("Canister not yet assigned"))?;
-        canister_data.last_access_time = Some(get_current_system_time_from_ic());
+        let current_time = get_current_system_time_from_ic();
+        canister_data.last_access_time = Some(current_time);
+        canister_data
+            .engagement_rewards
+            .record_access(get_day_since_epoch(current_time));
         Ok("Success".into())
     })
 }
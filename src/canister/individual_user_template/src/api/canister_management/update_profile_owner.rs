@@ -1,6 +1,7 @@
 use candid::Principal;
 use ic_cdk::api::{self, is_controller};
 use ic_cdk_macros::update;
+use shared_utils::common::utils::system_time::get_current_system_time_from_ic;
 
 use crate::{util::cycles::notify_to_recharge_canister, CANISTER_DATA};
 
@@ -19,6 +20,9 @@ pub async fn update_profile_owner(user_id: Option<Principal>) -> Result<(), Stri
             return Err("Canister Already has a profile Owner".into());
         }
         canister_data.profile.principal_id = user_id;
+        if user_id.is_some() {
+            canister_data.account_created_at = Some(get_current_system_time_from_ic());
+        }
         Ok(())
     })
 }
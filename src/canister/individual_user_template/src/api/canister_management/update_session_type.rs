@@ -7,7 +7,10 @@ use shared_utils::{
     common::utils::permissions::is_caller_controller_or_global_admin,
 };
 
-use crate::{util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+use crate::{
+    util::{cycles::notify_to_recharge_canister, referral::claim_referral_rewards_if_eligible},
+    CANISTER_DATA,
+};
 
 use super::update_last_access_time::update_last_canister_functionality_access_time;
 
@@ -16,7 +19,7 @@ fn update_session_type(session_type: SessionType) -> Result<String, String> {
     notify_to_recharge_canister();
     update_last_canister_functionality_access_time();
 
-    CANISTER_DATA.with_borrow_mut(|canister_data| {
+    let result = CANISTER_DATA.with_borrow_mut(|canister_data| {
         let current_session_type = &mut canister_data.session_type;
 
         match current_session_type {
@@ -30,5 +33,9 @@ fn update_session_type(session_type: SessionType) -> Result<String, String> {
         }
 
         Ok("Success".into())
-    })
+    });
+
+    claim_referral_rewards_if_eligible();
+
+    result
 }
@@ -1,18 +1,40 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use candid::{Nat, Principal};
 use ic_base_types::PrincipalId;
-use ic_cdk_macros::update;
+use ic_cdk_macros::{query, update};
 use ic_sns_root::pb::v1::{ListSnsCanistersRequest, ListSnsCanistersResponse};
-use icrc_ledger_types::icrc1::{
-    account::Account,
-    transfer::{Memo, TransferArg, TransferError},
+use icrc_ledger_types::{
+    icrc1::{
+        account::Account,
+        transfer::{Memo, TransferArg, TransferError},
+    },
+    icrc3::transactions::{GetTransactionsRequest, GetTransactionsResponse, TransactionRange},
+};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        cdao::{AirdropClaimRecord, AirdropClaimant, ClaimStatus},
+        error::AirdropError,
+        profile::UserProfileDetailsForFrontendV2,
+    },
+    common::utils::system_time,
+    constant::MAX_POSTS_IN_ONE_REQUEST,
+    pagination::PaginationError,
 };
-use shared_utils::canister_specific::individual_user_template::types::{
-    error::AirdropError, profile::UserProfileDetailsForFrontendV2,
+
+use crate::{
+    util::{cycles::notify_to_recharge_canister, subnet_orchestrator::SubnetOrchestrator},
+    CANISTER_DATA,
 };
 
-use crate::{util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+use super::airdrop_campaign::get_reserved_airdrop_amount;
+
+/// Transactions fetched per call when looking a transfer up in the ledger
+const LEDGER_TRANSACTIONS_PAGE_SIZE: u64 = 1000;
+/// Window after the `created_at_time` of a transfer in which the ledger accepts it, including its
+/// permitted clock drift
+const LEDGER_TRANSACTION_WINDOW_IN_SECONDS: u64 = 24 * 60 * 60 + 60;
 
-// TODO: Add checks to verify if the user_canister is from our network of yral canisters
 #[update]
 async fn request_airdrop(
     token_root: Principal,
@@ -22,15 +44,16 @@ async fn request_airdrop(
 ) -> Result<(), AirdropError> {
     notify_to_recharge_canister();
     let current_caller = ic_cdk::caller();
-    let profile_info = get_profile_info(user_canister).await?;
-
-    if profile_info.principal_id != current_caller {
-        return Err(AirdropError::CanisterPrincipalDoNotMatch);
-    }
+    verify_claimant_canister(current_caller, user_canister).await?;
 
-    if !is_airdrop_unclaimed(token_root, &current_caller)? {
-        // assertion for token owner is checked here will return err if deployed sns cans not found
-        return Err(AirdropError::AlreadyClaimedAirdrop);
+    let is_token_of_creator = CANISTER_DATA.with_borrow(|cans_data| {
+        cans_data
+            .cdao_canisters
+            .iter()
+            .any(|cdao| cdao.root == token_root)
+    });
+    if !is_token_of_creator {
+        return Err(AirdropError::InvalidRoot);
     }
 
     let amount = amount.min(100_000_000_000u64.into()); // 1000 * 10^8
@@ -39,41 +62,282 @@ async fn request_airdrop(
         return Err(AirdropError::RequestedAmountTooLow);
     }
 
-    set_airdrop_claiming(token_root, current_caller);
+    let amount = u64::try_from(&amount.0).unwrap_or(u64::MAX);
+    let ledger: Principal = get_ledger(token_root).await?.into();
+    let balance = get_balance(ledger).await?;
+    let ledger_start_index = get_ledger_log_length(ledger).await?;
 
-    request_airdrop_internal(token_root, current_caller, memo, amount)
-        .await
-        .inspect_err(|_| {
+    let claim = CANISTER_DATA.with_borrow_mut(|cans_data| {
+        let current_time = system_time::get_current_system_time_from_ic();
+        // * a retried claim is already part of the reserved amount, and its first transfer may
+        // have gone through
+        let is_retry = cans_data
+            .airdrop_claims
+            .get_claim(token_root, current_caller)
+            .is_some_and(|claim| claim.status == ClaimStatus::Claiming);
+        let reserved = get_reserved_airdrop_amount(cans_data, token_root, current_time);
+        if !is_retry && balance < reserved.saturating_add(amount) {
+            return Err(AirdropError::NoBalance);
+        }
+
+        cans_data.airdrop_claims.start_claim(
+            token_root,
+            current_caller,
+            amount,
+            memo,
+            ledger_start_index,
+            current_time,
+        )
+    })?;
+
+    let transfer_result = transfer_claim(
+        ledger,
+        current_caller,
+        claim.memo,
+        claim.amount,
+        claim.updated_at,
+        claim.ledger_start_index.unwrap_or_default(),
+    )
+    .await;
+
+    match transfer_result {
+        ClaimTransferResult::Transferred => {}
+        ClaimTransferResult::Failed(e) => {
             CANISTER_DATA.with_borrow_mut(|cans_data| {
                 cans_data
-                    .cdao_canisters
-                    .iter_mut()
-                    .find(|cdao| cdao.root == token_root)
-                    .map(|cdao| cdao.airdrop_info.set_airdrop_unclaimed(current_caller))
-                    .unwrap(); // can safely unwrap updating the states for the airdrop for the user creates it in place if not exists
+                    .airdrop_claims
+                    .revert_claim(token_root, current_caller)
             });
-        })?; // rollback to unclaimed if error
+            return Err(e);
+        }
+        // * the claim stays claiming, to be retried after the timeout
+        ClaimTransferResult::Unknown(e) => return Err(e),
+    }
+
+    CANISTER_DATA.with_borrow_mut(|cans_data| {
+        let current_time = system_time::get_current_system_time_from_ic();
+        cans_data
+            .airdrop_claims
+            .complete_claim(token_root, current_caller, current_time);
+    });
+    notify_airdrop_to_claimant(user_canister, token_root, claim.amount);
 
-    set_airdrop_claimed(token_root, current_caller);
     Ok(())
 }
 
-async fn request_airdrop_internal(
+#[query]
+fn get_airdrop_claim(
     token_root: Principal,
+    user_principal_id: Principal,
+) -> Option<AirdropClaimRecord> {
+    CANISTER_DATA.with_borrow(|cans_data| {
+        cans_data
+            .airdrop_claims
+            .get_claim(token_root, user_principal_id)
+    })
+}
+
+/// Pass the last principal of a page as `start_after` to get the next one
+#[query]
+fn get_airdrop_claimants(
+    token_root: Principal,
+    start_after: Option<Principal>,
+    limit: u64,
+) -> Result<Vec<AirdropClaimant>, PaginationError> {
+    if limit == 0 {
+        return Err(PaginationError::InvalidBoundsPassed);
+    }
+    if limit > MAX_POSTS_IN_ONE_REQUEST {
+        return Err(PaginationError::ExceededMaxNumberOfItemsAllowedInOneRequest);
+    }
+
+    Ok(CANISTER_DATA.with_borrow(|cans_data| {
+        cans_data
+            .airdrop_claims
+            .get_claimants(token_root, start_after, limit)
+    }))
+}
+
+/// Outcome of the transfer of a claim
+pub(super) enum ClaimTransferResult {
+    Transferred,
+    /// Nothing was transferred, the claim can be reverted
+    Failed(AirdropError),
+    /// The transfer may have gone through, the claim stays claiming
+    Unknown(AirdropError),
+}
+
+/// Transfers the tokens of a claim. A retried claim reuses the `created_at_time` of its first
+/// transfer, so the ledger deduplicates it. Past the ledger's deduplication window, the retry is
+/// rejected as too old and the first transfer is looked up in the ledger instead.
+pub(super) async fn transfer_claim(
+    ledger: Principal,
     current_caller: Principal,
     memo: Option<Memo>,
-    amount: Nat,
+    amount: u64,
+    created_at_time: SystemTime,
+    ledger_start_index: u64,
+) -> ClaimTransferResult {
+    let transfer_result = transfer_tokens(
+        ledger,
+        current_caller,
+        memo.clone(),
+        Nat::from(amount),
+        Some(created_at_time),
+    )
+    .await;
+
+    match transfer_result {
+        // * a retried claim whose first transfer had gone through
+        Ok(()) | Err(AirdropError::Transfer(TransferError::Duplicate { .. })) => {
+            ClaimTransferResult::Transferred
+        }
+        Err(AirdropError::Transfer(TransferError::TooOld)) => {
+            let transfer = find_claim_transfer(
+                ledger,
+                current_caller,
+                &memo,
+                amount,
+                created_at_time,
+                ledger_start_index,
+            )
+            .await;
+            match transfer {
+                Ok(true) => ClaimTransferResult::Transferred,
+                Ok(false) => {
+                    ClaimTransferResult::Failed(AirdropError::Transfer(TransferError::TooOld))
+                }
+                Err(e) => ClaimTransferResult::Unknown(e),
+            }
+        }
+        Err(e) => ClaimTransferResult::Failed(e),
+    }
+}
+
+pub(super) async fn get_ledger_log_length(ledger: Principal) -> Result<u64, AirdropError> {
+    let request = GetTransactionsRequest {
+        start: Nat::from(0u64),
+        length: Nat::from(0u64),
+    };
+    let (response,): (GetTransactionsResponse,) =
+        ic_cdk::call(ledger, "get_transactions", (request,)).await?;
+    Ok(u64::try_from(&response.log_length.0).unwrap_or(u64::MAX))
+}
+
+/// Looks the transfer of a claim up in the ledger, from the length of the transaction log before
+/// the first transfer to the end of the deduplication window of its `created_at_time`
+async fn find_claim_transfer(
+    ledger: Principal,
+    current_caller: Principal,
+    memo: &Option<Memo>,
+    amount: u64,
+    created_at_time: SystemTime,
+    ledger_start_index: u64,
+) -> Result<bool, AirdropError> {
+    let created_at_time = system_time_to_nanos(created_at_time);
+    let window_end =
+        created_at_time.saturating_add(LEDGER_TRANSACTION_WINDOW_IN_SECONDS * 1_000_000_000);
+    let from = Account {
+        owner: ic_cdk::id(),
+        subaccount: None,
+    };
+    let to = Account {
+        owner: current_caller,
+        subaccount: None,
+    };
+    let mut start = ledger_start_index;
+
+    loop {
+        let request = GetTransactionsRequest {
+            start: Nat::from(start),
+            length: Nat::from(LEDGER_TRANSACTIONS_PAGE_SIZE),
+        };
+        let (response,): (GetTransactionsResponse,) =
+            ic_cdk::call(ledger, "get_transactions", (request,)).await?;
+
+        let mut transactions = vec![];
+        for archived in response.archived_transactions {
+            let request = GetTransactionsRequest {
+                start: archived.start,
+                length: archived.length,
+            };
+            let (range,): (TransactionRange,) = ic_cdk::call(
+                archived.callback.canister_id,
+                &archived.callback.method,
+                (request,),
+            )
+            .await?;
+            transactions.extend(range.transactions);
+        }
+        transactions.extend(response.transactions);
+
+        let Some(last_timestamp) = transactions.last().map(|transaction| transaction.timestamp)
+        else {
+            return Ok(false);
+        };
+        let is_found = transactions.iter().any(|transaction| {
+            transaction.transfer.as_ref().is_some_and(|transfer| {
+                transfer.from == from
+                    && transfer.to == to
+                    && transfer.amount == amount
+                    && &transfer.memo == memo
+                    && transfer.created_at_time == Some(created_at_time)
+            })
+        });
+        if is_found {
+            return Ok(true);
+        }
+        if last_timestamp > window_end {
+            return Ok(false);
+        }
+
+        start += transactions.len() as u64;
+    }
+}
+
+/// Checks that the user canister belongs to the caller and to our network of canisters
+/// Lets the canister of the claimant record the airdrop in its notification inbox
+pub(super) fn notify_airdrop_to_claimant(
+    user_canister: Principal,
+    token_root: Principal,
+    amount: u64,
+) {
+    let Some(creator_principal_id) =
+        CANISTER_DATA.with_borrow(|cans_data| cans_data.profile.principal_id)
+    else {
+        return;
+    };
+
+    let _ = ic_cdk::notify(
+        user_canister,
+        "receive_token_airdrop_notification",
+        (token_root, Nat::from(amount), creator_principal_id),
+    );
+}
+
+pub(super) async fn verify_claimant_canister(
+    current_caller: Principal,
+    user_canister: Principal,
 ) -> Result<(), AirdropError> {
-    let ledger = get_ledger(token_root).await?;
-    let balance = get_balance(ledger.into()).await?;
+    let profile_info = get_profile_info(user_canister).await?;
+
+    if profile_info.principal_id != current_caller {
+        return Err(AirdropError::CanisterPrincipalDoNotMatch);
+    }
 
-    if balance < amount {
-        return Err(AirdropError::NoBalance);
+    let is_user_canister_in_the_network = SubnetOrchestrator::new()
+        .map_err(|_| AirdropError::UserCanisterNotInTheNetwork)?
+        .is_user_canister_in_the_network(current_caller, user_canister)
+        .await
+        .unwrap_or(false);
+
+    if !is_user_canister_in_the_network {
+        return Err(AirdropError::UserCanisterNotInTheNetwork);
     }
 
-    transfer_tokens(ledger.into(), current_caller, memo, amount).await?;
     Ok(())
 }
+
 async fn get_profile_info(
     user_canister: Principal,
 ) -> Result<UserProfileDetailsForFrontendV2, AirdropError> {
@@ -82,35 +346,7 @@ async fn get_profile_info(
     Ok(profile_info)
 }
 
-fn is_airdrop_unclaimed(
-    token_root: Principal,
-    current_caller: &Principal,
-) -> Result<bool, AirdropError> {
-    CANISTER_DATA
-        .with_borrow(|cans_data| {
-            cans_data
-                .cdao_canisters
-                .iter()
-                .find(|cdao| cdao.root == token_root)
-                .map(|cdao| cdao.airdrop_info.is_airdrop_unclaimed(current_caller))
-        })
-        .ok_or(AirdropError::InvalidRoot)
-}
-
-fn set_airdrop_claiming(token_root: Principal, current_caller: Principal) {
-    CANISTER_DATA.with_borrow_mut(|cans_data| {
-        if let Some(cdao) = cans_data
-            .cdao_canisters
-            .iter_mut()
-            .find(|cdao| cdao.root == token_root)
-        {
-            cdao.airdrop_info.set_airdrop_claiming(current_caller)
-        }
-    });
-    ic_cdk::println!("Setting airdrop claiming for user: {:?}", current_caller);
-}
-
-async fn get_ledger(token_root: Principal) -> Result<PrincipalId, AirdropError> {
+pub(super) async fn get_ledger(token_root: Principal) -> Result<PrincipalId, AirdropError> {
     let res: (ListSnsCanistersResponse,) = ic_cdk::call(
         token_root,
         "list_sns_canisters",
@@ -120,7 +356,7 @@ async fn get_ledger(token_root: Principal) -> Result<PrincipalId, AirdropError>
     res.0.ledger.ok_or(AirdropError::InvalidRoot)
 }
 
-async fn get_balance(ledger: Principal) -> Result<Nat, AirdropError> {
+pub(super) async fn get_balance(ledger: Principal) -> Result<Nat, AirdropError> {
     let account = Account {
         owner: ic_cdk::id(),
         subaccount: None,
@@ -134,6 +370,7 @@ async fn transfer_tokens(
     current_caller: Principal,
     memo: Option<Memo>,
     amount: Nat,
+    created_at_time: Option<SystemTime>,
 ) -> Result<(), AirdropError> {
     let transfer_args = TransferArg {
         from_subaccount: None,
@@ -142,7 +379,7 @@ async fn transfer_tokens(
             subaccount: None,
         },
         fee: None,
-        created_at_time: None,
+        created_at_time: created_at_time.map(system_time_to_nanos),
         memo,
         amount,
     };
@@ -152,14 +389,8 @@ async fn transfer_tokens(
     Ok(())
 }
 
-fn set_airdrop_claimed(token_root: Principal, current_caller: Principal) {
-    CANISTER_DATA.with_borrow_mut(|cans_data| {
-        if let Some(cdao) = cans_data
-            .cdao_canisters
-            .iter_mut()
-            .find(|cdao| cdao.root == token_root)
-        {
-            cdao.airdrop_info.set_airdrop_claimed(current_caller)
-        }
-    })
+fn system_time_to_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
 }
@@ -0,0 +1,272 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use ic_cdk_macros::{query, update};
+use icrc_ledger_types::icrc1::transfer::Memo;
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        cdao::{
+            AirdropCampaignConfig, AirdropCampaignDetails, AirdropClaimDetails, AirdropEligibility,
+        },
+        error::AirdropError,
+        follow::FollowEntryDetail,
+        hot_or_not::StablePrincipal,
+    },
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+use super::airdrop::{
+    get_balance, get_ledger, get_ledger_log_length, notify_airdrop_to_claimant, transfer_claim,
+    verify_claimant_canister, ClaimTransferResult,
+};
+
+/// Starts an airdrop of one of the creator's tokens. The canister must hold the whole budget on
+/// top of what its other airdrops may still pay out.
+#[update]
+async fn create_airdrop_campaign(config: AirdropCampaignConfig) -> Result<u64, AirdropError> {
+    notify_to_recharge_canister();
+
+    let current_caller = ic_cdk::caller();
+    let (my_principal_id, is_my_token) = CANISTER_DATA.with_borrow(|canister_data| {
+        (
+            canister_data.profile.principal_id,
+            canister_data
+                .cdao_canisters
+                .iter()
+                .any(|cdao| cdao.root == config.token_root),
+        )
+    });
+    if my_principal_id != Some(current_caller) {
+        return Err(AirdropError::Unauthenticated);
+    }
+    if !is_my_token {
+        return Err(AirdropError::InvalidRoot);
+    }
+
+    let ledger = get_ledger(config.token_root).await?;
+    let balance = get_balance(ledger.into()).await?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let current_time = get_current_system_time_from_ic();
+        // * checked after the await, so campaigns created meanwhile are accounted for
+        let reserved = get_reserved_airdrop_amount(canister_data, config.token_root, current_time);
+        if balance < reserved.saturating_add(config.total_budget) {
+            return Err(AirdropError::NoBalance);
+        }
+
+        canister_data
+            .airdrop_campaigns
+            .create_campaign(config, current_time)
+    })
+}
+
+/// Tokens the canister must keep for its airdrops of the token: the budgets campaigns may still
+/// pay out and the `request_airdrop` transfers under way
+pub(super) fn get_reserved_airdrop_amount(
+    canister_data: &CanisterData,
+    token_root: Principal,
+    current_time: SystemTime,
+) -> u64 {
+    canister_data
+        .airdrop_campaigns
+        .get_reserved_budget(token_root, current_time)
+        + canister_data.airdrop_claims.get_claiming_amount(token_root)
+}
+
+#[query]
+fn get_airdrop_campaigns(token_root: Principal) -> Vec<AirdropCampaignDetails> {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .airdrop_campaigns
+            .get_campaigns_of_token(token_root)
+    })
+}
+
+#[query]
+fn get_airdrop_claim_details(
+    campaign_id: u64,
+    user_principal_id: Principal,
+) -> Option<AirdropClaimDetails> {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data.airdrop_campaigns.get_claim_details(
+            campaign_id,
+            user_principal_id,
+            get_current_system_time_from_ic(),
+        )
+    })
+}
+
+/// Transfers the caller's share of the campaign, or its next vested tranche. Returns the amount
+/// transferred.
+#[update]
+async fn claim_airdrop(
+    campaign_id: u64,
+    memo: Option<Memo>,
+    user_canister: Principal,
+) -> Result<u64, AirdropError> {
+    notify_to_recharge_canister();
+
+    let current_caller = ic_cdk::caller();
+    let config = CANISTER_DATA.with_borrow(|canister_data| {
+        let config = canister_data
+            .airdrop_campaigns
+            .get_campaign_config(campaign_id)
+            .cloned()
+            .ok_or(AirdropError::CampaignNotFound)?;
+
+        if !is_eligible_for_airdrop(
+            canister_data,
+            &config.eligibility,
+            current_caller,
+            user_canister,
+        ) {
+            return Err(AirdropError::NotEligible);
+        }
+
+        Ok(config)
+    })?;
+
+    verify_claimant_canister(current_caller, user_canister).await?;
+
+    let ledger: Principal = get_ledger(config.token_root).await?.into();
+    let ledger_start_index = get_ledger_log_length(ledger).await?;
+
+    let claim = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.airdrop_campaigns.start_claim(
+            campaign_id,
+            current_caller,
+            memo,
+            ledger_start_index,
+            get_current_system_time_from_ic(),
+        )
+    })?;
+
+    let transfer_result = transfer_claim(
+        ledger,
+        current_caller,
+        claim.memo,
+        claim.claiming_amount,
+        claim.updated_at,
+        claim.ledger_start_index,
+    )
+    .await;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let current_time = get_current_system_time_from_ic();
+
+        match &transfer_result {
+            ClaimTransferResult::Transferred => {
+                canister_data.airdrop_campaigns.complete_claim(
+                    campaign_id,
+                    current_caller,
+                    current_time,
+                );
+            }
+            ClaimTransferResult::Failed(_) => canister_data
+                .airdrop_campaigns
+                .revert_claim(campaign_id, current_caller),
+            // * the claim stays claiming, to be retried after the timeout
+            ClaimTransferResult::Unknown(_) => {}
+        }
+    });
+
+    match transfer_result {
+        ClaimTransferResult::Transferred => {
+            notify_airdrop_to_claimant(user_canister, config.token_root, claim.claiming_amount);
+            Ok(claim.claiming_amount)
+        }
+        ClaimTransferResult::Failed(e) | ClaimTransferResult::Unknown(e) => Err(e),
+    }
+}
+
+fn is_eligible_for_airdrop(
+    canister_data: &CanisterData,
+    eligibility: &AirdropEligibility,
+    user_principal_id: Principal,
+    user_canister: Principal,
+) -> bool {
+    match eligibility {
+        AirdropEligibility::Everyone => true,
+        AirdropEligibility::Followers => {
+            canister_data
+                .follow_data
+                .follower
+                .contains(&FollowEntryDetail {
+                    principal_id: user_principal_id,
+                    canister_id: user_canister,
+                })
+        }
+        AirdropEligibility::Bettors => canister_data.all_created_posts.keys().any(|post_id| {
+            canister_data
+                .post_principal_map
+                .contains_key(&(*post_id, StablePrincipal(user_principal_id)))
+        }),
+        AirdropEligibility::Allowlist(allowlist) => allowlist.contains(&user_principal_id),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_is_eligible_for_airdrop() {
+        let mut canister_data = CanisterData::default();
+        let alice_principal_id = get_mock_user_alice_principal_id();
+        let alice_canister_id = get_mock_user_alice_canister_id();
+        let bob_principal_id = get_mock_user_bob_principal_id();
+
+        for eligibility in [AirdropEligibility::Followers, AirdropEligibility::Bettors] {
+            assert!(!is_eligible_for_airdrop(
+                &canister_data,
+                &eligibility,
+                alice_principal_id,
+                alice_canister_id
+            ));
+        }
+
+        canister_data.follow_data.follower.add(FollowEntryDetail {
+            principal_id: alice_principal_id,
+            canister_id: alice_canister_id,
+        });
+        assert!(is_eligible_for_airdrop(
+            &canister_data,
+            &AirdropEligibility::Followers,
+            alice_principal_id,
+            alice_canister_id
+        ));
+        assert!(!is_eligible_for_airdrop(
+            &canister_data,
+            &AirdropEligibility::Followers,
+            alice_principal_id,
+            Principal::anonymous()
+        ));
+
+        let allowlist = AirdropEligibility::Allowlist(vec![bob_principal_id]);
+        assert!(is_eligible_for_airdrop(
+            &canister_data,
+            &allowlist,
+            bob_principal_id,
+            alice_canister_id
+        ));
+        assert!(!is_eligible_for_airdrop(
+            &canister_data,
+            &allowlist,
+            alice_principal_id,
+            alice_canister_id
+        ));
+        assert!(is_eligible_for_airdrop(
+            &canister_data,
+            &AirdropEligibility::Everyone,
+            alice_principal_id,
+            alice_canister_id
+        ));
+    }
+}
@@ -0,0 +1,740 @@
+use std::{cell::RefCell, collections::BTreeSet, time::Duration};
+
+use candid::{Decode, Encode, Principal};
+use futures::{
+    stream::{FuturesOrdered, FuturesUnordered},
+    StreamExt, TryStreamExt,
+};
+use ic_base_types::PrincipalId;
+use ic_cdk::api::{
+    call::RejectionCode,
+    management_canister::main::{
+        deposit_cycles, install_code, uninstall_code, update_settings, CanisterIdRecord,
+        CanisterInstallMode, CanisterSettings, InstallCodeArgument, UpdateSettingsArgument,
+    },
+};
+use ic_cdk_macros::{query, update};
+use ic_sns_governance::pb::v1::governance::Version as SnsVersion;
+use ic_sns_init::{pb::v1::SnsInitPayload, SnsCanisterIds};
+use ic_sns_wasm::pb::v1::{GetWasmRequest, GetWasmResponse};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        cdao::{
+            AirdropInfo, CdaoDeploymentDetails, CdaoDeploymentStatus, CdaoDeploymentStep,
+            DeployedCdaoCanisters,
+        },
+        error::CdaoDeployError,
+    },
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::{
+            permissions::is_caller_controller_or_global_admin,
+            system_time::get_current_system_time_from_ic, task::run_task_concurrently,
+        },
+    },
+    constant::{
+        NNS_LEDGER_CANISTER_ID, SNS_TOKEN_ARCHIVE_MODULE_HASH, SNS_TOKEN_GOVERNANCE_MODULE_HASH,
+        SNS_TOKEN_INDEX_MODULE_HASH, SNS_TOKEN_LEDGER_MODULE_HASH, SNS_TOKEN_ROOT_MODULE_HASH,
+        SNS_TOKEN_SWAP_MODULE_HASH, USER_SNS_CANISTER_INITIAL_CYCLES,
+    },
+};
+
+use crate::{
+    data_model::cdao_deployment::CdaoDeployment,
+    util::{
+        cycles::request_cycles_from_subnet_orchestrator, subnet_orchestrator::SubnetOrchestrator,
+    },
+    CANISTER_DATA,
+};
+
+use super::utils::uninstall_code_and_return_empty_canisters_to_subnet_backup_pool;
+
+#[cfg(feature = "cdao_deployment_failure_injection")]
+use shared_utils::common::utils::permissions::is_caller_controller;
+
+/// Governance, ledger, root, swap and index
+const NUMBER_OF_SNS_CANISTERS: usize = 5;
+
+/// Creator token deployments that are still running, rolling back or that failed.
+#[query(guard = "is_caller_controller_or_global_admin")]
+fn get_cdao_deployments() -> Vec<CdaoDeploymentDetails> {
+    CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.cdao_deployments.get_deployment_details())
+}
+
+thread_local! {
+    /// Deployments run by a call of this canister version, which a resume must not run again
+    static RUNNING_CDAO_DEPLOYMENTS: RefCell<BTreeSet<u64>> = const { RefCell::new(BTreeSet::new()) };
+}
+
+#[cfg(feature = "cdao_deployment_failure_injection")]
+thread_local! {
+    static CDAO_DEPLOYMENT_FAILURE_INJECTION: std::cell::Cell<Option<CdaoDeploymentStep>> =
+        const { std::cell::Cell::new(None) };
+    static CDAO_COMPENSATION_FAILURE_INJECTION: std::cell::Cell<Option<CdaoDeploymentStep>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Makes deployments fail when they reach the step, to exercise their rollback. Only built for
+/// tests, and not persisted across upgrades.
+#[cfg(feature = "cdao_deployment_failure_injection")]
+#[update(guard = "is_caller_controller")]
+fn set_cdao_deployment_failure_injection(step: Option<CdaoDeploymentStep>) {
+    CDAO_DEPLOYMENT_FAILURE_INJECTION.set(step);
+}
+
+/// Makes rollbacks fail when they compensate the step, leaving the deployment rolling back.
+/// Only built for tests, and not persisted across upgrades.
+#[cfg(feature = "cdao_deployment_failure_injection")]
+#[update(guard = "is_caller_controller")]
+fn set_cdao_compensation_failure_injection(step: Option<CdaoDeploymentStep>) {
+    CDAO_COMPENSATION_FAILURE_INJECTION.set(step);
+}
+
+#[cfg(feature = "cdao_deployment_failure_injection")]
+fn is_failure_injected(step: CdaoDeploymentStep) -> bool {
+    CDAO_DEPLOYMENT_FAILURE_INJECTION.get() == Some(step)
+}
+
+#[cfg(not(feature = "cdao_deployment_failure_injection"))]
+fn is_failure_injected(_step: CdaoDeploymentStep) -> bool {
+    false
+}
+
+#[cfg(feature = "cdao_deployment_failure_injection")]
+fn is_compensation_failure_injected(step: CdaoDeploymentStep) -> bool {
+    CDAO_COMPENSATION_FAILURE_INJECTION.get() == Some(step)
+}
+
+#[cfg(not(feature = "cdao_deployment_failure_injection"))]
+fn is_compensation_failure_injected(_step: CdaoDeploymentStep) -> bool {
+    false
+}
+
+/// Resumes the deployments that are not running, e.g. the rollbacks that stopped at a
+/// compensation that failed.
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn resume_cdao_deployments() {
+    resume_unfinished_cdao_deployments();
+}
+
+pub fn resume_unfinished_cdao_deployments() {
+    let deployment_ids: Vec<u64> = CANISTER_DATA
+        .with_borrow(|canister_data| {
+            canister_data
+                .cdao_deployments
+                .get_unfinished_deployment_ids()
+        })
+        .into_iter()
+        .filter(|deployment_id| {
+            !RUNNING_CDAO_DEPLOYMENTS
+                .with_borrow(|running_deployments| running_deployments.contains(deployment_id))
+        })
+        .collect();
+
+    for deployment_id in deployment_ids {
+        ic_cdk_timers::set_timer(Duration::ZERO, move || {
+            ic_cdk::spawn(async move {
+                if let Err(e) = run_cdao_deployment(deployment_id).await {
+                    ic_cdk::println!("Resumed creator token deployment failed. {:?}", e);
+                }
+            })
+        });
+    }
+}
+
+/// Starts a deployment, which counts against the creator token quota from then on, and runs it
+/// once this canister got the cycles for the SNS canisters.
+pub async fn start_cdao_deployment(
+    encoded_init_payload: Vec<u8>,
+    swap_time: u64,
+) -> Result<DeployedCdaoCanisters, CdaoDeployError> {
+    let deployment_id = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.cdao_deployments.start(
+            encoded_init_payload,
+            swap_time,
+            get_current_system_time_from_ic(),
+        )
+    });
+    RUNNING_CDAO_DEPLOYMENTS
+        .with_borrow_mut(|running_deployments| running_deployments.insert(deployment_id));
+
+    // Alloting 0.5T more to the user canister to be on safer side while deploying canisters
+    let result =
+        match request_cycles_from_subnet_orchestrator(6 * USER_SNS_CANISTER_INITIAL_CYCLES).await {
+            Ok(()) => run_or_roll_back_cdao_deployment(deployment_id).await,
+            Err(e) => {
+                CANISTER_DATA.with_borrow_mut(|canister_data| {
+                    canister_data.cdao_deployments.cancel(deployment_id)
+                });
+                Err(CdaoDeployError::CycleError(e))
+            }
+        };
+
+    RUNNING_CDAO_DEPLOYMENTS
+        .with_borrow_mut(|running_deployments| running_deployments.remove(&deployment_id));
+
+    result
+}
+
+/// Runs the deployment from the step it is at and rolls it back when a step fails. Every step and
+/// compensation can be run again, so a deployment interrupted by an upgrade is resumed as is.
+pub async fn run_cdao_deployment(
+    deployment_id: u64,
+) -> Result<DeployedCdaoCanisters, CdaoDeployError> {
+    if !RUNNING_CDAO_DEPLOYMENTS
+        .with_borrow_mut(|running_deployments| running_deployments.insert(deployment_id))
+    {
+        return Err(CdaoDeployError::CallError(
+            RejectionCode::CanisterError,
+            format!("Deployment {} is already running", deployment_id),
+        ));
+    }
+
+    let result = run_or_roll_back_cdao_deployment(deployment_id).await;
+
+    RUNNING_CDAO_DEPLOYMENTS
+        .with_borrow_mut(|running_deployments| running_deployments.remove(&deployment_id));
+
+    result
+}
+
+async fn run_or_roll_back_cdao_deployment(
+    deployment_id: u64,
+) -> Result<DeployedCdaoCanisters, CdaoDeployError> {
+    let status = get_cdao_deployment(deployment_id)?.status;
+
+    let error = match status {
+        CdaoDeploymentStatus::InProgress => match run_cdao_deployment_steps(deployment_id).await {
+            Ok(deployed_canisters) => return Ok(deployed_canisters),
+            Err(e) => {
+                CANISTER_DATA.with_borrow_mut(|canister_data| {
+                    canister_data.cdao_deployments.start_rollback(
+                        deployment_id,
+                        format!("{:?}", e),
+                        get_current_system_time_from_ic(),
+                    )
+                });
+                e
+            }
+        },
+        CdaoDeploymentStatus::RollingBack | CdaoDeploymentStatus::Failed => {
+            CdaoDeployError::CallError(
+                RejectionCode::CanisterError,
+                format!("Deployment {} failed", deployment_id),
+            )
+        }
+    };
+
+    if let Err(e) = roll_back_cdao_deployment(deployment_id).await {
+        ic_cdk::println!(
+            "Rollback of creator token deployment {} stopped. {:?}",
+            deployment_id,
+            e
+        );
+    }
+
+    Err(error)
+}
+
+fn get_cdao_deployment(deployment_id: u64) -> Result<CdaoDeployment, CdaoDeployError> {
+    CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.cdao_deployments.get(deployment_id).cloned())
+        .ok_or_else(|| {
+            CdaoDeployError::CallError(
+                RejectionCode::CanisterError,
+                format!("Deployment {} not found", deployment_id),
+            )
+        })
+}
+
+async fn run_cdao_deployment_steps(
+    deployment_id: u64,
+) -> Result<DeployedCdaoCanisters, CdaoDeployError> {
+    loop {
+        let deployment = get_cdao_deployment(deployment_id)?;
+
+        if is_failure_injected(deployment.step) {
+            return Err(CdaoDeployError::CallError(
+                RejectionCode::CanisterError,
+                format!("Failure injected at step {:?}", deployment.step),
+            ));
+        }
+
+        match deployment.step {
+            CdaoDeploymentStep::AllotCanisters => {
+                allot_canisters(deployment_id, deployment.canister_ids.len()).await?
+            }
+            CdaoDeploymentStep::DepositCycles => {
+                deposit_cycles_to_canisters(deployment_id, &deployment).await?
+            }
+            CdaoDeploymentStep::InstallWasms => install_sns_wasms(&deployment).await?,
+            CdaoDeploymentStep::UpdateControllers => {
+                update_sns_controllers(&deployment.canister_ids).await?
+            }
+            CdaoDeploymentStep::Register => {
+                let deployed_canisters = register_cdao_canisters(&deployment.canister_ids);
+                CANISTER_DATA.with_borrow_mut(|canister_data| {
+                    canister_data
+                        .cdao_deployments
+                        .complete_step(deployment_id, get_current_system_time_from_ic())
+                });
+
+                return Ok(deployed_canisters);
+            }
+        }
+
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data
+                .cdao_deployments
+                .complete_step(deployment_id, get_current_system_time_from_ic())
+        });
+    }
+}
+
+/// Compensates the steps in reverse order. Stops at a compensation that fails and leaves the
+/// deployment rolling back at that step, to be compensated again when it is resumed.
+async fn roll_back_cdao_deployment(deployment_id: u64) -> Result<(), CdaoDeployError> {
+    while let Ok(deployment) = get_cdao_deployment(deployment_id) {
+        if deployment.status != CdaoDeploymentStatus::RollingBack {
+            return Ok(());
+        }
+
+        let compensation_result = if is_compensation_failure_injected(deployment.step) {
+            Err(CdaoDeployError::CallError(
+                RejectionCode::CanisterError,
+                format!("Failure injected at compensation {:?}", deployment.step),
+            ))
+        } else {
+            compensate_cdao_deployment_step(deployment).await
+        };
+
+        if let Err(e) = compensation_result {
+            CANISTER_DATA.with_borrow_mut(|canister_data| {
+                canister_data.cdao_deployments.record_compensation_error(
+                    deployment_id,
+                    format!("{:?}", e),
+                    get_current_system_time_from_ic(),
+                )
+            });
+            return Err(e);
+        }
+
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data
+                .cdao_deployments
+                .complete_compensation(deployment_id, get_current_system_time_from_ic())
+        });
+    }
+
+    Ok(())
+}
+
+async fn compensate_cdao_deployment_step(
+    deployment: CdaoDeployment,
+) -> Result<(), CdaoDeployError> {
+    match deployment.step {
+        CdaoDeploymentStep::Register => {
+            unregister_cdao_canisters(&deployment.canister_ids);
+            Ok(())
+        }
+        CdaoDeploymentStep::UpdateControllers => reset_controllers(&deployment.canister_ids).await,
+        CdaoDeploymentStep::InstallWasms => uninstall_sns_wasms(&deployment.canister_ids).await,
+        // * the cycles go back to the pool with the canisters
+        CdaoDeploymentStep::DepositCycles => Ok(()),
+        // * canisters that can't be returned are kept as empty canisters and returned later
+        CdaoDeploymentStep::AllotCanisters => {
+            uninstall_code_and_return_empty_canisters_to_subnet_backup_pool(
+                deployment.canister_ids,
+            )
+            .await;
+            Ok(())
+        }
+    }
+}
+
+fn get_sns_canister_ids(canister_ids: &[Principal]) -> SnsCanisterIds {
+    SnsCanisterIds {
+        governance: PrincipalId::from(canister_ids[0]),
+        ledger: PrincipalId::from(canister_ids[1]),
+        root: PrincipalId::from(canister_ids[2]),
+        swap: PrincipalId::from(canister_ids[3]),
+        index: PrincipalId::from(canister_ids[4]),
+    }
+}
+
+/// Allots the canisters that are still missing. Each canister is recorded as soon as it is
+/// allotted, so that a rollback returns it.
+async fn allot_canisters(
+    deployment_id: u64,
+    number_of_allotted_canisters: usize,
+) -> Result<(), CdaoDeployError> {
+    let subnet_orchestrator = SubnetOrchestrator::new()
+        .map_err(|e| CdaoDeployError::CallError(RejectionCode::CanisterError, e))?;
+
+    let number_of_missing_canisters =
+        NUMBER_OF_SNS_CANISTERS.saturating_sub(number_of_allotted_canisters);
+    let canister_ids_futures =
+        (0..number_of_missing_canisters).map(|_| subnet_orchestrator.allot_empty_canister());
+
+    let mut canister_ids_stream = futures::stream::iter(canister_ids_futures)
+        .boxed()
+        .buffer_unordered(NUMBER_OF_SNS_CANISTERS);
+
+    let mut allot_error = None;
+    while let Some(canister_id_result) = canister_ids_stream.next().await {
+        match canister_id_result {
+            Ok(canister_id) => CANISTER_DATA.with_borrow_mut(|canister_data| {
+                canister_data.cdao_deployments.add_canister_id(
+                    deployment_id,
+                    canister_id,
+                    get_current_system_time_from_ic(),
+                )
+            }),
+            Err(e) => allot_error = Some(e),
+        }
+    }
+
+    match allot_error {
+        Some(e) => Err(CdaoDeployError::CallError(RejectionCode::CanisterError, e)),
+        None => Ok(()),
+    }
+}
+
+/// Deposits to the canisters that were not funded yet. Each canister is recorded as soon as
+/// its deposit succeeds, so that a resumed step does not fund it again.
+async fn deposit_cycles_to_canisters(
+    deployment_id: u64,
+    deployment: &CdaoDeployment,
+) -> Result<(), CdaoDeployError> {
+    let mut deposit_cycles_stream = deployment
+        .canister_ids
+        .iter()
+        .filter(|canister_id| !deployment.funded_canister_ids.contains(canister_id))
+        .map(|canister_id| async move {
+            deposit_cycles(
+                CanisterIdRecord {
+                    canister_id: *canister_id,
+                },
+                USER_SNS_CANISTER_INITIAL_CYCLES,
+            )
+            .await
+            .map(|_| *canister_id)
+        })
+        .collect::<FuturesUnordered<_>>();
+
+    let mut deposit_error = None;
+    while let Some(deposit_cycles_result) = deposit_cycles_stream.next().await {
+        match deposit_cycles_result {
+            Ok(canister_id) => CANISTER_DATA.with_borrow_mut(|canister_data| {
+                canister_data.cdao_deployments.add_funded_canister_id(
+                    deployment_id,
+                    canister_id,
+                    get_current_system_time_from_ic(),
+                )
+            }),
+            Err(e) => deposit_error = Some(e),
+        }
+    }
+
+    match deposit_error {
+        Some(e) => Err(CdaoDeployError::CallError(e.0, e.1)),
+        None => Ok(()),
+    }
+}
+
+/// Reinstalls rather than installs, so that a partially run step can be run again.
+async fn install_canister_wasm(
+    wasm: Vec<u8>,
+    arg: Vec<u8>,
+    canister_id: PrincipalId,
+) -> Result<(), CdaoDeployError> {
+    let install_arg = InstallCodeArgument {
+        mode: CanisterInstallMode::Reinstall,
+        canister_id: canister_id.0,
+        wasm_module: wasm,
+        arg,
+    };
+    install_code(install_arg).await?;
+    Ok(())
+}
+
+async fn install_sns_wasms(deployment: &CdaoDeployment) -> Result<(), CdaoDeployError> {
+    let init_payload = Decode!(&deployment.init_payload, SnsInitPayload)
+        .map_err(|e| CdaoDeployError::InvalidInitPayload(e.to_string()))?;
+    let sns_canisters = get_sns_canister_ids(&deployment.canister_ids);
+
+    let gov_hash = hex::decode(SNS_TOKEN_GOVERNANCE_MODULE_HASH).unwrap();
+    let ledger_hash = hex::decode(SNS_TOKEN_LEDGER_MODULE_HASH).unwrap();
+    let root_hash = hex::decode(SNS_TOKEN_ROOT_MODULE_HASH).unwrap();
+    let swap_hash = hex::decode(SNS_TOKEN_SWAP_MODULE_HASH).unwrap();
+    let index_hash = hex::decode(SNS_TOKEN_INDEX_MODULE_HASH).unwrap();
+    let arhive_hash = hex::decode(SNS_TOKEN_ARCHIVE_MODULE_HASH).unwrap();
+
+    let sns_version = SnsVersion {
+        governance_wasm_hash: gov_hash.clone(),
+        ledger_wasm_hash: ledger_hash.clone(),
+        root_wasm_hash: root_hash.clone(),
+        swap_wasm_hash: swap_hash.clone(),
+        index_wasm_hash: index_hash.clone(),
+        archive_wasm_hash: arhive_hash.clone(),
+    };
+
+    let mut payloads = init_payload
+        .build_canister_payloads(&sns_canisters, Some(sns_version), true)
+        .map_err(CdaoDeployError::InvalidInitPayload)?;
+    let time_seconds = ic_cdk::api::time() / 1_000_000_000;
+    payloads.swap.swap_start_timestamp_seconds = Some(time_seconds);
+    payloads.swap.swap_due_timestamp_seconds = Some(time_seconds + deployment.swap_time);
+    payloads.swap.icp_ledger_canister_id = NNS_LEDGER_CANISTER_ID.into();
+    payloads.swap.nns_governance_canister_id = ic_cdk::id().to_string();
+
+    let sns_wasm = CANISTER_DATA
+        .with(|cdata| {
+            cdata
+                .borrow()
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdSnsWasm)
+                .copied()
+        })
+        .ok_or_else(|| {
+            CdaoDeployError::CallError(
+                RejectionCode::CanisterError,
+                "SNS WASM not specified in config".into(),
+            )
+        })?;
+
+    let wasm_bins: Vec<Vec<u8>> = [gov_hash, ledger_hash, root_hash, swap_hash, index_hash]
+        .into_iter()
+        .map(|hash| async move {
+            let req = GetWasmRequest { hash };
+            let wasm_res =
+                ic_cdk::call::<_, (GetWasmResponse,)>(sns_wasm, "get_wasm", (req,)).await?;
+            let sns_wasm = wasm_res.0.wasm.ok_or_else(|| {
+                CdaoDeployError::CallError(
+                    RejectionCode::CanisterError,
+                    "SNS WASM returned no wasm for the hash".into(),
+                )
+            })?;
+            Ok::<_, CdaoDeployError>(sns_wasm.wasm)
+        })
+        .collect::<FuturesOrdered<_>>()
+        .try_collect()
+        .await?;
+
+    let install_args = [
+        Encode!(&payloads.governance).unwrap(),
+        Encode!(&payloads.ledger).unwrap(),
+        Encode!(&payloads.root).unwrap(),
+        Encode!(&payloads.swap).unwrap(),
+        Encode!(&payloads.index_ng).unwrap(),
+    ];
+    let canisters = [
+        sns_canisters.governance,
+        sns_canisters.ledger,
+        sns_canisters.root,
+        sns_canisters.swap,
+        sns_canisters.index,
+    ];
+
+    wasm_bins
+        .into_iter()
+        .zip(install_args)
+        .zip(canisters)
+        .map(|((wasm, arg), canister_id)| install_canister_wasm(wasm, arg, canister_id))
+        .collect::<FuturesUnordered<_>>()
+        .try_collect::<Vec<()>>()
+        .await?;
+
+    Ok(())
+}
+
+async fn uninstall_sns_wasms(canister_ids: &[Principal]) -> Result<(), CdaoDeployError> {
+    let mut failed_canister_ids = vec![];
+    let uninstall_code_tasks = canister_ids.iter().map(|canister_id| async {
+        uninstall_code(CanisterIdRecord {
+            canister_id: *canister_id,
+        })
+        .await
+        .map_err(|e| (*canister_id, e.1))
+    });
+
+    let uninstall_callback = |uninstall_result: Result<(), (Principal, String)>| {
+        if let Err(e) = uninstall_result {
+            ic_cdk::println!("Error Uninstall Code from canister {}. Error: {}", e.0, e.1);
+            failed_canister_ids.push(e.0);
+        }
+    };
+
+    run_task_concurrently(uninstall_code_tasks, 10, uninstall_callback, || false).await;
+
+    if !failed_canister_ids.is_empty() {
+        return Err(CdaoDeployError::CallError(
+            RejectionCode::CanisterError,
+            format!("Failed to uninstall code from {:?}", failed_canister_ids),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn update_controllers(
+    canister_id: PrincipalId,
+    controllers: Vec<Principal>,
+) -> Result<(), CdaoDeployError> {
+    update_settings(UpdateSettingsArgument {
+        canister_id: canister_id.0,
+        settings: CanisterSettings {
+            controllers: Some(controllers),
+            ..Default::default()
+        },
+    })
+    .await?;
+    Ok(())
+}
+
+async fn update_sns_controllers(canister_ids: &[Principal]) -> Result<(), CdaoDeployError> {
+    let SnsCanisterIds {
+        governance,
+        ledger,
+        root,
+        swap,
+        index,
+    } = get_sns_canister_ids(canister_ids);
+
+    let admin_canister = CANISTER_DATA
+        .with(|cdata| {
+            cdata
+                .borrow()
+                .known_principal_ids
+                .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+                .copied()
+        })
+        .ok_or_else(|| {
+            CdaoDeployError::CallError(
+                RejectionCode::CanisterError,
+                "Super admin not specified in config".into(),
+            )
+        })?;
+
+    let user_can = ic_cdk::id();
+    let mut update_ctrls_futs = FuturesUnordered::new();
+    update_ctrls_futs.push(update_controllers(
+        governance,
+        vec![admin_canister, user_can, root.0],
+    ));
+    update_ctrls_futs.push(update_controllers(
+        root,
+        vec![admin_canister, user_can, governance.0],
+    ));
+    update_ctrls_futs.push(update_controllers(
+        ledger,
+        vec![admin_canister, user_can, root.0],
+    ));
+    update_ctrls_futs.push(update_controllers(
+        swap,
+        vec![
+            admin_canister,
+            user_can,
+            root.0,
+            ic_nns_constants::ROOT_CANISTER_ID.into(),
+        ],
+    ));
+    update_ctrls_futs.push(update_controllers(
+        index,
+        vec![admin_canister, user_can, root.0],
+    ));
+
+    update_ctrls_futs.try_collect::<Vec<()>>().await?;
+
+    Ok(())
+}
+
+/// Leaves this canister as the only controller, so that the SNS canisters cannot act on each
+/// other once they are returned.
+async fn reset_controllers(canister_ids: &[Principal]) -> Result<(), CdaoDeployError> {
+    let mut failed_canister_ids = vec![];
+    let reset_controllers_tasks = canister_ids.iter().map(|canister_id| async {
+        update_controllers(PrincipalId::from(*canister_id), vec![ic_cdk::id()])
+            .await
+            .map_err(|e| (*canister_id, e))
+    });
+
+    let reset_controllers_callback = |reset_result: Result<(), (Principal, CdaoDeployError)>| {
+        if let Err(e) = reset_result {
+            ic_cdk::println!(
+                "Error resetting controllers of canister {}. Error: {:?}",
+                e.0,
+                e.1
+            );
+            failed_canister_ids.push(e.0);
+        }
+    };
+
+    run_task_concurrently(
+        reset_controllers_tasks,
+        10,
+        reset_controllers_callback,
+        || false,
+    )
+    .await;
+
+    if !failed_canister_ids.is_empty() {
+        return Err(CdaoDeployError::CallError(
+            RejectionCode::CanisterError,
+            format!(
+                "Failed to reset the controllers of {:?}",
+                failed_canister_ids
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Registers the token. Safe to run again for a token that is already registered.
+fn register_cdao_canisters(canister_ids: &[Principal]) -> DeployedCdaoCanisters {
+    let sns_canisters = get_sns_canister_ids(canister_ids);
+    let deployed_cans = DeployedCdaoCanisters {
+        governance: sns_canisters.governance.0,
+        ledger: sns_canisters.ledger.0,
+        root: sns_canisters.root.0,
+        swap: sns_canisters.swap.0,
+        index: sns_canisters.index.0,
+        airdrop_info: AirdropInfo::default(),
+    };
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let is_registered = canister_data
+            .cdao_canisters
+            .iter()
+            .any(|deployed_canisters| deployed_canisters.root == deployed_cans.root);
+        if !is_registered {
+            canister_data.cdao_canisters.push(deployed_cans.clone());
+        }
+        canister_data.token_roots.insert(deployed_cans.root, ());
+    });
+
+    let send_creator_dao_stats_res = SubnetOrchestrator::new().and_then(|subnet_orchestrator| {
+        subnet_orchestrator.send_creator_dao_stats(vec![deployed_cans.root].into_iter().collect())
+    });
+
+    if let Err(e) = send_creator_dao_stats_res {
+        ic_cdk::println!("Error sending creator stats to subnet orchestrator {}", e)
+    }
+
+    deployed_cans
+}
+
+fn unregister_cdao_canisters(canister_ids: &[Principal]) {
+    let root = canister_ids[2];
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .cdao_canisters
+            .retain(|deployed_canisters| deployed_canisters.root != root);
+        canister_data.token_roots.remove(&root);
+        canister_data.wallet.remove_token(root);
+        canister_data.cdao_token_statuses.remove(&root);
+    });
+}
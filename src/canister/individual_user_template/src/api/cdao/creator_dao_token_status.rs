@@ -0,0 +1,337 @@
+use std::{cell::RefCell, collections::BTreeSet, time::SystemTime};
+
+use candid::{Nat, Principal};
+use ic_cdk_macros::{query, update};
+use shared_utils::{
+    canister_specific::individual_user_template::types::cdao::{
+        CreatorDaoTokenStatus, CreatorDaoTokenStatusError, DeployedCdaoCanisters,
+    },
+    common::utils::{
+        permissions::is_caller_controller, system_time::get_current_system_time_from_ic,
+        task::run_task_concurrently,
+    },
+    types::sns_canisters::{
+        sns_governance::{self, ListNeurons},
+        swap::{self, GetDerivedStateArg, GetLifecycleArg},
+    },
+};
+
+use crate::{data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+const LIST_NEURONS_PAGE_SIZE: u32 = 100;
+
+thread_local! {
+    static CREATOR_DAO_TOKEN_STATUS_REFRESHES_IN_PROGRESS: RefCell<BTreeSet<Principal>> =
+        const { RefCell::new(BTreeSet::new()) };
+}
+
+/// Consolidated status of a creator token. The profile owner and the controllers refresh it
+/// once it is older than `CREATOR_DAO_TOKEN_STATUS_TTL_IN_SECONDS`; everyone else is served the
+/// last fetched status. An update, so that refreshed statuses are cached.
+#[update]
+async fn get_creator_dao_token_status(
+    root_canister_id: Principal,
+) -> Result<CreatorDaoTokenStatus, CreatorDaoTokenStatusError> {
+    notify_to_recharge_canister();
+
+    if !is_caller_allowed_to_refresh_creator_dao_token_status() {
+        return CANISTER_DATA
+            .with_borrow(|canister_data| get_cached_status(canister_data, root_canister_id));
+    }
+
+    get_creator_dao_token_status_with_cache(root_canister_id).await
+}
+
+fn is_caller_allowed_to_refresh_creator_dao_token_status() -> bool {
+    let profile_owner =
+        CANISTER_DATA.with_borrow(|canister_data| canister_data.profile.principal_id);
+
+    profile_owner == Some(ic_cdk::caller()) || is_caller_controller().is_ok()
+}
+
+/// Last fetched status of every creator token of this user, which may be stale.
+#[query]
+fn get_cached_creator_dao_token_statuses() -> Vec<CreatorDaoTokenStatus> {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .cdao_token_statuses
+            .values()
+            .cloned()
+            .collect()
+    })
+}
+
+#[update(guard = "is_caller_controller")]
+async fn send_creator_dao_token_statuses_to_subnet_orchestrator(
+) -> Result<Vec<CreatorDaoTokenStatus>, String> {
+    notify_to_recharge_canister();
+
+    let root_canister_ids: Vec<Principal> = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .cdao_canisters
+            .iter()
+            .map(|deployed_canisters| deployed_canisters.root)
+            .collect()
+    });
+
+    let mut creator_dao_token_statuses = vec![];
+
+    let status_tasks = root_canister_ids
+        .into_iter()
+        .map(get_creator_dao_token_status_with_cache);
+
+    let status_callback =
+        |status_result: Result<CreatorDaoTokenStatus, CreatorDaoTokenStatusError>| {
+            match status_result {
+                Ok(status) => creator_dao_token_statuses.push(status),
+                Err(e) => ic_cdk::println!("Error fetching creator dao token status. {:?}", e),
+            }
+        };
+
+    run_task_concurrently(status_tasks, 5, status_callback, || false).await;
+
+    Ok(creator_dao_token_statuses)
+}
+
+/// Last fetched status of the token, however old it is.
+fn get_cached_status(
+    canister_data: &CanisterData,
+    root_canister_id: Principal,
+) -> Result<CreatorDaoTokenStatus, CreatorDaoTokenStatusError> {
+    if !canister_data
+        .cdao_canisters
+        .iter()
+        .any(|deployed_canisters| deployed_canisters.root == root_canister_id)
+    {
+        return Err(CreatorDaoTokenStatusError::TokenNotFound);
+    }
+
+    canister_data
+        .cdao_token_statuses
+        .get(&root_canister_id)
+        .cloned()
+        .ok_or(CreatorDaoTokenStatusError::StatusNotFetched)
+}
+
+/// Returns the cached status if it is still fresh, or the canisters to fetch it from.
+fn get_cached_status_or_deployed_canisters(
+    canister_data: &CanisterData,
+    root_canister_id: Principal,
+    current_time: SystemTime,
+) -> Result<Result<CreatorDaoTokenStatus, DeployedCdaoCanisters>, CreatorDaoTokenStatusError> {
+    let deployed_canisters = canister_data
+        .cdao_canisters
+        .iter()
+        .find(|deployed_canisters| deployed_canisters.root == root_canister_id)
+        .ok_or(CreatorDaoTokenStatusError::TokenNotFound)?;
+
+    match canister_data.cdao_token_statuses.get(&root_canister_id) {
+        Some(status) if !status.is_stale(current_time) => Ok(Ok(status.clone())),
+        _ => Ok(Err(deployed_canisters.clone())),
+    }
+}
+
+pub async fn get_creator_dao_token_status_with_cache(
+    root_canister_id: Principal,
+) -> Result<CreatorDaoTokenStatus, CreatorDaoTokenStatusError> {
+    let cached_status_or_deployed_canisters = CANISTER_DATA.with_borrow(|canister_data| {
+        get_cached_status_or_deployed_canisters(
+            canister_data,
+            root_canister_id,
+            get_current_system_time_from_ic(),
+        )
+    })?;
+
+    let deployed_canisters = match cached_status_or_deployed_canisters {
+        Ok(cached_status) => return Ok(cached_status),
+        Err(deployed_canisters) => deployed_canisters,
+    };
+
+    // * a refresh of this token is already running, the stale status is served meanwhile
+    if !CREATOR_DAO_TOKEN_STATUS_REFRESHES_IN_PROGRESS
+        .with_borrow_mut(|refreshes_in_progress| refreshes_in_progress.insert(root_canister_id))
+    {
+        return CANISTER_DATA
+            .with_borrow(|canister_data| get_cached_status(canister_data, root_canister_id));
+    }
+
+    let status_result =
+        fetch_creator_dao_token_status(&deployed_canisters, get_current_system_time_from_ic())
+            .await;
+
+    CREATOR_DAO_TOKEN_STATUS_REFRESHES_IN_PROGRESS
+        .with_borrow_mut(|refreshes_in_progress| refreshes_in_progress.remove(&root_canister_id));
+
+    let status = status_result?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        // * the token may have been deleted while the status was fetched
+        if canister_data
+            .cdao_canisters
+            .iter()
+            .any(|deployed_canisters| deployed_canisters.root == root_canister_id)
+        {
+            canister_data
+                .cdao_token_statuses
+                .insert(root_canister_id, status.clone());
+        }
+    });
+
+    Ok(status)
+}
+
+async fn fetch_creator_dao_token_status(
+    deployed_canisters: &DeployedCdaoCanisters,
+    current_time: SystemTime,
+) -> Result<CreatorDaoTokenStatus, CreatorDaoTokenStatusError> {
+    let swap_canister = swap::Service(deployed_canisters.swap);
+
+    let (lifecycle,) = swap_canister
+        .get_lifecycle(GetLifecycleArg {})
+        .await
+        .map_err(|e| CreatorDaoTokenStatusError::SwapCallFailed(e.1))?;
+
+    let (derived_state,) = swap_canister
+        .get_derived_state(GetDerivedStateArg {})
+        .await
+        .map_err(|e| CreatorDaoTokenStatusError::SwapCallFailed(e.1))?;
+
+    let (total_supply,): (Nat,) = ic_cdk::call(deployed_canisters.ledger, "icrc1_total_supply", ())
+        .await
+        .map_err(|e| CreatorDaoTokenStatusError::LedgerCallFailed(e.1))?;
+
+    let number_of_neurons = count_neurons(deployed_canisters.governance).await?;
+
+    Ok(CreatorDaoTokenStatus {
+        root: deployed_canisters.root,
+        swap_lifecycle: lifecycle.lifecycle.unwrap_or_default().into(),
+        swap_open_timestamp_seconds: lifecycle.decentralization_sale_open_timestamp_seconds,
+        swap_termination_timestamp_seconds: lifecycle
+            .decentralization_swap_termination_timestamp_seconds,
+        number_of_participants: derived_state.direct_participant_count.unwrap_or_default()
+            + derived_state.cf_participant_count.unwrap_or_default(),
+        raised_icp_e8s: derived_state.buyer_total_icp_e8s.unwrap_or_default(),
+        total_supply,
+        number_of_neurons,
+        fetched_at: current_time,
+    })
+}
+
+async fn count_neurons(
+    governance_canister_id: Principal,
+) -> Result<u64, CreatorDaoTokenStatusError> {
+    let governance_canister = sns_governance::Service(governance_canister_id);
+    let mut number_of_neurons = 0;
+    let mut start_page_at = None;
+
+    loop {
+        let (list_neurons_response,) = governance_canister
+            .list_neurons(ListNeurons {
+                of_principal: None,
+                limit: LIST_NEURONS_PAGE_SIZE,
+                start_page_at,
+            })
+            .await
+            .map_err(|e| CreatorDaoTokenStatusError::GovernanceCallFailed(e.1))?;
+
+        let neurons = list_neurons_response.neurons;
+        number_of_neurons += neurons.len() as u64;
+
+        if neurons.len() < LIST_NEURONS_PAGE_SIZE as usize {
+            return Ok(number_of_neurons);
+        }
+
+        start_page_at = neurons.into_iter().last().and_then(|neuron| neuron.id);
+        if start_page_at.is_none() {
+            return Ok(number_of_neurons);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use shared_utils::canister_specific::individual_user_template::types::cdao::{
+        CreatorDaoSwapLifecycle, CREATOR_DAO_TOKEN_STATUS_TTL_IN_SECONDS,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_cached_status_or_deployed_canisters() {
+        let mut canister_data = CanisterData::default();
+        let root = get_mock_user_alice_canister_id();
+        let deployed_canisters = DeployedCdaoCanisters {
+            governance: root,
+            ledger: root,
+            root,
+            swap: root,
+            index: root,
+            airdrop_info: Default::default(),
+        };
+        canister_data
+            .cdao_canisters
+            .push(deployed_canisters.clone());
+
+        let fetched_at = UNIX_EPOCH + Duration::from_secs(1000);
+
+        assert_eq!(
+            get_cached_status_or_deployed_canisters(
+                &canister_data,
+                get_mock_user_bob_canister_id(),
+                fetched_at
+            ),
+            Err(CreatorDaoTokenStatusError::TokenNotFound)
+        );
+        assert_eq!(
+            get_cached_status_or_deployed_canisters(&canister_data, root, fetched_at),
+            Ok(Err(deployed_canisters.clone()))
+        );
+        assert_eq!(
+            get_cached_status(&canister_data, root),
+            Err(CreatorDaoTokenStatusError::StatusNotFetched)
+        );
+        assert_eq!(
+            get_cached_status(&canister_data, get_mock_user_bob_canister_id()),
+            Err(CreatorDaoTokenStatusError::TokenNotFound)
+        );
+
+        let status = CreatorDaoTokenStatus {
+            root,
+            swap_lifecycle: CreatorDaoSwapLifecycle::Open,
+            swap_open_timestamp_seconds: Some(1000),
+            swap_termination_timestamp_seconds: None,
+            number_of_participants: 3,
+            raised_icp_e8s: 300,
+            total_supply: Nat::from(1_000_000_u64),
+            number_of_neurons: 5,
+            fetched_at,
+        };
+        canister_data
+            .cdao_token_statuses
+            .insert(root, status.clone());
+
+        assert_eq!(
+            get_cached_status_or_deployed_canisters(
+                &canister_data,
+                root,
+                fetched_at + Duration::from_secs(10)
+            ),
+            Ok(Ok(status.clone()))
+        );
+        assert_eq!(
+            get_cached_status_or_deployed_canisters(
+                &canister_data,
+                root,
+                fetched_at + Duration::from_secs(CREATOR_DAO_TOKEN_STATUS_TTL_IN_SECONDS)
+            ),
+            Ok(Err(deployed_canisters))
+        );
+        // * the stale status is still served to callers that can't refresh it
+        assert_eq!(get_cached_status(&canister_data, root), Ok(status));
+    }
+}
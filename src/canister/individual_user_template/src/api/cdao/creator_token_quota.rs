@@ -0,0 +1,141 @@
+use std::time::SystemTime;
+
+use ic_cdk_macros::{query, update};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        creator_token_quota::{CreatorTokenQuota, CreatorTokenQuotaConfig},
+        session::SessionType,
+    },
+    common::utils::{
+        permissions::is_caller_controller_or_global_admin,
+        system_time::get_current_system_time_from_ic,
+    },
+};
+
+use crate::{data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+#[query]
+fn get_creator_token_quota() -> CreatorTokenQuota {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        get_creator_token_quota_impl(canister_data, get_current_system_time_from_ic())
+    })
+}
+
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn update_creator_token_quota_config(config: CreatorTokenQuotaConfig) {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA
+        .with_borrow_mut(|canister_data| canister_data.creator_token_quota.set_config(config));
+}
+
+/// Replaces the quota of this user, or restores the configured one with `None`.
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn update_creator_token_quota_override(quota_override: Option<u64>) {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .creator_token_quota
+            .set_quota_override(quota_override)
+    });
+}
+
+pub fn get_creator_token_quota_impl(
+    canister_data: &CanisterData,
+    current_time: SystemTime,
+) -> CreatorTokenQuota {
+    let is_registered = matches!(
+        canister_data.session_type,
+        Some(SessionType::RegisteredSession)
+    );
+    let account_age = canister_data
+        .account_created_at
+        .and_then(|account_created_at| current_time.duration_since(account_created_at).ok());
+
+    let quota = canister_data
+        .creator_token_quota
+        .get_effective_quota(is_registered, account_age);
+    let used = (canister_data.cdao_canisters.len()
+        + canister_data
+            .cdao_deployments
+            .get_number_of_unfinished_deployments()) as u64;
+
+    CreatorTokenQuota {
+        quota,
+        used,
+        remaining: quota.saturating_sub(used),
+        quota_override: canister_data.creator_token_quota.get_quota_override(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use shared_utils::canister_specific::individual_user_template::types::cdao::DeployedCdaoCanisters;
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    #[test]
+    fn test_get_creator_token_quota_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.account_created_at = Some(UNIX_EPOCH);
+        canister_data
+            .creator_token_quota
+            .set_config(CreatorTokenQuotaConfig {
+                default_quota: 3,
+                require_registered_session: true,
+                min_account_age_in_seconds: 60,
+            });
+
+        let current_time = UNIX_EPOCH + Duration::from_secs(60);
+        assert_eq!(
+            get_creator_token_quota_impl(&canister_data, current_time),
+            CreatorTokenQuota {
+                quota: 0,
+                used: 0,
+                remaining: 0,
+                quota_override: None,
+            }
+        );
+
+        canister_data.session_type = Some(SessionType::RegisteredSession);
+        let canister_id = get_mock_user_alice_canister_id();
+        canister_data.cdao_canisters.push(DeployedCdaoCanisters {
+            governance: canister_id,
+            ledger: canister_id,
+            root: canister_id,
+            swap: canister_id,
+            index: canister_id,
+            airdrop_info: Default::default(),
+        });
+        canister_data
+            .cdao_deployments
+            .start(vec![], 300, current_time);
+
+        assert_eq!(
+            get_creator_token_quota_impl(&canister_data, current_time),
+            CreatorTokenQuota {
+                quota: 3,
+                used: 2,
+                remaining: 1,
+                quota_override: None,
+            }
+        );
+
+        canister_data
+            .creator_token_quota
+            .set_quota_override(Some(1));
+        assert_eq!(
+            get_creator_token_quota_impl(&canister_data, current_time),
+            CreatorTokenQuota {
+                quota: 1,
+                used: 2,
+                remaining: 0,
+                quota_override: Some(1),
+            }
+        );
+    }
+}
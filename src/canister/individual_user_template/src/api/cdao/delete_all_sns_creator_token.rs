@@ -67,6 +67,12 @@ pub fn update_or_delete_sns_creator_token(deployed_canisters: DeployedCdaoCanist
                 canister_data
                     .cdao_canisters
                     .retain(|key| *key != deployed_canisters);
+                canister_data
+                    .cdao_token_statuses
+                    .remove(&deployed_canisters.root);
+                canister_data
+                    .airdrop_claims
+                    .remove_claims_of_token(deployed_canisters.root);
             });
 
             ic_cdk::println!(
@@ -0,0 +1,413 @@
+use std::{cell::RefCell, collections::BTreeSet, time::SystemTime};
+
+use candid::{Nat, Principal};
+use ic_cdk_macros::{query, update};
+use ic_sns_root::pb::v1::{ListSnsCanistersRequest, ListSnsCanistersResponse};
+use icrc_ledger_types::icrc1::{
+    account::{Account, Subaccount},
+    transfer::{Memo, TransferArg, TransferError},
+};
+use sha2::{Digest, Sha256};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        error::SnsNeuronError,
+        sns_neuron::{PendingSnsNeuronStake, SnsNeuronDetails, SnsVote},
+    },
+    common::utils::system_time::get_current_system_time_from_ic,
+    types::sns_canisters::sns_governance::{
+        self, ClaimOrRefresh, Command, Command1, Configure, DissolveState, IncreaseDissolveDelay,
+        ListNeurons, ManageNeuron, MemoAndController, Neuron, NeuronId, Operation, ProposalId,
+        RegisterVote,
+    },
+};
+
+use crate::{data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+const MAX_NEURONS_IN_ONE_REQUEST: u32 = 100;
+
+thread_local! {
+    // Memos of the stakes whose neuron is being claimed
+    static SNS_NEURON_STAKES_IN_PROGRESS: RefCell<BTreeSet<u64>> = const { RefCell::new(BTreeSet::new()) };
+}
+
+/// Stakes tokens held by this canister into a new neuron on the token's governance canister.
+/// The neuron is controlled by this canister. Returns the id of the neuron.
+#[update]
+async fn stake_creator_token(
+    token_root: Principal,
+    amount_e8s: u64,
+    additional_dissolve_delay_seconds: u32,
+) -> Result<Vec<u8>, SnsNeuronError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    let (ledger, governance) = get_ledger_and_governance(token_root).await?;
+
+    let balance: (Nat,) = ic_cdk::call(
+        ledger,
+        "icrc1_balance_of",
+        (Account {
+            owner: ic_cdk::id(),
+            subaccount: None,
+        },),
+    )
+    .await?;
+    if balance.0 < amount_e8s {
+        return Err(SnsNeuronError::NoBalance);
+    }
+
+    let stake = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        record_pending_stake(
+            canister_data,
+            ic_cdk::id(),
+            token_root,
+            amount_e8s,
+            additional_dissolve_delay_seconds,
+            get_current_system_time_from_ic(),
+        )
+    });
+    SNS_NEURON_STAKES_IN_PROGRESS.with_borrow_mut(|stakes| stakes.insert(stake.memo));
+
+    let stake_result = transfer_and_claim_stake(ledger, governance, &stake).await;
+
+    SNS_NEURON_STAKES_IN_PROGRESS.with_borrow_mut(|stakes| stakes.remove(&stake.memo));
+    stake_result
+}
+
+/// Claims the neuron of a pending stake and applies its dissolve delay, when staking was
+/// interrupted after the transfer. Returns the id of the neuron.
+#[update]
+async fn claim_or_refresh_sns_neuron(memo: u64) -> Result<Vec<u8>, SnsNeuronError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    let stake = CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.pending_sns_neuron_stakes.get(&memo).cloned())
+        .ok_or(SnsNeuronError::PendingStakeNotFound)?;
+    if !SNS_NEURON_STAKES_IN_PROGRESS.with_borrow_mut(|stakes| stakes.insert(memo)) {
+        return Err(SnsNeuronError::StakeInProgress);
+    }
+
+    let claim_result = async {
+        let (_, governance) = get_ledger_and_governance(stake.token_root).await?;
+        claim_pending_stake(governance, &stake).await
+    }
+    .await;
+
+    SNS_NEURON_STAKES_IN_PROGRESS.with_borrow_mut(|stakes| stakes.remove(&memo));
+    claim_result
+}
+
+#[query]
+fn get_pending_sns_neuron_stakes() -> Vec<PendingSnsNeuronStake> {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .pending_sns_neuron_stakes
+            .values()
+            .cloned()
+            .collect()
+    })
+}
+
+/// Allocates the memo of a new neuron and records its stake as pending, before the transfer
+fn record_pending_stake(
+    canister_data: &mut CanisterData,
+    controller: Principal,
+    token_root: Principal,
+    amount_e8s: u64,
+    additional_dissolve_delay_seconds: u32,
+    current_time: SystemTime,
+) -> PendingSnsNeuronStake {
+    let memo = canister_data.next_sns_neuron_memo;
+    canister_data.next_sns_neuron_memo += 1;
+
+    let stake = PendingSnsNeuronStake {
+        token_root,
+        memo,
+        neuron_id: compute_neuron_staking_subaccount(controller, memo).to_vec(),
+        amount_e8s,
+        additional_dissolve_delay_seconds,
+        created_at: current_time,
+    };
+    canister_data
+        .pending_sns_neuron_stakes
+        .insert(memo, stake.clone());
+
+    stake
+}
+
+async fn transfer_and_claim_stake(
+    ledger: Principal,
+    governance: Principal,
+    stake: &PendingSnsNeuronStake,
+) -> Result<Vec<u8>, SnsNeuronError> {
+    let neuron_subaccount = compute_neuron_staking_subaccount(ic_cdk::id(), stake.memo);
+    let transfer_args = TransferArg {
+        from_subaccount: None,
+        to: Account {
+            owner: governance,
+            subaccount: Some(neuron_subaccount),
+        },
+        fee: None,
+        created_at_time: None,
+        memo: Some(Memo::from(stake.memo)),
+        amount: Nat::from(stake.amount_e8s),
+    };
+    // * the stake stays pending when the ledger could not be reached, as it may have been sent
+    let transfer_res: (Result<Nat, TransferError>,) =
+        ic_cdk::call(ledger, "icrc1_transfer", (transfer_args,)).await?;
+    if let Err(e) = transfer_res.0 {
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data.pending_sns_neuron_stakes.remove(&stake.memo);
+        });
+        return Err(SnsNeuronError::Transfer(e));
+    }
+
+    claim_pending_stake(governance, stake).await
+}
+
+/// Claims the neuron of the stake, or refreshes it if it was already claimed, then applies the
+/// dissolve delay. The stake is no longer pending once both succeeded.
+async fn claim_pending_stake(
+    governance: Principal,
+    stake: &PendingSnsNeuronStake,
+) -> Result<Vec<u8>, SnsNeuronError> {
+    manage_neuron(
+        governance,
+        stake.neuron_id.clone(),
+        Command::ClaimOrRefresh(ClaimOrRefresh {
+            by: Some(sns_governance::By::MemoAndController(MemoAndController {
+                controller: Some(ic_cdk::id()),
+                memo: stake.memo,
+            })),
+        }),
+    )
+    .await?;
+
+    if stake.additional_dissolve_delay_seconds > 0 {
+        increase_dissolve_delay(
+            governance,
+            stake.neuron_id.clone(),
+            stake.additional_dissolve_delay_seconds,
+        )
+        .await?;
+    }
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.pending_sns_neuron_stakes.remove(&stake.memo);
+    });
+    Ok(stake.neuron_id.clone())
+}
+
+#[update]
+async fn increase_sns_neuron_dissolve_delay(
+    token_root: Principal,
+    neuron_id: Vec<u8>,
+    additional_dissolve_delay_seconds: u32,
+) -> Result<(), SnsNeuronError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    let (_, governance) = get_ledger_and_governance(token_root).await?;
+    increase_dissolve_delay(governance, neuron_id, additional_dissolve_delay_seconds).await
+}
+
+#[update]
+async fn vote_on_sns_proposal(
+    token_root: Principal,
+    neuron_id: Vec<u8>,
+    proposal_id: u64,
+    vote: SnsVote,
+) -> Result<(), SnsNeuronError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    let (_, governance) = get_ledger_and_governance(token_root).await?;
+    manage_neuron(
+        governance,
+        neuron_id,
+        Command::RegisterVote(RegisterVote {
+            vote: vote.to_governance_vote(),
+            proposal: Some(ProposalId { id: proposal_id }),
+        }),
+    )
+    .await
+}
+
+/// Neurons of the token controlled by this canister
+#[update]
+async fn list_sns_neurons(token_root: Principal) -> Result<Vec<SnsNeuronDetails>, SnsNeuronError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    let (_, governance) = get_ledger_and_governance(token_root).await?;
+    let (list_neurons_response,) = sns_governance::Service(governance)
+        .list_neurons(ListNeurons {
+            of_principal: Some(ic_cdk::id()),
+            limit: MAX_NEURONS_IN_ONE_REQUEST,
+            start_page_at: None,
+        })
+        .await?;
+
+    Ok(list_neurons_response
+        .neurons
+        .into_iter()
+        .filter_map(get_sns_neuron_details)
+        .collect())
+}
+
+fn check_caller_is_owner() -> Result<(), SnsNeuronError> {
+    let my_principal_id =
+        CANISTER_DATA.with_borrow(|canister_data| canister_data.profile.principal_id);
+    if my_principal_id != Some(ic_cdk::caller()) {
+        return Err(SnsNeuronError::Unauthenticated);
+    }
+    Ok(())
+}
+
+async fn get_ledger_and_governance(
+    token_root: Principal,
+) -> Result<(Principal, Principal), SnsNeuronError> {
+    let (res,): (ListSnsCanistersResponse,) = ic_cdk::call(
+        token_root,
+        "list_sns_canisters",
+        (ListSnsCanistersRequest {},),
+    )
+    .await?;
+
+    match (res.ledger, res.governance) {
+        (Some(ledger), Some(governance)) => Ok((ledger.into(), governance.into())),
+        _ => Err(SnsNeuronError::InvalidRoot),
+    }
+}
+
+async fn increase_dissolve_delay(
+    governance: Principal,
+    neuron_id: Vec<u8>,
+    additional_dissolve_delay_seconds: u32,
+) -> Result<(), SnsNeuronError> {
+    manage_neuron(
+        governance,
+        neuron_id,
+        Command::Configure(Configure {
+            operation: Some(Operation::IncreaseDissolveDelay(IncreaseDissolveDelay {
+                additional_dissolve_delay_seconds,
+            })),
+        }),
+    )
+    .await
+}
+
+async fn manage_neuron(
+    governance: Principal,
+    neuron_id: Vec<u8>,
+    command: Command,
+) -> Result<(), SnsNeuronError> {
+    let (manage_neuron_response,) = sns_governance::Service(governance)
+        .manage_neuron(ManageNeuron {
+            subaccount: neuron_id.into(),
+            command: Some(command),
+        })
+        .await?;
+
+    match manage_neuron_response.command {
+        Some(Command1::Error(governance_error)) => {
+            Err(SnsNeuronError::Governance(governance_error.error_message))
+        }
+        Some(_) => Ok(()),
+        None => Err(SnsNeuronError::Governance(
+            "Empty response from the governance canister".to_string(),
+        )),
+    }
+}
+
+/// Subaccount of the governance canister the stake of a new neuron is sent to, as computed by
+/// SNS governance when claiming the neuron
+fn compute_neuron_staking_subaccount(controller: Principal, memo: u64) -> Subaccount {
+    let mut hasher = Sha256::new();
+    hasher.update([0x0c]);
+    hasher.update(b"neuron-stake");
+    hasher.update(controller.as_slice());
+    hasher.update(memo.to_be_bytes());
+    hasher.finalize().into()
+}
+
+fn get_sns_neuron_details(neuron: Neuron) -> Option<SnsNeuronDetails> {
+    let NeuronId { id } = neuron.id?;
+    let (dissolve_delay_seconds, when_dissolved_timestamp_seconds) = match neuron.dissolve_state {
+        Some(DissolveState::DissolveDelaySeconds(seconds)) => (Some(seconds), None),
+        Some(DissolveState::WhenDissolvedTimestampSeconds(timestamp)) => (None, Some(timestamp)),
+        None => (None, None),
+    };
+
+    Some(SnsNeuronDetails {
+        neuron_id: id.into_vec(),
+        stake_e8s: neuron.cached_neuron_stake_e8s,
+        maturity_e8s_equivalent: neuron.maturity_e8s_equivalent,
+        dissolve_delay_seconds,
+        when_dissolved_timestamp_seconds,
+        created_timestamp_seconds: neuron.created_timestamp_seconds,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    #[test]
+    fn test_compute_neuron_staking_subaccount() {
+        let controller = Principal::from_slice(&[1, 2, 3]);
+
+        // * sha256(0x0c || "neuron-stake" || controller || memo as big endian)
+        assert_eq!(
+            hex::encode(compute_neuron_staking_subaccount(controller, 7)),
+            "bc8c6fb3a980e5d7a6c5e56676b0479bfb022200ecf5fb3c9cc65efd8376cb0f"
+        );
+        assert_eq!(
+            hex::encode(compute_neuron_staking_subaccount(controller, 8)),
+            "35d4f01b13a55e7c8c39a114816c7be41be11fd399e1dc0cb973d38f65c8c16a"
+        );
+    }
+
+    #[test]
+    fn test_record_pending_stake_allocates_a_new_memo() {
+        let mut canister_data = CanisterData::default();
+        let controller = Principal::from_slice(&[1, 2, 3]);
+        let token_root = get_mock_user_alice_canister_id();
+
+        let first_stake = record_pending_stake(
+            &mut canister_data,
+            controller,
+            token_root,
+            100,
+            0,
+            UNIX_EPOCH,
+        );
+        let second_stake = record_pending_stake(
+            &mut canister_data,
+            controller,
+            token_root,
+            200,
+            60,
+            UNIX_EPOCH,
+        );
+
+        assert_eq!(first_stake.memo, 0);
+        assert_eq!(second_stake.memo, 1);
+        assert_eq!(canister_data.next_sns_neuron_memo, 2);
+        assert_eq!(
+            second_stake.neuron_id,
+            compute_neuron_staking_subaccount(controller, 1).to_vec()
+        );
+        assert_ne!(first_stake.neuron_id, second_stake.neuron_id);
+        assert_eq!(
+            canister_data.pending_sns_neuron_stakes.get(&1),
+            Some(&second_stake)
+        );
+        assert_eq!(canister_data.pending_sns_neuron_stakes.len(), 2);
+    }
+}
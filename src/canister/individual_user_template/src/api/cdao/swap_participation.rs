@@ -0,0 +1,433 @@
+use std::{cell::RefCell, collections::BTreeSet, future::Future};
+
+use candid::{Nat, Principal};
+use ic_cdk_macros::{query, update};
+use ic_sns_root::pb::v1::{ListSnsCanistersRequest, ListSnsCanistersResponse};
+use icrc_ledger_types::icrc1::{
+    account::{Account, Subaccount},
+    transfer::{Memo, TransferArg, TransferError},
+};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        error::SwapParticipationError,
+        swap_participation::{SwapCommitment, SwapCommitmentStatus},
+    },
+    common::utils::system_time::get_current_system_time_from_ic,
+    constant::NNS_LEDGER_CANISTER_ID,
+    types::sns_canisters::swap::{
+        self, ErrorRefundIcpRequest, GetBuyerStateRequest, GetLifecycleArg, NewSaleTicketRequest,
+        NotifyPaymentFailureArg, RefreshBuyerTokensRequest, Result2, Ticket, TransferableAmount,
+    },
+};
+
+use crate::{util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+// * values of `Lifecycle` in the swap canister
+const SWAP_LIFECYCLE_OPEN: i32 = 2;
+const SWAP_LIFECYCLE_COMMITTED: i32 = 3;
+const SWAP_LIFECYCLE_ABORTED: i32 = 4;
+
+// * values of `Err.Type` of `new_sale_ticket` in the swap canister
+const NEW_SALE_TICKET_ERROR_SALE_NOT_OPEN: i32 = 1;
+const NEW_SALE_TICKET_ERROR_SALE_CLOSED: i32 = 2;
+const NEW_SALE_TICKET_ERROR_TICKET_EXISTS: i32 = 3;
+const NEW_SALE_TICKET_ERROR_INVALID_USER_AMOUNT: i32 = 4;
+
+thread_local! {
+    // Root canisters of the tokens whose swap participation is being updated
+    static SWAP_PARTICIPATIONS_IN_PROGRESS: RefCell<BTreeSet<Principal>> = const { RefCell::new(BTreeSet::new()) };
+}
+
+/// Outcome of a commitment once the swap is over
+#[derive(Debug, PartialEq, Eq)]
+enum SwapSettlement {
+    Settled(SwapCommitmentStatus),
+    /// ICP is left on the subaccount of the canister in the swap and has to be refunded
+    RefundIcp,
+}
+
+/// Commits ICP held by this canister to the swap of a creator token. The canister is the buyer,
+/// so the neurons of the swap are controlled by it once the swap is finalized.
+#[update]
+async fn participate_in_creator_token_swap(
+    token_root: Principal,
+    amount_icp_e8s: u64,
+    confirmation_text: Option<String>,
+) -> Result<SwapCommitment, SwapParticipationError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    with_participation_in_progress(token_root, async {
+        let swap_canister = get_swap(token_root).await?;
+        let swap = swap::Service(swap_canister);
+
+        let (lifecycle_response,) = swap.get_lifecycle(GetLifecycleArg {}).await?;
+        if lifecycle_response.lifecycle != Some(SWAP_LIFECYCLE_OPEN) {
+            return Err(SwapParticipationError::SwapNotOpen);
+        }
+
+        let ticket = get_sale_ticket(&swap, amount_icp_e8s).await?;
+
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data.swap_commitments.record_pending_commitment(
+                token_root,
+                swap_canister,
+                get_current_system_time_from_ic(),
+            )
+        });
+
+        if let Err(e) = transfer_icp_to_swap(swap_canister, &ticket).await {
+            let _ = swap
+                .notify_payment_failure(NotifyPaymentFailureArg {})
+                .await;
+            // * a rejected call may still have moved the ICP, the commitment stays pending so
+            // that it can be refreshed or refunded
+            if matches!(e, SwapParticipationError::Transfer(_)) {
+                CANISTER_DATA.with_borrow_mut(|canister_data| {
+                    canister_data
+                        .swap_commitments
+                        .revert_pending_commitment(token_root, get_current_system_time_from_ic())
+                });
+            }
+            return Err(e);
+        }
+
+        refresh_commitment(token_root, swap_canister, confirmation_text).await
+    })
+    .await
+}
+
+/// Asks the swap to account for the ICP sent by this canister, when participating was
+/// interrupted after the transfer
+#[update]
+async fn refresh_creator_token_swap_commitment(
+    token_root: Principal,
+    confirmation_text: Option<String>,
+) -> Result<SwapCommitment, SwapParticipationError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    let swap_canister = CANISTER_DATA
+        .with_borrow(|canister_data| {
+            canister_data
+                .swap_commitments
+                .get_commitment(token_root)
+                .filter(|commitment| is_unsettled(commitment.status))
+                .map(|commitment| commitment.swap_canister)
+        })
+        .ok_or(SwapParticipationError::CommitmentNotFound)?;
+
+    with_participation_in_progress(
+        token_root,
+        refresh_commitment(token_root, swap_canister, confirmation_text),
+    )
+    .await
+}
+
+/// Settles a commitment once the swap is over. A committed swap is claimed once it has been
+/// finalized, ICP the swap did not keep is refunded to this canister. Can be called again when
+/// the refund failed.
+#[update]
+async fn claim_creator_token_swap(
+    token_root: Principal,
+) -> Result<SwapCommitment, SwapParticipationError> {
+    notify_to_recharge_canister();
+    check_caller_is_owner()?;
+
+    let swap_canister = CANISTER_DATA
+        .with_borrow(|canister_data| {
+            canister_data
+                .swap_commitments
+                .get_commitment(token_root)
+                .filter(|commitment| is_unsettled(commitment.status))
+                .map(|commitment| commitment.swap_canister)
+        })
+        .ok_or(SwapParticipationError::NothingToClaim)?;
+
+    with_participation_in_progress(token_root, async {
+        let swap = swap::Service(swap_canister);
+
+        let (lifecycle_response,) = swap.get_lifecycle(GetLifecycleArg {}).await?;
+        // * finalizing the swap sweeps the ICP of the buyer to governance, or back to the buyer
+        // when the swap was aborted
+        let (buyer_state_response,) = swap
+            .get_buyer_state(GetBuyerStateRequest {
+                principal_id: Some(ic_cdk::id()),
+            })
+            .await?;
+        let buyer_icp = buyer_state_response
+            .buyer_state
+            .and_then(|buyer_state| buyer_state.icp);
+
+        let status = match get_swap_settlement(lifecycle_response.lifecycle, buyer_icp.as_ref())? {
+            SwapSettlement::Settled(status) => status,
+            SwapSettlement::RefundIcp => {
+                let (refund_response,) = swap
+                    .error_refund_icp(ErrorRefundIcpRequest {
+                        source_principal_id: Some(ic_cdk::id()),
+                    })
+                    .await?;
+                if let Some(swap::Result_::Err(e)) = refund_response.result {
+                    return Err(SwapParticipationError::Swap(
+                        e.description.unwrap_or_default(),
+                    ));
+                }
+                SwapCommitmentStatus::Refunded
+            }
+        };
+
+        CANISTER_DATA
+            .with_borrow_mut(|canister_data| {
+                canister_data.swap_commitments.set_status(
+                    token_root,
+                    status,
+                    get_current_system_time_from_ic(),
+                )
+            })
+            .ok_or(SwapParticipationError::NothingToClaim)
+    })
+    .await
+}
+
+#[query]
+fn get_creator_token_swap_commitments() -> Vec<SwapCommitment> {
+    CANISTER_DATA.with_borrow(|canister_data| canister_data.swap_commitments.get_commitments())
+}
+
+fn check_caller_is_owner() -> Result<(), SwapParticipationError> {
+    let my_principal_id =
+        CANISTER_DATA.with_borrow(|canister_data| canister_data.profile.principal_id);
+    if my_principal_id != Some(ic_cdk::caller()) {
+        return Err(SwapParticipationError::Unauthenticated);
+    }
+    Ok(())
+}
+
+/// Runs the update of a participation, one at a time per token
+async fn with_participation_in_progress<F>(
+    token_root: Principal,
+    update: F,
+) -> Result<SwapCommitment, SwapParticipationError>
+where
+    F: Future<Output = Result<SwapCommitment, SwapParticipationError>>,
+{
+    if !SWAP_PARTICIPATIONS_IN_PROGRESS.with_borrow_mut(|tokens| tokens.insert(token_root)) {
+        return Err(SwapParticipationError::ParticipationInProgress);
+    }
+
+    let result = update.await;
+
+    SWAP_PARTICIPATIONS_IN_PROGRESS.with_borrow_mut(|tokens| tokens.remove(&token_root));
+    result
+}
+
+fn is_unsettled(status: SwapCommitmentStatus) -> bool {
+    matches!(
+        status,
+        SwapCommitmentStatus::Pending | SwapCommitmentStatus::Committed
+    )
+}
+
+fn get_swap_settlement(
+    lifecycle: Option<i32>,
+    buyer_icp: Option<&TransferableAmount>,
+) -> Result<SwapSettlement, SwapParticipationError> {
+    let is_swept = buyer_icp.is_some_and(|icp| icp.transfer_success_timestamp_seconds > 0);
+    let is_accepted = buyer_icp.is_some_and(|icp| icp.amount_e8s > 0);
+
+    match lifecycle {
+        Some(SWAP_LIFECYCLE_COMMITTED) if is_swept => {
+            Ok(SwapSettlement::Settled(SwapCommitmentStatus::Claimed))
+        }
+        // * the swap closed before it accepted the ICP of an interrupted participation
+        Some(SWAP_LIFECYCLE_COMMITTED) if !is_accepted => Ok(SwapSettlement::RefundIcp),
+        Some(SWAP_LIFECYCLE_ABORTED) if is_swept => {
+            Ok(SwapSettlement::Settled(SwapCommitmentStatus::Refunded))
+        }
+        Some(SWAP_LIFECYCLE_ABORTED) => Ok(SwapSettlement::RefundIcp),
+        _ => Err(SwapParticipationError::SwapNotFinalized),
+    }
+}
+
+/// Records the ICP the swap has accepted from this canister
+async fn refresh_commitment(
+    token_root: Principal,
+    swap_canister: Principal,
+    confirmation_text: Option<String>,
+) -> Result<SwapCommitment, SwapParticipationError> {
+    let (refresh_response,) = swap::Service(swap_canister)
+        .refresh_buyer_tokens(RefreshBuyerTokensRequest {
+            buyer: ic_cdk::id().to_text(),
+            confirmation_text,
+        })
+        .await?;
+
+    Ok(CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.swap_commitments.record_commitment(
+            token_root,
+            swap_canister,
+            refresh_response.icp_accepted_participation_e8s,
+            get_current_system_time_from_ic(),
+        )
+    }))
+}
+
+async fn get_swap(token_root: Principal) -> Result<Principal, SwapParticipationError> {
+    let (res,): (ListSnsCanistersResponse,) = ic_cdk::call(
+        token_root,
+        "list_sns_canisters",
+        (ListSnsCanistersRequest {},),
+    )
+    .await?;
+
+    res.swap
+        .map(Principal::from)
+        .ok_or(SwapParticipationError::InvalidRoot)
+}
+
+/// Opens a ticket for the amount, or picks up the ticket left open by an earlier attempt
+async fn get_sale_ticket(
+    swap: &swap::Service,
+    amount_icp_e8s: u64,
+) -> Result<Ticket, SwapParticipationError> {
+    let (new_sale_ticket_response,) = swap
+        .new_sale_ticket(NewSaleTicketRequest {
+            subaccount: None,
+            amount_icp_e8s,
+        })
+        .await?;
+
+    let ticket = match new_sale_ticket_response.result {
+        Some(Result2::Ok(ok)) => ok.ticket,
+        Some(Result2::Err(e)) => match e.error_type {
+            NEW_SALE_TICKET_ERROR_TICKET_EXISTS => e.existing_ticket,
+            NEW_SALE_TICKET_ERROR_SALE_NOT_OPEN | NEW_SALE_TICKET_ERROR_SALE_CLOSED => {
+                return Err(SwapParticipationError::SwapNotOpen)
+            }
+            NEW_SALE_TICKET_ERROR_INVALID_USER_AMOUNT => {
+                let invalid_user_amount =
+                    e.invalid_user_amount.unwrap_or(swap::InvalidUserAmount {
+                        min_amount_icp_e8s_included: 0,
+                        max_amount_icp_e8s_included: 0,
+                    });
+                return Err(SwapParticipationError::InvalidAmount {
+                    min_amount_icp_e8s: invalid_user_amount.min_amount_icp_e8s_included,
+                    max_amount_icp_e8s: invalid_user_amount.max_amount_icp_e8s_included,
+                });
+            }
+            error_type => {
+                return Err(SwapParticipationError::Swap(format!(
+                    "new_sale_ticket failed with error type {error_type}"
+                )))
+            }
+        },
+        None => None,
+    };
+
+    ticket.ok_or_else(|| {
+        SwapParticipationError::Swap("new_sale_ticket returned no ticket".to_string())
+    })
+}
+
+/// The memo and creation time come from the ticket, so retrying the transfer of an existing
+/// ticket is deduplicated by the ledger.
+async fn transfer_icp_to_swap(
+    swap_canister: Principal,
+    ticket: &Ticket,
+) -> Result<(), SwapParticipationError> {
+    let transfer_args = TransferArg {
+        from_subaccount: None,
+        to: Account {
+            owner: swap_canister,
+            subaccount: Some(principal_to_subaccount(ic_cdk::id())),
+        },
+        fee: None,
+        created_at_time: Some(ticket.creation_time),
+        memo: Some(Memo::from(ticket.ticket_id)),
+        amount: Nat::from(ticket.amount_icp_e8s),
+    };
+    let icp_ledger = Principal::from_text(NNS_LEDGER_CANISTER_ID).unwrap();
+    let (transfer_res,): (Result<Nat, TransferError>,) =
+        ic_cdk::call(icp_ledger, "icrc1_transfer", (transfer_args,)).await?;
+
+    match transfer_res {
+        Ok(_) | Err(TransferError::Duplicate { .. }) => Ok(()),
+        Err(e) => Err(SwapParticipationError::Transfer(e)),
+    }
+}
+
+/// Subaccount of the swap canister that holds the ICP committed by the principal
+fn principal_to_subaccount(principal: Principal) -> Subaccount {
+    let principal_bytes = principal.as_slice();
+    let mut subaccount = [0; 32];
+    subaccount[0] = principal_bytes.len() as u8;
+    subaccount[1..1 + principal_bytes.len()].copy_from_slice(principal_bytes);
+    subaccount
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn buyer_icp(amount_e8s: u64, transfer_success_timestamp_seconds: u64) -> TransferableAmount {
+        TransferableAmount {
+            transfer_fee_paid_e8s: None,
+            transfer_start_timestamp_seconds: transfer_success_timestamp_seconds,
+            amount_e8s,
+            amount_transferred_e8s: None,
+            transfer_success_timestamp_seconds,
+        }
+    }
+
+    #[test]
+    fn test_principal_to_subaccount_matches_the_swap_canister() {
+        // * subaccount the swap canister derives from the principal `2vxsx-fae`
+        let subaccount = principal_to_subaccount(Principal::anonymous());
+        let mut expected = [0; 32];
+        expected[0] = 1;
+        expected[1] = 4;
+        assert_eq!(subaccount, expected);
+
+        let subaccount = principal_to_subaccount(Principal::management_canister());
+        assert_eq!(subaccount, [0; 32]);
+    }
+
+    #[test]
+    fn test_get_swap_settlement() {
+        let swept = buyer_icp(100, 10);
+        let accepted = buyer_icp(100, 0);
+
+        assert_eq!(
+            get_swap_settlement(Some(SWAP_LIFECYCLE_COMMITTED), Some(&swept)),
+            Ok(SwapSettlement::Settled(SwapCommitmentStatus::Claimed))
+        );
+        assert_eq!(
+            get_swap_settlement(Some(SWAP_LIFECYCLE_COMMITTED), Some(&accepted)),
+            Err(SwapParticipationError::SwapNotFinalized)
+        );
+        assert_eq!(
+            get_swap_settlement(Some(SWAP_LIFECYCLE_COMMITTED), None),
+            Ok(SwapSettlement::RefundIcp)
+        );
+        assert_eq!(
+            get_swap_settlement(Some(SWAP_LIFECYCLE_ABORTED), Some(&swept)),
+            Ok(SwapSettlement::Settled(SwapCommitmentStatus::Refunded))
+        );
+        assert_eq!(
+            get_swap_settlement(Some(SWAP_LIFECYCLE_ABORTED), Some(&accepted)),
+            Ok(SwapSettlement::RefundIcp)
+        );
+        assert_eq!(
+            get_swap_settlement(Some(SWAP_LIFECYCLE_ABORTED), None),
+            Ok(SwapSettlement::RefundIcp)
+        );
+        assert_eq!(
+            get_swap_settlement(Some(SWAP_LIFECYCLE_OPEN), Some(&accepted)),
+            Err(SwapParticipationError::SwapNotFinalized)
+        );
+        assert_eq!(
+            get_swap_settlement(None, None),
+            Err(SwapParticipationError::SwapNotFinalized)
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::cell::Cell;
+
+use ic_cdk::caller;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        engagement_reward::{EngagementRewardClaim, EngagementRewardError},
+        session::SessionType,
+    },
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    util::{
+        cycles::notify_to_recharge_canister,
+        engagement_reward::{
+            claim_engagement_rewards_impl, get_claimable_engagement_rewards,
+            get_unused_engagement_reward_claims,
+        },
+        subnet_orchestrator::SubnetOrchestrator,
+    },
+    CANISTER_DATA,
+};
+
+thread_local! {
+    static ENGAGEMENT_REWARD_CLAIM_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Mints the rewards for every engagement milestone reached and not yet claimed today, as far
+/// as the network caps kept by the user index allow.
+#[update]
+async fn claim_engagement_rewards() -> Result<Vec<EngagementRewardClaim>, EngagementRewardError> {
+    notify_to_recharge_canister();
+
+    let (profile_owner, session_type) = CANISTER_DATA.with_borrow(|canister_data| {
+        (
+            canister_data.profile.principal_id,
+            canister_data.session_type,
+        )
+    });
+
+    if profile_owner != Some(caller()) {
+        return Err(EngagementRewardError::Unauthorized);
+    }
+
+    if session_type != Some(SessionType::RegisteredSession) {
+        return Err(EngagementRewardError::UserNotSignedUp);
+    }
+
+    update_last_canister_functionality_access_time();
+
+    if ENGAGEMENT_REWARD_CLAIM_IN_PROGRESS.replace(true) {
+        return Err(EngagementRewardError::ClaimInProgress);
+    }
+
+    let claim_result = claim_engagement_rewards_within_network_caps().await;
+
+    ENGAGEMENT_REWARD_CLAIM_IN_PROGRESS.set(false);
+
+    claim_result
+}
+
+async fn claim_engagement_rewards_within_network_caps(
+) -> Result<Vec<EngagementRewardClaim>, EngagementRewardError> {
+    let claimable_rewards = CANISTER_DATA.with_borrow(|canister_data| {
+        get_claimable_engagement_rewards(canister_data, get_current_system_time_from_ic())
+    });
+
+    if claimable_rewards.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let subnet_orchestrator = SubnetOrchestrator::new()
+        .map_err(EngagementRewardError::UserIndexCrossCanisterCallFailed)?;
+
+    let granted_rewards = subnet_orchestrator
+        .reserve_engagement_rewards(claimable_rewards)
+        .await
+        .map_err(EngagementRewardError::UserIndexCrossCanisterCallFailed)?;
+
+    let claims = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        claim_engagement_rewards_impl(
+            canister_data,
+            granted_rewards.clone(),
+            get_current_system_time_from_ic(),
+        )
+    });
+
+    // * a reservation that fails to be released is only held until the day's cap starts over
+    let unused_rewards = get_unused_engagement_reward_claims(&granted_rewards, &claims);
+    if !unused_rewards.is_empty() {
+        if let Err(e) = subnet_orchestrator
+            .release_engagement_rewards(unused_rewards)
+            .await
+        {
+            ic_cdk::println!("Failed to release unused engagement rewards: {}", e);
+        }
+    }
+
+    Ok(claims)
+}
@@ -0,0 +1,9 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::engagement_reward::EngagementRewardsConfig;
+
+use crate::CANISTER_DATA;
+
+#[query]
+fn get_engagement_rewards_config() -> EngagementRewardsConfig {
+    CANISTER_DATA.with_borrow(|canister_data| canister_data.engagement_rewards.get_config())
+}
@@ -0,0 +1,14 @@
+use ic_cdk_macros::query;
+use shared_utils::{
+    canister_specific::individual_user_template::types::engagement_reward::EngagementRewardsStatus,
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{util::engagement_reward::get_engagement_rewards_status_impl, CANISTER_DATA};
+
+#[query]
+fn get_engagement_rewards_status() -> EngagementRewardsStatus {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        get_engagement_rewards_status_impl(canister_data, get_current_system_time_from_ic())
+    })
+}
@@ -0,0 +1,4 @@
+pub mod claim_engagement_rewards;
+pub mod get_engagement_rewards_config;
+pub mod get_engagement_rewards_status;
+pub mod update_engagement_rewards_config;
@@ -0,0 +1,18 @@
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::engagement_reward::EngagementRewardsConfig,
+    common::utils::permissions::is_caller_controller_or_global_admin,
+};
+
+use crate::{util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn update_engagement_rewards_config(engagement_rewards_config: EngagementRewardsConfig) {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .engagement_rewards
+            .set_config(engagement_rewards_config)
+    });
+}
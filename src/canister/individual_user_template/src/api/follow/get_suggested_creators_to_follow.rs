@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use candid::Principal;
+use futures::StreamExt;
+use ic_cdk_macros::update;
+use shared_utils::canister_specific::individual_user_template::types::{
+    error::FollowAnotherUserProfileError,
+    follow::{FollowEntryDetail, FollowEntryId, SuggestedCreator},
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA,
+};
+
+/// Number of most recently followed profiles whose followings are looked up
+pub const MAX_FOLLOWEES_TO_FAN_OUT_TO: usize = 20;
+pub const MAX_SUGGESTED_CREATORS_RETURNED: usize = 10;
+
+/// Suggests creators to follow by aggregating the followings of the profiles this user follows,
+/// ranked by how many of those profiles follow each creator.
+///
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can call this.
+#[update]
+async fn get_suggested_creators_to_follow(
+) -> Result<Vec<SuggestedCreator>, FollowAnotherUserProfileError> {
+    notify_to_recharge_canister();
+
+    let current_caller = ic_cdk::caller();
+
+    update_last_canister_functionality_access_time();
+
+    let followees_to_fan_out_to = CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_data = canister_data_ref_cell.borrow();
+        validate_incoming_request(&canister_data, &current_caller)?;
+        Ok::<_, FollowAnotherUserProfileError>(get_followees_to_fan_out_to(&canister_data))
+    })?;
+
+    let followings_futures = followees_to_fan_out_to
+        .into_iter()
+        .map(|followee| async move {
+            ic_cdk::call::<_, (Vec<(FollowEntryId, FollowEntryDetail)>,)>(
+                followee.canister_id,
+                "get_principals_this_profile_follows_paginated",
+                (None::<u64>,),
+            )
+            .await
+            .map(|(followings,)| {
+                followings
+                    .into_iter()
+                    .map(|(_, follow_entry_detail)| follow_entry_detail)
+                    .collect::<Vec<FollowEntryDetail>>()
+            })
+        });
+
+    let followings_of_followees: Vec<Vec<FollowEntryDetail>> =
+        futures::stream::iter(followings_futures)
+            .buffer_unordered(MAX_FOLLOWEES_TO_FAN_OUT_TO)
+            .filter_map(|result| async move {
+                result
+                    .inspect_err(|e| {
+                        ic_cdk::println!("Error fetching followings of followee {:?}", e)
+                    })
+                    .ok()
+            })
+            .collect()
+            .await;
+
+    Ok(CANISTER_DATA.with(|canister_data_ref_cell| {
+        rank_suggested_creators(&canister_data_ref_cell.borrow(), followings_of_followees)
+    }))
+}
+
+fn validate_incoming_request(
+    canister_data: &CanisterData,
+    current_caller: &Principal,
+) -> Result<(), FollowAnotherUserProfileError> {
+    if *current_caller == Principal::anonymous() {
+        return Err(FollowAnotherUserProfileError::Unauthenticated);
+    }
+
+    if canister_data.profile.principal_id != Some(*current_caller) {
+        return Err(FollowAnotherUserProfileError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+fn get_followees_to_fan_out_to(canister_data: &CanisterData) -> Vec<FollowEntryDetail> {
+    canister_data
+        .follow_data
+        .following
+        .sorted_index
+        .values()
+        .rev()
+        .take(MAX_FOLLOWEES_TO_FAN_OUT_TO)
+        .cloned()
+        .collect()
+}
+
+fn rank_suggested_creators(
+    canister_data: &CanisterData,
+    followings_of_followees: Vec<Vec<FollowEntryDetail>>,
+) -> Vec<SuggestedCreator> {
+    let my_principal_id = canister_data.profile.principal_id;
+    let mut overlap_counts: HashMap<FollowEntryDetail, u64> = HashMap::new();
+
+    followings_of_followees
+        .into_iter()
+        .flatten()
+        .filter(|follow_entry_detail| {
+            Some(follow_entry_detail.principal_id) != my_principal_id
+                && !canister_data
+                    .follow_data
+                    .following
+                    .contains(follow_entry_detail)
+        })
+        .for_each(|follow_entry_detail| {
+            *overlap_counts.entry(follow_entry_detail).or_default() += 1;
+        });
+
+    let mut suggested_creators: Vec<SuggestedCreator> = overlap_counts
+        .into_iter()
+        .map(
+            |(follow_entry_detail, followed_by_count)| SuggestedCreator {
+                principal_id: follow_entry_detail.principal_id,
+                canister_id: follow_entry_detail.canister_id,
+                followed_by_count,
+            },
+        )
+        .collect();
+
+    // * ties are broken by principal so that the ordering is deterministic
+    suggested_creators.sort_by(|a, b| {
+        b.followed_by_count
+            .cmp(&a.followed_by_count)
+            .then_with(|| a.principal_id.cmp(&b.principal_id))
+    });
+    suggested_creators.truncate(MAX_SUGGESTED_CREATORS_RETURNED);
+
+    suggested_creators
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+        get_mock_user_charlie_canister_id, get_mock_user_charlie_principal_id,
+        get_mock_user_dan_canister_id, get_mock_user_dan_principal_id,
+    };
+
+    use super::*;
+
+    fn alice() -> FollowEntryDetail {
+        FollowEntryDetail {
+            principal_id: get_mock_user_alice_principal_id(),
+            canister_id: get_mock_user_alice_canister_id(),
+        }
+    }
+
+    fn bob() -> FollowEntryDetail {
+        FollowEntryDetail {
+            principal_id: get_mock_user_bob_principal_id(),
+            canister_id: get_mock_user_bob_canister_id(),
+        }
+    }
+
+    fn charlie() -> FollowEntryDetail {
+        FollowEntryDetail {
+            principal_id: get_mock_user_charlie_principal_id(),
+            canister_id: get_mock_user_charlie_canister_id(),
+        }
+    }
+
+    fn dan() -> FollowEntryDetail {
+        FollowEntryDetail {
+            principal_id: get_mock_user_dan_principal_id(),
+            canister_id: get_mock_user_dan_canister_id(),
+        }
+    }
+
+    #[test]
+    fn test_validate_incoming_request() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+
+        assert_eq!(
+            validate_incoming_request(&canister_data, &Principal::anonymous()),
+            Err(FollowAnotherUserProfileError::Unauthenticated)
+        );
+        assert_eq!(
+            validate_incoming_request(&canister_data, &get_mock_user_bob_principal_id()),
+            Err(FollowAnotherUserProfileError::Unauthorized)
+        );
+        assert_eq!(
+            validate_incoming_request(&canister_data, &get_mock_user_alice_principal_id()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_get_followees_to_fan_out_to() {
+        let mut canister_data = CanisterData::default();
+
+        (0..(MAX_FOLLOWEES_TO_FAN_OUT_TO as u64 + 5)).for_each(|id: u64| {
+            canister_data.follow_data.following.add(FollowEntryDetail {
+                principal_id: Principal::self_authenticating(id.to_ne_bytes()),
+                canister_id: Principal::self_authenticating(id.to_ne_bytes()),
+            });
+        });
+
+        let result = get_followees_to_fan_out_to(&canister_data);
+
+        assert_eq!(result.len(), MAX_FOLLOWEES_TO_FAN_OUT_TO);
+        assert_eq!(
+            result[0].principal_id,
+            Principal::self_authenticating((MAX_FOLLOWEES_TO_FAN_OUT_TO as u64 + 4).to_ne_bytes())
+        );
+    }
+
+    #[test]
+    fn test_rank_suggested_creators() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+        canister_data.follow_data.following.add(bob());
+
+        let result = rank_suggested_creators(
+            &canister_data,
+            vec![
+                vec![alice(), charlie(), dan()],
+                vec![charlie(), bob()],
+                vec![charlie()],
+            ],
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                SuggestedCreator {
+                    principal_id: charlie().principal_id,
+                    canister_id: charlie().canister_id,
+                    followed_by_count: 3,
+                },
+                SuggestedCreator {
+                    principal_id: dan().principal_id,
+                    canister_id: dan().canister_id,
+                    followed_by_count: 1,
+                },
+            ]
+        );
+
+        let result = rank_suggested_creators(
+            &canister_data,
+            (0..(MAX_SUGGESTED_CREATORS_RETURNED as u64 + 5))
+                .map(|id: u64| {
+                    vec![FollowEntryDetail {
+                        principal_id: Principal::self_authenticating(id.to_ne_bytes()),
+                        canister_id: Principal::self_authenticating(id.to_ne_bytes()),
+                    }]
+                })
+                .collect(),
+        );
+
+        assert_eq!(result.len(), MAX_SUGGESTED_CREATORS_RETURNED);
+    }
+}
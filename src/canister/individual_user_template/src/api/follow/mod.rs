@@ -1,5 +1,7 @@
 pub mod do_i_follow_this_user;
 pub mod get_principals_that_follow_this_profile_paginated;
 pub mod get_principals_this_profile_follows_paginated;
+pub mod get_suggested_creators_to_follow;
 pub mod update_profiles_i_follow_toggle_list_with_specified_profile;
 pub mod update_profiles_that_follow_me_toggle_list_with_specified_profile;
+pub mod which_of_these_users_follow_me;
@@ -1,7 +1,11 @@
 use candid::{CandidType, Deserialize, Principal};
 use ic_cdk_macros::update;
-use shared_utils::canister_specific::individual_user_template::types::{
-    error::FollowAnotherUserProfileError, follow::FollowEntryDetail,
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        error::FollowAnotherUserProfileError, follow::FollowEntryDetail,
+        notification::NotificationType,
+    },
+    common::utils::system_time,
 };
 
 use crate::{
@@ -33,11 +37,23 @@ async fn update_profiles_that_follow_me_toggle_list_with_specified_profile(
     CANISTER_DATA.with(|canister_data_ref_cell| {
         let mut canister_data = canister_data_ref_cell.borrow_mut();
 
-        update_profiles_that_follow_me_toggle_list_with_specified_profile_impl(
+        let follow_status = update_profiles_that_follow_me_toggle_list_with_specified_profile_impl(
             &mut canister_data,
             &calling_canister_principal,
             &arg,
-        )
+        )?;
+
+        if follow_status {
+            canister_data.notification_inbox.add_notification(
+                NotificationType::NewFollower {
+                    follower_principal_id: arg.follower_principal_id,
+                    follower_canister_id: arg.follower_canister_id,
+                },
+                system_time::get_current_system_time_from_ic(),
+            );
+        }
+
+        Ok(follow_status)
     })
 }
 
@@ -0,0 +1,148 @@
+use std::collections::HashSet;
+
+use candid::Principal;
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::error::FollowAnotherUserProfileError;
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    data_model::CanisterData, CANISTER_DATA,
+};
+
+pub const MAX_PRINCIPALS_TO_CHECK_IN_ONE_REQUEST: usize = 100;
+
+/// Returns the subset of the supplied principals that follow this profile. At most
+/// `MAX_PRINCIPALS_TO_CHECK_IN_ONE_REQUEST` principals can be checked in one request.
+///
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can call this.
+#[query]
+fn which_of_these_users_follow_me(
+    principals: Vec<Principal>,
+) -> Result<Vec<Principal>, FollowAnotherUserProfileError> {
+    let current_caller = ic_cdk::caller();
+
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with(|canister_data| {
+        let canister_data = canister_data.borrow();
+        which_of_these_users_follow_me_impl(&canister_data, &principals, &current_caller)
+    })
+}
+
+fn which_of_these_users_follow_me_impl(
+    canister_data: &CanisterData,
+    principals: &[Principal],
+    current_caller: &Principal,
+) -> Result<Vec<Principal>, FollowAnotherUserProfileError> {
+    if *current_caller == Principal::anonymous() {
+        return Err(FollowAnotherUserProfileError::Unauthenticated);
+    }
+
+    let my_principal_id = canister_data
+        .profile
+        .principal_id
+        .expect("My principal ID not set");
+
+    if my_principal_id != *current_caller {
+        return Err(FollowAnotherUserProfileError::Unauthorized);
+    }
+
+    if principals.len() > MAX_PRINCIPALS_TO_CHECK_IN_ONE_REQUEST {
+        return Err(FollowAnotherUserProfileError::TooManyPrincipalsToCheck);
+    }
+
+    let my_followers: HashSet<Principal> = canister_data
+        .follow_data
+        .follower
+        .members
+        .keys()
+        .map(|follow_entry_detail| follow_entry_detail.principal_id)
+        .collect();
+
+    Ok(principals
+        .iter()
+        .filter(|principal| my_followers.contains(*principal))
+        .copied()
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::individual_user_template::types::follow::FollowEntryDetail;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_principal_id,
+        get_mock_user_dan_canister_id, get_mock_user_dan_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_which_of_these_users_follow_me_impl() {
+        let mut canister_data = CanisterData::default();
+        let principals = vec![
+            get_mock_user_bob_principal_id(),
+            get_mock_user_charlie_principal_id(),
+            get_mock_user_dan_principal_id(),
+        ];
+
+        let result = which_of_these_users_follow_me_impl(
+            &canister_data,
+            &principals,
+            &Principal::anonymous(),
+        );
+
+        assert_eq!(result, Err(FollowAnotherUserProfileError::Unauthenticated));
+
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+
+        let result = which_of_these_users_follow_me_impl(
+            &canister_data,
+            &principals,
+            &get_mock_user_charlie_principal_id(),
+        );
+
+        assert_eq!(result, Err(FollowAnotherUserProfileError::Unauthorized));
+
+        let current_caller = get_mock_user_alice_principal_id();
+
+        let result =
+            which_of_these_users_follow_me_impl(&canister_data, &principals, &current_caller);
+
+        assert_eq!(result, Ok(vec![]));
+
+        canister_data.follow_data.follower.add(FollowEntryDetail {
+            principal_id: get_mock_user_bob_principal_id(),
+            canister_id: get_mock_user_bob_canister_id(),
+        });
+        canister_data.follow_data.follower.add(FollowEntryDetail {
+            principal_id: get_mock_user_dan_principal_id(),
+            canister_id: get_mock_user_dan_canister_id(),
+        });
+
+        let result =
+            which_of_these_users_follow_me_impl(&canister_data, &principals, &current_caller);
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                get_mock_user_bob_principal_id(),
+                get_mock_user_dan_principal_id()
+            ])
+        );
+
+        let too_many_principals =
+            vec![get_mock_user_bob_principal_id(); MAX_PRINCIPALS_TO_CHECK_IN_ONE_REQUEST + 1];
+        let result = which_of_these_users_follow_me_impl(
+            &canister_data,
+            &too_many_principals,
+            &current_caller,
+        );
+
+        assert_eq!(
+            result,
+            Err(FollowAnotherUserProfileError::TooManyPrincipalsToCheck)
+        );
+    }
+}
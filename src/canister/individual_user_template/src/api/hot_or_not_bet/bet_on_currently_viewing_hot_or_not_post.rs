@@ -3,7 +3,7 @@ use ic_cdk_macros::update;
 use shared_utils::{
     canister_specific::individual_user_template::types::{
         arg::PlaceBetArg,
-        error::BetOnCurrentlyViewingPostError,
+        error::{BetOnCurrentlyViewingPostError, TokenError},
         hot_or_not::{BetOutcomeForBetMaker, BettingStatus, PlacedBetDetail},
     },
     common::{
@@ -37,7 +37,7 @@ async fn bet_on_currently_viewing_post(
     })?;
 
     update_last_canister_functionality_access_time();
-    update_token_balance_before_bet_happens(place_bet_arg.bet_amount);
+    update_token_balance_before_bet_happens(place_bet_arg.bet_amount)?;
 
     let response = ic_cdk::call::<_, (Result<BettingStatus, BetOnCurrentlyViewingPostError>,)>(
         place_bet_arg.post_canister_id,
@@ -73,8 +73,8 @@ async fn bet_on_currently_viewing_post(
             CANISTER_DATA.with(|canister_data_ref_cell| {
                 let canister_data = &mut canister_data_ref_cell.borrow_mut();
 
-                let my_token_balance = &mut canister_data.my_token_balance;
-                my_token_balance.handle_token_event(TokenEvent::Stake {
+                // * the bet is already placed on the post, so a failure here is only logged
+                if let Err(e) = canister_data.apply_token_event(TokenEvent::Stake {
                     amount: place_bet_arg.bet_amount,
                     details: StakeEvent::BetOnHotOrNotPost {
                         post_canister_id: place_bet_arg.post_canister_id,
@@ -83,7 +83,9 @@ async fn bet_on_currently_viewing_post(
                         bet_direction: place_bet_arg.bet_direction.clone(),
                     },
                     timestamp: current_time,
-                });
+                }) {
+                    ic_cdk::println!("Failed to record stake for bet: {:?}", e);
+                }
 
                 let all_hot_or_not_bets_placed = &mut canister_data.all_hot_or_not_bets_placed;
                 all_hot_or_not_bets_placed.insert(
@@ -108,19 +110,22 @@ async fn bet_on_currently_viewing_post(
 
 // this #[update] is for local testing only see: src/lib/integration_tests/tests/upgrade/excessive_tokens_test.rs
 // #[update]
-pub fn update_token_balance_before_bet_happens(bet_amount: u64) {
+pub fn update_token_balance_before_bet_happens(bet_amount: u64) -> Result<(), TokenError> {
     CANISTER_DATA.with_borrow_mut(|canister_data| {
         canister_data
             .my_token_balance
-            .adjust_balance_pre_bet(bet_amount);
-    });
+            .adjust_balance_pre_bet(bet_amount)
+    })
 }
 
 fn update_token_balance_after_bet_placement_fails(bet_amount: u64) {
     CANISTER_DATA.with_borrow_mut(|canister_data| {
-        canister_data
+        if let Err(e) = canister_data
             .my_token_balance
-            .adjust_balance_for_failed_bet_placement(bet_amount);
+            .adjust_balance_for_failed_bet_placement(bet_amount)
+        {
+            ic_cdk::println!("Failed to refund bet amount {}: {:?}", bet_amount, e);
+        }
     });
 }
 
@@ -1,7 +1,9 @@
 use ic_cdk_macros::update;
 
 use shared_utils::{
-    canister_specific::individual_user_template::types::hot_or_not::BetOutcomeForBetMaker,
+    canister_specific::individual_user_template::types::{
+        hot_or_not::BetOutcomeForBetMaker, notification::NotificationType,
+    },
     common::{
         types::{
             app_primitive_type::PostId,
@@ -66,8 +68,31 @@ fn receive_bet_winnings_when_distributed(post_id: PostId, outcome: BetOutcomeFor
             .cloned()
             .unwrap();
 
-        let my_token_balance = &mut canister_data.my_token_balance;
-        my_token_balance.handle_token_event(TokenEvent::HotOrNotOutcomePayout {
+        let bet_outcome_notification_type = match outcome {
+            BetOutcomeForBetMaker::Won(winnings_amount) => Some(NotificationType::BetWon {
+                post_canister_id: post_creator_canister_id,
+                post_id,
+                winnings_amount,
+            }),
+            BetOutcomeForBetMaker::Lost => Some(NotificationType::BetLost {
+                post_canister_id: post_creator_canister_id,
+                post_id,
+            }),
+            BetOutcomeForBetMaker::Draw(refund_amount) => Some(NotificationType::BetDraw {
+                post_canister_id: post_creator_canister_id,
+                post_id,
+                refund_amount,
+            }),
+            BetOutcomeForBetMaker::AwaitingResult => None,
+        };
+
+        if let Some(notification_type) = bet_outcome_notification_type {
+            canister_data
+                .notification_inbox
+                .add_notification(notification_type, current_time);
+        }
+
+        let payout_result = canister_data.apply_token_event(TokenEvent::HotOrNotOutcomePayout {
             amount: match outcome {
                 BetOutcomeForBetMaker::Draw(amount) => amount,
                 BetOutcomeForBetMaker::Won(amount) => amount,
@@ -87,5 +112,14 @@ fn receive_bet_winnings_when_distributed(post_id: PostId, outcome: BetOutcomeFor
             },
             timestamp: current_time,
         });
+
+        if let Err(e) = payout_result {
+            ic_cdk::println!(
+                "Failed to credit winnings for post {} from canister {}: {:?}",
+                post_id,
+                post_creator_canister_id,
+                e
+            );
+        }
     });
 }
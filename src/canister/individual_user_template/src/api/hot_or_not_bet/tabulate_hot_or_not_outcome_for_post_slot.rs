@@ -1,12 +1,18 @@
 use candid::Principal;
 use ic_cdk::api::canister_balance;
 use shared_utils::{
-    canister_specific::individual_user_template::types::hot_or_not::{
-        BetDirection, BetMakerInformedStatus, BetOutcomeForBetMaker, BetPayout, GlobalBetId,
-        GlobalRoomId, RoomBetPossibleOutcomes,
+    canister_specific::individual_user_template::types::{
+        hot_or_not::{
+            BetDirection, BetMakerInformedStatus, BetOutcomeForBetMaker, BetPayout, GlobalBetId,
+            GlobalRoomId, RoomBetPossibleOutcomes,
+        },
+        notification::NotificationType,
     },
     common::{
-        types::known_principal::KnownPrincipalType,
+        types::{
+            known_principal::KnownPrincipalType,
+            utility_token::token_event::{HotOrNotOutcomePayoutEvent, TokenEvent},
+        },
         utils::{system_time, task::run_task_concurrently},
     },
 };
@@ -51,16 +57,55 @@ pub async fn tabulate_hot_or_not_outcome_for_post_slot(post_id: u64, slot_id: u8
             return;
         };
 
-        let token_balance = &mut canister_data.my_token_balance;
+        let commission_token_events = match post_to_tabulate_results_for
+            .tabulate_hot_or_not_outcome_for_slot_v1(
+                &this_canister_id,
+                &slot_id,
+                &current_time,
+                &mut canister_data.room_details_map,
+                &mut canister_data.bet_details_map,
+            ) {
+            Ok(commission_token_events) => commission_token_events,
+            Err(e) => {
+                ic_cdk::println!(
+                    "Failed to compute outcome for post:{post_id} and slot:{slot_id}: {:?}",
+                    e
+                );
+                return;
+            }
+        };
 
-        post_to_tabulate_results_for.tabulate_hot_or_not_outcome_for_slot_v1(
-            &this_canister_id,
-            &slot_id,
-            token_balance,
-            &current_time,
-            &mut canister_data.room_details_map,
-            &mut canister_data.bet_details_map,
-        );
+        // * only commissions that were credited are reported to the creator
+        let mut commission_earned: u64 = 0;
+        for token_event in commission_token_events {
+            let amount = match &token_event {
+                TokenEvent::HotOrNotOutcomePayout {
+                    amount,
+                    details: HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet { .. },
+                    ..
+                } => *amount,
+                _ => 0,
+            };
+
+            match canister_data.apply_token_event(token_event) {
+                Ok(_) => commission_earned = commission_earned.saturating_add(amount),
+                Err(e) => ic_cdk::println!(
+                    "Failed to credit commission for post:{post_id} and slot:{slot_id}: {:?}",
+                    e
+                ),
+            }
+        }
+
+        if commission_earned > 0 {
+            canister_data.notification_inbox.add_notification(
+                NotificationType::CommissionEarned {
+                    post_id,
+                    slot_id,
+                    amount: commission_earned,
+                },
+                current_time,
+            );
+        }
 
         canister_data
             .all_created_posts
@@ -1,31 +1,134 @@
-use shared_utils::common::types::http::{HeaderField, HttpRequest, HttpResponse};
+use std::time::SystemTime;
+
+use candid::Principal;
+use percent_encoding::percent_decode_str;
+use shared_utils::{
+    canister_specific::individual_user_template::types::kv_storage::NamespacePermission,
+    common::{
+        types::http::{HeaderField, HttpRequest, HttpResponse},
+        utils::system_time::get_current_system_time_from_ic,
+    },
+};
+
+use crate::{data_model::kv_storage::AppStorage, CANISTER_DATA};
 
 use super::monitoring::metrics::metrics;
 
+const KV_STORAGE_PATH_PREFIX: &str = "/kv/";
+// Media types that browsers do not run scripts from, besides any audio and video type
+const SERVED_CONTENT_TYPES: [&str; 7] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "text/plain",
+    "application/json",
+    "application/octet-stream",
+];
+const SERVED_CONTENT_TYPE_PREFIXES: [&str; 2] = ["audio/", "video/"];
+
+struct Asset {
+    bytes: Vec<u8>,
+    content_type: String,
+    cache_control: String,
+}
+
 fn get_path(url: &str) -> Option<&str> {
     url.split('?').next()
 }
 
-fn retrieve(path: &str) -> Option<Vec<u8>> {
+/// Namespace id and percent-decoded key of a `/kv/<namespace_id>/<key>` path
+fn parse_key_value_path(path: &str) -> Option<(u64, String)> {
+    let (namespace_id, key) = path.strip_prefix(KV_STORAGE_PATH_PREFIX)?.split_once('/')?;
+    let namespace_id = namespace_id.parse().ok()?;
+    let key = percent_decode_str(key).decode_utf8().ok()?;
+
+    Some((namespace_id, key.into_owned()))
+}
+
+/// Content types are served as they were uploaded, so only media types known not to run scripts
+/// are accepted
+fn is_served_content_type(content_type: &str) -> bool {
+    if !content_type
+        .chars()
+        .all(|c| c.is_ascii_graphic() || c == ' ')
+    {
+        return false;
+    }
+
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    SERVED_CONTENT_TYPES.contains(&media_type.as_str())
+        || SERVED_CONTENT_TYPE_PREFIXES.iter().any(|prefix| {
+            media_type
+                .strip_prefix(prefix)
+                .is_some_and(|subtype| !subtype.is_empty() && !subtype.contains(['/', ' ']))
+        })
+}
+
+/// Values of namespaces that anonymous callers can read are served at
+/// `/kv/<namespace_id>/<key>`, with the key percent-encoded.
+fn retrieve_key_value(path: &str, current_time: SystemTime) -> Option<Asset> {
+    let (namespace_id, key) = parse_key_value_path(path)?;
+
+    AppStorage::get_a_namespace(
+        Principal::anonymous(),
+        namespace_id,
+        NamespacePermission::Read,
+    )
+    .ok()?;
+    let entry = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .app_storage
+            .get_live_entry(namespace_id, key, current_time)
+    })?;
+
+    let content_type = entry
+        .content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    if !is_served_content_type(&content_type) {
+        return None;
+    }
+
+    Some(Asset {
+        bytes: entry.value,
+        content_type,
+        cache_control: "no-cache".to_string(),
+    })
+}
+
+fn retrieve(path: &str) -> Option<Asset> {
     match path {
-        "/metrics" => Some(metrics().as_bytes().to_vec()),
-        _ => None,
+        "/metrics" => Some(Asset {
+            bytes: metrics().as_bytes().to_vec(),
+            content_type: "text/plain".to_string(),
+            cache_control: format!("max-age={}", 600),
+        }),
+        _ => retrieve_key_value(path, get_current_system_time_from_ic()),
     }
 }
 
 #[ic_cdk_macros::query]
 fn http_request(request: HttpRequest) -> HttpResponse {
     let path = get_path(request.url.as_str()).unwrap_or("/");
-    if let Some(bytes) = retrieve(path) {
+    if let Some(asset) = retrieve(path) {
         HttpResponse {
             status_code: 200,
             headers: vec![
                 //HeaderField("Content-Encoding".to_string(), "gzip".to_string()),
-                HeaderField("Content-Length".to_string(), format!("{}", bytes.len())),
-                HeaderField("Cache-Control".to_string(), format!("max-age={}", 600)),
-                HeaderField("Content-Type".to_string(), "text/plain".to_string()),
+                HeaderField(
+                    "Content-Length".to_string(),
+                    format!("{}", asset.bytes.len()),
+                ),
+                HeaderField("Cache-Control".to_string(), asset.cache_control),
+                HeaderField("Content-Type".to_string(), asset.content_type),
+                HeaderField("X-Content-Type-Options".to_string(), "nosniff".to_string()),
             ],
-            body: bytes,
+            body: asset.bytes,
         }
     } else {
         HttpResponse {
@@ -35,3 +138,111 @@ fn http_request(request: HttpRequest) -> HttpResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::canister_specific::individual_user_template::types::kv_storage::{
+        KeyValueAction, KeyValueCondition, KeyValueOperation,
+    };
+    use test_utils::setup::test_constants::get_mock_user_alice_principal_id;
+
+    use super::*;
+
+    fn write_blob(key: &str, content_type: Option<&str>) -> KeyValueOperation {
+        KeyValueOperation {
+            key: key.into(),
+            condition: KeyValueCondition::Any,
+            action: KeyValueAction::WriteBlob {
+                value: b"value".to_vec(),
+                content_type: content_type.map(str::to_string),
+                expires_at: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_key_value_path() {
+        assert_eq!(
+            parse_key_value_path("/kv/3/greeting%20card"),
+            Some((3, "greeting card".to_string()))
+        );
+        assert_eq!(
+            parse_key_value_path("/kv/3/images/avatar.png"),
+            Some((3, "images/avatar.png".to_string()))
+        );
+        assert_eq!(parse_key_value_path("/kv/3/"), Some((3, String::new())));
+        assert_eq!(parse_key_value_path("/kv/3/%FF"), None);
+        assert_eq!(parse_key_value_path("/kv/three/key"), None);
+        assert_eq!(parse_key_value_path("/kv/3"), None);
+        assert_eq!(parse_key_value_path("/metrics"), None);
+    }
+
+    #[test]
+    fn test_is_served_content_type() {
+        assert!(is_served_content_type("image/png"));
+        assert!(is_served_content_type("IMAGE/JPEG"));
+        assert!(is_served_content_type("text/plain; charset=utf-8"));
+        assert!(is_served_content_type("application/octet-stream"));
+        assert!(is_served_content_type("video/mp4"));
+        assert!(is_served_content_type("Audio/MPEG"));
+        assert!(!is_served_content_type("text/html"));
+        assert!(!is_served_content_type("Text/HTML; charset=utf-8"));
+        assert!(!is_served_content_type("image/svg+xml"));
+        assert!(!is_served_content_type("application/pdf"));
+        assert!(!is_served_content_type("text/css"));
+        assert!(!is_served_content_type("video/"));
+        assert!(!is_served_content_type("text/plain\r\nSet-Cookie: a=b"));
+        assert!(!is_served_content_type("plain"));
+    }
+
+    #[test]
+    fn test_only_values_readable_by_anonymous_callers_are_served() {
+        let alice = get_mock_user_alice_principal_id();
+        CANISTER_DATA
+            .with_borrow_mut(|canister_data| canister_data.profile.principal_id = Some(alice));
+        let namespace_id = AppStorage::create_a_namespace(alice, "public".into())
+            .unwrap()
+            .id;
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data
+                .app_storage
+                .apply_key_value_operations(
+                    namespace_id,
+                    vec![
+                        write_blob("greeting card", Some("text/plain")),
+                        write_blob("page", Some("text/html")),
+                        write_blob("raw", None),
+                    ],
+                    UNIX_EPOCH,
+                )
+                .unwrap();
+        });
+        let retrieve = |key: &str| {
+            retrieve_key_value(&format!("/kv/{namespace_id}/{key}"), UNIX_EPOCH)
+                .map(|asset| (asset.bytes, asset.content_type))
+        };
+
+        assert_eq!(retrieve("greeting%20card"), None);
+
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data.app_storage.set_namespace_permission(
+                namespace_id,
+                Principal::anonymous(),
+                Some(NamespacePermission::Read),
+            )
+        });
+        assert_eq!(
+            retrieve("greeting%20card"),
+            Some((b"value".to_vec(), "text/plain".to_string()))
+        );
+        assert_eq!(
+            retrieve("raw"),
+            Some((b"value".to_vec(), "application/octet-stream".to_string()))
+        );
+        assert_eq!(retrieve("page"), None);
+        assert_eq!(retrieve("missing"), None);
+        assert!(retrieve_key_value(&format!("/kv/{}/raw", namespace_id + 1), UNIX_EPOCH).is_none());
+    }
+}
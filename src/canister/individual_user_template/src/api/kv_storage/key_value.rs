@@ -1,7 +1,11 @@
 use ic_cdk::caller;
 use ic_cdk_macros::{query, update};
-use shared_utils::canister_specific::individual_user_template::types::kv_storage::NamespaceErrors;
-use std::collections::BTreeMap;
+use shared_utils::canister_specific::individual_user_template::types::kv_storage::{
+    KeyValueAction, KeyValueBlobForFrontend, KeyValueCondition, KeyValueEntryForFrontend,
+    KeyValueOperation, NamespaceErrors, NamespaceKeysPage, NamespacePermission,
+    MAX_NAMESPACE_KEYS_PAGE_SIZE,
+};
+use std::{collections::BTreeMap, time::SystemTime};
 
 use crate::data_model::kv_storage::AppStorage;
 
@@ -10,9 +14,9 @@ fn delete_multiple_key_value_pairs(
     namespace_id: u64,
     keys: Vec<String>,
 ) -> Result<(), NamespaceErrors> {
-    let namespace = AppStorage::get_a_namespace(caller(), namespace_id)?;
-    namespace.delete_multiple_keys(keys);
-    Ok(())
+    let namespace =
+        AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    namespace.delete_multiple_keys(keys)
 }
 
 #[update]
@@ -20,7 +24,8 @@ fn write_multiple_key_value_pairs(
     namespace_id: u64,
     pairs: BTreeMap<String, String>,
 ) -> Result<(), NamespaceErrors> {
-    let namespace = AppStorage::get_a_namespace(caller(), namespace_id)?;
+    let namespace =
+        AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
     namespace.write_multiple_key_value_pairs(pairs)
 }
 
@@ -30,28 +35,107 @@ fn write_key_value_pair(
     key: String,
     value: String,
 ) -> Result<Option<String>, NamespaceErrors> {
-    let namespace = AppStorage::get_a_namespace(caller(), namespace_id)?;
+    let namespace =
+        AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
     let prev_value = namespace.write_key_value_pair(key, value)?;
     Ok(prev_value)
 }
 
 #[query]
 fn list_namespace_keys(namespace_id: u64) -> Result<Vec<String>, NamespaceErrors> {
-    let namespace = AppStorage::get_a_namespace(caller(), namespace_id)?;
+    let namespace = AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::Read)?;
     Ok(namespace.list_keys())
 }
 
+/// Keys in ascending order, optionally only those starting with the prefix. Pass the
+/// `next_cursor` of a page as the cursor to get the next page.
+#[query]
+fn list_namespace_keys_paginated(
+    namespace_id: u64,
+    prefix: Option<String>,
+    cursor: Option<String>,
+    limit: u64,
+) -> Result<NamespaceKeysPage, NamespaceErrors> {
+    let namespace = AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::Read)?;
+    let limit = limit.min(MAX_NAMESPACE_KEYS_PAGE_SIZE) as usize;
+    Ok(namespace.list_keys_paginated(&prefix.unwrap_or_default(), cursor, limit))
+}
+
 #[update]
 fn delete_key_value_pair(
     namespace_id: u64,
     key: String,
 ) -> Result<Option<String>, NamespaceErrors> {
-    let namespace = AppStorage::get_a_namespace(caller(), namespace_id)?;
-    Ok(namespace.delete_key_value_pair(key))
+    let namespace =
+        AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    namespace.delete_key_value_pair(key)
 }
 
 #[query]
 fn read_key_value_pair(namespace_id: u64, key: String) -> Result<Option<String>, NamespaceErrors> {
-    let namespace = AppStorage::get_a_namespace(caller(), namespace_id)?;
-    Ok(namespace.read_key_value_pair(key))
+    let namespace = AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::Read)?;
+    namespace.read_key_value_pair(key)
+}
+
+#[query]
+fn read_multiple_key_value_pairs(
+    namespace_id: u64,
+    keys: Vec<String>,
+) -> Result<BTreeMap<String, String>, NamespaceErrors> {
+    let namespace = AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::Read)?;
+    namespace.read_multiple_key_value_pairs(keys)
+}
+
+#[query]
+fn read_key_value_entry(
+    namespace_id: u64,
+    key: String,
+) -> Result<Option<KeyValueEntryForFrontend>, NamespaceErrors> {
+    let namespace = AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::Read)?;
+    namespace
+        .read_key_value_entry(key)
+        .map(KeyValueEntryForFrontend::try_from)
+        .transpose()
+}
+
+#[query]
+fn read_key_value_blob(
+    namespace_id: u64,
+    key: String,
+) -> Result<Option<KeyValueBlobForFrontend>, NamespaceErrors> {
+    let namespace = AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::Read)?;
+    Ok(namespace
+        .read_key_value_entry(key)
+        .map(KeyValueBlobForFrontend::from))
+}
+
+/// Writes the value only if the key is in the expected state and returns its new version.
+#[update]
+fn compare_and_swap_key_value_pair(
+    namespace_id: u64,
+    key: String,
+    condition: KeyValueCondition,
+    value: String,
+    expires_at: Option<SystemTime>,
+) -> Result<u64, NamespaceErrors> {
+    let namespace =
+        AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    let mut new_versions = namespace.execute_transaction(vec![KeyValueOperation {
+        key,
+        condition,
+        action: KeyValueAction::Write { value, expires_at },
+    }])?;
+    Ok(new_versions.pop().flatten().unwrap_or_default())
+}
+
+/// Applies all operations or none of them. Returns the new version of each written key and
+/// `None` for deleted keys, in the order of the operations.
+#[update]
+fn execute_key_value_transaction(
+    namespace_id: u64,
+    operations: Vec<KeyValueOperation>,
+) -> Result<Vec<Option<u64>>, NamespaceErrors> {
+    let namespace =
+        AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    namespace.execute_transaction(operations)
 }
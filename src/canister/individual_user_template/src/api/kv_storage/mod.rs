@@ -1,2 +1,3 @@
 pub mod key_value;
 pub mod namespace;
+pub mod upload;
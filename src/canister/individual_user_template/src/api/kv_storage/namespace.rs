@@ -1,13 +1,16 @@
 use std::borrow::Borrow;
 
+use candid::Principal;
 use ic_cdk::caller;
 use ic_cdk_macros::{query, update};
 use shared_utils::canister_specific::individual_user_template::types::kv_storage::{
-    NamespaceErrors, NamespaceForFrontend,
+    NamespaceErrors, NamespaceForFrontend, NamespaceLimits, NamespacePermission,
+    NamespaceSettingsForFrontend,
 };
 
 use crate::{
     data_model::kv_storage::{AppStorage, Namespace},
+    util::kv_storage_expiry::start_kv_storage_expiry_sweeper_timer_if_needed,
     CANISTER_DATA,
 };
 
@@ -20,3 +23,69 @@ fn create_a_namespace(title: String) -> Result<NamespaceForFrontend, NamespaceEr
 fn list_namespaces(start_index: usize, limit: usize) -> Vec<NamespaceForFrontend> {
     AppStorage::list_namespaces(start_index, limit)
 }
+
+#[query]
+fn get_namespace_settings(
+    namespace_id: u64,
+) -> Result<NamespaceSettingsForFrontend, NamespaceErrors> {
+    AppStorage::get_a_namespace_to_manage(caller(), namespace_id)?;
+    let settings = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .app_storage
+            .get_namespace_settings(namespace_id)
+    });
+    Ok(NamespaceSettingsForFrontend::from(settings))
+}
+
+#[update]
+fn grant_namespace_permission(
+    namespace_id: u64,
+    principal: Principal,
+    permission: NamespacePermission,
+) -> Result<(), NamespaceErrors> {
+    AppStorage::get_a_namespace_to_manage(caller(), namespace_id)?;
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.app_storage.set_namespace_permission(
+            namespace_id,
+            principal,
+            Some(permission),
+        )
+    });
+    Ok(())
+}
+
+#[update]
+fn revoke_namespace_permission(
+    namespace_id: u64,
+    principal: Principal,
+) -> Result<(), NamespaceErrors> {
+    AppStorage::get_a_namespace_to_manage(caller(), namespace_id)?;
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .app_storage
+            .set_namespace_permission(namespace_id, principal, None)
+    });
+    Ok(())
+}
+
+#[update]
+fn update_namespace_limits(
+    namespace_id: u64,
+    limits: NamespaceLimits,
+) -> Result<(), NamespaceErrors> {
+    AppStorage::get_a_namespace_to_manage(caller(), namespace_id)?;
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .app_storage
+            .set_namespace_limits(namespace_id, limits)
+    })
+}
+
+#[update]
+fn delete_namespace(namespace_id: u64) -> Result<(), NamespaceErrors> {
+    AppStorage::get_a_namespace_to_manage(caller(), namespace_id)?;
+    CANISTER_DATA
+        .with_borrow_mut(|canister_data| canister_data.app_storage.remove_namespace(namespace_id));
+    start_kv_storage_expiry_sweeper_timer_if_needed();
+    Ok(())
+}
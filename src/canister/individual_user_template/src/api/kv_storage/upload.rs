@@ -0,0 +1,89 @@
+use std::time::SystemTime;
+
+use ic_cdk::caller;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::kv_storage::{
+        KeyValueCondition, NamespaceErrors, NamespacePermission,
+    },
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{
+    data_model::kv_storage::AppStorage,
+    util::kv_storage_expiry::start_kv_storage_expiry_sweeper_timer_if_needed, CANISTER_DATA,
+};
+
+/// Starts uploading a value too big for a single message. Chunks are sent with
+/// `upload_key_value_chunk` and the value is written to the key on `commit_key_value_upload`.
+/// Uploads that are not committed within an hour are discarded.
+#[update]
+fn start_key_value_upload(
+    namespace_id: u64,
+    key: String,
+    total_size_in_bytes: u64,
+    content_type: Option<String>,
+) -> Result<u64, NamespaceErrors> {
+    AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    let upload_id = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.app_storage.start_upload(
+            namespace_id,
+            key,
+            content_type,
+            total_size_in_bytes,
+            caller(),
+            get_current_system_time_from_ic(),
+        )
+    })?;
+    start_kv_storage_expiry_sweeper_timer_if_needed();
+    Ok(upload_id)
+}
+
+#[update]
+fn upload_key_value_chunk(
+    namespace_id: u64,
+    upload_id: u64,
+    offset: u64,
+    chunk: Vec<u8>,
+) -> Result<(), NamespaceErrors> {
+    AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .app_storage
+            .upload_chunk(namespace_id, upload_id, caller(), offset, chunk)
+    })
+}
+
+/// Writes the uploaded value only if the key is in the expected state and returns its new
+/// version.
+#[update]
+fn commit_key_value_upload(
+    namespace_id: u64,
+    upload_id: u64,
+    condition: KeyValueCondition,
+    expires_at: Option<SystemTime>,
+) -> Result<u64, NamespaceErrors> {
+    AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    let new_version = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.app_storage.commit_upload(
+            namespace_id,
+            upload_id,
+            caller(),
+            condition,
+            expires_at,
+            get_current_system_time_from_ic(),
+        )
+    })?;
+    start_kv_storage_expiry_sweeper_timer_if_needed();
+    Ok(new_version)
+}
+
+#[update]
+fn cancel_key_value_upload(namespace_id: u64, upload_id: u64) -> Result<(), NamespaceErrors> {
+    AppStorage::get_a_namespace(caller(), namespace_id, NamespacePermission::ReadWrite)?;
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .app_storage
+            .cancel_upload(namespace_id, upload_id, caller())
+    })
+}
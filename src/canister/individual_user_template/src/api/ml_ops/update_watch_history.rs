@@ -1,6 +1,10 @@
 use shared_utils::{
     canister_specific::individual_user_template::types::ml_data::WatchHistoryItem,
-    common::utils::permissions::is_caller_controller_or_global_admin,
+    common::utils::{
+        permissions::is_caller_controller_or_global_admin,
+        system_time::get_current_system_time_from_ic,
+    },
+    types::utility_token_supply_stats::get_day_since_epoch,
 };
 
 use ic_cdk_macros::update;
@@ -14,8 +18,15 @@ use crate::{
 fn update_watch_history(watch_history_item: WatchHistoryItem) -> Result<String, String> {
     update_last_canister_functionality_access_time();
 
+    let today = get_day_since_epoch(get_current_system_time_from_ic());
+
     CANISTER_DATA.with(|canister_data| {
         let mut canister_data = canister_data.borrow_mut();
+        canister_data.engagement_rewards.record_post_watched(
+            today,
+            watch_history_item.publisher_canister_id,
+            watch_history_item.post_id,
+        );
         canister_data.watch_history.insert(watch_history_item, ());
 
         // keep removing oldest items until the len is less than or equal to 3000
@@ -1,16 +1,20 @@
 pub mod canister_lifecycle;
 pub mod canister_management;
 pub mod cycle_management;
+pub mod engagement_reward;
 pub mod follow;
 pub mod hot_or_not_bet;
 pub mod http;
 pub mod kv_storage;
 pub mod ml_ops;
 pub mod monitoring;
+pub mod notification;
 pub mod post;
 pub mod profile;
+pub mod referral;
 pub mod snapshot;
 pub mod token;
+pub mod wallet;
 pub mod well_known_principal;
 pub mod cdao;
 pub mod device_id_management;
@@ -0,0 +1,123 @@
+use candid::Principal;
+use ic_cdk_macros::query;
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        error::NotificationError,
+        notification::{Notification, NotificationId},
+    },
+    pagination::{self, PaginationError},
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    data_model::CanisterData, CANISTER_DATA,
+};
+
+/// Returns notifications newest first.
+///
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can read their notifications.
+#[query]
+fn get_notifications_with_pagination(
+    from_inclusive_index: u64,
+    limit: u64,
+) -> Result<Vec<(NotificationId, Notification)>, NotificationError> {
+    let current_caller = ic_cdk::caller();
+
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        get_notifications_with_pagination_impl(
+            &canister_data_ref_cell.borrow(),
+            &current_caller,
+            from_inclusive_index,
+            limit,
+        )
+    })
+}
+
+fn get_notifications_with_pagination_impl(
+    canister_data: &CanisterData,
+    current_caller: &Principal,
+    from_inclusive_index: u64,
+    limit: u64,
+) -> Result<Vec<(NotificationId, Notification)>, NotificationError> {
+    if canister_data.profile.principal_id != Some(*current_caller) {
+        return Err(NotificationError::Unauthorized);
+    }
+
+    let (from_inclusive_index, limit) = pagination::get_pagination_bounds_cursor(
+        from_inclusive_index,
+        limit,
+        canister_data.notification_inbox.len(),
+    )
+    .map_err(|e| match e {
+        PaginationError::InvalidBoundsPassed | PaginationError::ReachedEndOfItemsList => {
+            NotificationError::ReachedEndOfItemsList
+        }
+        PaginationError::ExceededMaxNumberOfItemsAllowedInOneRequest => {
+            NotificationError::ExceededMaxNumberOfItemsAllowedInOneRequest
+        }
+    })?;
+
+    Ok(canister_data
+        .notification_inbox
+        .get_notifications(from_inclusive_index, limit))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::canister_specific::individual_user_template::types::notification::NotificationType;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_notifications_with_pagination_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+
+        let result = get_notifications_with_pagination_impl(
+            &canister_data,
+            &get_mock_user_bob_principal_id(),
+            0,
+            10,
+        );
+        assert_eq!(result, Err(NotificationError::Unauthorized));
+
+        let result = get_notifications_with_pagination_impl(
+            &canister_data,
+            &get_mock_user_alice_principal_id(),
+            0,
+            10,
+        );
+        assert_eq!(result, Err(NotificationError::ReachedEndOfItemsList));
+
+        (0..15).for_each(|_| {
+            canister_data.notification_inbox.add_notification(
+                NotificationType::NewFollower {
+                    follower_principal_id: get_mock_user_bob_principal_id(),
+                    follower_canister_id: get_mock_user_bob_canister_id(),
+                },
+                UNIX_EPOCH,
+            );
+        });
+
+        let result = get_notifications_with_pagination_impl(
+            &canister_data,
+            &get_mock_user_alice_principal_id(),
+            10,
+            10,
+        )
+        .unwrap();
+        assert_eq!(
+            result.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![4, 3, 2, 1, 0]
+        );
+    }
+}
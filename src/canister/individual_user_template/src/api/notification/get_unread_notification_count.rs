@@ -0,0 +1,26 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::error::NotificationError;
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    CANISTER_DATA,
+};
+
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can read their notifications.
+#[query]
+fn get_unread_notification_count() -> Result<u64, NotificationError> {
+    let current_caller = ic_cdk::caller();
+
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let canister_data = canister_data_ref_cell.borrow();
+
+        if canister_data.profile.principal_id != Some(current_caller) {
+            return Err(NotificationError::Unauthorized);
+        }
+
+        Ok(canister_data.notification_inbox.unread_count())
+    })
+}
@@ -0,0 +1,59 @@
+use ic_cdk_macros::update;
+use shared_utils::canister_specific::individual_user_template::types::{
+    error::NotificationError, notification::NotificationId,
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    util::cycles::notify_to_recharge_canister, CANISTER_DATA,
+};
+
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can update their notifications.
+#[update]
+fn mark_notifications_as_read(
+    notification_ids: Vec<NotificationId>,
+) -> Result<(), NotificationError> {
+    notify_to_recharge_canister();
+
+    let current_caller = ic_cdk::caller();
+
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        if canister_data.profile.principal_id != Some(current_caller) {
+            return Err(NotificationError::Unauthorized);
+        }
+
+        canister_data
+            .notification_inbox
+            .mark_as_read(&notification_ids);
+
+        Ok(())
+    })
+}
+
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can update their notifications.
+#[update]
+fn mark_all_notifications_as_read() -> Result<(), NotificationError> {
+    notify_to_recharge_canister();
+
+    let current_caller = ic_cdk::caller();
+
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        if canister_data.profile.principal_id != Some(current_caller) {
+            return Err(NotificationError::Unauthorized);
+        }
+
+        canister_data.notification_inbox.mark_all_as_read();
+
+        Ok(())
+    })
+}
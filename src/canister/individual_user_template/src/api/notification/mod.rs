@@ -0,0 +1,5 @@
+pub mod get_notifications_with_pagination;
+pub mod get_unread_notification_count;
+pub mod mark_notifications_as_read;
+pub mod receive_post_comment_notification;
+pub mod receive_token_airdrop_notification;
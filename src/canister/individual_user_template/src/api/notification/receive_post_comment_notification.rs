@@ -0,0 +1,129 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        error::NotificationError, notification::NotificationType,
+    },
+    common::{
+        types::app_primitive_type::PostId,
+        utils::{permissions::is_caller_controller_or_global_admin, system_time},
+    },
+};
+
+use crate::{data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+/// Records a comment on a post of this profile. Comments are kept off-chain, by the service that
+/// reports them here.
+///
+/// # Access Control
+/// Only the controllers of this canister and the global admin can call this.
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn receive_post_comment_notification(
+    post_id: PostId,
+    commented_by_principal_id: Principal,
+) -> Result<(), NotificationError> {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        receive_post_comment_notification_impl(
+            canister_data,
+            post_id,
+            commented_by_principal_id,
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+fn receive_post_comment_notification_impl(
+    canister_data: &mut CanisterData,
+    post_id: PostId,
+    commented_by_principal_id: Principal,
+    current_time: SystemTime,
+) -> Result<(), NotificationError> {
+    if !canister_data.all_created_posts.contains_key(&post_id) {
+        return Err(NotificationError::PostNotFound);
+    }
+
+    if canister_data.profile.principal_id != Some(commented_by_principal_id) {
+        canister_data.notification_inbox.add_notification(
+            NotificationType::PostCommented {
+                post_id,
+                commented_by_principal_id,
+            },
+            current_time,
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::canister_specific::individual_user_template::types::post::{
+        Post, PostDetailsFromFrontend,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_receive_post_comment_notification_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+
+        let result = receive_post_comment_notification_impl(
+            &mut canister_data,
+            0,
+            get_mock_user_bob_principal_id(),
+            UNIX_EPOCH,
+        );
+        assert_eq!(result, Err(NotificationError::PostNotFound));
+
+        canister_data.all_created_posts.insert(
+            0,
+            Post::new(
+                0,
+                &PostDetailsFromFrontend {
+                    is_nsfw: false,
+                    description: "My first post".into(),
+                    hashtags: vec![],
+                    video_uid: "video-1".into(),
+                    creator_consent_for_inclusion_in_hot_or_not: false,
+                },
+                &UNIX_EPOCH,
+            ),
+        );
+
+        // * the creator commenting on their own post is not notified
+        receive_post_comment_notification_impl(
+            &mut canister_data,
+            0,
+            get_mock_user_alice_principal_id(),
+            UNIX_EPOCH,
+        )
+        .unwrap();
+        assert!(canister_data.notification_inbox.is_empty());
+
+        receive_post_comment_notification_impl(
+            &mut canister_data,
+            0,
+            get_mock_user_bob_principal_id(),
+            UNIX_EPOCH,
+        )
+        .unwrap();
+        let notifications = canister_data.notification_inbox.get_notifications(0, 10);
+        assert_eq!(
+            notifications[0].1.notification_type,
+            NotificationType::PostCommented {
+                post_id: 0,
+                commented_by_principal_id: get_mock_user_bob_principal_id(),
+            }
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use candid::{Nat, Principal};
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::notification::NotificationType,
+    common::utils::system_time,
+};
+
+use crate::{
+    util::{cycles::notify_to_recharge_canister, subnet_orchestrator::SubnetOrchestrator},
+    CANISTER_DATA,
+};
+
+/// Records an airdrop of a creator token that the user of this canister claimed.
+///
+/// # Access Control
+/// Only the canister of the creator, which must belong to the network, can call this.
+#[update]
+async fn receive_token_airdrop_notification(
+    token_root: Principal,
+    amount: Nat,
+    creator_principal_id: Principal,
+) {
+    notify_to_recharge_canister();
+
+    let creator_canister_id = ic_cdk::caller();
+    let Ok(subnet_orchestrator) = SubnetOrchestrator::new() else {
+        return;
+    };
+    let is_creator_canister_in_the_network = subnet_orchestrator
+        .is_user_canister_in_the_network(creator_principal_id, creator_canister_id)
+        .await
+        .unwrap_or(false);
+    if !is_creator_canister_in_the_network {
+        return;
+    }
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.notification_inbox.add_notification(
+            NotificationType::TokenAirdropReceived {
+                token_root,
+                creator_canister_id,
+                amount,
+            },
+            system_time::get_current_system_time_from_ic(),
+        );
+    });
+}
@@ -1,4 +1,5 @@
 use ic_cdk_macros::update;
+use shared_utils::common::utils::system_time;
 
 use crate::{
     api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
@@ -24,10 +25,19 @@ fn update_post_toggle_like_status_by_caller(id: u64) -> bool {
 
         let updated_like_status = post_to_update.toggle_like_status(&caller_id);
 
-        canister_data_ref_cell
-            .borrow_mut()
-            .all_created_posts
-            .insert(id, post_to_update);
+        let mut canister_data = canister_data_ref_cell.borrow_mut();
+
+        canister_data.all_created_posts.insert(id, post_to_update);
+
+        if updated_like_status && canister_data.profile.principal_id != Some(caller_id) {
+            canister_data
+                .notification_inbox
+                .add_post_liked_notification(
+                    id,
+                    caller_id,
+                    system_time::get_current_system_time_from_ic(),
+                );
+        }
 
         updated_like_status
     });
@@ -0,0 +1,31 @@
+use crate::{data_model::CanisterData, CANISTER_DATA};
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::profile::UserProfileDetailsForFrontendV3;
+
+#[query]
+fn get_profile_details_v3() -> UserProfileDetailsForFrontendV3 {
+    CANISTER_DATA.with_borrow(get_profile_details_v3_impl)
+}
+
+pub(crate) fn get_profile_details_v3_impl(
+    canister_data: &CanisterData,
+) -> UserProfileDetailsForFrontendV3 {
+    let profile = canister_data.profile.clone();
+
+    UserProfileDetailsForFrontendV3 {
+        principal_id: profile.principal_id.unwrap(),
+        display_name: profile.display_name,
+        unique_user_name: profile.unique_user_name,
+        profile_picture_url: profile.profile_picture_url,
+        profile_stats: profile.profile_stats,
+        followers_count: canister_data.follow_data.follower.len() as u64,
+        following_count: canister_data.follow_data.following.len() as u64,
+        lifetime_earnings: canister_data.my_token_balance.lifetime_earnings,
+        referrer_details: profile.referrer_details,
+        migration_info: canister_data.migration_info,
+        bio: profile.bio,
+        external_links: profile.external_links,
+        location: profile.location,
+        banner_picture_url: profile.banner_picture_url,
+    }
+}
@@ -1,5 +1,8 @@
 pub mod get_profile_details;
 pub mod get_profile_details_v2;
+pub mod get_profile_details_v3;
+pub mod update_profile_change_unique_username;
+pub mod update_profile_details_v2;
 pub mod update_profile_display_details;
 pub mod update_profile_set_unique_username_once;
 pub mod update_referrer_details;
@@ -0,0 +1,324 @@
+use std::time::SystemTime;
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA,
+};
+use candid::Principal;
+use ic_cdk::api::call;
+use ic_cdk_macros::{query, update};
+use shared_utils::{
+    canister_specific::individual_user_template::types::profile::UniqueUserNameHistoryEntry,
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::{
+            system_time::get_current_system_time_from_ic,
+            validation::{normalize_unique_user_name, validate_unique_user_name},
+        },
+    },
+    constant::MAX_UNIQUE_USER_NAME_HISTORY_ENTRIES,
+    types::canister_specific::{
+        individual_user_template::error_types::UpdateProfileChangeUniqueUsernameError,
+        user_index::error_types::ChangeUniqueUsernameError,
+    },
+};
+
+const MAX_USER_INDEX_REVERT_ATTEMPTS: usize = 3;
+
+/// Renames the profile. The user index swaps the names and keeps the old one reserved for a
+/// cooldown. If the profile changed while the index was being updated, the index change is
+/// reverted so that both sides keep agreeing on the current name.
+///
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can update their details.
+#[update]
+async fn update_profile_change_unique_username(
+    new_unique_username: String,
+) -> Result<(), UpdateProfileChangeUniqueUsernameError> {
+    notify_to_recharge_canister();
+    // * access control
+    let current_caller = ic_cdk::caller();
+    let my_principal_id = CANISTER_DATA
+        .with(|canister_data_ref_cell| canister_data_ref_cell.borrow().profile.principal_id)
+        .unwrap();
+
+    if current_caller != my_principal_id {
+        return Err(UpdateProfileChangeUniqueUsernameError::NotAuthorized);
+    }
+
+    update_last_canister_functionality_access_time();
+
+    let new_unique_username = normalize_unique_user_name(&new_unique_username);
+    let old_unique_username = CANISTER_DATA.with(|canister_data_ref_cell| {
+        validate_unique_username_change(&canister_data_ref_cell.borrow(), &new_unique_username)
+    })?;
+
+    let user_index_canister_principal_id = CANISTER_DATA.with(|canister_data_ref_cell| {
+        canister_data_ref_cell
+            .borrow()
+            .known_principal_ids
+            .get(&KnownPrincipalType::CanisterIdUserIndex)
+            .cloned()
+            .unwrap()
+    });
+
+    // * cross canister call
+    let (response,): (Result<(), ChangeUniqueUsernameError>,) = call::call(
+        user_index_canister_principal_id,
+        "update_index_with_changed_unique_user_name_for_user_principal_id",
+        (
+            old_unique_username.clone(),
+            new_unique_username.clone(),
+            current_caller,
+        ),
+    )
+    .await
+    .map_err(|_| UpdateProfileChangeUniqueUsernameError::UserIndexCrossCanisterCallFailed)?;
+
+    response.map_err(map_change_unique_username_error)?;
+
+    let applied = CANISTER_DATA.with(|canister_data_ref_cell| {
+        apply_unique_username_change(
+            &mut canister_data_ref_cell.borrow_mut(),
+            &old_unique_username,
+            new_unique_username.clone(),
+            get_current_system_time_from_ic(),
+        )
+    });
+
+    if !applied {
+        revert_unique_username_change_in_user_index(
+            user_index_canister_principal_id,
+            old_unique_username,
+            new_unique_username,
+            current_caller,
+        )
+        .await?;
+
+        return Err(UpdateProfileChangeUniqueUsernameError::ProfileChangedDuringUpdate);
+    }
+
+    Ok(())
+}
+
+/// The user index treats an already reverted change as reverted, so failed calls are retried.
+async fn revert_unique_username_change_in_user_index(
+    user_index_canister_principal_id: Principal,
+    old_unique_username: String,
+    new_unique_username: String,
+    user_principal_id: Principal,
+) -> Result<(), UpdateProfileChangeUniqueUsernameError> {
+    for _ in 0..MAX_USER_INDEX_REVERT_ATTEMPTS {
+        let revert_result: call::CallResult<(Result<(), ChangeUniqueUsernameError>,)> = call::call(
+            user_index_canister_principal_id,
+            "revert_unique_user_name_change_for_user_principal_id",
+            (
+                old_unique_username.clone(),
+                new_unique_username.clone(),
+                user_principal_id,
+            ),
+        )
+        .await;
+
+        match revert_result {
+            Ok((Ok(()),)) => return Ok(()),
+            Ok((Err(e),)) => {
+                ic_cdk::println!("Error reverting unique username change {:?}", e);
+                break;
+            }
+            Err(e) => ic_cdk::println!("Error reverting unique username change {:?}", e),
+        }
+    }
+
+    Err(UpdateProfileChangeUniqueUsernameError::UserIndexRevertFailed)
+}
+
+#[query]
+fn get_unique_username_history() -> Vec<UniqueUserNameHistoryEntry> {
+    CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.profile.unique_user_name_history.clone())
+}
+
+/// Returns the current unique username that is being replaced.
+fn validate_unique_username_change(
+    canister_data: &CanisterData,
+    new_unique_username: &str,
+) -> Result<String, UpdateProfileChangeUniqueUsernameError> {
+    let old_unique_username = canister_data
+        .profile
+        .unique_user_name
+        .clone()
+        .ok_or(UpdateProfileChangeUniqueUsernameError::NoUniqueUsernameSet)?;
+
+    validate_unique_user_name(new_unique_username)
+        .map_err(UpdateProfileChangeUniqueUsernameError::InvalidUsername)?;
+
+    if normalize_unique_user_name(&old_unique_username) == new_unique_username {
+        return Err(UpdateProfileChangeUniqueUsernameError::UsernameUnchanged);
+    }
+
+    Ok(old_unique_username)
+}
+
+/// Returns false without touching the profile if its username is no longer `old_unique_username`.
+fn apply_unique_username_change(
+    canister_data: &mut CanisterData,
+    old_unique_username: &str,
+    new_unique_username: String,
+    changed_at: SystemTime,
+) -> bool {
+    let profile = &mut canister_data.profile;
+
+    if profile.unique_user_name.as_deref() != Some(old_unique_username) {
+        return false;
+    }
+
+    profile
+        .unique_user_name_history
+        .push(UniqueUserNameHistoryEntry {
+            unique_user_name: old_unique_username.to_string(),
+            changed_at,
+        });
+
+    if profile.unique_user_name_history.len() > MAX_UNIQUE_USER_NAME_HISTORY_ENTRIES {
+        profile.unique_user_name_history.remove(0);
+    }
+
+    profile.unique_user_name = Some(new_unique_username);
+
+    true
+}
+
+fn map_change_unique_username_error(
+    error: ChangeUniqueUsernameError,
+) -> UpdateProfileChangeUniqueUsernameError {
+    match error {
+        ChangeUniqueUsernameError::InvalidUsername(e) => {
+            UpdateProfileChangeUniqueUsernameError::InvalidUsername(e)
+        }
+        ChangeUniqueUsernameError::UsernameUnchanged => {
+            UpdateProfileChangeUniqueUsernameError::UsernameUnchanged
+        }
+        ChangeUniqueUsernameError::UsernameAlreadyTaken => {
+            UpdateProfileChangeUniqueUsernameError::UsernameAlreadyTaken
+        }
+        ChangeUniqueUsernameError::UsernameInCooldown => {
+            UpdateProfileChangeUniqueUsernameError::UsernameInCooldown
+        }
+        ChangeUniqueUsernameError::UsernameNotOwnedByUser => {
+            UpdateProfileChangeUniqueUsernameError::UsernameNotOwnedByUser
+        }
+        ChangeUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId => {
+            UpdateProfileChangeUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId
+        }
+        ChangeUniqueUsernameError::UserCanisterEntryDoesNotExist => {
+            UpdateProfileChangeUniqueUsernameError::UserCanisterEntryDoesNotExist
+        }
+        ChangeUniqueUsernameError::GlobalUsernameRegistryUnavailable => {
+            UpdateProfileChangeUniqueUsernameError::GlobalUsernameRegistryUnavailable
+        }
+        ChangeUniqueUsernameError::GlobalUsernameRegistryRejected(e) => {
+            UpdateProfileChangeUniqueUsernameError::GlobalUsernameRegistryRejected(e)
+        }
+        ChangeUniqueUsernameError::GlobalUsernameRegistryRevertPending => {
+            UpdateProfileChangeUniqueUsernameError::GlobalUsernameRegistryRevertPending
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::types::canister_specific::user_index::error_types::UsernameValidationError;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_unique_username_change() {
+        let mut canister_data = CanisterData::default();
+
+        assert_eq!(
+            validate_unique_username_change(&canister_data, "cooler_alice"),
+            Err(UpdateProfileChangeUniqueUsernameError::NoUniqueUsernameSet)
+        );
+
+        canister_data.profile.unique_user_name = Some("cool_alice".to_string());
+
+        assert_eq!(
+            validate_unique_username_change(&canister_data, "root"),
+            Err(UpdateProfileChangeUniqueUsernameError::InvalidUsername(
+                UsernameValidationError::Reserved
+            ))
+        );
+        assert_eq!(
+            validate_unique_username_change(&canister_data, "cool_alice"),
+            Err(UpdateProfileChangeUniqueUsernameError::UsernameUnchanged)
+        );
+        assert_eq!(
+            validate_unique_username_change(&canister_data, "cooler_alice"),
+            Ok("cool_alice".to_string())
+        );
+
+        // * names stored before they were normalized match their lowercase form
+        canister_data.profile.unique_user_name = Some("Cool_Alice".to_string());
+        assert_eq!(
+            validate_unique_username_change(&canister_data, "cool_alice"),
+            Err(UpdateProfileChangeUniqueUsernameError::UsernameUnchanged)
+        );
+    }
+
+    #[test]
+    fn test_apply_unique_username_change() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.unique_user_name = Some("cool_alice".to_string());
+
+        assert!(!apply_unique_username_change(
+            &mut canister_data,
+            "someone_else",
+            "cooler_alice".to_string(),
+            UNIX_EPOCH,
+        ));
+        assert_eq!(
+            canister_data.profile.unique_user_name,
+            Some("cool_alice".to_string())
+        );
+
+        assert!(apply_unique_username_change(
+            &mut canister_data,
+            "cool_alice",
+            "cooler_alice".to_string(),
+            UNIX_EPOCH,
+        ));
+        assert_eq!(
+            canister_data.profile.unique_user_name,
+            Some("cooler_alice".to_string())
+        );
+        assert_eq!(
+            canister_data.profile.unique_user_name_history,
+            vec![UniqueUserNameHistoryEntry {
+                unique_user_name: "cool_alice".to_string(),
+                changed_at: UNIX_EPOCH,
+            }]
+        );
+
+        (0..MAX_UNIQUE_USER_NAME_HISTORY_ENTRIES).for_each(|i| {
+            let old_unique_username = canister_data.profile.unique_user_name.clone().unwrap();
+            apply_unique_username_change(
+                &mut canister_data,
+                &old_unique_username,
+                format!("alice_{}", i),
+                UNIX_EPOCH,
+            );
+        });
+
+        assert_eq!(
+            canister_data.profile.unique_user_name_history.len(),
+            MAX_UNIQUE_USER_NAME_HISTORY_ENTRIES
+        );
+        assert_eq!(
+            canister_data.profile.unique_user_name_history[0].unique_user_name,
+            "cooler_alice"
+        );
+    }
+}
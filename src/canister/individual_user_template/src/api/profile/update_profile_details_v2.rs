@@ -0,0 +1,158 @@
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA,
+};
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::profile::{
+        UserProfileDetailsForFrontendV3, UserProfileUpdateDetailsFromFrontendV2,
+    },
+    common::utils::validation::{
+        validate_bio, validate_display_name, validate_external_links, validate_location,
+        validate_url,
+    },
+    types::canister_specific::individual_user_template::error_types::ProfileFieldValidationError,
+};
+
+use super::{
+    get_profile_details_v3::get_profile_details_v3_impl,
+    update_profile_display_details::UpdateProfileDetailsError,
+};
+
+/// Replaces all editable profile fields at once. Fields passed as `None` or empty are cleared.
+///
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can update their details.
+#[update]
+fn update_profile_details_v2(
+    user_profile_details: UserProfileUpdateDetailsFromFrontendV2,
+) -> Result<UserProfileDetailsForFrontendV3, UpdateProfileDetailsError> {
+    notify_to_recharge_canister();
+
+    let current_caller = ic_cdk::caller();
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        update_profile_details_v2_impl(canister_data, &current_caller, user_profile_details)
+    })?;
+
+    update_last_canister_functionality_access_time();
+
+    Ok(CANISTER_DATA.with_borrow(get_profile_details_v3_impl))
+}
+
+fn validate_profile_update_details(
+    user_profile_details: &UserProfileUpdateDetailsFromFrontendV2,
+) -> Result<(), ProfileFieldValidationError> {
+    if let Some(display_name) = user_profile_details.display_name.as_deref() {
+        validate_display_name(display_name)?;
+    }
+    if let Some(profile_picture_url) = user_profile_details.profile_picture_url.as_deref() {
+        validate_url(profile_picture_url)?;
+    }
+    if let Some(bio) = user_profile_details.bio.as_deref() {
+        validate_bio(bio)?;
+    }
+    if let Some(location) = user_profile_details.location.as_deref() {
+        validate_location(location)?;
+    }
+    if let Some(banner_picture_url) = user_profile_details.banner_picture_url.as_deref() {
+        validate_url(banner_picture_url)?;
+    }
+
+    validate_external_links(&user_profile_details.external_links)
+}
+
+fn update_profile_details_v2_impl(
+    canister_data: &mut CanisterData,
+    current_caller: &Principal,
+    user_profile_details: UserProfileUpdateDetailsFromFrontendV2,
+) -> Result<(), UpdateProfileDetailsError> {
+    if canister_data.profile.principal_id != Some(*current_caller) {
+        return Err(UpdateProfileDetailsError::NotAuthorized);
+    }
+
+    validate_profile_update_details(&user_profile_details)
+        .map_err(UpdateProfileDetailsError::InvalidProfileField)?;
+
+    let profile = &mut canister_data.profile;
+
+    profile.display_name = user_profile_details.display_name;
+    profile.profile_picture_url = user_profile_details.profile_picture_url;
+    profile.bio = user_profile_details.bio;
+    profile.external_links = user_profile_details.external_links;
+    profile.location = user_profile_details.location;
+    profile.banner_picture_url = user_profile_details.banner_picture_url;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::common::utils::validation::BIO_MAX_LENGTH;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    fn profile_update_details() -> UserProfileUpdateDetailsFromFrontendV2 {
+        UserProfileUpdateDetailsFromFrontendV2 {
+            display_name: Some("Alice".to_string()),
+            profile_picture_url: Some("https://yral.com/alice.png".to_string()),
+            bio: Some("hello".to_string()),
+            external_links: vec!["https://x.com/alice".to_string()],
+            location: Some("Mumbai".to_string()),
+            banner_picture_url: None,
+        }
+    }
+
+    #[test]
+    fn test_update_profile_details_v2_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+
+        assert_eq!(
+            update_profile_details_v2_impl(
+                &mut canister_data,
+                &get_mock_user_bob_principal_id(),
+                profile_update_details()
+            ),
+            Err(UpdateProfileDetailsError::NotAuthorized)
+        );
+
+        let mut invalid_profile_update_details = profile_update_details();
+        invalid_profile_update_details.bio = Some("a".repeat(BIO_MAX_LENGTH + 1));
+
+        assert_eq!(
+            update_profile_details_v2_impl(
+                &mut canister_data,
+                &get_mock_user_alice_principal_id(),
+                invalid_profile_update_details
+            ),
+            Err(UpdateProfileDetailsError::InvalidProfileField(
+                ProfileFieldValidationError::BioTooLong {
+                    max_length: BIO_MAX_LENGTH as u64
+                }
+            ))
+        );
+        assert_eq!(canister_data.profile.bio, None);
+
+        assert_eq!(
+            update_profile_details_v2_impl(
+                &mut canister_data,
+                &get_mock_user_alice_principal_id(),
+                profile_update_details()
+            ),
+            Ok(())
+        );
+
+        let profile_details = get_profile_details_v3_impl(&canister_data);
+        assert_eq!(profile_details.bio, Some("hello".to_string()));
+        assert_eq!(
+            profile_details.external_links,
+            vec!["https://x.com/alice".to_string()]
+        );
+        assert_eq!(profile_details.location, Some("Mumbai".to_string()));
+    }
+}
@@ -4,13 +4,18 @@ use crate::{
 };
 use candid::CandidType;
 use ic_cdk_macros::update;
-use shared_utils::canister_specific::individual_user_template::types::profile::{
-    UserProfileDetailsForFrontend, UserProfileUpdateDetailsFromFrontend,
+use shared_utils::{
+    canister_specific::individual_user_template::types::profile::{
+        UserProfileDetailsForFrontend, UserProfileUpdateDetailsFromFrontend,
+    },
+    common::utils::validation::{validate_display_name, validate_url},
+    types::canister_specific::individual_user_template::error_types::ProfileFieldValidationError,
 };
 
-#[derive(CandidType)]
+#[derive(CandidType, Debug, PartialEq, Eq)]
 pub enum UpdateProfileDetailsError {
     NotAuthorized,
+    InvalidProfileField(ProfileFieldValidationError),
 }
 
 /// # Access Control
@@ -32,6 +37,15 @@ fn update_profile_display_details(
 
     update_last_canister_functionality_access_time();
 
+    if let Some(display_name) = user_profile_details.display_name.as_deref() {
+        validate_display_name(display_name)
+            .map_err(UpdateProfileDetailsError::InvalidProfileField)?;
+    }
+    if let Some(profile_picture_url) = user_profile_details.profile_picture_url.as_deref() {
+        validate_url(profile_picture_url)
+            .map_err(UpdateProfileDetailsError::InvalidProfileField)?;
+    }
+
     CANISTER_DATA.with(|canister_data_ref_cell| {
         let profile = &mut canister_data_ref_cell.borrow_mut().profile;
 
@@ -5,7 +5,10 @@ use crate::{
 use ic_cdk::api::call;
 use ic_cdk_macros::update;
 use shared_utils::{
-    common::types::known_principal::KnownPrincipalType,
+    common::{
+        types::known_principal::KnownPrincipalType,
+        utils::validation::{normalize_unique_user_name, validate_unique_user_name},
+    },
     types::canister_specific::{
         individual_user_template::error_types::UpdateProfileSetUniqueUsernameError,
         user_index::error_types::SetUniqueUsernameError,
@@ -31,6 +34,10 @@ async fn update_profile_set_unique_username_once(
 
     update_last_canister_functionality_access_time();
 
+    let new_unique_username = normalize_unique_user_name(&new_unique_username);
+    validate_unique_user_name(&new_unique_username)
+        .map_err(UpdateProfileSetUniqueUsernameError::InvalidUsername)?;
+
     let user_index_canister_principal_id = CANISTER_DATA.with(|canister_data_ref_cell| {
         canister_data_ref_cell
             .borrow()
@@ -67,5 +74,17 @@ async fn update_profile_set_unique_username_once(
         Err(SetUniqueUsernameError::UserCanisterEntryDoesNotExist) => {
             Err(UpdateProfileSetUniqueUsernameError::UserCanisterEntryDoesNotExist)
         }
+        Err(SetUniqueUsernameError::InvalidUsername(e)) => {
+            Err(UpdateProfileSetUniqueUsernameError::InvalidUsername(e))
+        }
+        Err(SetUniqueUsernameError::GlobalUsernameRegistryUnavailable) => {
+            Err(UpdateProfileSetUniqueUsernameError::GlobalUsernameRegistryUnavailable)
+        }
+        Err(SetUniqueUsernameError::GlobalUsernameRegistryRejected(e)) => {
+            Err(UpdateProfileSetUniqueUsernameError::GlobalUsernameRegistryRejected(e))
+        }
+        Err(SetUniqueUsernameError::GlobalUsernameRegistryRevertPending) => {
+            Err(UpdateProfileSetUniqueUsernameError::GlobalUsernameRegistryRevertPending)
+        }
     }
 }
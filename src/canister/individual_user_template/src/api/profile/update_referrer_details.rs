@@ -7,7 +7,8 @@ use shared_utils::canister_specific::individual_user_template::types::{
 
 use crate::{
     api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
-    util::cycles::notify_to_recharge_canister, CANISTER_DATA,
+    util::{cycles::notify_to_recharge_canister, referral::claim_referral_rewards_if_eligible},
+    CANISTER_DATA,
 };
 
 #[update]
@@ -36,11 +37,15 @@ async fn update_referrer_details(referrer: UserCanisterDetails) -> Result<String
         let profile = &mut canister_data.profile;
 
         if profile.referrer_details.is_some() {
-            return Err("Referrer is already set".into());
+            return Err(String::from("Referrer is already set"));
         }
 
         profile.referrer_details = Some(referrer);
 
-        Ok("Success".into())
-    })
+        Ok(())
+    })?;
+
+    claim_referral_rewards_if_eligible();
+
+    Ok("Success".into())
 }
@@ -0,0 +1,11 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::referral::ReferralStatus;
+
+use crate::CANISTER_DATA;
+
+/// Status of the reward for being referred. `None` until the user is registered with a
+/// referrer.
+#[query]
+fn get_my_referral_status() -> Option<ReferralStatus> {
+    CANISTER_DATA.with_borrow(|canister_data| canister_data.referrals.get_my_referral_status())
+}
@@ -0,0 +1,7 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::referral::get_referral_code_for_user_canister;
+
+#[query]
+fn get_referral_code() -> String {
+    get_referral_code_for_user_canister(ic_cdk::id())
+}
@@ -0,0 +1,9 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::referral::ReferralConfig;
+
+use crate::CANISTER_DATA;
+
+#[query]
+fn get_referral_config() -> ReferralConfig {
+    CANISTER_DATA.with_borrow(|canister_data| canister_data.referrals.get_config())
+}
@@ -0,0 +1,31 @@
+use ic_cdk_macros::query;
+use shared_utils::{
+    canister_specific::individual_user_template::types::referral::ReferralDetails,
+    pagination::{self, PaginationError},
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    CANISTER_DATA,
+};
+
+/// Lists the users this user referred along with the status of each referral.
+#[query]
+fn get_referrals_with_pagination(
+    from_inclusive_index: u64,
+    to_exclusive_index: u64,
+) -> Result<Vec<ReferralDetails>, PaginationError> {
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with_borrow(|canister_data| {
+        let (from_inclusive_index, to_exclusive_index) = pagination::get_pagination_bounds(
+            from_inclusive_index,
+            to_exclusive_index,
+            canister_data.referrals.len(),
+        )?;
+
+        Ok(canister_data
+            .referrals
+            .get_referrals(from_inclusive_index, to_exclusive_index))
+    })
+}
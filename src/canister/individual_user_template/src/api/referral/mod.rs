@@ -0,0 +1,7 @@
+pub mod get_my_referral_status;
+pub mod get_referral_code;
+pub mod get_referral_config;
+pub mod get_referrals_with_pagination;
+pub mod receive_referral_from_referee_canister;
+pub mod update_referral_config;
+pub mod update_referrer_details_with_referral_code;
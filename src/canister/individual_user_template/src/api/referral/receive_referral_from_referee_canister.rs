@@ -0,0 +1,46 @@
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::referral::ReferralError,
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{
+    util::{
+        cycles::notify_to_recharge_canister,
+        referral::{evaluate_and_record_referral, get_user_canister_id_from_user_index},
+    },
+    CANISTER_DATA,
+};
+
+/// Called by the referee's canister once the referee registers. Rewards this user as the
+/// referrer unless the referral is rejected. A referral that was already decided gets the same
+/// answer again.
+///
+/// # Access Control
+/// The calling canister must be the canister the user index has on record for
+/// `referee_principal_id`.
+#[update]
+async fn receive_referral_from_referee_canister(
+    referee_principal_id: Principal,
+    referee_device_ids: Vec<String>,
+) -> Result<(), ReferralError> {
+    notify_to_recharge_canister();
+
+    let referee_canister_id = ic_cdk::caller();
+
+    let sender_canister_id = get_user_canister_id_from_user_index(referee_principal_id).await?;
+    if sender_canister_id != Some(referee_canister_id) {
+        return Err(ReferralError::SenderIsNotAUserCanisterOnNetwork);
+    }
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        evaluate_and_record_referral(
+            canister_data,
+            referee_principal_id,
+            referee_canister_id,
+            referee_device_ids.into_iter().collect(),
+            get_current_system_time_from_ic(),
+        )
+    })
+}
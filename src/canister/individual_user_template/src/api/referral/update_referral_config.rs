@@ -0,0 +1,15 @@
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::referral::ReferralConfig,
+    common::utils::permissions::is_caller_controller_or_global_admin,
+};
+
+use crate::{util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn update_referral_config(referral_config: ReferralConfig) {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA
+        .with_borrow_mut(|canister_data| canister_data.referrals.set_config(referral_config));
+}
@@ -0,0 +1,87 @@
+use candid::Principal;
+use ic_cdk::{call, caller};
+use ic_cdk_macros::update;
+use shared_utils::canister_specific::individual_user_template::types::{
+    profile::{UserCanisterDetails, UserProfileDetailsForFrontend},
+    referral::{get_user_canister_id_from_referral_code, ReferralError, ReferralRejectionReason},
+    session::SessionType,
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    util::{cycles::notify_to_recharge_canister, referral::claim_referral_rewards_if_eligible},
+    CANISTER_DATA,
+};
+
+/// Sets the owner of the referral code as the referrer of this user. The referral rewards are
+/// claimed once this user is registered.
+#[update]
+async fn update_referrer_details_with_referral_code(
+    referral_code: String,
+) -> Result<UserCanisterDetails, ReferralError> {
+    notify_to_recharge_canister();
+
+    let profile_owner =
+        CANISTER_DATA.with_borrow(|canister_data| canister_data.profile.principal_id);
+    if profile_owner != Some(caller()) {
+        return Err(ReferralError::Unauthorized);
+    }
+
+    update_last_canister_functionality_access_time();
+
+    let referrer_canister_id = get_user_canister_id_from_referral_code(&referral_code)
+        .ok_or(ReferralError::InvalidReferralCode)?;
+
+    if referrer_canister_id == ic_cdk::id() {
+        return Err(ReferralError::Rejected(
+            ReferralRejectionReason::SelfReferral,
+        ));
+    }
+
+    let referrer_already_set =
+        CANISTER_DATA.with_borrow(|canister_data| canister_data.profile.referrer_details.is_some());
+    if referrer_already_set {
+        return Err(ReferralError::ReferrerAlreadySet);
+    }
+
+    let referrer = get_referrer_details(referrer_canister_id).await?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let profile = &mut canister_data.profile;
+
+        if profile.referrer_details.is_some() {
+            return Err(ReferralError::ReferrerAlreadySet);
+        }
+
+        profile.referrer_details = Some(referrer.clone());
+
+        Ok(())
+    })?;
+
+    claim_referral_rewards_if_eligible();
+
+    Ok(referrer)
+}
+
+async fn get_referrer_details(
+    referrer_canister_id: Principal,
+) -> Result<UserCanisterDetails, ReferralError> {
+    let (referrer_session_type_result,): (Result<SessionType, String>,) =
+        call(referrer_canister_id, "get_session_type", ())
+            .await
+            .map_err(|e| ReferralError::ReferrerCrossCanisterCallFailed(e.1))?;
+
+    if referrer_session_type_result != Ok(SessionType::RegisteredSession) {
+        return Err(ReferralError::ReferrerNotSignedUp);
+    }
+
+    let (referrer_profile,): (UserProfileDetailsForFrontend,) =
+        call(referrer_canister_id, "get_profile_details", ())
+            .await
+            .map_err(|e| ReferralError::ReferrerCrossCanisterCallFailed(e.1))?;
+
+    Ok(UserCanisterDetails {
+        profile_owner: referrer_profile.principal_id,
+        user_canister_id: referrer_canister_id,
+    })
+}
@@ -38,7 +38,8 @@ use shared_utils::{
 
 use crate::data_model::_default_room_details;
 use crate::data_model::{
-    CanisterData, _default_bet_details, _default_post_principal_map, _default_slot_details_map,
+    _default_bet_details, _default_post_principal_map, _default_slot_details_map,
+    _default_utility_token_transaction_history, CanisterData,
 };
 
 pub mod get_snapshot;
@@ -182,9 +183,9 @@ impl From<&CanisterData> for CanisterDataForSnapshot {
         let my_token_balance = TokenBalanceForSnapshot {
             utility_token_balance: canister_data.my_token_balance.utility_token_balance,
             utility_token_transaction_history: canister_data
-                .my_token_balance
                 .utility_token_transaction_history
-                .clone(),
+                .iter()
+                .collect(),
             lifetime_earnings: canister_data.my_token_balance.lifetime_earnings,
         };
 
@@ -300,13 +301,19 @@ impl From<CanisterDataForSnapshot> for CanisterData {
 
         let my_token_balance = TokenBalance {
             utility_token_balance: canister_data.my_token_balance.utility_token_balance,
-            utility_token_transaction_history: canister_data
-                .my_token_balance
-                .utility_token_transaction_history
-                .clone(),
+            utility_token_transaction_history: BTreeMap::new(),
             lifetime_earnings: canister_data.my_token_balance.lifetime_earnings,
         };
 
+        let mut utility_token_transaction_history = _default_utility_token_transaction_history();
+        canister_data
+            .my_token_balance
+            .utility_token_transaction_history
+            .iter()
+            .for_each(|(k, v)| {
+                utility_token_transaction_history.insert(*k, v.clone());
+            });
+
         let follow_data = FollowData {
             follower: FollowList {
                 sorted_index: canister_data.follow_data.follower.sorted_index.clone(),
@@ -362,6 +369,7 @@ impl From<CanisterDataForSnapshot> for CanisterData {
             last_canister_functionality_access_time: canister_data
                 .last_canister_functionality_access_time,
             migration_info: canister_data.migration_info,
+            utility_token_transaction_history,
             ..Default::default()
         }
     }
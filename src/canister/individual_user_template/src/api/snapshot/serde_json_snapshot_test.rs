@@ -224,6 +224,11 @@ mod test {
                     not_bets_received: 100,
                 },
                 referrer_details: None,
+                unique_user_name_history: vec![],
+                bio: Some("dadfk".to_string()),
+                external_links: vec!["https://dadfk.com".to_string()],
+                location: None,
+                banner_picture_url: None,
             },
             version_details: VersionDetails {
                 version_number: 1,
@@ -242,6 +247,7 @@ mod test {
             serde_json::from_str(serde_str.unwrap().as_str()).unwrap();
 
         let canister_data = CanisterData::from(canister_data_snapshot);
+        assert_eq!(canister_data.utility_token_transaction_history.len(), 1);
 
         // println!("canister_data: {:?}", canister_data.all_created_posts);
     }
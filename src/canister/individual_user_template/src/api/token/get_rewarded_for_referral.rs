@@ -1,19 +1,26 @@
+use std::{collections::BTreeSet, time::SystemTime};
+
 use crate::{
     api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
-    util::cycles::notify_to_recharge_canister, CANISTER_DATA,
+    data_model::CanisterData,
+    util::{
+        cycles::notify_to_recharge_canister,
+        referral::{
+            credit_referral_reward, evaluate_and_record_referral,
+            get_user_canister_id_from_user_index,
+        },
+    },
+    CANISTER_DATA,
 };
 use candid::Principal;
 use ic_cdk_macros::update;
-use shared_utils::common::{
-    types::{
-        known_principal::KnownPrincipalType,
-        utility_token::token_event::{MintEvent, TokenEvent},
-    },
-    utils::system_time,
+use shared_utils::{
+    canister_specific::individual_user_template::types::referral::{ReferralError, ReferralStatus},
+    common::{types::known_principal::KnownPrincipalType, utils::system_time},
 };
 
 #[update]
-fn get_rewarded_for_referral(referrer: Principal, referree: Principal) {
+async fn get_rewarded_for_referral(referrer: Principal, referree: Principal) {
     // * access control
     notify_to_recharge_canister();
     let request_maker = ic_cdk::caller();
@@ -32,28 +39,171 @@ fn get_rewarded_for_referral(referrer: Principal, referree: Principal) {
 
     update_last_canister_functionality_access_time();
 
-    let current_time = system_time::get_current_system_time_from_ic();
-
-    CANISTER_DATA.with(|canister_data_ref_cell| {
-        let my_token_balance = &mut canister_data_ref_cell.borrow_mut().my_token_balance;
-
-        let referral_reward_amount =
-            TokenEvent::get_token_amount_for_token_event(&TokenEvent::Mint {
-                amount: 0,
-                details: MintEvent::Referral {
-                    referrer_user_principal_id: referrer,
-                    referee_user_principal_id: referree,
-                },
-                timestamp: current_time,
-            });
-
-        my_token_balance.handle_token_event(TokenEvent::Mint {
-            amount: referral_reward_amount,
-            details: MintEvent::Referral {
-                referrer_user_principal_id: referrer,
-                referee_user_principal_id: referree,
-            },
-            timestamp: current_time,
+    let is_referrer = CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.profile.principal_id == Some(referrer));
+
+    let referral_result = if is_referrer {
+        reward_referrer_for_referral(referree).await
+    } else {
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            reward_referee_for_referral(
+                canister_data,
+                referrer,
+                referree,
+                system_time::get_current_system_time_from_ic(),
+            );
         });
-    });
+        Ok(())
+    };
+
+    if let Err(e) = referral_result {
+        ic_cdk::println!("Failed to reward the referral: {:?}", e);
+    }
+}
+
+/// Goes through the same checks and records as a referral received from the referee's
+/// canister, so a referral is only ever rewarded once whichever way it comes in. The referee's
+/// devices are not known here, so the device checks have nothing to match.
+async fn reward_referrer_for_referral(referree: Principal) -> Result<(), ReferralError> {
+    let referee_canister_id = get_user_canister_id_from_user_index(referree)
+        .await?
+        .ok_or(ReferralError::SenderIsNotAUserCanisterOnNetwork)?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        evaluate_and_record_referral(
+            canister_data,
+            referree,
+            referee_canister_id,
+            BTreeSet::new(),
+            system_time::get_current_system_time_from_ic(),
+        )
+    })
+}
+
+/// The reward is taken from the referral config. A user whose referral was already claimed or
+/// decided through the referral flow is not rewarded again.
+fn reward_referee_for_referral(
+    canister_data: &mut CanisterData,
+    referrer: Principal,
+    referree: Principal,
+    current_time: SystemTime,
+) {
+    if canister_data.referrals.get_my_referral_status().is_some() {
+        return;
+    }
+
+    let referee_reward_amount = canister_data.referrals.get_config().referee_reward_amount;
+    let amount = credit_referral_reward(
+        canister_data,
+        referrer,
+        referree,
+        referee_reward_amount,
+        current_time,
+    );
+    canister_data
+        .referrals
+        .set_my_referral_status(Some(ReferralStatus::Rewarded { amount }));
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::canister_specific::individual_user_template::types::referral::{
+        ReferralConfig, ReferralRejectionReason,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_reward_referee_for_referral() {
+        let mut referee_canister_data = CanisterData::default();
+        referee_canister_data.profile.principal_id = Some(get_mock_user_bob_principal_id());
+        referee_canister_data.referrals.set_config(ReferralConfig {
+            referee_reward_amount: 200,
+            ..Default::default()
+        });
+
+        (0..2).for_each(|_| {
+            reward_referee_for_referral(
+                &mut referee_canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_bob_principal_id(),
+                UNIX_EPOCH,
+            )
+        });
+        assert_eq!(
+            referee_canister_data.my_token_balance.utility_token_balance,
+            200
+        );
+        assert_eq!(
+            referee_canister_data.referrals.get_my_referral_status(),
+            Some(ReferralStatus::Rewarded { amount: 200 })
+        );
+
+        // * a referral rejected by the referrer is not rewarded here either
+        let mut rejected_referee_canister_data = CanisterData::default();
+        rejected_referee_canister_data
+            .referrals
+            .set_my_referral_status(Some(ReferralStatus::Rejected {
+                reason: ReferralRejectionReason::ReferralRing,
+            }));
+        reward_referee_for_referral(
+            &mut rejected_referee_canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+            UNIX_EPOCH,
+        );
+        assert_eq!(
+            rejected_referee_canister_data
+                .my_token_balance
+                .utility_token_balance,
+            0
+        );
+    }
+
+    #[test]
+    fn test_referrer_is_rewarded_once_through_either_referral_path() {
+        let mut referrer_canister_data = CanisterData::default();
+        referrer_canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+        referrer_canister_data.referrals.set_config(ReferralConfig {
+            referrer_reward_amount: 300,
+            ..Default::default()
+        });
+
+        // * rewarded through the user index first
+        assert_eq!(
+            evaluate_and_record_referral(
+                &mut referrer_canister_data,
+                get_mock_user_bob_principal_id(),
+                get_mock_user_bob_canister_id(),
+                BTreeSet::new(),
+                UNIX_EPOCH,
+            ),
+            Ok(())
+        );
+        // * and then claimed by the referee's canister
+        assert_eq!(
+            evaluate_and_record_referral(
+                &mut referrer_canister_data,
+                get_mock_user_bob_principal_id(),
+                get_mock_user_bob_canister_id(),
+                BTreeSet::from(["bob-phone".to_string()]),
+                UNIX_EPOCH,
+            ),
+            Ok(())
+        );
+
+        assert_eq!(
+            referrer_canister_data
+                .my_token_balance
+                .utility_token_balance,
+            300
+        );
+        assert_eq!(referrer_canister_data.referrals.len(), 1);
+    }
 }
@@ -36,7 +36,6 @@ fn get_rewarded_for_signing_up() {
     CANISTER_DATA.with(|canister_data_ref_cell| {
         let mut canister_data_ref = canister_data_ref_cell.borrow_mut();
         let my_principal_id = canister_data_ref.profile.principal_id.unwrap();
-        let my_token_balance = &mut canister_data_ref.my_token_balance;
 
         let signup_reward_amount =
             TokenEvent::get_token_amount_for_token_event(&TokenEvent::Mint {
@@ -47,12 +46,16 @@ fn get_rewarded_for_signing_up() {
                 timestamp: current_time,
             });
 
-        my_token_balance.handle_token_event(TokenEvent::Mint {
+        let reward_result = canister_data_ref.apply_token_event(TokenEvent::Mint {
             amount: signup_reward_amount,
             details: MintEvent::NewUserSignup {
                 new_user_principal_id: my_principal_id,
             },
             timestamp: current_time,
         });
+
+        if let Err(e) = reward_result {
+            ic_cdk::println!("Failed to credit signup reward: {:?}", e);
+        }
     });
 }
@@ -1,14 +1,21 @@
 use crate::{
     api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
-    CANISTER_DATA,
+    data_model::CanisterData, CANISTER_DATA,
 };
 use ic_cdk_macros::query;
 use shared_utils::{
-    common::types::utility_token::token_event::TokenEvent,
+    common::types::utility_token::token_event::{
+        FilteredTokenEventPage, TokenEvent, TokenEventFilter,
+    },
+    constant::MAX_POSTS_IN_ONE_REQUEST,
     pagination::{self, PaginationError},
     types::canister_specific::individual_user_template::error_types::GetUserUtilityTokenTransactionHistoryError,
 };
 
+/// Bounds the work of one filtered page when few events match the filter.
+const MAX_TOKEN_EVENTS_SCANNED_PER_FILTERED_PAGE: usize = 1000;
+
+/// Returns events newest first.
 #[query]
 fn get_user_utility_token_transaction_history_with_pagination(
     from_inclusive_id: u64,
@@ -16,18 +23,103 @@ fn get_user_utility_token_transaction_history_with_pagination(
 ) -> Result<Vec<(u64, TokenEvent)>, GetUserUtilityTokenTransactionHistoryError> {
     update_last_canister_functionality_access_time();
 
+    CANISTER_DATA.with_borrow(|canister_data| {
+        get_user_utility_token_transaction_history_with_pagination_impl(
+            canister_data,
+            from_inclusive_id,
+            to_exclusive_id,
+        )
+    })
+}
+
+/// Returns up to `limit` events matching `filter` older than `before_exclusive_id`, newest first.
+/// Pass `None` to start from the newest event and the returned cursor to read further.
+#[query]
+fn get_user_utility_token_transaction_history_filtered(
+    before_exclusive_id: Option<u64>,
+    limit: u64,
+    filter: TokenEventFilter,
+) -> Result<FilteredTokenEventPage, GetUserUtilityTokenTransactionHistoryError> {
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with_borrow(|canister_data| {
+        get_user_utility_token_transaction_history_filtered_impl(
+            canister_data,
+            before_exclusive_id,
+            limit,
+            &filter,
+        )
+    })
+}
+
+fn get_user_utility_token_transaction_history_with_pagination_impl(
+    canister_data: &CanisterData,
+    from_inclusive_id: u64,
+    to_exclusive_id: u64,
+) -> Result<Vec<(u64, TokenEvent)>, GetUserUtilityTokenTransactionHistoryError> {
+    let utility_token_transaction_history = &canister_data.utility_token_transaction_history;
+
     let (from_inclusive_id, to_exclusive_id) = pagination::get_pagination_bounds(
         from_inclusive_id,
         to_exclusive_id,
-        CANISTER_DATA.with(|canister_data_ref_cell| {
-            canister_data_ref_cell
-                .borrow()
-                .my_token_balance
-                .utility_token_transaction_history
-                .len()
-        }) as u64,
+        utility_token_transaction_history.len(),
     )
-    .map_err(|e| match e {
+    .map_err(map_pagination_error)?;
+
+    Ok(utility_token_transaction_history
+        .iter()
+        .rev()
+        .skip(from_inclusive_id as usize)
+        .take((to_exclusive_id - from_inclusive_id) as usize)
+        .collect())
+}
+
+fn get_user_utility_token_transaction_history_filtered_impl(
+    canister_data: &CanisterData,
+    before_exclusive_id: Option<u64>,
+    limit: u64,
+    filter: &TokenEventFilter,
+) -> Result<FilteredTokenEventPage, GetUserUtilityTokenTransactionHistoryError> {
+    if limit == 0 {
+        return Err(GetUserUtilityTokenTransactionHistoryError::InvalidBoundsPassed);
+    }
+
+    if limit > MAX_POSTS_IN_ONE_REQUEST {
+        return Err(
+            GetUserUtilityTokenTransactionHistoryError::ExceededMaxNumberOfItemsAllowedInOneRequest,
+        );
+    }
+
+    let mut events = vec![];
+    let mut next_before_exclusive_id = None;
+
+    for (number_of_scanned_events, (id, token_event)) in canister_data
+        .utility_token_transaction_history
+        .range(..before_exclusive_id.unwrap_or(u64::MAX))
+        .rev()
+        .enumerate()
+    {
+        if events.len() as u64 == limit
+            || number_of_scanned_events == MAX_TOKEN_EVENTS_SCANNED_PER_FILTERED_PAGE
+        {
+            // * the next page starts with this event since it was not looked at
+            next_before_exclusive_id = Some(id + 1);
+            break;
+        }
+
+        if filter.matches(&token_event) {
+            events.push((id, token_event));
+        }
+    }
+
+    Ok(FilteredTokenEventPage {
+        events,
+        next_before_exclusive_id,
+    })
+}
+
+fn map_pagination_error(e: PaginationError) -> GetUserUtilityTokenTransactionHistoryError {
+    match e {
         PaginationError::InvalidBoundsPassed => {
             GetUserUtilityTokenTransactionHistoryError::InvalidBoundsPassed
         }
@@ -37,18 +129,149 @@ fn get_user_utility_token_transaction_history_with_pagination(
         PaginationError::ExceededMaxNumberOfItemsAllowedInOneRequest => {
             GetUserUtilityTokenTransactionHistoryError::ExceededMaxNumberOfItemsAllowedInOneRequest
         }
-    })?;
-
-    Ok(CANISTER_DATA.with(|canister_data_ref_cell| {
-        canister_data_ref_cell
-            .borrow()
-            .my_token_balance
-            .utility_token_transaction_history
-            .iter()
-            .rev()
-            .skip(from_inclusive_id as usize)
-            .take((to_exclusive_id - from_inclusive_id) as usize)
-            .map(|(time, token_event)| (*time, token_event.clone()))
-            .collect()
-    }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use shared_utils::common::types::utility_token::token_event::TokenEventType;
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    fn get_canister_data_with_receives_and_transfers() -> CanisterData {
+        let mut canister_data = CanisterData::default();
+
+        (0..2000_u64).for_each(|i| {
+            canister_data
+                .apply_token_event(TokenEvent::Receive {
+                    amount: 1,
+                    from_account: get_mock_user_alice_canister_id(),
+                    timestamp: UNIX_EPOCH + Duration::from_secs(i),
+                    memo: None,
+                })
+                .unwrap();
+            canister_data
+                .apply_token_event(TokenEvent::Transfer {
+                    amount: 1,
+                    to_account: get_mock_user_alice_canister_id(),
+                    timestamp: UNIX_EPOCH + Duration::from_secs(i),
+                    memo: None,
+                })
+                .unwrap();
+        });
+
+        canister_data
+    }
+
+    fn ids(events: &[(u64, TokenEvent)]) -> Vec<u64> {
+        events.iter().map(|(id, _)| *id).collect()
+    }
+
+    #[test]
+    fn test_get_user_utility_token_transaction_history_with_pagination_impl() {
+        let canister_data = get_canister_data_with_receives_and_transfers();
+
+        // * nothing is pruned
+        assert_eq!(canister_data.utility_token_transaction_history.len(), 4000);
+
+        let newest =
+            get_user_utility_token_transaction_history_with_pagination_impl(&canister_data, 0, 2)
+                .unwrap();
+        assert_eq!(ids(&newest), vec![4000, 3999]);
+
+        assert!(matches!(
+            get_user_utility_token_transaction_history_with_pagination_impl(
+                &canister_data,
+                4000,
+                4010
+            ),
+            Err(GetUserUtilityTokenTransactionHistoryError::ReachedEndOfItemsList)
+        ));
+    }
+
+    #[test]
+    fn test_get_user_utility_token_transaction_history_filtered_impl() {
+        let canister_data = get_canister_data_with_receives_and_transfers();
+        let receives = TokenEventFilter {
+            event_types: vec![TokenEventType::Receive],
+            ..Default::default()
+        };
+
+        let newest_receives = get_user_utility_token_transaction_history_filtered_impl(
+            &canister_data,
+            None,
+            2,
+            &receives,
+        )
+        .unwrap();
+        assert_eq!(ids(&newest_receives.events), vec![3999, 3997]);
+        assert_eq!(newest_receives.next_before_exclusive_id, Some(3997));
+
+        let next_receives = get_user_utility_token_transaction_history_filtered_impl(
+            &canister_data,
+            newest_receives.next_before_exclusive_id,
+            2,
+            &receives,
+        )
+        .unwrap();
+        assert_eq!(ids(&next_receives.events), vec![3995, 3993]);
+
+        let oldest_receives = get_user_utility_token_transaction_history_filtered_impl(
+            &canister_data,
+            Some(4),
+            10,
+            &receives,
+        )
+        .unwrap();
+        assert_eq!(ids(&oldest_receives.events), vec![3, 1]);
+        assert_eq!(oldest_receives.next_before_exclusive_id, None);
+
+        assert!(matches!(
+            get_user_utility_token_transaction_history_filtered_impl(
+                &canister_data,
+                None,
+                MAX_POSTS_IN_ONE_REQUEST + 1,
+                &receives
+            ),
+            Err(GetUserUtilityTokenTransactionHistoryError::ExceededMaxNumberOfItemsAllowedInOneRequest)
+        ));
+    }
+
+    #[test]
+    fn test_filtered_history_scans_a_bounded_number_of_events_per_page() {
+        let canister_data = get_canister_data_with_receives_and_transfers();
+        let transfers_in_range = TokenEventFilter {
+            event_types: vec![TokenEventType::Transfer],
+            from_inclusive_timestamp: Some(UNIX_EPOCH + Duration::from_secs(10)),
+            to_exclusive_timestamp: Some(UNIX_EPOCH + Duration::from_secs(12)),
+        };
+
+        let first_page = get_user_utility_token_transaction_history_filtered_impl(
+            &canister_data,
+            None,
+            10,
+            &transfers_in_range,
+        )
+        .unwrap();
+        assert!(first_page.events.is_empty());
+        assert_eq!(first_page.next_before_exclusive_id, Some(3001));
+
+        let mut matching_events = vec![];
+        let mut before_exclusive_id = first_page.next_before_exclusive_id;
+        while let Some(id) = before_exclusive_id {
+            let page = get_user_utility_token_transaction_history_filtered_impl(
+                &canister_data,
+                Some(id),
+                10,
+                &transfers_in_range,
+            )
+            .unwrap();
+            matching_events.extend(page.events);
+            before_exclusive_id = page.next_before_exclusive_id;
+        }
+        assert_eq!(ids(&matching_events), vec![24, 22]);
+    }
 }
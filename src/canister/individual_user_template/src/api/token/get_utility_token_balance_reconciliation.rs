@@ -0,0 +1,90 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::token::TokenBalanceReconciliation;
+
+use crate::CANISTER_DATA;
+
+/// Recomputes the balance from the full transaction history, reading only the events recorded
+/// after the last reconciliation checkpoint. Events pruned before the history was moved to stable
+/// memory are missing, so older profiles can show drift from those alone.
+#[query]
+fn get_utility_token_balance_reconciliation() -> TokenBalanceReconciliation {
+    CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.get_utility_token_balance_reconciliation())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::{
+        canister_specific::individual_user_template::types::token::TokenBalanceReconciliationCheckpoint,
+        common::types::utility_token::token_event::TokenEvent,
+    };
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use crate::data_model::CanisterData;
+
+    fn receive(amount: u64) -> TokenEvent {
+        TokenEvent::Receive {
+            amount,
+            from_account: get_mock_user_alice_canister_id(),
+            timestamp: UNIX_EPOCH,
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn test_get_utility_token_balance_reconciliation_reads_from_the_checkpoint() {
+        let mut canister_data = CanisterData::default();
+
+        canister_data.apply_token_event(receive(10)).unwrap();
+        canister_data.apply_token_event(receive(20)).unwrap();
+        assert_eq!(
+            canister_data.token_balance_reconciliation_checkpoint,
+            TokenBalanceReconciliationCheckpoint {
+                last_token_event_id: 2,
+                recomputed_balance: 30,
+                number_of_events: 2,
+            }
+        );
+
+        // * events recorded without going through the checkpoint, e.g. migrated history
+        canister_data
+            .utility_token_transaction_history
+            .insert(3, receive(5));
+        canister_data.my_token_balance.utility_token_balance += 5;
+
+        let reconciliation = canister_data.get_utility_token_balance_reconciliation();
+        assert_eq!(reconciliation.recomputed_balance, 35);
+        assert_eq!(reconciliation.number_of_events, 3);
+        assert_eq!(reconciliation.drift, 0);
+
+        // * the checkpoint lags until it is advanced
+        canister_data.apply_token_event(receive(1)).unwrap();
+        assert_eq!(
+            canister_data
+                .token_balance_reconciliation_checkpoint
+                .last_token_event_id,
+            2
+        );
+
+        canister_data.advance_token_balance_reconciliation_checkpoint();
+        assert_eq!(
+            canister_data.token_balance_reconciliation_checkpoint,
+            TokenBalanceReconciliationCheckpoint {
+                last_token_event_id: 4,
+                recomputed_balance: 36,
+                number_of_events: 4,
+            }
+        );
+        assert_eq!(
+            canister_data.get_utility_token_balance_reconciliation(),
+            canister_data.my_token_balance.reconcile_balance(
+                canister_data
+                    .utility_token_transaction_history
+                    .iter()
+                    .map(|(_, token_event)| token_event)
+            )
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::token::UtilityTokenLedgerMirrorStatus;
+
+use crate::CANISTER_DATA;
+
+#[query]
+fn get_utility_token_ledger_mirror_status() -> UtilityTokenLedgerMirrorStatus {
+    CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.utility_token_ledger_mirror.get_status())
+}
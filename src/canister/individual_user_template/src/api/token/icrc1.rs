@@ -0,0 +1,116 @@
+use candid::Nat;
+use ic_cdk_macros::query;
+use icrc_ledger_types::{icrc::generic_metadata_value::MetadataValue, icrc1::account::Account};
+use shared_utils::canister_specific::individual_user_template::types::token::{
+    UTILITY_TOKEN_DECIMALS, UTILITY_TOKEN_FEE, UTILITY_TOKEN_NAME, UTILITY_TOKEN_SYMBOL,
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    data_model::CanisterData, CANISTER_DATA,
+};
+
+// * Read only ICRC-1 view of the utility token. The ledger scope is this canister, so the only
+// * account holding tokens is the default account of the profile owner.
+
+#[query]
+fn icrc1_name() -> String {
+    UTILITY_TOKEN_NAME.to_string()
+}
+
+#[query]
+fn icrc1_symbol() -> String {
+    UTILITY_TOKEN_SYMBOL.to_string()
+}
+
+#[query]
+fn icrc1_decimals() -> u8 {
+    UTILITY_TOKEN_DECIMALS
+}
+
+#[query]
+fn icrc1_fee() -> Nat {
+    Nat::from(UTILITY_TOKEN_FEE)
+}
+
+#[query]
+fn icrc1_metadata() -> Vec<(String, MetadataValue)> {
+    vec![
+        (
+            "icrc1:name".to_string(),
+            MetadataValue::Text(UTILITY_TOKEN_NAME.to_string()),
+        ),
+        (
+            "icrc1:symbol".to_string(),
+            MetadataValue::Text(UTILITY_TOKEN_SYMBOL.to_string()),
+        ),
+        (
+            "icrc1:decimals".to_string(),
+            MetadataValue::Nat(Nat::from(UTILITY_TOKEN_DECIMALS)),
+        ),
+        (
+            "icrc1:fee".to_string(),
+            MetadataValue::Nat(Nat::from(UTILITY_TOKEN_FEE)),
+        ),
+    ]
+}
+
+#[query]
+fn icrc1_total_supply() -> Nat {
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA.with_borrow(|canister_data| {
+        Nat::from(canister_data.my_token_balance.get_utility_token_balance())
+    })
+}
+
+#[query]
+fn icrc1_balance_of(account: Account) -> Nat {
+    update_last_canister_functionality_access_time();
+
+    CANISTER_DATA
+        .with_borrow(|canister_data| Nat::from(get_balance_of_impl(canister_data, &account)))
+}
+
+fn get_balance_of_impl(canister_data: &CanisterData, account: &Account) -> u64 {
+    let is_profile_owner_account = canister_data.profile.principal_id == Some(account.owner)
+        && account
+            .subaccount
+            .is_none_or(|subaccount| subaccount == [0; 32]);
+
+    if is_profile_owner_account {
+        canister_data.my_token_balance.get_utility_token_balance()
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_balance_of_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+        canister_data.my_token_balance.utility_token_balance = 1000;
+
+        let balance_of =
+            |owner, subaccount| get_balance_of_impl(&canister_data, &Account { owner, subaccount });
+
+        assert_eq!(balance_of(get_mock_user_alice_principal_id(), None), 1000);
+        assert_eq!(
+            balance_of(get_mock_user_alice_principal_id(), Some([0; 32])),
+            1000
+        );
+        assert_eq!(
+            balance_of(get_mock_user_alice_principal_id(), Some([1; 32])),
+            0
+        );
+        assert_eq!(balance_of(get_mock_user_bob_principal_id(), None), 0);
+    }
+}
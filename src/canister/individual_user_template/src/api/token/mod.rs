@@ -2,3 +2,10 @@ pub mod get_rewarded_for_referral;
 pub mod get_rewarded_for_signing_up;
 pub mod get_user_utility_token_transaction_history_with_pagination;
 pub mod get_utility_token_balance;
+pub mod get_utility_token_balance_reconciliation;
+pub mod get_utility_token_ledger_mirror_status;
+pub mod icrc1;
+pub mod receive_utility_tokens_transfer;
+pub mod send_utility_token_supply_stats_to_subnet_orchestrator;
+pub mod set_utility_token_ledger_mirror;
+pub mod transfer_utility_tokens;
@@ -0,0 +1,219 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    common::{
+        types::utility_token::token_event::TokenEvent,
+        utils::system_time::get_current_system_time_from_ic,
+    },
+    types::canister_specific::individual_user_template::error_types::UtilityTokenTransferError,
+};
+
+use crate::{
+    data_model::{utility_token_transfer::UtilityTokenTransferId, CanisterData},
+    util::{cycles::notify_to_recharge_canister, subnet_orchestrator::SubnetOrchestrator},
+    CANISTER_DATA,
+};
+
+/// Credits a transfer sent by `transfer_utility_tokens` on another user canister. A transfer
+/// that was already credited is acknowledged again without crediting it a second time.
+///
+/// # Access Control
+/// The calling canister must be the canister the network has on record for
+/// `from_user_principal_id`.
+#[update]
+async fn receive_utility_tokens_transfer(
+    from_user_principal_id: Principal,
+    transfer_id: UtilityTokenTransferId,
+    amount: u64,
+    memo: Option<String>,
+) -> Result<(), UtilityTokenTransferError> {
+    notify_to_recharge_canister();
+
+    let from_canister_id = ic_cdk::caller();
+
+    let already_received = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .utility_token_transfers
+            .is_transfer_received(from_canister_id, transfer_id)
+    });
+
+    if already_received {
+        return Ok(());
+    }
+
+    verify_sender_is_user_canister_on_network(from_user_principal_id, from_canister_id).await?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        credit_utility_tokens_from_transfer(
+            canister_data,
+            from_canister_id,
+            transfer_id,
+            amount,
+            memo,
+            get_current_system_time_from_ic(),
+        )
+    })?;
+
+    Ok(())
+}
+
+async fn verify_sender_is_user_canister_on_network(
+    from_user_principal_id: Principal,
+    from_canister_id: Principal,
+) -> Result<(), UtilityTokenTransferError> {
+    let is_user_canister_in_the_network = SubnetOrchestrator::new()
+        .map_err(|_| UtilityTokenTransferError::UserIndexCanisterIdNotFound)?
+        .is_user_canister_in_the_network(from_user_principal_id, from_canister_id)
+        .await
+        .map_err(|_| UtilityTokenTransferError::UserIndexCrossCanisterCallFailed)?;
+
+    if !is_user_canister_in_the_network {
+        return Err(UtilityTokenTransferError::SenderIsNotAUserCanisterOnNetwork);
+    }
+
+    Ok(())
+}
+
+/// Returns false without crediting if the transfer was already credited. A transfer that
+/// cannot be credited is not marked as received.
+fn credit_utility_tokens_from_transfer(
+    canister_data: &mut CanisterData,
+    from_canister_id: Principal,
+    transfer_id: UtilityTokenTransferId,
+    amount: u64,
+    memo: Option<String>,
+    current_time: SystemTime,
+) -> Result<bool, UtilityTokenTransferError> {
+    if canister_data
+        .utility_token_transfers
+        .is_transfer_received(from_canister_id, transfer_id)
+    {
+        return Ok(false);
+    }
+
+    canister_data.apply_token_event(TokenEvent::Receive {
+        amount,
+        from_account: from_canister_id,
+        timestamp: current_time,
+        memo,
+    })?;
+
+    canister_data
+        .utility_token_transfers
+        .mark_transfer_received(from_canister_id, transfer_id, current_time);
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_credit_utility_tokens_from_transfer_is_idempotent() {
+        let mut canister_data = CanisterData::default();
+
+        assert_eq!(
+            credit_utility_tokens_from_transfer(
+                &mut canister_data,
+                get_mock_user_alice_canister_id(),
+                0,
+                40,
+                Some("for the video".to_string()),
+                UNIX_EPOCH,
+            ),
+            Ok(true)
+        );
+        assert_eq!(
+            credit_utility_tokens_from_transfer(
+                &mut canister_data,
+                get_mock_user_alice_canister_id(),
+                0,
+                40,
+                Some("for the video".to_string()),
+                UNIX_EPOCH,
+            ),
+            Ok(false)
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 40);
+
+        // * transfer ids are only unique per sending canister
+        assert_eq!(
+            credit_utility_tokens_from_transfer(
+                &mut canister_data,
+                get_mock_user_bob_canister_id(),
+                0,
+                10,
+                None,
+                UNIX_EPOCH,
+            ),
+            Ok(true)
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 50);
+
+        assert!(canister_data
+            .utility_token_transfers
+            .is_transfer_received(get_mock_user_alice_canister_id(), 0));
+        assert!(!canister_data
+            .utility_token_transfers
+            .is_transfer_received(get_mock_user_alice_canister_id(), 1));
+
+        let history: Vec<TokenEvent> = canister_data
+            .utility_token_transaction_history
+            .iter()
+            .map(|(_, token_event)| token_event)
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                TokenEvent::Receive {
+                    amount: 40,
+                    from_account: get_mock_user_alice_canister_id(),
+                    timestamp: UNIX_EPOCH,
+                    memo: Some("for the video".to_string()),
+                },
+                TokenEvent::Receive {
+                    amount: 10,
+                    from_account: get_mock_user_bob_canister_id(),
+                    timestamp: UNIX_EPOCH,
+                    memo: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_credit_utility_tokens_from_transfer_rejects_overflow() {
+        let mut canister_data = CanisterData::default();
+        canister_data.my_token_balance.utility_token_balance = u64::MAX;
+
+        assert_eq!(
+            credit_utility_tokens_from_transfer(
+                &mut canister_data,
+                get_mock_user_alice_canister_id(),
+                0,
+                1,
+                None,
+                UNIX_EPOCH,
+            ),
+            Err(UtilityTokenTransferError::BalanceOverflow)
+        );
+
+        assert_eq!(
+            canister_data.my_token_balance.utility_token_balance,
+            u64::MAX
+        );
+        assert!(!canister_data
+            .utility_token_transfers
+            .is_transfer_received(get_mock_user_alice_canister_id(), 0));
+        assert!(canister_data.utility_token_transaction_history.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::hot_or_not::BetOutcomeForBetMaker,
+    common::utils::permissions::is_caller_controller,
+    types::utility_token_supply_stats::UtilityTokenSupplyStats,
+};
+
+use crate::{data_model::CanisterData, util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+#[update(guard = "is_caller_controller")]
+fn send_utility_token_supply_stats_to_subnet_orchestrator(
+) -> Result<UtilityTokenSupplyStats, String> {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA
+        .with_borrow(|canister_data| Ok(get_utility_token_supply_stats_impl(canister_data)))
+}
+
+/// Totals come from the supply stats checkpoint, so only the events recorded since it was last
+/// advanced are read.
+fn get_utility_token_supply_stats_impl(canister_data: &CanisterData) -> UtilityTokenSupplyStats {
+    let mut utility_token_supply_stats = canister_data
+        .get_utility_token_supply_stats_checkpoint()
+        .stats;
+
+    utility_token_supply_stats.circulating =
+        canister_data.my_token_balance.utility_token_balance as u128;
+    utility_token_supply_stats.staked_in_open_bets = canister_data
+        .all_hot_or_not_bets_placed
+        .values()
+        .filter(|placed_bet_detail| {
+            placed_bet_detail.outcome_received == BetOutcomeForBetMaker::AwaitingResult
+        })
+        .map(|placed_bet_detail| placed_bet_detail.amount_bet as u128)
+        .sum();
+    utility_token_supply_stats.number_of_individual_canisters = 1;
+    utility_token_supply_stats.update_burned();
+
+    utility_token_supply_stats
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::{
+        canister_specific::individual_user_template::types::hot_or_not::{
+            BetDirection, PlacedBetDetail,
+        },
+        common::types::utility_token::token_event::{MintEvent, StakeEvent, TokenEvent},
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_utility_token_supply_stats_impl() {
+        let mut canister_data = CanisterData::default();
+
+        canister_data
+            .apply_token_event(TokenEvent::Mint {
+                amount: 1000,
+                details: MintEvent::NewUserSignup {
+                    new_user_principal_id: get_mock_user_alice_principal_id(),
+                },
+                timestamp: UNIX_EPOCH,
+            })
+            .unwrap();
+        canister_data
+            .my_token_balance
+            .adjust_balance_pre_bet(100)
+            .unwrap();
+        canister_data
+            .apply_token_event(TokenEvent::Stake {
+                amount: 100,
+                details: StakeEvent::BetOnHotOrNotPost {
+                    post_canister_id: get_mock_user_bob_canister_id(),
+                    post_id: 0,
+                    bet_amount: 100,
+                    bet_direction: BetDirection::Hot,
+                },
+                timestamp: UNIX_EPOCH,
+            })
+            .unwrap();
+        canister_data.all_hot_or_not_bets_placed.insert(
+            (get_mock_user_bob_canister_id(), 0),
+            PlacedBetDetail {
+                canister_id: get_mock_user_bob_canister_id(),
+                post_id: 0,
+                slot_id: 1,
+                room_id: 1,
+                amount_bet: 100,
+                bet_direction: BetDirection::Hot,
+                bet_placed_at: UNIX_EPOCH,
+                outcome_received: BetOutcomeForBetMaker::AwaitingResult,
+            },
+        );
+        canister_data
+            .apply_token_event(TokenEvent::Transfer {
+                amount: 50,
+                to_account: get_mock_user_alice_canister_id(),
+                timestamp: UNIX_EPOCH,
+                memo: None,
+            })
+            .unwrap();
+
+        assert_eq!(
+            get_utility_token_supply_stats_impl(&canister_data),
+            UtilityTokenSupplyStats {
+                minted: 1000,
+                burned: 0,
+                staked: 100,
+                staked_in_open_bets: 100,
+                paid_out: 0,
+                circulating: 850,
+                number_of_individual_canisters: 1,
+            }
+        );
+        assert_eq!(
+            canister_data
+                .utility_token_supply_stats_checkpoint
+                .last_token_event_id,
+            3
+        );
+
+        canister_data
+            .all_hot_or_not_bets_placed
+            .get_mut(&(get_mock_user_bob_canister_id(), 0))
+            .unwrap()
+            .outcome_received = BetOutcomeForBetMaker::Lost;
+
+        let utility_token_supply_stats = get_utility_token_supply_stats_impl(&canister_data);
+        assert_eq!(utility_token_supply_stats.staked_in_open_bets, 0);
+        assert_eq!(utility_token_supply_stats.burned, 100);
+    }
+}
@@ -0,0 +1,100 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::common::utils::{
+    permissions::is_caller_controller_or_global_admin, system_time::get_current_system_time_from_ic,
+};
+
+use crate::{
+    data_model::CanisterData,
+    util::{
+        cycles::notify_to_recharge_canister,
+        utility_token_ledger_mirror::{
+            start_utility_token_ledger_mirror_timer, stop_utility_token_ledger_mirror_timer,
+        },
+    },
+    CANISTER_DATA,
+};
+
+/// Mirrors every balance change to the given ICRC-1 ledger, starting with the current balance.
+/// Passing `None` stops mirroring. This canister has to be the minting account of the ledger.
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn set_utility_token_ledger_mirror(ledger_canister_id: Option<Principal>) {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        set_utility_token_ledger_mirror_impl(
+            canister_data,
+            ledger_canister_id,
+            get_current_system_time_from_ic(),
+        )
+    });
+
+    match ledger_canister_id {
+        Some(_) => start_utility_token_ledger_mirror_timer(),
+        None => stop_utility_token_ledger_mirror_timer(),
+    }
+}
+
+fn set_utility_token_ledger_mirror_impl(
+    canister_data: &mut CanisterData,
+    ledger_canister_id: Option<Principal>,
+    current_time: SystemTime,
+) {
+    match ledger_canister_id {
+        Some(ledger_canister_id) => canister_data.utility_token_ledger_mirror.enable(
+            ledger_canister_id,
+            canister_data.my_token_balance.get_utility_token_balance(),
+            current_time,
+        ),
+        None => canister_data.utility_token_ledger_mirror.disable(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::common::types::utility_token::token_event::TokenEvent;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_set_utility_token_ledger_mirror_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data.my_token_balance.utility_token_balance = 500;
+
+        set_utility_token_ledger_mirror_impl(
+            &mut canister_data,
+            Some(get_mock_user_bob_canister_id()),
+            UNIX_EPOCH,
+        );
+
+        canister_data
+            .apply_token_event(TokenEvent::Receive {
+                amount: 20,
+                from_account: get_mock_user_alice_canister_id(),
+                timestamp: UNIX_EPOCH,
+                memo: None,
+            })
+            .unwrap();
+
+        let status = canister_data.utility_token_ledger_mirror.get_status();
+        assert_eq!(
+            status.ledger_canister_id,
+            Some(get_mock_user_bob_canister_id())
+        );
+        // * the opening balance and the receive
+        assert_eq!(status.number_of_pending_operations, 2);
+
+        set_utility_token_ledger_mirror_impl(&mut canister_data, None, UNIX_EPOCH);
+
+        let status = canister_data.utility_token_ledger_mirror.get_status();
+        assert_eq!(status.ledger_canister_id, None);
+        assert_eq!(status.number_of_pending_operations, 0);
+    }
+}
@@ -0,0 +1,479 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use ic_cdk::api::call::{self, CallResult, RejectionCode};
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::profile::UserProfileDetailsForFrontendV2,
+    common::{
+        types::utility_token::token_event::TokenEvent,
+        utils::system_time::get_current_system_time_from_ic,
+    },
+    types::canister_specific::individual_user_template::error_types::UtilityTokenTransferError,
+};
+
+use crate::{
+    api::canister_management::update_last_access_time::update_last_canister_functionality_access_time,
+    data_model::{
+        utility_token_transfer::{OutgoingUtilityTokenTransferStatus, UtilityTokenTransferId},
+        CanisterData,
+    },
+    util::{cycles::notify_to_recharge_canister, subnet_orchestrator::SubnetOrchestrator},
+    CANISTER_DATA,
+};
+
+pub const MAX_UTILITY_TOKEN_TRANSFER_MEMO_LENGTH: usize = 256;
+const MAX_RECEIVER_CALL_ATTEMPTS: u8 = 3;
+
+/// Sends utility tokens to another user canister. The amount is debited before the receiving
+/// canister is called and refunded if it does not accept the transfer.
+///
+/// `transfer_id` is chosen by the caller and makes the call idempotent: calling again with the
+/// id of a completed transfer returns without sending it again, and the id of a refunded transfer
+/// can be used to send the same transfer again. The receiver never credits a transfer id twice.
+///
+/// # Access Control
+/// Only the user whose profile details are stored in this canister can transfer tokens.
+#[update]
+async fn transfer_utility_tokens(
+    transfer_id: UtilityTokenTransferId,
+    to_canister_id: Principal,
+    amount: u64,
+    memo: Option<String>,
+) -> Result<UtilityTokenTransferId, UtilityTokenTransferError> {
+    notify_to_recharge_canister();
+
+    let from_user_principal_id = ic_cdk::caller();
+
+    let is_already_completed = CANISTER_DATA.with_borrow(|canister_data| {
+        validate_utility_tokens_transfer(
+            canister_data,
+            from_user_principal_id,
+            ic_cdk::id(),
+            transfer_id,
+            to_canister_id,
+            amount,
+            &memo,
+        )
+    })?;
+
+    if is_already_completed {
+        return Ok(transfer_id);
+    }
+
+    verify_receiver_is_user_canister_on_network(to_canister_id).await?;
+
+    let is_debited = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        debit_utility_tokens_for_transfer(
+            canister_data,
+            transfer_id,
+            to_canister_id,
+            amount,
+            memo.clone(),
+            get_current_system_time_from_ic(),
+        )
+    })?;
+
+    if !is_debited {
+        // * a concurrent call with the same id completed the transfer
+        return Ok(transfer_id);
+    }
+
+    update_last_canister_functionality_access_time();
+
+    let mut attempts = 1;
+    let mut call_result = send_transfer_to_receiver(
+        to_canister_id,
+        from_user_principal_id,
+        transfer_id,
+        amount,
+        memo.clone(),
+    )
+    .await;
+
+    // * the message never reached the receiver, so retrying with the same id is safe
+    while attempts < MAX_RECEIVER_CALL_ATTEMPTS
+        && matches!(call_result, Err((RejectionCode::SysTransient, _)))
+    {
+        attempts += 1;
+        call_result = send_transfer_to_receiver(
+            to_canister_id,
+            from_user_principal_id,
+            transfer_id,
+            amount,
+            memo.clone(),
+        )
+        .await;
+    }
+
+    let transfer_result = match call_result {
+        Ok((receiver_result,)) => receiver_result,
+        Err((_, message)) => Err(UtilityTokenTransferError::ReceiverCrossCanisterCallFailed(
+            message,
+        )),
+    };
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| match &transfer_result {
+        Ok(()) => canister_data
+            .utility_token_transfers
+            .finish_outgoing_transfer(
+                transfer_id,
+                OutgoingUtilityTokenTransferStatus::Completed,
+                get_current_system_time_from_ic(),
+            ),
+        Err(_) => revert_utility_tokens_transfer(
+            canister_data,
+            transfer_id,
+            to_canister_id,
+            amount,
+            get_current_system_time_from_ic(),
+        ),
+    });
+
+    transfer_result.map(|_| transfer_id)
+}
+
+async fn send_transfer_to_receiver(
+    to_canister_id: Principal,
+    from_user_principal_id: Principal,
+    transfer_id: UtilityTokenTransferId,
+    amount: u64,
+    memo: Option<String>,
+) -> CallResult<(Result<(), UtilityTokenTransferError>,)> {
+    call::call(
+        to_canister_id,
+        "receive_utility_tokens_transfer",
+        (from_user_principal_id, transfer_id, amount, memo),
+    )
+    .await
+}
+
+async fn verify_receiver_is_user_canister_on_network(
+    to_canister_id: Principal,
+) -> Result<(), UtilityTokenTransferError> {
+    let (receiver_profile,): (UserProfileDetailsForFrontendV2,) =
+        call::call(to_canister_id, "get_profile_details_v2", ())
+            .await
+            .map_err(|_| UtilityTokenTransferError::ReceiverIsNotAUserCanisterOnNetwork)?;
+
+    let is_user_canister_in_the_network = SubnetOrchestrator::new()
+        .map_err(|_| UtilityTokenTransferError::UserIndexCanisterIdNotFound)?
+        .is_user_canister_in_the_network(receiver_profile.principal_id, to_canister_id)
+        .await
+        .map_err(|_| UtilityTokenTransferError::UserIndexCrossCanisterCallFailed)?;
+
+    if !is_user_canister_in_the_network {
+        return Err(UtilityTokenTransferError::ReceiverIsNotAUserCanisterOnNetwork);
+    }
+
+    Ok(())
+}
+
+/// Returns true if the transfer with this id was already completed.
+fn validate_utility_tokens_transfer(
+    canister_data: &CanisterData,
+    caller: Principal,
+    my_canister_id: Principal,
+    transfer_id: UtilityTokenTransferId,
+    to_canister_id: Principal,
+    amount: u64,
+    memo: &Option<String>,
+) -> Result<bool, UtilityTokenTransferError> {
+    if canister_data.profile.principal_id != Some(caller) {
+        return Err(UtilityTokenTransferError::Unauthorized);
+    }
+
+    if to_canister_id == my_canister_id {
+        return Err(UtilityTokenTransferError::CannotTransferToSelf);
+    }
+
+    if amount == 0 {
+        return Err(UtilityTokenTransferError::InvalidAmount);
+    }
+
+    if memo
+        .as_ref()
+        .is_some_and(|memo| memo.len() > MAX_UTILITY_TOKEN_TRANSFER_MEMO_LENGTH)
+    {
+        return Err(UtilityTokenTransferError::MemoTooLong {
+            max_length: MAX_UTILITY_TOKEN_TRANSFER_MEMO_LENGTH as u64,
+        });
+    }
+
+    canister_data
+        .utility_token_transfers
+        .check_outgoing_transfer(transfer_id, to_canister_id, amount, memo)
+}
+
+/// Returns false without debiting if the transfer with this id was already completed. The
+/// request must have passed `validate_utility_tokens_transfer`; only the transfer id is checked
+/// again as another call may have used it in the meantime.
+fn debit_utility_tokens_for_transfer(
+    canister_data: &mut CanisterData,
+    transfer_id: UtilityTokenTransferId,
+    to_canister_id: Principal,
+    amount: u64,
+    memo: Option<String>,
+    current_time: SystemTime,
+) -> Result<bool, UtilityTokenTransferError> {
+    let is_already_completed = canister_data
+        .utility_token_transfers
+        .check_outgoing_transfer(transfer_id, to_canister_id, amount, &memo)?;
+
+    if is_already_completed {
+        return Ok(false);
+    }
+
+    canister_data.apply_token_event(TokenEvent::Transfer {
+        amount,
+        to_account: to_canister_id,
+        timestamp: current_time,
+        memo: memo.clone(),
+    })?;
+
+    canister_data
+        .utility_token_transfers
+        .start_outgoing_transfer(transfer_id, to_canister_id, amount, memo, current_time);
+
+    Ok(true)
+}
+
+fn revert_utility_tokens_transfer(
+    canister_data: &mut CanisterData,
+    transfer_id: UtilityTokenTransferId,
+    to_canister_id: Principal,
+    amount: u64,
+    current_time: SystemTime,
+) {
+    if let Err(e) = canister_data.apply_token_event(TokenEvent::TransferReverted {
+        amount,
+        to_account: to_canister_id,
+        timestamp: current_time,
+    }) {
+        ic_cdk::println!("Failed to refund transfer to {}: {:?}", to_canister_id, e);
+    }
+
+    canister_data
+        .utility_token_transfers
+        .finish_outgoing_transfer(
+            transfer_id,
+            OutgoingUtilityTokenTransferStatus::Reverted,
+            current_time,
+        );
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    fn canister_data_with_balance(balance: u64) -> CanisterData {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+        canister_data.my_token_balance.utility_token_balance = balance;
+        canister_data
+    }
+
+    #[test]
+    fn test_validate_utility_tokens_transfer_rejects_invalid_requests() {
+        let canister_data = canister_data_with_balance(100);
+
+        assert_eq!(
+            validate_utility_tokens_transfer(
+                &canister_data,
+                get_mock_user_bob_principal_id(),
+                get_mock_user_alice_canister_id(),
+                0,
+                get_mock_user_bob_canister_id(),
+                10,
+                &None,
+            ),
+            Err(UtilityTokenTransferError::Unauthorized)
+        );
+        assert_eq!(
+            validate_utility_tokens_transfer(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                0,
+                get_mock_user_alice_canister_id(),
+                10,
+                &None,
+            ),
+            Err(UtilityTokenTransferError::CannotTransferToSelf)
+        );
+        assert_eq!(
+            validate_utility_tokens_transfer(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                0,
+                get_mock_user_bob_canister_id(),
+                0,
+                &None,
+            ),
+            Err(UtilityTokenTransferError::InvalidAmount)
+        );
+        assert_eq!(
+            validate_utility_tokens_transfer(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                0,
+                get_mock_user_bob_canister_id(),
+                10,
+                &Some("a".repeat(MAX_UTILITY_TOKEN_TRANSFER_MEMO_LENGTH + 1)),
+            ),
+            Err(UtilityTokenTransferError::MemoTooLong {
+                max_length: MAX_UTILITY_TOKEN_TRANSFER_MEMO_LENGTH as u64
+            })
+        );
+        assert_eq!(
+            validate_utility_tokens_transfer(
+                &canister_data,
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                0,
+                get_mock_user_bob_canister_id(),
+                10,
+                &None,
+            ),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_debit_utility_tokens_for_transfer_rejects_insufficient_balance() {
+        let mut canister_data = canister_data_with_balance(100);
+
+        assert_eq!(
+            debit_utility_tokens_for_transfer(
+                &mut canister_data,
+                0,
+                get_mock_user_bob_canister_id(),
+                101,
+                None,
+                UNIX_EPOCH,
+            ),
+            Err(UtilityTokenTransferError::InsufficientBalance { balance: 100 })
+        );
+
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 100);
+        assert!(canister_data.utility_token_transaction_history.is_empty());
+        assert_eq!(
+            canister_data
+                .utility_token_transfers
+                .get_outgoing_transfer(0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_debit_and_revert_utility_tokens_transfer() {
+        let mut canister_data = canister_data_with_balance(100);
+
+        assert_eq!(
+            debit_utility_tokens_for_transfer(
+                &mut canister_data,
+                0,
+                get_mock_user_bob_canister_id(),
+                40,
+                Some("for the video".to_string()),
+                UNIX_EPOCH,
+            ),
+            Ok(true)
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 60);
+        canister_data
+            .utility_token_transfers
+            .finish_outgoing_transfer(0, OutgoingUtilityTokenTransferStatus::Completed, UNIX_EPOCH);
+
+        // * a retried call for a completed transfer does not debit again
+        assert_eq!(
+            debit_utility_tokens_for_transfer(
+                &mut canister_data,
+                0,
+                get_mock_user_bob_canister_id(),
+                40,
+                Some("for the video".to_string()),
+                UNIX_EPOCH,
+            ),
+            Ok(false)
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 60);
+
+        assert_eq!(
+            debit_utility_tokens_for_transfer(
+                &mut canister_data,
+                1,
+                get_mock_user_bob_canister_id(),
+                60,
+                None,
+                UNIX_EPOCH,
+            ),
+            Ok(true)
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 0);
+        assert_eq!(
+            debit_utility_tokens_for_transfer(
+                &mut canister_data,
+                1,
+                get_mock_user_bob_canister_id(),
+                60,
+                None,
+                UNIX_EPOCH,
+            ),
+            Err(UtilityTokenTransferError::TransferInProgress)
+        );
+
+        revert_utility_tokens_transfer(
+            &mut canister_data,
+            1,
+            get_mock_user_bob_canister_id(),
+            60,
+            UNIX_EPOCH,
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 60);
+        assert_eq!(
+            canister_data
+                .utility_token_transfers
+                .get_outgoing_transfer(1)
+                .map(|transfer| transfer.status),
+            Some(OutgoingUtilityTokenTransferStatus::Reverted)
+        );
+
+        let history: Vec<TokenEvent> = canister_data
+            .utility_token_transaction_history
+            .iter()
+            .map(|(_, token_event)| token_event)
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                TokenEvent::Transfer {
+                    amount: 40,
+                    to_account: get_mock_user_bob_canister_id(),
+                    timestamp: UNIX_EPOCH,
+                    memo: Some("for the video".to_string()),
+                },
+                TokenEvent::Transfer {
+                    amount: 60,
+                    to_account: get_mock_user_bob_canister_id(),
+                    timestamp: UNIX_EPOCH,
+                    memo: None,
+                },
+                TokenEvent::TransferReverted {
+                    amount: 60,
+                    to_account: get_mock_user_bob_canister_id(),
+                    timestamp: UNIX_EPOCH,
+                },
+            ]
+        );
+    }
+}
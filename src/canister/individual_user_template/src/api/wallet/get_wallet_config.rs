@@ -0,0 +1,9 @@
+use ic_cdk_macros::query;
+use shared_utils::canister_specific::individual_user_template::types::wallet::WalletConfig;
+
+use crate::CANISTER_DATA;
+
+#[query]
+fn get_wallet_config() -> WalletConfig {
+    CANISTER_DATA.with_borrow(|canister_data| canister_data.wallet.get_config())
+}
@@ -0,0 +1,223 @@
+use std::cell::Cell;
+
+use candid::{Nat, Principal};
+use futures::StreamExt;
+use ic_cdk_macros::update;
+use ic_sns_root::pb::v1::{ListSnsCanistersRequest, ListSnsCanistersResponse};
+use icrc_ledger_types::{icrc::generic_metadata_value::MetadataValue, icrc1::account::Account};
+use shared_utils::{
+    canister_specific::individual_user_template::types::wallet::{
+        WalletOverview, WalletOverviewError, WalletToken, WalletTokenMetadata,
+    },
+    common::utils::system_time::get_current_system_time_from_ic,
+    pagination,
+};
+
+use crate::{
+    util::{
+        cycles::notify_to_recharge_canister,
+        wallet_zero_balance_sweeper::start_wallet_zero_balance_sweeper_timer_if_needed,
+    },
+    CANISTER_DATA,
+};
+
+const MAX_TOKENS_TO_FETCH_CONCURRENTLY: usize = 10;
+
+thread_local! {
+    static WALLET_OVERVIEW_FETCH_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Balances and metadata of a page of the tokens held by the user. Tokens seen at a zero balance
+/// are removed by the zero balance sweeper once the grace period is over, so pages stay stable
+/// while the wallet is browsed. Only the profile owner can fetch it, one overview at a time.
+#[update]
+async fn get_wallet_overview(
+    from_inclusive_index: u64,
+    limit: u64,
+) -> Result<WalletOverview, WalletOverviewError> {
+    notify_to_recharge_canister();
+
+    let current_caller = ic_cdk::caller();
+    let (token_roots, total_number_of_tokens) = CANISTER_DATA.with_borrow(|canister_data| {
+        if canister_data.profile.principal_id != Some(current_caller) {
+            return Err(WalletOverviewError::Unauthorized);
+        }
+
+        let total_number_of_tokens = canister_data.token_roots.len();
+        let (from_inclusive_index, limit) = pagination::get_pagination_bounds_cursor(
+            from_inclusive_index,
+            limit,
+            total_number_of_tokens,
+        )
+        .map_err(WalletOverviewError::Pagination)?;
+        let token_roots: Vec<(Principal, Option<Principal>)> = canister_data
+            .token_roots
+            .iter()
+            .skip(from_inclusive_index as usize)
+            .take(limit as usize)
+            .map(|(token_root, _)| (token_root, canister_data.wallet.get_ledger(token_root)))
+            .collect();
+
+        Ok((token_roots, total_number_of_tokens))
+    })?;
+
+    if WALLET_OVERVIEW_FETCH_IN_PROGRESS.replace(true) {
+        return Err(WalletOverviewError::FetchInProgress);
+    }
+
+    let account = Account {
+        owner: current_caller,
+        subaccount: None,
+    };
+    let token_futures = token_roots
+        .into_iter()
+        .map(|(token_root, cached_ledger)| get_wallet_token(token_root, cached_ledger, account));
+    let mut tokens: Vec<WalletToken> = futures::stream::iter(token_futures)
+        .buffered(MAX_TOKENS_TO_FETCH_CONCURRENTLY)
+        .collect()
+        .await;
+
+    WALLET_OVERVIEW_FETCH_IN_PROGRESS.set(false);
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let current_time = get_current_system_time_from_ic();
+
+        for token in tokens.iter_mut() {
+            if let Some(ledger) = token.ledger {
+                canister_data.wallet.cache_ledger(token.token_root, ledger);
+            }
+            let Some(balance) = &token.balance else {
+                continue;
+            };
+
+            // * tokens of the creator stay in the wallet
+            let is_token_of_creator = canister_data
+                .cdao_canisters
+                .iter()
+                .any(|cdao| cdao.root == token.token_root);
+            if is_token_of_creator {
+                canister_data.wallet.clear_zero_balance(token.token_root);
+                continue;
+            }
+            token.zero_balance_since =
+                canister_data
+                    .wallet
+                    .record_balance(token.token_root, balance, current_time);
+        }
+    });
+    start_wallet_zero_balance_sweeper_timer_if_needed();
+
+    Ok(WalletOverview {
+        tokens,
+        total_number_of_tokens,
+    })
+}
+
+async fn get_wallet_token(
+    token_root: Principal,
+    cached_ledger: Option<Principal>,
+    account: Account,
+) -> WalletToken {
+    let mut token = WalletToken {
+        token_root,
+        ledger: cached_ledger,
+        balance: None,
+        metadata: None,
+        error: None,
+        zero_balance_since: None,
+    };
+
+    let ledger = match cached_ledger {
+        Some(ledger) => ledger,
+        None => match get_ledger(token_root).await {
+            Ok(ledger) => ledger,
+            Err(e) => {
+                token.error = Some(e);
+                return token;
+            }
+        },
+    };
+    token.ledger = Some(ledger);
+
+    let (balance_res, metadata_res) = futures::join!(
+        ic_cdk::call::<_, (Nat,)>(ledger, "icrc1_balance_of", (account,)),
+        ic_cdk::call::<_, (Vec<(String, MetadataValue)>,)>(ledger, "icrc1_metadata", ()),
+    );
+
+    match balance_res {
+        Ok((balance,)) => token.balance = Some(balance),
+        Err(e) => token.error = Some(e.1),
+    }
+    match metadata_res {
+        Ok((metadata,)) => token.metadata = Some(get_wallet_token_metadata(metadata)),
+        Err(e) => token.error = Some(e.1),
+    }
+
+    token
+}
+
+async fn get_ledger(token_root: Principal) -> Result<Principal, String> {
+    let (res,): (ListSnsCanistersResponse,) = ic_cdk::call(
+        token_root,
+        "list_sns_canisters",
+        (ListSnsCanistersRequest {},),
+    )
+    .await
+    .map_err(|e| e.1)?;
+
+    res.ledger
+        .map(Principal::from)
+        .ok_or_else(|| "Ledger not found for the token root".to_string())
+}
+
+fn get_wallet_token_metadata(metadata: Vec<(String, MetadataValue)>) -> WalletTokenMetadata {
+    metadata.into_iter().fold(
+        WalletTokenMetadata::default(),
+        |mut token_metadata, (key, value)| {
+            match (key.as_str(), value) {
+                ("icrc1:name", MetadataValue::Text(name)) => token_metadata.name = Some(name),
+                ("icrc1:symbol", MetadataValue::Text(symbol)) => {
+                    token_metadata.symbol = Some(symbol)
+                }
+                ("icrc1:decimals", MetadataValue::Nat(decimals)) => {
+                    token_metadata.decimals = u8::try_from(&decimals.0).ok()
+                }
+                ("icrc1:logo", MetadataValue::Text(logo)) => token_metadata.logo = Some(logo),
+                _ => {}
+            }
+            token_metadata
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_wallet_token_metadata() {
+        let metadata = get_wallet_token_metadata(vec![
+            (
+                "icrc1:symbol".to_string(),
+                MetadataValue::Text("CDAO".to_string()),
+            ),
+            ("icrc1:decimals".to_string(), MetadataValue::Nat(8u8.into())),
+            (
+                "icrc1:fee".to_string(),
+                MetadataValue::Nat(10_000u64.into()),
+            ),
+            // * values of an unexpected type are ignored
+            ("icrc1:logo".to_string(), MetadataValue::Nat(1u8.into())),
+        ]);
+
+        assert_eq!(
+            metadata,
+            WalletTokenMetadata {
+                name: None,
+                symbol: Some("CDAO".to_string()),
+                decimals: Some(8),
+                logo: None,
+            }
+        );
+    }
+}
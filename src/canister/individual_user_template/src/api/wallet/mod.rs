@@ -0,0 +1,3 @@
+pub mod get_wallet_config;
+pub mod get_wallet_overview;
+pub mod update_wallet_config;
@@ -0,0 +1,14 @@
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::wallet::WalletConfig,
+    common::utils::permissions::is_caller_controller_or_global_admin,
+};
+
+use crate::{util::cycles::notify_to_recharge_canister, CANISTER_DATA};
+
+#[update(guard = "is_caller_controller_or_global_admin")]
+fn update_wallet_config(wallet_config: WalletConfig) {
+    notify_to_recharge_canister();
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| canister_data.wallet.set_config(wallet_config));
+}
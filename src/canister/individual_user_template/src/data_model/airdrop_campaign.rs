@@ -0,0 +1,718 @@
+use std::{borrow::Cow, collections::BTreeMap, time::SystemTime};
+
+use candid::Principal;
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use icrc_ledger_types::icrc1::transfer::Memo;
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::{
+    cdao::{
+        AirdropCampaignConfig, AirdropCampaignDetails, AirdropClaim, AirdropClaimDetails,
+        AirdropInfo, ClaimStatus,
+    },
+    error::AirdropError,
+};
+
+use super::memory::{get_airdrop_campaign_claims_memory, Memory};
+
+/// Claim of a principal in an airdrop campaign
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropCampaignClaimRecord {
+    pub status: ClaimStatus,
+    /// Transfers made so far, a vesting airdrop is claimed in several tranches
+    pub claims: Vec<AirdropClaim>,
+    /// Amount of the transfer while claiming
+    pub claiming_amount: u64,
+    /// While claiming, this is also the `created_at_time` of the transfer, so the ledger
+    /// deduplicates a retried transfer that had gone through
+    pub updated_at: SystemTime,
+    /// Memo of the transfer while claiming
+    pub memo: Option<Memo>,
+    /// While claiming, the length of the ledger's transaction log before the transfer
+    pub ledger_start_index: u64,
+}
+
+impl AirdropCampaignClaimRecord {
+    pub fn is_claiming_timed_out(&self, current_time: SystemTime) -> bool {
+        self.status
+            .is_claiming_timed_out(self.updated_at, current_time)
+    }
+
+    fn get_claimed_amount(&self) -> u64 {
+        self.claims.iter().map(|claim| claim.amount).sum()
+    }
+}
+
+impl Storable for AirdropCampaignClaimRecord {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut bytes = vec![];
+        ciborium::ser::into_writer(self, &mut bytes).unwrap();
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let claim: Self = ciborium::de::from_reader(bytes.as_ref()).unwrap();
+        claim
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AirdropCampaign {
+    config: AirdropCampaignConfig,
+    /// No longer updated, the claims of the campaign are kept in stable memory
+    #[serde(default)]
+    airdrop_info: AirdropInfo,
+    allocated_budget: u64,
+    #[serde(default)]
+    distributed_amount: u64,
+    #[serde(default)]
+    number_of_claimants: u64,
+    created_at: SystemTime,
+}
+
+impl AirdropCampaign {
+    fn is_active(&self, current_time: SystemTime) -> bool {
+        self.config.start_time <= current_time && current_time < self.config.end_time
+    }
+
+    fn has_budget_for_new_claimant(&self) -> bool {
+        self.allocated_budget + self.config.amount_per_claim <= self.config.total_budget
+    }
+
+    /// Amount the principal can claim now, ignoring whether a new claimant still fits in the
+    /// campaign.
+    fn get_claimable_amount(
+        &self,
+        claim: Option<&AirdropCampaignClaimRecord>,
+        current_time: SystemTime,
+    ) -> u64 {
+        let first_claimed_at = claim
+            .and_then(|claim| claim.claims.first())
+            .map(|claim| claim.claimed_at)
+            .unwrap_or(current_time);
+        let vested_amount = match self.config.vesting {
+            Some(vesting) => vesting.get_vested_amount(
+                self.config.amount_per_claim,
+                first_claimed_at,
+                current_time,
+            ),
+            None => self.config.amount_per_claim,
+        };
+
+        vested_amount.saturating_sub(claim.map_or(0, |claim| claim.get_claimed_amount()))
+    }
+
+    /// Part of the budget that may still be paid out. Once the campaign ended, only what was
+    /// allocated to its claimants, as their vested tranches remain claimable.
+    fn get_reserved_budget(&self, current_time: SystemTime) -> u64 {
+        let budget = if current_time < self.config.end_time {
+            self.config.total_budget
+        } else {
+            self.allocated_budget
+        };
+
+        budget.saturating_sub(self.distributed_amount)
+    }
+
+    fn get_details(&self, campaign_id: u64) -> AirdropCampaignDetails {
+        AirdropCampaignDetails {
+            campaign_id,
+            config: self.config.clone(),
+            allocated_budget: self.allocated_budget,
+            distributed_amount: self.distributed_amount,
+            number_of_claimants: self.number_of_claimants,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AirdropCampaigns {
+    next_campaign_id: u64,
+    campaigns: BTreeMap<u64, AirdropCampaign>,
+    // Key is the campaign id and the claimant's principal
+    #[serde(skip, default = "_default_airdrop_campaign_claims")]
+    claims: StableBTreeMap<(u64, Principal), AirdropCampaignClaimRecord, Memory>,
+}
+
+impl Default for AirdropCampaigns {
+    fn default() -> Self {
+        Self {
+            next_campaign_id: 0,
+            campaigns: BTreeMap::new(),
+            claims: _default_airdrop_campaign_claims(),
+        }
+    }
+}
+
+impl AirdropCampaigns {
+    pub fn create_campaign(
+        &mut self,
+        config: AirdropCampaignConfig,
+        current_time: SystemTime,
+    ) -> Result<u64, AirdropError> {
+        if config.amount_per_claim == 0 || config.total_budget < config.amount_per_claim {
+            return Err(AirdropError::InvalidCampaignConfig(
+                "Total budget must cover at least one non zero claim".into(),
+            ));
+        }
+        if config.end_time <= config.start_time || config.end_time <= current_time {
+            return Err(AirdropError::InvalidCampaignConfig(
+                "End time must be after the start time and in the future".into(),
+            ));
+        }
+        if config
+            .vesting
+            .is_some_and(|vesting| vesting.number_of_tranches == 0)
+        {
+            return Err(AirdropError::InvalidCampaignConfig(
+                "Vesting needs at least one tranche".into(),
+            ));
+        }
+
+        let campaign_id = self.next_campaign_id;
+        self.next_campaign_id += 1;
+        self.campaigns.insert(
+            campaign_id,
+            AirdropCampaign {
+                config,
+                airdrop_info: AirdropInfo::default(),
+                allocated_budget: 0,
+                created_at: current_time,
+            },
+        );
+
+        Ok(campaign_id)
+    }
+
+    pub fn get_campaign_config(&self, campaign_id: u64) -> Option<&AirdropCampaignConfig> {
+        self.campaigns
+            .get(&campaign_id)
+            .map(|campaign| &campaign.config)
+    }
+
+    /// Tokens the campaigns of the token may still pay out, which the canister must keep
+    pub fn get_reserved_budget(&self, token_root: Principal, current_time: SystemTime) -> u64 {
+        self.campaigns
+            .values()
+            .filter(|campaign| campaign.config.token_root == token_root)
+            .map(|campaign| campaign.get_reserved_budget(current_time))
+            .sum()
+    }
+
+    pub fn get_campaigns_of_token(&self, token_root: Principal) -> Vec<AirdropCampaignDetails> {
+        self.campaigns
+            .iter()
+            .filter(|(_, campaign)| campaign.config.token_root == token_root)
+            .map(|(campaign_id, campaign)| campaign.get_details(*campaign_id))
+            .collect()
+    }
+
+    pub fn get_claim_details(
+        &self,
+        campaign_id: u64,
+        user_principal_id: Principal,
+        current_time: SystemTime,
+    ) -> Option<AirdropClaimDetails> {
+        let campaign = self.campaigns.get(&campaign_id)?;
+        let claim = self.claims.get(&(campaign_id, user_principal_id));
+        let status = claim
+            .as_ref()
+            .map(|claim| claim.status.clone())
+            .unwrap_or_default();
+        let claims = claim
+            .as_ref()
+            .map(|claim| claim.claims.clone())
+            .unwrap_or_default();
+
+        let can_claim = match status {
+            ClaimStatus::Unclaimed => {
+                !claims.is_empty()
+                    || (campaign.is_active(current_time) && campaign.has_budget_for_new_claimant())
+            }
+            ClaimStatus::Claiming | ClaimStatus::Claimed => false,
+        };
+
+        Some(AirdropClaimDetails {
+            status,
+            claimed_amount: claim.as_ref().map_or(0, |claim| claim.get_claimed_amount()),
+            claimable_amount: if can_claim {
+                campaign.get_claimable_amount(claim.as_ref(), current_time)
+            } else {
+                0
+            },
+            claims,
+        })
+    }
+
+    /// Marks the principal as claiming and returns the claim to transfer. A new claimant
+    /// reserves the whole amount per claim from the budget, so vested tranches can always be paid.
+    /// A claim that is stuck in `Claiming` past the timeout is returned as it was, to retry the
+    /// same transfer. `ledger_start_index` is the current length of the ledger's transaction log.
+    pub fn start_claim(
+        &mut self,
+        campaign_id: u64,
+        user_principal_id: Principal,
+        memo: Option<Memo>,
+        ledger_start_index: u64,
+        current_time: SystemTime,
+    ) -> Result<AirdropCampaignClaimRecord, AirdropError> {
+        let campaign = self
+            .campaigns
+            .get_mut(&campaign_id)
+            .ok_or(AirdropError::CampaignNotFound)?;
+        let key = (campaign_id, user_principal_id);
+        let claim = self.claims.get(&key);
+
+        match &claim {
+            Some(claim) if claim.status == ClaimStatus::Claimed => {
+                return Err(AirdropError::AlreadyClaimedAirdrop);
+            }
+            Some(claim) if claim.status == ClaimStatus::Claiming => {
+                return if claim.is_claiming_timed_out(current_time) {
+                    Ok(claim.clone())
+                } else {
+                    Err(AirdropError::ClaimInProgress)
+                };
+            }
+            _ => {}
+        }
+
+        let is_new_claimant = claim.as_ref().map_or(true, |claim| claim.claims.is_empty());
+        if is_new_claimant {
+            if !campaign.is_active(current_time) {
+                return Err(AirdropError::CampaignNotActive);
+            }
+            if !campaign.has_budget_for_new_claimant() {
+                return Err(AirdropError::BudgetExhausted);
+            }
+        }
+
+        let amount = campaign.get_claimable_amount(claim.as_ref(), current_time);
+        if amount == 0 {
+            return Err(AirdropError::NothingToClaim);
+        }
+
+        if is_new_claimant {
+            campaign.allocated_budget += campaign.config.amount_per_claim;
+        }
+        let claim = AirdropCampaignClaimRecord {
+            status: ClaimStatus::Claiming,
+            claims: claim.map(|claim| claim.claims).unwrap_or_default(),
+            claiming_amount: amount,
+            updated_at: current_time,
+            memo,
+            ledger_start_index,
+        };
+        self.claims.insert(key, claim.clone());
+
+        Ok(claim)
+    }
+
+    pub fn complete_claim(
+        &mut self,
+        campaign_id: u64,
+        user_principal_id: Principal,
+        current_time: SystemTime,
+    ) {
+        let key = (campaign_id, user_principal_id);
+        let (Some(campaign), Some(mut claim)) =
+            (self.campaigns.get_mut(&campaign_id), self.claims.get(&key))
+        else {
+            return;
+        };
+        if claim.status != ClaimStatus::Claiming {
+            return;
+        }
+
+        claim.claims.push(AirdropClaim {
+            amount: claim.claiming_amount,
+            claimed_at: current_time,
+        });
+        campaign.distributed_amount += claim.claiming_amount;
+        if claim.claims.len() == 1 {
+            campaign.number_of_claimants += 1;
+        }
+
+        claim.status = if claim.get_claimed_amount() >= campaign.config.amount_per_claim {
+            ClaimStatus::Claimed
+        } else {
+            ClaimStatus::Unclaimed
+        };
+        claim.claiming_amount = 0;
+        claim.updated_at = current_time;
+        claim.memo = None;
+        self.claims.insert(key, claim);
+    }
+
+    /// Undoes `start_claim` after the transfer failed
+    pub fn revert_claim(&mut self, campaign_id: u64, user_principal_id: Principal) {
+        let key = (campaign_id, user_principal_id);
+        let (Some(campaign), Some(mut claim)) =
+            (self.campaigns.get_mut(&campaign_id), self.claims.get(&key))
+        else {
+            return;
+        };
+
+        if claim.claims.is_empty() {
+            campaign.allocated_budget = campaign
+                .allocated_budget
+                .saturating_sub(campaign.config.amount_per_claim);
+            self.claims.remove(&key);
+        } else {
+            claim.status = ClaimStatus::Unclaimed;
+            claim.claiming_amount = 0;
+            claim.memo = None;
+            self.claims.insert(key, claim);
+        }
+    }
+
+    /// Moves the claims kept on the heap by earlier versions. Claims that were stuck in
+    /// `Claiming` did not record the amount and can be claimed again.
+    pub fn migrate_airdrop_info(&mut self, current_time: SystemTime) {
+        for (campaign_id, campaign) in self.campaigns.iter_mut() {
+            let statuses =
+                std::mem::take(&mut campaign.airdrop_info.principals_who_successfully_claimed);
+            let mut claim_history = std::mem::take(&mut campaign.airdrop_info.claim_history);
+
+            for (user_principal_id, status) in statuses {
+                let claims = claim_history.remove(&user_principal_id).unwrap_or_default();
+                if claims.is_empty() {
+                    if status == ClaimStatus::Claiming {
+                        campaign.allocated_budget = campaign
+                            .allocated_budget
+                            .saturating_sub(campaign.config.amount_per_claim);
+                    }
+                    continue;
+                }
+
+                let claim = AirdropCampaignClaimRecord {
+                    status: if status == ClaimStatus::Claimed {
+                        ClaimStatus::Claimed
+                    } else {
+                        ClaimStatus::Unclaimed
+                    },
+                    claiming_amount: 0,
+                    updated_at: claims
+                        .last()
+                        .map(|claim| claim.claimed_at)
+                        .unwrap_or(current_time),
+                    memo: None,
+                    ledger_start_index: 0,
+                    claims,
+                };
+                campaign.distributed_amount += claim.get_claimed_amount();
+                campaign.number_of_claimants += 1;
+                self.claims.insert((*campaign_id, user_principal_id), claim);
+            }
+        }
+    }
+}
+
+fn _default_airdrop_campaign_claims(
+) -> StableBTreeMap<(u64, Principal), AirdropCampaignClaimRecord, Memory> {
+    StableBTreeMap::init(get_airdrop_campaign_claims_memory())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use shared_utils::canister_specific::individual_user_template::types::cdao::{
+        AirdropEligibility, AirdropVesting, AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_principal_id,
+    };
+
+    use super::*;
+
+    fn get_campaign_config(vesting: Option<AirdropVesting>) -> AirdropCampaignConfig {
+        AirdropCampaignConfig {
+            token_root: get_mock_user_alice_canister_id(),
+            total_budget: 250,
+            amount_per_claim: 100,
+            eligibility: AirdropEligibility::Everyone,
+            start_time: UNIX_EPOCH + Duration::from_secs(100),
+            end_time: UNIX_EPOCH + Duration::from_secs(1000),
+            vesting,
+        }
+    }
+
+    #[test]
+    fn test_create_campaign_validates_config() {
+        let mut airdrop_campaigns = AirdropCampaigns::default();
+
+        let mut config = get_campaign_config(None);
+        config.total_budget = 50;
+        assert!(matches!(
+            airdrop_campaigns.create_campaign(config, UNIX_EPOCH),
+            Err(AirdropError::InvalidCampaignConfig(_))
+        ));
+
+        assert!(matches!(
+            airdrop_campaigns.create_campaign(
+                get_campaign_config(None),
+                UNIX_EPOCH + Duration::from_secs(1000)
+            ),
+            Err(AirdropError::InvalidCampaignConfig(_))
+        ));
+
+        assert!(matches!(
+            airdrop_campaigns.create_campaign(
+                get_campaign_config(Some(AirdropVesting {
+                    number_of_tranches: 0,
+                    vesting_duration_in_seconds: 100,
+                })),
+                UNIX_EPOCH
+            ),
+            Err(AirdropError::InvalidCampaignConfig(_))
+        ));
+
+        assert_eq!(
+            airdrop_campaigns.create_campaign(get_campaign_config(None), UNIX_EPOCH),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_claims_are_limited_by_time_and_budget() {
+        let mut airdrop_campaigns = AirdropCampaigns::default();
+        let campaign_id = airdrop_campaigns
+            .create_campaign(get_campaign_config(None), UNIX_EPOCH)
+            .unwrap();
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+        let charlie = get_mock_user_charlie_principal_id();
+
+        assert_eq!(
+            airdrop_campaigns.start_claim(campaign_id, alice, None, 0, UNIX_EPOCH),
+            Err(AirdropError::CampaignNotActive)
+        );
+
+        let current_time = UNIX_EPOCH + Duration::from_secs(100);
+        assert_eq!(
+            airdrop_campaigns
+                .start_claim(campaign_id, alice, None, 0, current_time)
+                .map(|claim| claim.claiming_amount),
+            Ok(100)
+        );
+        assert_eq!(
+            airdrop_campaigns.start_claim(campaign_id, alice, None, 0, current_time),
+            Err(AirdropError::ClaimInProgress)
+        );
+        airdrop_campaigns.complete_claim(campaign_id, alice, current_time);
+        assert_eq!(
+            airdrop_campaigns.start_claim(campaign_id, alice, None, 0, current_time),
+            Err(AirdropError::AlreadyClaimedAirdrop)
+        );
+
+        // * a failed transfer gives the reserved budget back
+        assert_eq!(
+            airdrop_campaigns
+                .start_claim(campaign_id, bob, None, 0, current_time)
+                .map(|claim| claim.claiming_amount),
+            Ok(100)
+        );
+        airdrop_campaigns.revert_claim(campaign_id, bob);
+        assert_eq!(
+            airdrop_campaigns
+                .start_claim(campaign_id, bob, None, 0, current_time)
+                .map(|claim| claim.claiming_amount),
+            Ok(100)
+        );
+        airdrop_campaigns.complete_claim(campaign_id, bob, current_time);
+
+        assert_eq!(
+            airdrop_campaigns.start_claim(campaign_id, charlie, None, 0, current_time),
+            Err(AirdropError::BudgetExhausted)
+        );
+
+        let details = airdrop_campaigns.get_campaigns_of_token(get_mock_user_alice_canister_id());
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].allocated_budget, 200);
+        assert_eq!(details[0].distributed_amount, 200);
+        assert_eq!(details[0].number_of_claimants, 2);
+
+        let claim_details = airdrop_campaigns
+            .get_claim_details(campaign_id, alice, current_time)
+            .unwrap();
+        assert_eq!(claim_details.status, ClaimStatus::Claimed);
+        assert_eq!(claim_details.claimed_amount, 100);
+        assert_eq!(claim_details.claimable_amount, 0);
+    }
+
+    #[test]
+    fn test_vested_claims_are_paid_in_tranches() {
+        let mut airdrop_campaigns = AirdropCampaigns::default();
+        let campaign_id = airdrop_campaigns
+            .create_campaign(
+                get_campaign_config(Some(AirdropVesting {
+                    number_of_tranches: 2,
+                    vesting_duration_in_seconds: 2000,
+                })),
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        let alice = get_mock_user_alice_principal_id();
+
+        let first_claimed_at = UNIX_EPOCH + Duration::from_secs(500);
+        assert_eq!(
+            airdrop_campaigns
+                .start_claim(campaign_id, alice, None, 0, first_claimed_at)
+                .map(|claim| claim.claiming_amount),
+            Ok(50)
+        );
+        airdrop_campaigns.complete_claim(campaign_id, alice, first_claimed_at);
+
+        assert_eq!(
+            airdrop_campaigns.start_claim(campaign_id, alice, None, 0, first_claimed_at),
+            Err(AirdropError::NothingToClaim)
+        );
+
+        // * vested tranches can be claimed after the campaign ended
+        let current_time = first_claimed_at + Duration::from_secs(2000);
+        let claim_details = airdrop_campaigns
+            .get_claim_details(campaign_id, alice, current_time)
+            .unwrap();
+        assert_eq!(claim_details.status, ClaimStatus::Unclaimed);
+        assert_eq!(claim_details.claimable_amount, 50);
+
+        assert_eq!(
+            airdrop_campaigns
+                .start_claim(campaign_id, alice, None, 0, current_time)
+                .map(|claim| claim.claiming_amount),
+            Ok(50)
+        );
+        airdrop_campaigns.complete_claim(campaign_id, alice, current_time);
+
+        let claim_details = airdrop_campaigns
+            .get_claim_details(campaign_id, alice, current_time)
+            .unwrap();
+        assert_eq!(claim_details.status, ClaimStatus::Claimed);
+        assert_eq!(claim_details.claims.len(), 2);
+    }
+
+    #[test]
+    fn test_get_reserved_budget() {
+        let mut airdrop_campaigns = AirdropCampaigns::default();
+        let token_root = get_mock_user_alice_canister_id();
+        let campaign_id = airdrop_campaigns
+            .create_campaign(
+                get_campaign_config(Some(AirdropVesting {
+                    number_of_tranches: 2,
+                    vesting_duration_in_seconds: 2000,
+                })),
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        airdrop_campaigns
+            .create_campaign(get_campaign_config(None), UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(
+            airdrop_campaigns.get_reserved_budget(token_root, UNIX_EPOCH),
+            500
+        );
+        assert_eq!(
+            airdrop_campaigns.get_reserved_budget(get_mock_user_bob_principal_id(), UNIX_EPOCH),
+            0
+        );
+
+        let alice = get_mock_user_alice_principal_id();
+        let claimed_at = UNIX_EPOCH + Duration::from_secs(500);
+        airdrop_campaigns
+            .start_claim(campaign_id, alice, None, 0, claimed_at)
+            .unwrap();
+        airdrop_campaigns.complete_claim(campaign_id, alice, claimed_at);
+        assert_eq!(
+            airdrop_campaigns.get_reserved_budget(token_root, claimed_at),
+            450
+        );
+
+        // * once ended, only the tranches still owed to the claimants stay reserved
+        let ended_at = UNIX_EPOCH + Duration::from_secs(1000);
+        assert_eq!(
+            airdrop_campaigns.get_reserved_budget(token_root, ended_at),
+            50
+        );
+    }
+
+    #[test]
+    fn test_claim_can_be_retried_after_the_claiming_timeout() {
+        let mut airdrop_campaigns = AirdropCampaigns::default();
+        let campaign_id = airdrop_campaigns
+            .create_campaign(get_campaign_config(None), UNIX_EPOCH)
+            .unwrap();
+        let alice = get_mock_user_alice_principal_id();
+
+        let started_at = UNIX_EPOCH + Duration::from_secs(100);
+        let claim = airdrop_campaigns
+            .start_claim(campaign_id, alice, Some(Memo::from(1)), 7, started_at)
+            .unwrap();
+        assert_eq!(claim.status, ClaimStatus::Claiming);
+
+        // * the retry transfers the same amount with the same memo and `created_at_time`
+        let timed_out_at = started_at + Duration::from_secs(AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS);
+        assert_eq!(
+            airdrop_campaigns.start_claim(campaign_id, alice, None, 9, timed_out_at),
+            Ok(claim)
+        );
+        assert_eq!(
+            airdrop_campaigns.get_campaigns_of_token(get_mock_user_alice_canister_id())[0]
+                .allocated_budget,
+            100
+        );
+
+        airdrop_campaigns.complete_claim(campaign_id, alice, timed_out_at);
+        let claim_details = airdrop_campaigns
+            .get_claim_details(campaign_id, alice, timed_out_at)
+            .unwrap();
+        assert_eq!(claim_details.status, ClaimStatus::Claimed);
+        assert_eq!(claim_details.claimed_amount, 100);
+    }
+
+    #[test]
+    fn test_migrate_airdrop_info() {
+        let mut airdrop_campaigns = AirdropCampaigns::default();
+        let campaign_id = airdrop_campaigns
+            .create_campaign(get_campaign_config(None), UNIX_EPOCH)
+            .unwrap();
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+
+        let campaign = airdrop_campaigns.campaigns.get_mut(&campaign_id).unwrap();
+        campaign.allocated_budget = 200;
+        campaign.airdrop_info.set_airdrop_claimed(alice);
+        campaign.airdrop_info.add_claim(
+            alice,
+            AirdropClaim {
+                amount: 100,
+                claimed_at: UNIX_EPOCH + Duration::from_secs(10),
+            },
+        );
+        campaign.airdrop_info.set_airdrop_claiming(bob);
+
+        airdrop_campaigns.migrate_airdrop_info(UNIX_EPOCH);
+
+        let details = airdrop_campaigns.get_campaigns_of_token(get_mock_user_alice_canister_id());
+        assert_eq!(details[0].allocated_budget, 100);
+        assert_eq!(details[0].distributed_amount, 100);
+        assert_eq!(details[0].number_of_claimants, 1);
+
+        let claim_details = airdrop_campaigns
+            .get_claim_details(campaign_id, alice, UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(claim_details.status, ClaimStatus::Claimed);
+        assert_eq!(claim_details.claimed_amount, 100);
+        let claim_details = airdrop_campaigns
+            .get_claim_details(campaign_id, bob, UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(claim_details.status, ClaimStatus::Unclaimed);
+        assert_eq!(
+            airdrop_campaigns.campaigns[&campaign_id].airdrop_info,
+            AirdropInfo::default()
+        );
+    }
+}
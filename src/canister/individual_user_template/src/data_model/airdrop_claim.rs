@@ -0,0 +1,326 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use ic_stable_structures::StableBTreeMap;
+use icrc_ledger_types::icrc1::transfer::Memo;
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::{
+    cdao::{AirdropClaimRecord, AirdropClaimant, AirdropInfo, ClaimStatus},
+    error::AirdropError,
+};
+
+use super::memory::{get_airdrop_claims_memory, Memory};
+
+/// Claims of the open airdrops of the creator's tokens. A principal without a claim has not
+/// claimed.
+#[derive(Serialize, Deserialize)]
+pub struct AirdropClaims {
+    // Key is the root canister id of the token and the claimant's principal
+    #[serde(skip, default = "_default_airdrop_claims")]
+    claims: StableBTreeMap<(Principal, Principal), AirdropClaimRecord, Memory>,
+}
+
+impl Default for AirdropClaims {
+    fn default() -> Self {
+        Self {
+            claims: _default_airdrop_claims(),
+        }
+    }
+}
+
+impl AirdropClaims {
+    pub fn get_claim(
+        &self,
+        token_root: Principal,
+        user_principal_id: Principal,
+    ) -> Option<AirdropClaimRecord> {
+        self.claims.get(&(token_root, user_principal_id))
+    }
+
+    /// Marks the principal as claiming and returns the claim to transfer. A claim that is stuck
+    /// in `Claiming` past the timeout is returned as it was, to retry the same transfer.
+    /// `ledger_start_index` is the current length of the ledger's transaction log.
+    pub fn start_claim(
+        &mut self,
+        token_root: Principal,
+        user_principal_id: Principal,
+        amount: u64,
+        memo: Option<Memo>,
+        ledger_start_index: u64,
+        current_time: SystemTime,
+    ) -> Result<AirdropClaimRecord, AirdropError> {
+        let key = (token_root, user_principal_id);
+
+        match self.claims.get(&key) {
+            Some(claim) if claim.status == ClaimStatus::Claimed => {
+                Err(AirdropError::AlreadyClaimedAirdrop)
+            }
+            Some(claim) if claim.status == ClaimStatus::Claiming => {
+                if claim.is_claiming_timed_out(current_time) {
+                    Ok(claim)
+                } else {
+                    Err(AirdropError::ClaimInProgress)
+                }
+            }
+            _ => {
+                let claim = AirdropClaimRecord {
+                    status: ClaimStatus::Claiming,
+                    amount,
+                    updated_at: current_time,
+                    memo,
+                    ledger_start_index: Some(ledger_start_index),
+                };
+                self.claims.insert(key, claim.clone());
+                Ok(claim)
+            }
+        }
+    }
+
+    pub fn complete_claim(
+        &mut self,
+        token_root: Principal,
+        user_principal_id: Principal,
+        current_time: SystemTime,
+    ) {
+        let key = (token_root, user_principal_id);
+        if let Some(mut claim) = self.claims.get(&key) {
+            claim.status = ClaimStatus::Claimed;
+            claim.updated_at = current_time;
+            claim.memo = None;
+            claim.ledger_start_index = None;
+            self.claims.insert(key, claim);
+        }
+    }
+
+    /// Undoes `start_claim` after the transfer failed
+    pub fn revert_claim(&mut self, token_root: Principal, user_principal_id: Principal) {
+        self.claims.remove(&(token_root, user_principal_id));
+    }
+
+    /// Tokens of the claims whose transfer may still be made
+    pub fn get_claiming_amount(&self, token_root: Principal) -> u64 {
+        self.claims
+            .range((token_root, Principal::from_slice(&[]))..)
+            .take_while(|((root, _), _)| *root == token_root)
+            .filter(|(_, claim)| claim.status == ClaimStatus::Claiming)
+            .map(|(_, claim)| claim.amount)
+            .sum()
+    }
+
+    /// Claimants of the token ordered by principal, starting after `start_after`
+    pub fn get_claimants(
+        &self,
+        token_root: Principal,
+        start_after: Option<Principal>,
+        limit: u64,
+    ) -> Vec<AirdropClaimant> {
+        let start_key = (
+            token_root,
+            start_after.unwrap_or(Principal::from_slice(&[])),
+        );
+
+        self.claims
+            .range(start_key..)
+            .take_while(|((root, _), _)| *root == token_root)
+            .filter(|((_, principal_id), _)| Some(*principal_id) != start_after)
+            .take(limit as usize)
+            .map(|((_, principal_id), claim)| AirdropClaimant {
+                principal_id,
+                claim,
+            })
+            .collect()
+    }
+
+    pub fn remove_claims_of_token(&mut self, token_root: Principal) {
+        let principal_ids: Vec<Principal> = self
+            .claims
+            .range((token_root, Principal::from_slice(&[]))..)
+            .take_while(|((root, _), _)| *root == token_root)
+            .map(|((_, principal_id), _)| principal_id)
+            .collect();
+
+        for principal_id in principal_ids {
+            self.claims.remove(&(token_root, principal_id));
+        }
+    }
+
+    /// Moves the claims kept on the heap by earlier versions. Claims that were stuck in
+    /// `Claiming` did not record the amount and are dropped, so they can be claimed again.
+    pub fn migrate_airdrop_info(
+        &mut self,
+        token_root: Principal,
+        airdrop_info: &mut AirdropInfo,
+        current_time: SystemTime,
+    ) {
+        let statuses = std::mem::take(&mut airdrop_info.principals_who_successfully_claimed);
+        let claim_history = std::mem::take(&mut airdrop_info.claim_history);
+
+        for (user_principal_id, status) in statuses {
+            if status != ClaimStatus::Claimed {
+                continue;
+            }
+
+            let claims = claim_history
+                .get(&user_principal_id)
+                .map(|claims| claims.as_slice())
+                .unwrap_or_default();
+            self.claims.insert(
+                (token_root, user_principal_id),
+                AirdropClaimRecord {
+                    status,
+                    amount: claims.iter().map(|claim| claim.amount).sum(),
+                    updated_at: claims
+                        .last()
+                        .map(|claim| claim.claimed_at)
+                        .unwrap_or(current_time),
+                    memo: None,
+                    ledger_start_index: None,
+                },
+            );
+        }
+    }
+}
+
+fn _default_airdrop_claims() -> StableBTreeMap<(Principal, Principal), AirdropClaimRecord, Memory> {
+    StableBTreeMap::init(get_airdrop_claims_memory())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use shared_utils::canister_specific::individual_user_template::types::cdao::{
+        AirdropClaim, AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+        get_mock_user_charlie_principal_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_claim_can_be_retried_after_the_claiming_timeout() {
+        let mut airdrop_claims = AirdropClaims::default();
+        let token_root = get_mock_user_alice_canister_id();
+        let alice = get_mock_user_alice_principal_id();
+
+        let claim = airdrop_claims
+            .start_claim(token_root, alice, 100, None, 0, UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(claim.status, ClaimStatus::Claiming);
+        assert_eq!(
+            airdrop_claims.start_claim(token_root, alice, 200, None, 0, UNIX_EPOCH),
+            Err(AirdropError::ClaimInProgress)
+        );
+
+        let timed_out_at = UNIX_EPOCH + Duration::from_secs(AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS);
+        assert_eq!(
+            airdrop_claims.start_claim(token_root, alice, 200, None, 5, timed_out_at),
+            Ok(claim)
+        );
+
+        airdrop_claims.complete_claim(token_root, alice, timed_out_at);
+        assert_eq!(
+            airdrop_claims.start_claim(token_root, alice, 100, None, 0, timed_out_at),
+            Err(AirdropError::AlreadyClaimedAirdrop)
+        );
+        let claim = airdrop_claims.get_claim(token_root, alice).unwrap();
+        assert_eq!(claim.status, ClaimStatus::Claimed);
+        assert_eq!(claim.amount, 100);
+
+        // * a failed transfer can be retried right away
+        let bob = get_mock_user_bob_principal_id();
+        airdrop_claims
+            .start_claim(token_root, bob, 100, None, 0, UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(airdrop_claims.get_claiming_amount(token_root), 100);
+        airdrop_claims.revert_claim(token_root, bob);
+        assert_eq!(airdrop_claims.get_claiming_amount(token_root), 0);
+        assert!(airdrop_claims.get_claim(token_root, bob).is_none());
+    }
+
+    #[test]
+    fn test_get_claimants_is_paginated_per_token() {
+        let mut airdrop_claims = AirdropClaims::default();
+        let token_root = get_mock_user_alice_canister_id();
+        let other_token_root = get_mock_user_bob_canister_id();
+        let claimants = [
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+            get_mock_user_charlie_principal_id(),
+        ];
+
+        for claimant in claimants {
+            for root in [token_root, other_token_root] {
+                airdrop_claims
+                    .start_claim(root, claimant, 100, None, 0, UNIX_EPOCH)
+                    .unwrap();
+                airdrop_claims.complete_claim(root, claimant, UNIX_EPOCH);
+            }
+        }
+
+        let mut sorted_claimants = claimants.to_vec();
+        sorted_claimants.sort();
+
+        let first_page = airdrop_claims.get_claimants(token_root, None, 2);
+        assert_eq!(
+            first_page
+                .iter()
+                .map(|claimant| claimant.principal_id)
+                .collect::<Vec<_>>(),
+            sorted_claimants[..2]
+        );
+
+        let second_page =
+            airdrop_claims.get_claimants(token_root, Some(first_page[1].principal_id), 2);
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].principal_id, sorted_claimants[2]);
+
+        airdrop_claims.remove_claims_of_token(token_root);
+        assert!(airdrop_claims
+            .get_claimants(token_root, None, 10)
+            .is_empty());
+        assert_eq!(
+            airdrop_claims
+                .get_claimants(other_token_root, None, 10)
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_migrate_airdrop_info() {
+        let mut airdrop_claims = AirdropClaims::default();
+        let token_root = get_mock_user_alice_canister_id();
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+
+        let mut airdrop_info = AirdropInfo::default();
+        airdrop_info.set_airdrop_claimed(alice);
+        airdrop_info.add_claim(
+            alice,
+            AirdropClaim {
+                amount: 100,
+                claimed_at: UNIX_EPOCH + Duration::from_secs(10),
+            },
+        );
+        airdrop_info.set_airdrop_claiming(bob);
+
+        airdrop_claims.migrate_airdrop_info(token_root, &mut airdrop_info, UNIX_EPOCH);
+
+        assert_eq!(
+            airdrop_claims.get_claim(token_root, alice),
+            Some(AirdropClaimRecord {
+                status: ClaimStatus::Claimed,
+                amount: 100,
+                updated_at: UNIX_EPOCH + Duration::from_secs(10),
+                memo: None,
+                ledger_start_index: None,
+            })
+        );
+        assert!(airdrop_claims.get_claim(token_root, bob).is_none());
+        assert_eq!(airdrop_info, AirdropInfo::default());
+    }
+}
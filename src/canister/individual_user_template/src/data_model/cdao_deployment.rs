@@ -0,0 +1,417 @@
+use std::{collections::BTreeMap, time::SystemTime};
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::cdao::{
+    CdaoDeploymentDetails, CdaoDeploymentStatus, CdaoDeploymentStep,
+};
+
+/// Failed deployments kept for the admin query, the oldest are dropped first
+pub const MAX_FAILED_CDAO_DEPLOYMENTS: usize = 20;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CdaoDeployment {
+    /// Candid encoded `SnsInitPayload`
+    pub init_payload: Vec<u8>,
+    pub swap_time: u64,
+    pub status: CdaoDeploymentStatus,
+    pub step: CdaoDeploymentStep,
+    /// Allotted canisters, in the order governance, ledger, root, swap and index. Kept once the
+    /// deployment failed, as the canisters that were returned.
+    pub canister_ids: Vec<Principal>,
+    /// Allotted canisters the initial cycles were deposited to, so that a resumed deposit does
+    /// not fund a canister twice
+    #[serde(default)]
+    pub funded_canister_ids: Vec<Principal>,
+    pub error: Option<String>,
+    /// Error of the last compensation that failed, cleared once it succeeds
+    #[serde(default)]
+    pub compensation_error: Option<String>,
+    started_at: SystemTime,
+    updated_at: SystemTime,
+}
+
+impl CdaoDeployment {
+    pub fn is_unfinished(&self) -> bool {
+        matches!(
+            self.status,
+            CdaoDeploymentStatus::InProgress | CdaoDeploymentStatus::RollingBack
+        )
+    }
+}
+
+/// Deployments that are running, rolling back or failed. A deployment is removed once it
+/// completes.
+#[derive(Default, Serialize, Deserialize)]
+pub struct CdaoDeployments {
+    next_deployment_id: u64,
+    deployments: BTreeMap<u64, CdaoDeployment>,
+}
+
+impl CdaoDeployments {
+    pub fn start(
+        &mut self,
+        init_payload: Vec<u8>,
+        swap_time: u64,
+        current_time: SystemTime,
+    ) -> u64 {
+        let deployment_id = self.next_deployment_id;
+        self.next_deployment_id += 1;
+
+        self.deployments.insert(
+            deployment_id,
+            CdaoDeployment {
+                init_payload,
+                swap_time,
+                status: CdaoDeploymentStatus::InProgress,
+                step: CdaoDeploymentStep::AllotCanisters,
+                canister_ids: vec![],
+                funded_canister_ids: vec![],
+                error: None,
+                compensation_error: None,
+                started_at: current_time,
+                updated_at: current_time,
+            },
+        );
+
+        deployment_id
+    }
+
+    /// Removes a deployment that did not allot any canister yet, releasing its quota slot.
+    pub fn cancel(&mut self, deployment_id: u64) {
+        let is_not_started = self
+            .deployments
+            .get(&deployment_id)
+            .is_some_and(|deployment| {
+                deployment.status == CdaoDeploymentStatus::InProgress
+                    && deployment.step == CdaoDeploymentStep::AllotCanisters
+                    && deployment.canister_ids.is_empty()
+            });
+
+        if is_not_started {
+            self.deployments.remove(&deployment_id);
+        }
+    }
+
+    pub fn get(&self, deployment_id: u64) -> Option<&CdaoDeployment> {
+        self.deployments.get(&deployment_id)
+    }
+
+    pub fn add_canister_id(
+        &mut self,
+        deployment_id: u64,
+        canister_id: Principal,
+        current_time: SystemTime,
+    ) {
+        if let Some(deployment) = self.deployments.get_mut(&deployment_id) {
+            deployment.canister_ids.push(canister_id);
+            deployment.updated_at = current_time;
+        }
+    }
+
+    pub fn add_funded_canister_id(
+        &mut self,
+        deployment_id: u64,
+        canister_id: Principal,
+        current_time: SystemTime,
+    ) {
+        if let Some(deployment) = self.deployments.get_mut(&deployment_id) {
+            if !deployment.funded_canister_ids.contains(&canister_id) {
+                deployment.funded_canister_ids.push(canister_id);
+                deployment.updated_at = current_time;
+            }
+        }
+    }
+
+    /// Moves an in progress deployment to its next step, and removes it after the last one.
+    pub fn complete_step(&mut self, deployment_id: u64, current_time: SystemTime) {
+        let Some(deployment) = self.deployments.get_mut(&deployment_id) else {
+            return;
+        };
+        if deployment.status != CdaoDeploymentStatus::InProgress {
+            return;
+        }
+
+        match deployment.step.next() {
+            Some(next_step) => {
+                deployment.step = next_step;
+                deployment.updated_at = current_time;
+            }
+            None => {
+                self.deployments.remove(&deployment_id);
+            }
+        }
+    }
+
+    /// Rolls back from the step that failed, which may have partially run.
+    pub fn start_rollback(&mut self, deployment_id: u64, error: String, current_time: SystemTime) {
+        if let Some(deployment) = self.deployments.get_mut(&deployment_id) {
+            if deployment.status == CdaoDeploymentStatus::InProgress {
+                deployment.status = CdaoDeploymentStatus::RollingBack;
+                deployment.error = Some(error);
+                deployment.updated_at = current_time;
+            }
+        }
+    }
+
+    /// Keeps a rolling back deployment at the step whose compensation failed.
+    pub fn record_compensation_error(
+        &mut self,
+        deployment_id: u64,
+        error: String,
+        current_time: SystemTime,
+    ) {
+        if let Some(deployment) = self.deployments.get_mut(&deployment_id) {
+            if deployment.status == CdaoDeploymentStatus::RollingBack {
+                deployment.compensation_error = Some(error);
+                deployment.updated_at = current_time;
+            }
+        }
+    }
+
+    /// Moves a rolling back deployment to the step to compensate next, and marks it failed once
+    /// the first step was compensated.
+    pub fn complete_compensation(&mut self, deployment_id: u64, current_time: SystemTime) {
+        let Some(deployment) = self.deployments.get_mut(&deployment_id) else {
+            return;
+        };
+        if deployment.status != CdaoDeploymentStatus::RollingBack {
+            return;
+        }
+
+        deployment.updated_at = current_time;
+        deployment.compensation_error = None;
+        match deployment.step.previous() {
+            Some(previous_step) => deployment.step = previous_step,
+            None => {
+                deployment.status = CdaoDeploymentStatus::Failed;
+                self.remove_excess_failed_deployments();
+            }
+        }
+    }
+
+    fn remove_excess_failed_deployments(&mut self) {
+        let failed_deployment_ids: Vec<u64> = self
+            .deployments
+            .iter()
+            .filter(|(_, deployment)| deployment.status == CdaoDeploymentStatus::Failed)
+            .map(|(deployment_id, _)| *deployment_id)
+            .collect();
+
+        let number_of_excess_deployments = failed_deployment_ids
+            .len()
+            .saturating_sub(MAX_FAILED_CDAO_DEPLOYMENTS);
+
+        for deployment_id in failed_deployment_ids
+            .into_iter()
+            .take(number_of_excess_deployments)
+        {
+            self.deployments.remove(&deployment_id);
+        }
+    }
+
+    pub fn get_number_of_unfinished_deployments(&self) -> usize {
+        self.deployments
+            .values()
+            .filter(|deployment| deployment.is_unfinished())
+            .count()
+    }
+
+    pub fn get_unfinished_deployment_ids(&self) -> Vec<u64> {
+        self.deployments
+            .iter()
+            .filter(|(_, deployment)| deployment.is_unfinished())
+            .map(|(deployment_id, _)| *deployment_id)
+            .collect()
+    }
+
+    pub fn get_deployment_details(&self) -> Vec<CdaoDeploymentDetails> {
+        self.deployments
+            .iter()
+            .map(|(deployment_id, deployment)| CdaoDeploymentDetails {
+                deployment_id: *deployment_id,
+                status: deployment.status,
+                step: deployment.step,
+                canister_ids: deployment.canister_ids.clone(),
+                error: deployment.error.clone(),
+                compensation_error: deployment.compensation_error.clone(),
+                started_at: deployment.started_at,
+                updated_at: deployment.updated_at,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    #[test]
+    fn test_deployment_runs_every_step_and_is_removed_when_completed() {
+        let mut cdao_deployments = CdaoDeployments::default();
+        let deployment_id = cdao_deployments.start(vec![], 300, UNIX_EPOCH);
+        cdao_deployments.add_canister_id(
+            deployment_id,
+            get_mock_user_alice_canister_id(),
+            UNIX_EPOCH,
+        );
+
+        let mut steps = vec![];
+        while let Some(deployment) = cdao_deployments.get(deployment_id) {
+            steps.push(deployment.step);
+            cdao_deployments.complete_step(deployment_id, UNIX_EPOCH);
+        }
+
+        assert_eq!(
+            steps,
+            vec![
+                CdaoDeploymentStep::AllotCanisters,
+                CdaoDeploymentStep::DepositCycles,
+                CdaoDeploymentStep::InstallWasms,
+                CdaoDeploymentStep::UpdateControllers,
+                CdaoDeploymentStep::Register,
+            ]
+        );
+        assert_eq!(cdao_deployments.get_number_of_unfinished_deployments(), 0);
+    }
+
+    #[test]
+    fn test_rollback_compensates_steps_in_reverse_order() {
+        let mut cdao_deployments = CdaoDeployments::default();
+        let deployment_id = cdao_deployments.start(vec![], 300, UNIX_EPOCH);
+        cdao_deployments.add_canister_id(
+            deployment_id,
+            get_mock_user_alice_canister_id(),
+            UNIX_EPOCH,
+        );
+        cdao_deployments.complete_step(deployment_id, UNIX_EPOCH);
+        cdao_deployments.complete_step(deployment_id, UNIX_EPOCH);
+
+        let failed_at = UNIX_EPOCH + Duration::from_secs(10);
+        cdao_deployments.start_rollback(deployment_id, "install failed".into(), failed_at);
+        assert_eq!(
+            cdao_deployments.get_unfinished_deployment_ids(),
+            vec![deployment_id]
+        );
+
+        // * steps are not advanced while rolling back
+        cdao_deployments.complete_step(deployment_id, failed_at);
+        assert_eq!(
+            cdao_deployments.get(deployment_id).unwrap().step,
+            CdaoDeploymentStep::InstallWasms
+        );
+
+        // * a failed compensation keeps the deployment rolling back at the same step
+        cdao_deployments.record_compensation_error(
+            deployment_id,
+            "uninstall failed".into(),
+            failed_at,
+        );
+        let deployment = cdao_deployments.get(deployment_id).unwrap();
+        assert_eq!(deployment.status, CdaoDeploymentStatus::RollingBack);
+        assert_eq!(deployment.step, CdaoDeploymentStep::InstallWasms);
+        assert_eq!(
+            deployment.compensation_error,
+            Some("uninstall failed".into())
+        );
+
+        let mut compensated_steps = vec![];
+        while cdao_deployments.get(deployment_id).unwrap().status
+            == CdaoDeploymentStatus::RollingBack
+        {
+            compensated_steps.push(cdao_deployments.get(deployment_id).unwrap().step);
+            cdao_deployments.complete_compensation(deployment_id, failed_at);
+        }
+
+        assert_eq!(
+            compensated_steps,
+            vec![
+                CdaoDeploymentStep::InstallWasms,
+                CdaoDeploymentStep::DepositCycles,
+                CdaoDeploymentStep::AllotCanisters,
+            ]
+        );
+
+        let details = cdao_deployments.get_deployment_details();
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].status, CdaoDeploymentStatus::Failed);
+        assert_eq!(details[0].error, Some("install failed".into()));
+        assert_eq!(details[0].compensation_error, None);
+        assert_eq!(
+            details[0].canister_ids,
+            vec![get_mock_user_alice_canister_id()]
+        );
+        assert_eq!(cdao_deployments.get_number_of_unfinished_deployments(), 0);
+    }
+
+    #[test]
+    fn test_funded_canisters_are_recorded_once() {
+        let mut cdao_deployments = CdaoDeployments::default();
+        let deployment_id = cdao_deployments.start(vec![], 300, UNIX_EPOCH);
+        cdao_deployments.add_canister_id(
+            deployment_id,
+            get_mock_user_alice_canister_id(),
+            UNIX_EPOCH,
+        );
+
+        cdao_deployments.add_funded_canister_id(
+            deployment_id,
+            get_mock_user_alice_canister_id(),
+            UNIX_EPOCH,
+        );
+        cdao_deployments.add_funded_canister_id(
+            deployment_id,
+            get_mock_user_alice_canister_id(),
+            UNIX_EPOCH,
+        );
+
+        assert_eq!(
+            cdao_deployments
+                .get(deployment_id)
+                .unwrap()
+                .funded_canister_ids,
+            vec![get_mock_user_alice_canister_id()]
+        );
+    }
+
+    #[test]
+    fn test_only_deployments_that_did_not_allot_canisters_are_cancelled() {
+        let mut cdao_deployments = CdaoDeployments::default();
+        let deployment_id = cdao_deployments.start(vec![], 300, UNIX_EPOCH);
+        let started_deployment_id = cdao_deployments.start(vec![], 300, UNIX_EPOCH);
+        cdao_deployments.add_canister_id(
+            started_deployment_id,
+            get_mock_user_alice_canister_id(),
+            UNIX_EPOCH,
+        );
+        assert_eq!(cdao_deployments.get_number_of_unfinished_deployments(), 2);
+
+        cdao_deployments.cancel(deployment_id);
+        cdao_deployments.cancel(started_deployment_id);
+
+        assert!(cdao_deployments.get(deployment_id).is_none());
+        assert_eq!(
+            cdao_deployments.get_unfinished_deployment_ids(),
+            vec![started_deployment_id]
+        );
+    }
+
+    #[test]
+    fn test_failed_deployments_are_capped() {
+        let mut cdao_deployments = CdaoDeployments::default();
+
+        for _ in 0..MAX_FAILED_CDAO_DEPLOYMENTS + 2 {
+            let deployment_id = cdao_deployments.start(vec![], 300, UNIX_EPOCH);
+            cdao_deployments.start_rollback(deployment_id, "allot failed".into(), UNIX_EPOCH);
+            cdao_deployments.complete_compensation(deployment_id, UNIX_EPOCH);
+        }
+
+        let details = cdao_deployments.get_deployment_details();
+        assert_eq!(details.len(), MAX_FAILED_CDAO_DEPLOYMENTS);
+        assert_eq!(details[0].deployment_id, 2);
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::creator_token_quota::CreatorTokenQuotaConfig;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct CreatorTokenQuotaSettings {
+    config: CreatorTokenQuotaConfig,
+    /// Quota of this user set by the platform, which replaces the config
+    quota_override: Option<u64>,
+}
+
+impl CreatorTokenQuotaSettings {
+    pub fn get_config(&self) -> CreatorTokenQuotaConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: CreatorTokenQuotaConfig) {
+        self.config = config;
+    }
+
+    pub fn get_quota_override(&self) -> Option<u64> {
+        self.quota_override
+    }
+
+    pub fn set_quota_override(&mut self, quota_override: Option<u64>) {
+        self.quota_override = quota_override;
+    }
+
+    /// An override applies whether or not the account meets the criteria of the config.
+    pub fn get_effective_quota(&self, is_registered: bool, account_age: Option<Duration>) -> u64 {
+        if let Some(quota_override) = self.quota_override {
+            return quota_override;
+        }
+
+        if self.config.require_registered_session && !is_registered {
+            return 0;
+        }
+
+        let is_account_old_enough = self.config.min_account_age_in_seconds == 0
+            || account_age
+                .is_some_and(|age| age.as_secs() >= self.config.min_account_age_in_seconds);
+        if !is_account_old_enough {
+            return 0;
+        }
+
+        self.config.default_quota
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_effective_quota() {
+        let mut quota_settings = CreatorTokenQuotaSettings::default();
+        assert_eq!(quota_settings.get_effective_quota(false, None), 2);
+
+        quota_settings.set_config(CreatorTokenQuotaConfig {
+            default_quota: 5,
+            require_registered_session: true,
+            min_account_age_in_seconds: 100,
+        });
+        assert_eq!(
+            quota_settings.get_effective_quota(false, Some(Duration::from_secs(100))),
+            0
+        );
+        assert_eq!(
+            quota_settings.get_effective_quota(true, Some(Duration::from_secs(99))),
+            0
+        );
+        assert_eq!(quota_settings.get_effective_quota(true, None), 0);
+        assert_eq!(
+            quota_settings.get_effective_quota(true, Some(Duration::from_secs(100))),
+            5
+        );
+
+        // * an override replaces the config and its criteria
+        quota_settings.set_quota_override(Some(1));
+        assert_eq!(quota_settings.get_effective_quota(false, None), 1);
+        quota_settings.set_quota_override(Some(0));
+        assert_eq!(
+            quota_settings.get_effective_quota(true, Some(Duration::from_secs(100))),
+            0
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::engagement_reward::{
+    EngagementMilestone, EngagementRewardsConfig,
+};
+
+#[derive(Default, Serialize, Deserialize, Clone, Copy)]
+struct LoginStreak {
+    /// Days since the unix epoch
+    last_access_day: Option<u64>,
+    streak_days: u64,
+}
+
+/// Enough distinct posts to reach any sensible daily threshold
+const MAX_POSTS_WATCHED_TRACKED_PER_DAY: usize = 1000;
+
+/// Posts this canister was told about on the day it was told, by the time it received them and
+/// not by the time the client says the post was viewed.
+#[derive(Default, Serialize, Deserialize)]
+struct PostsWatchedOnDay {
+    /// Days since the unix epoch
+    day: u64,
+    // Publisher canister id and post id
+    posts: BTreeSet<(Principal, u64)>,
+}
+
+#[derive(Default, Serialize, Deserialize, Clone, Copy)]
+pub struct MilestoneClaims {
+    pub total_rewarded_amount: u64,
+    pub last_claimed_day: Option<u64>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct EngagementRewards {
+    config: EngagementRewardsConfig,
+    login_streak: LoginStreak,
+    claims: BTreeMap<EngagementMilestone, MilestoneClaims>,
+    #[serde(default)]
+    posts_watched_today: PostsWatchedOnDay,
+}
+
+impl EngagementRewards {
+    pub fn get_config(&self) -> EngagementRewardsConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: EngagementRewardsConfig) {
+        self.config = config;
+    }
+
+    /// Extends the streak when the user was last seen the day before, and restarts it after a
+    /// missed day.
+    pub fn record_access(&mut self, today: u64) {
+        let streak = &mut self.login_streak;
+
+        streak.streak_days = match streak.last_access_day {
+            Some(last_access_day) if last_access_day == today => streak.streak_days,
+            Some(last_access_day) if last_access_day + 1 == today => streak.streak_days + 1,
+            _ => 1,
+        };
+        streak.last_access_day = Some(today);
+    }
+
+    /// A streak still counts on the day after the last access, until it is extended or broken.
+    pub fn get_login_streak_days(&self, today: u64) -> u64 {
+        match self.login_streak.last_access_day {
+            Some(last_access_day) if last_access_day == today || last_access_day + 1 == today => {
+                self.login_streak.streak_days
+            }
+            _ => 0,
+        }
+    }
+
+    /// Only counts streaks that include today, as the streak reward is for showing up today.
+    pub fn get_todays_login_streak_days(&self, today: u64) -> u64 {
+        if self.login_streak.last_access_day == Some(today) {
+            self.login_streak.streak_days
+        } else {
+            0
+        }
+    }
+
+    /// Counts each post once a day. Watching the same post again adds nothing.
+    pub fn record_post_watched(
+        &mut self,
+        today: u64,
+        publisher_canister_id: Principal,
+        post_id: u64,
+    ) {
+        let posts_watched = &mut self.posts_watched_today;
+        if posts_watched.day != today {
+            posts_watched.day = today;
+            posts_watched.posts.clear();
+        }
+
+        if posts_watched.posts.len() < MAX_POSTS_WATCHED_TRACKED_PER_DAY {
+            posts_watched.posts.insert((publisher_canister_id, post_id));
+        }
+    }
+
+    pub fn get_posts_watched(&self, today: u64) -> u64 {
+        if self.posts_watched_today.day == today {
+            self.posts_watched_today.posts.len() as u64
+        } else {
+            0
+        }
+    }
+
+    pub fn get_claims(&self, milestone: EngagementMilestone) -> MilestoneClaims {
+        self.claims.get(&milestone).copied().unwrap_or_default()
+    }
+
+    /// Amount a claim for the milestone would mint today, zero once it was claimed for today (or
+    /// at all for one time milestones) or its cap is used up.
+    pub fn get_claimable_amount(&self, milestone: EngagementMilestone, today: u64) -> u64 {
+        let claims = self.get_claims(milestone);
+
+        let already_claimed = if milestone.is_daily() {
+            claims.last_claimed_day == Some(today)
+        } else {
+            claims.last_claimed_day.is_some()
+        };
+
+        if already_claimed {
+            return 0;
+        }
+
+        let rule = self.config.get_rule(milestone);
+        rule.reward_amount.min(
+            rule.max_total_reward_amount
+                .saturating_sub(claims.total_rewarded_amount),
+        )
+    }
+
+    pub fn record_claim(&mut self, milestone: EngagementMilestone, today: u64, amount: u64) {
+        let claims = self.claims.entry(milestone).or_default();
+        claims.total_rewarded_amount = claims.total_rewarded_amount.saturating_add(amount);
+        claims.last_claimed_day = Some(today);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::canister_specific::individual_user_template::types::engagement_reward::EngagementRewardRule;
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    #[test]
+    fn test_login_streak() {
+        let mut engagement_rewards = EngagementRewards::default();
+        assert_eq!(engagement_rewards.get_login_streak_days(10), 0);
+
+        engagement_rewards.record_access(10);
+        engagement_rewards.record_access(10);
+        engagement_rewards.record_access(11);
+        engagement_rewards.record_access(12);
+        assert_eq!(engagement_rewards.get_todays_login_streak_days(12), 3);
+        assert_eq!(engagement_rewards.get_login_streak_days(13), 3);
+        assert_eq!(engagement_rewards.get_todays_login_streak_days(13), 0);
+        assert_eq!(engagement_rewards.get_login_streak_days(14), 0);
+
+        // * a missed day restarts the streak
+        engagement_rewards.record_access(14);
+        assert_eq!(engagement_rewards.get_todays_login_streak_days(14), 1);
+    }
+
+    #[test]
+    fn test_posts_watched() {
+        let mut engagement_rewards = EngagementRewards::default();
+        let publisher_canister_id = get_mock_user_alice_canister_id();
+
+        engagement_rewards.record_post_watched(5, publisher_canister_id, 1);
+        engagement_rewards.record_post_watched(5, publisher_canister_id, 1);
+        engagement_rewards.record_post_watched(5, publisher_canister_id, 2);
+        assert_eq!(engagement_rewards.get_posts_watched(5), 2);
+        assert_eq!(engagement_rewards.get_posts_watched(6), 0);
+
+        // * the count starts over on the next day
+        engagement_rewards.record_post_watched(6, publisher_canister_id, 1);
+        assert_eq!(engagement_rewards.get_posts_watched(6), 1);
+        assert_eq!(engagement_rewards.get_posts_watched(5), 0);
+    }
+
+    #[test]
+    fn test_claimable_amount() {
+        let mut engagement_rewards = EngagementRewards::default();
+        engagement_rewards.set_config(EngagementRewardsConfig {
+            daily_posts_watched: EngagementRewardRule {
+                reward_amount: 10,
+                max_total_reward_amount: 25,
+            },
+            first_post: EngagementRewardRule {
+                reward_amount: 100,
+                max_total_reward_amount: 100,
+            },
+            ..Default::default()
+        });
+
+        let posts_watched = EngagementMilestone::DailyPostsWatched;
+        assert_eq!(
+            engagement_rewards.get_claimable_amount(posts_watched, 1),
+            10
+        );
+        engagement_rewards.record_claim(posts_watched, 1, 10);
+
+        // * a daily milestone is claimed once a day
+        assert_eq!(engagement_rewards.get_claimable_amount(posts_watched, 1), 0);
+        assert_eq!(
+            engagement_rewards.get_claimable_amount(posts_watched, 2),
+            10
+        );
+        engagement_rewards.record_claim(posts_watched, 2, 10);
+
+        // * the cap limits the last claim
+        assert_eq!(engagement_rewards.get_claimable_amount(posts_watched, 3), 5);
+        engagement_rewards.record_claim(posts_watched, 3, 5);
+        assert_eq!(engagement_rewards.get_claimable_amount(posts_watched, 4), 0);
+
+        let first_post = EngagementMilestone::FirstPost;
+        engagement_rewards.record_claim(first_post, 1, 100);
+        assert_eq!(engagement_rewards.get_claimable_amount(first_post, 2), 0);
+        assert_eq!(
+            engagement_rewards
+                .get_claims(first_post)
+                .total_rewarded_amount,
+            100
+        );
+    }
+}
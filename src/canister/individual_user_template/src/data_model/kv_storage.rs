@@ -1,19 +1,35 @@
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    ops,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use crate::CANISTER_DATA;
+use crate::{
+    util::kv_storage_expiry::start_kv_storage_expiry_sweeper_timer_if_needed, CANISTER_DATA,
+};
 
 use super::memory::{
-    get_kv_storage_namespace_key_value_memory, get_kv_storage_namespace_memory, Memory,
+    get_kv_storage_deleted_namespace_memory, get_kv_storage_key_expiry_memory,
+    get_kv_storage_namespace_key_value_memory, get_kv_storage_namespace_memory,
+    get_kv_storage_namespace_settings_memory, get_kv_storage_next_namespace_id_memory,
+    get_kv_storage_upload_chunk_memory, get_kv_storage_upload_memory, Memory,
 };
 use candid::{CandidType, Decode, Encode, Principal};
 use ic_cdk::api::time;
-use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use ic_stable_structures::{storable::Bound, StableBTreeMap, StableCell, Storable};
 use serde::{Deserialize, Serialize};
 use shared_utils::{
     canister_specific::individual_user_template::types::kv_storage::{
-        NamespaceErrors, NamespaceForFrontend,
+        KeyValueAction, KeyValueBlobForFrontend, KeyValueCondition, KeyValueEntryForFrontend,
+        KeyValueOperation, NamespaceErrors, NamespaceForFrontend, NamespaceKeysPage,
+        NamespaceLimits, NamespacePermission, NamespaceSettingsForFrontend, NamespaceUsage,
+        KEY_VALUE_UPLOAD_TIMEOUT_IN_SECONDS, MAX_KEY_VALUE_TRANSACTION_OPERATIONS,
+        MAX_PENDING_UPLOADS_PER_NAMESPACE, MAX_VALUE_SIZE_IN_BYTES,
+    },
+    common::{
+        types::app_primitive_type::PostId, utils::system_time::get_current_system_time_from_ic,
     },
-    common::types::app_primitive_type::PostId,
 };
 
 type NamespaceId = u64;
@@ -54,43 +70,29 @@ impl Storable for Namespace {
 }
 
 impl Namespace {
-    pub fn is_value_above_the_size_limit(&self, value: &str) -> bool {
-        value.len() > 400
-    }
-
     pub fn write_key_value_pair(
         &self,
         key: String,
         value: String,
     ) -> Result<Option<String>, NamespaceErrors> {
-        let namespace_key = NameSpaceKey {
-            key,
-            namespace_id: self.id,
-        };
-        if self.is_value_above_the_size_limit(&value) {
-            return Err(NamespaceErrors::ValueTooBig);
-        }
-
-        let prev_value = CANISTER_DATA.with_borrow_mut(|canister_data| {
-            canister_data
-                .app_storage
-                .namespace_key_value
-                .insert(namespace_key, value)
-        });
-
-        Ok(prev_value)
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            let mut prev_values = canister_data.app_storage.write_key_value_pairs(
+                self.id,
+                BTreeMap::from([(key, value)]),
+                get_current_system_time_from_ic(),
+            )?;
+            Ok(prev_values.pop().flatten())
+        })
     }
 
-    pub fn delete_key_value_pair(&self, key: String) -> Option<String> {
-        let namespace_key = NameSpaceKey {
-            key,
-            namespace_id: self.id,
-        };
+    pub fn delete_key_value_pair(&self, key: String) -> Result<Option<String>, NamespaceErrors> {
         CANISTER_DATA.with_borrow_mut(|canister_data| {
-            canister_data
-                .app_storage
-                .namespace_key_value
-                .remove(&namespace_key)
+            let mut prev_values = canister_data.app_storage.delete_keys(
+                self.id,
+                vec![key],
+                get_current_system_time_from_ic(),
+            )?;
+            Ok(prev_values.pop().flatten())
         })
     }
 
@@ -98,67 +100,107 @@ impl Namespace {
         &self,
         pairs: BTreeMap<String, String>,
     ) -> Result<(), NamespaceErrors> {
-        let invalid_pair = pairs
-            .iter()
-            .any(|(_, val)| self.is_value_above_the_size_limit(val));
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data.app_storage.write_key_value_pairs(
+                self.id,
+                pairs,
+                get_current_system_time_from_ic(),
+            )
+        })?;
 
-        if invalid_pair {
-            return Err(NamespaceErrors::ValueTooBig);
-        }
+        Ok(())
+    }
 
+    pub fn delete_multiple_keys(&self, keys: Vec<String>) -> Result<(), NamespaceErrors> {
         CANISTER_DATA.with_borrow_mut(|canister_data| {
-            pairs.into_iter().for_each(|pair| {
-                let namespace_key = NameSpaceKey {
-                    key: pair.0,
-                    namespace_id: self.id,
-                };
-
-                canister_data
-                    .app_storage
-                    .namespace_key_value
-                    .insert(namespace_key, pair.1);
-            });
-        });
+            canister_data
+                .app_storage
+                .delete_keys(self.id, keys, get_current_system_time_from_ic())
+        })?;
 
         Ok(())
     }
 
-    pub fn delete_multiple_keys(&self, keys: Vec<String>) {
-        CANISTER_DATA.with_borrow_mut(|canister_data| {
-            keys.into_iter().for_each(|key| {
-                let namespace_key = NameSpaceKey {
-                    key,
-                    namespace_id: self.id,
-                };
-                canister_data
-                    .app_storage
-                    .namespace_key_value
-                    .remove(&namespace_key);
-            })
-        })
+    /// Applies all operations or none of them and returns the new version of each written key,
+    /// `None` for deleted keys.
+    pub fn execute_transaction(
+        &self,
+        operations: Vec<KeyValueOperation>,
+    ) -> Result<Vec<Option<u64>>, NamespaceErrors> {
+        if operations.is_empty() || operations.len() > MAX_KEY_VALUE_TRANSACTION_OPERATIONS {
+            return Err(NamespaceErrors::InvalidTransaction);
+        }
+
+        let outcomes = CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data.app_storage.apply_key_value_operations(
+                self.id,
+                operations,
+                get_current_system_time_from_ic(),
+            )
+        })?;
+        start_kv_storage_expiry_sweeper_timer_if_needed();
+
+        Ok(outcomes
+            .into_iter()
+            .map(|(_, new_version)| new_version)
+            .collect())
     }
 
     pub fn list_keys(&self) -> Vec<String> {
+        let current_time = get_current_system_time_from_ic();
         CANISTER_DATA.with_borrow(|canister_data| {
             canister_data
                 .app_storage
-                .namespace_key_value
-                .iter()
-                .map(|(key, _)| key.key)
+                .namespace_entries(self.id, "", None)
+                .filter(|(_, entry)| !entry.is_expired(current_time))
+                .map(|(namespace_key, _)| namespace_key.key)
                 .collect()
         })
     }
 
-    pub fn read_key_value_pair(&self, key: String) -> Option<String> {
-        let namespace_key = NameSpaceKey {
-            key,
-            namespace_id: self.id,
-        };
+    pub fn list_keys_paginated(
+        &self,
+        prefix: &str,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> NamespaceKeysPage {
         CANISTER_DATA.with_borrow(|canister_data| {
-            canister_data
-                .app_storage
-                .namespace_key_value
-                .get(&namespace_key)
+            canister_data.app_storage.list_keys_paginated(
+                self.id,
+                prefix,
+                cursor,
+                limit,
+                get_current_system_time_from_ic(),
+            )
+        })
+    }
+
+    pub fn read_multiple_key_value_pairs(
+        &self,
+        keys: Vec<String>,
+    ) -> Result<BTreeMap<String, String>, NamespaceErrors> {
+        CANISTER_DATA.with_borrow(|canister_data| {
+            canister_data.app_storage.read_key_value_pairs(
+                self.id,
+                keys,
+                get_current_system_time_from_ic(),
+            )
+        })
+    }
+
+    pub fn read_key_value_pair(&self, key: String) -> Result<Option<String>, NamespaceErrors> {
+        self.read_key_value_entry(key)
+            .map(KeyValueEntry::into_text)
+            .transpose()
+    }
+
+    pub fn read_key_value_entry(&self, key: String) -> Option<KeyValueEntry> {
+        CANISTER_DATA.with_borrow(|canister_data| {
+            canister_data.app_storage.get_live_entry(
+                self.id,
+                key,
+                get_current_system_time_from_ic(),
+            )
         })
     }
 
@@ -168,7 +210,7 @@ impl Namespace {
         }
 
         let new_namespace_id = CANISTER_DATA
-            .with_borrow(|canister_data| canister_data.app_storage.namespace_list.len());
+            .with_borrow_mut(|canister_data| canister_data.app_storage.allocate_namespace_id());
 
         let new_namespace = Namespace {
             id: new_namespace_id,
@@ -180,6 +222,220 @@ impl Namespace {
     }
 }
 
+/// A stored value with its version. Versions come from a counter of the namespace, so they grow
+/// with every write of the key and are not given again after the key was deleted or expired.
+/// Expired entries are treated as missing until the sweeper removes them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KeyValueEntry {
+    #[serde(with = "serde_bytes")]
+    pub value: Vec<u8>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    pub version: u64,
+    #[serde(default)]
+    pub expires_at: Option<SystemTime>,
+}
+
+impl KeyValueEntry {
+    pub fn is_expired(&self, current_time: SystemTime) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= current_time)
+    }
+
+    pub fn into_text(self) -> Result<String, NamespaceErrors> {
+        String::from_utf8(self.value).map_err(|_| NamespaceErrors::ValueNotText)
+    }
+
+    fn into_text_lossy(self) -> String {
+        String::from_utf8_lossy(&self.value).into_owned()
+    }
+}
+
+impl TryFrom<KeyValueEntry> for KeyValueEntryForFrontend {
+    type Error = NamespaceErrors;
+
+    fn try_from(value: KeyValueEntry) -> Result<Self, Self::Error> {
+        Ok(KeyValueEntryForFrontend {
+            version: value.version,
+            expires_at: value.expires_at,
+            value: value.into_text()?,
+        })
+    }
+}
+
+impl From<KeyValueEntry> for KeyValueBlobForFrontend {
+    fn from(value: KeyValueEntry) -> Self {
+        KeyValueBlobForFrontend {
+            value: value.value,
+            content_type: value.content_type,
+            version: value.version,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+// Entries written before values were binary
+#[derive(Deserialize)]
+struct TextKeyValueEntry {
+    value: String,
+    version: u64,
+    #[serde(default)]
+    expires_at: Option<SystemTime>,
+}
+
+// Entries are prefixed with a byte that never starts a UTF-8 string, so that the plain string
+// values stored before entries were versioned can still be read
+const KEY_VALUE_ENTRY_MARKER: u8 = 0xFE;
+const TEXT_KEY_VALUE_ENTRY_MARKER: u8 = 0xFF;
+
+impl Storable for KeyValueEntry {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let mut bytes = vec![KEY_VALUE_ENTRY_MARKER];
+        ciborium::ser::into_writer(self, &mut bytes).unwrap();
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        match bytes.split_first() {
+            Some((&KEY_VALUE_ENTRY_MARKER, entry_bytes)) => {
+                ciborium::de::from_reader(entry_bytes).unwrap()
+            }
+            Some((&TEXT_KEY_VALUE_ENTRY_MARKER, entry_bytes)) => {
+                let entry: TextKeyValueEntry = ciborium::de::from_reader(entry_bytes).unwrap();
+                KeyValueEntry {
+                    value: entry.value.into_bytes(),
+                    content_type: None,
+                    version: entry.version,
+                    expires_at: entry.expires_at,
+                }
+            }
+            _ => KeyValueEntry {
+                value: bytes.into_owned(),
+                content_type: None,
+                version: 1,
+                expires_at: None,
+            },
+        }
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// A value uploaded in chunks, which is written to its key once all chunks are received.
+/// Chunks are stored by their offset in the value.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KeyValueUpload {
+    pub namespace_id: NamespaceId,
+    pub key: String,
+    pub content_type: Option<String>,
+    pub total_size_in_bytes: u64,
+    pub received_size_in_bytes: u64,
+    pub uploaded_by: Principal,
+    pub started_at: SystemTime,
+}
+
+impl KeyValueUpload {
+    pub fn is_timed_out(&self, current_time: SystemTime) -> bool {
+        current_time
+            .duration_since(self.started_at)
+            .is_ok_and(|elapsed| elapsed.as_secs() >= KEY_VALUE_UPLOAD_TIMEOUT_IN_SECONDS)
+    }
+}
+
+impl Storable for KeyValueUpload {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let mut bytes = vec![];
+        ciborium::ser::into_writer(self, &mut bytes).unwrap();
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        let upload: Self = ciborium::de::from_reader(bytes.as_ref()).unwrap();
+        upload
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// The value, content type and expiry written by an action, `None` for deletes.
+fn into_written_value(
+    action: KeyValueAction,
+) -> Option<(Vec<u8>, Option<String>, Option<SystemTime>)> {
+    match action {
+        KeyValueAction::Write { value, expires_at } => Some((value.into_bytes(), None, expires_at)),
+        KeyValueAction::WriteBlob {
+            value,
+            content_type,
+            expires_at,
+        } => Some((value, content_type, expires_at)),
+        KeyValueAction::Delete => None,
+    }
+}
+
+fn written_value(action: &KeyValueAction) -> Option<(&[u8], Option<SystemTime>)> {
+    match action {
+        KeyValueAction::Write { value, expires_at } => Some((value.as_bytes(), *expires_at)),
+        KeyValueAction::WriteBlob {
+            value, expires_at, ..
+        } => Some((value.as_slice(), *expires_at)),
+        KeyValueAction::Delete => None,
+    }
+}
+
+/// Access control, limits and usage of a namespace. Namespaces without settings use the default
+/// limits and grant no permissions.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct NamespaceSettings {
+    #[serde(default)]
+    pub permissions: BTreeMap<Principal, NamespacePermission>,
+    #[serde(default)]
+    pub limits: NamespaceLimits,
+    #[serde(default)]
+    pub usage: NamespaceUsage,
+    /// Last version given to an entry of the namespace
+    #[serde(default)]
+    pub last_version: u64,
+}
+
+impl From<NamespaceSettings> for NamespaceSettingsForFrontend {
+    fn from(value: NamespaceSettings) -> Self {
+        NamespaceSettingsForFrontend {
+            permissions: value.permissions.into_iter().collect(),
+            limits: value.limits,
+            usage: value.usage,
+        }
+    }
+}
+
+impl Storable for NamespaceSettings {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let mut bytes = vec![];
+        ciborium::ser::into_writer(self, &mut bytes).unwrap();
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        let settings: Self = ciborium::de::from_reader(bytes.as_ref()).unwrap();
+        settings
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+fn entry_size_in_bytes(key: &str, value: &[u8]) -> u64 {
+    (key.len() + value.len()) as u64
+}
+
+fn upload_size_in_bytes(key: &str, total_size_in_bytes: u64) -> u64 {
+    key.len() as u64 + total_size_in_bytes
+}
+
+fn expiry_index_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct NameSpaceKey {
     pub namespace_id: u64,
@@ -209,7 +465,24 @@ pub struct AppStorage {
     #[serde(skip, default = "_default_namespace_list")]
     namespace_list: StableBTreeMap<u64, Namespace, Memory>,
     #[serde(skip, default = "_default_namespace_key_value")]
-    namespace_key_value: StableBTreeMap<NameSpaceKey, String, Memory>,
+    namespace_key_value: StableBTreeMap<NameSpaceKey, KeyValueEntry, Memory>,
+    #[serde(skip, default = "_default_namespace_settings")]
+    namespace_settings: StableBTreeMap<NamespaceId, NamespaceSettings, Memory>,
+    // Keys of expiring entries by their expiry time in nanoseconds
+    #[serde(skip, default = "_default_key_expiries")]
+    key_expiries: StableBTreeMap<(u64, NameSpaceKey), (), Memory>,
+    #[serde(skip, default = "_default_key_value_uploads")]
+    key_value_uploads: StableBTreeMap<u64, KeyValueUpload, Memory>,
+    // Key is the upload id and the offset of the chunk
+    #[serde(skip, default = "_default_key_value_upload_chunks")]
+    key_value_upload_chunks: StableBTreeMap<(u64, u64), Vec<u8>, Memory>,
+    #[serde(default)]
+    next_key_value_upload_id: u64,
+    #[serde(skip, default = "_default_next_namespace_id")]
+    next_namespace_id: StableCell<NamespaceId, Memory>,
+    // Namespaces whose keys, uploads and settings are still being removed
+    #[serde(skip, default = "_default_deleted_namespace_ids")]
+    deleted_namespace_ids: StableBTreeMap<NamespaceId, (), Memory>,
 }
 
 impl Default for AppStorage {
@@ -217,14 +490,58 @@ impl Default for AppStorage {
         Self {
             namespace_list: _default_namespace_list(),
             namespace_key_value: _default_namespace_key_value(),
+            namespace_settings: _default_namespace_settings(),
+            key_expiries: _default_key_expiries(),
+            key_value_uploads: _default_key_value_uploads(),
+            key_value_upload_chunks: _default_key_value_upload_chunks(),
+            next_key_value_upload_id: 0,
+            next_namespace_id: _default_next_namespace_id(),
+            deleted_namespace_ids: _default_deleted_namespace_ids(),
         }
     }
 }
 
 impl AppStorage {
+    /// Returns the namespace if the caller is the profile owner, the namespace owner or has been
+    /// granted the required permission.
     pub fn get_a_namespace(
         caller: Principal,
         namespace_uid: u64,
+        required_permission: NamespacePermission,
+    ) -> Result<Namespace, NamespaceErrors> {
+        CANISTER_DATA.with_borrow(|canister_data| {
+            let namespace = canister_data
+                .app_storage
+                .namespace_list
+                .get(&namespace_uid)
+                .ok_or(NamespaceErrors::NamespaceNotFound)?;
+            let profile_owner = canister_data
+                .profile
+                .principal_id
+                .ok_or(NamespaceErrors::UserNotSignedUp)?;
+            if caller == profile_owner || namespace.namespace_owner_id == caller {
+                return Ok(namespace);
+            }
+
+            let is_permitted = canister_data
+                .app_storage
+                .get_namespace_settings(namespace_uid)
+                .permissions
+                .get(&caller)
+                .is_some_and(|permission| permission.allows(required_permission));
+            if !is_permitted {
+                return Err(NamespaceErrors::Unauthorized);
+            }
+
+            Ok(namespace)
+        })
+    }
+
+    /// Only the profile owner and the namespace owner can change the permissions and limits of
+    /// a namespace or delete it.
+    pub fn get_a_namespace_to_manage(
+        caller: Principal,
+        namespace_uid: u64,
     ) -> Result<Namespace, NamespaceErrors> {
         CANISTER_DATA.with_borrow(|canister_data| {
             let namespace = canister_data
@@ -240,10 +557,689 @@ impl AppStorage {
                 return Err(NamespaceErrors::Unauthorized);
             }
 
-            Ok(namespace.clone())
+            Ok(namespace)
         })
     }
 
+    pub fn get_namespace_settings(&self, namespace_id: NamespaceId) -> NamespaceSettings {
+        self.namespace_settings
+            .get(&namespace_id)
+            .unwrap_or_default()
+    }
+
+    pub fn set_namespace_permission(
+        &mut self,
+        namespace_id: NamespaceId,
+        principal: Principal,
+        permission: Option<NamespacePermission>,
+    ) {
+        let mut settings = self.get_namespace_settings(namespace_id);
+        match permission {
+            Some(permission) => {
+                settings.permissions.insert(principal, permission);
+            }
+            None => {
+                settings.permissions.remove(&principal);
+            }
+        }
+        self.namespace_settings.insert(namespace_id, settings);
+    }
+
+    /// Limits below the current usage of the namespace are rejected, so that existing keys never
+    /// put the namespace over its quota.
+    pub fn set_namespace_limits(
+        &mut self,
+        namespace_id: NamespaceId,
+        limits: NamespaceLimits,
+    ) -> Result<(), NamespaceErrors> {
+        let mut settings = self.get_namespace_settings(namespace_id);
+        let usage = settings.usage;
+
+        let is_invalid = limits.max_value_size_in_bytes == 0
+            || limits.max_value_size_in_bytes > MAX_VALUE_SIZE_IN_BYTES
+            || limits
+                .max_number_of_keys
+                .is_some_and(|max_number_of_keys| usage.number_of_keys > max_number_of_keys)
+            || limits
+                .max_total_size_in_bytes
+                .is_some_and(|max_total_size| usage.total_size_in_bytes > max_total_size);
+        if is_invalid {
+            return Err(NamespaceErrors::InvalidLimits);
+        }
+
+        settings.limits = limits;
+        self.namespace_settings.insert(namespace_id, settings);
+        Ok(())
+    }
+
+    pub fn get_live_entry(
+        &self,
+        namespace_id: NamespaceId,
+        key: String,
+        current_time: SystemTime,
+    ) -> Option<KeyValueEntry> {
+        self.namespace_key_value
+            .get(&NameSpaceKey { namespace_id, key })
+            .filter(|entry| !entry.is_expired(current_time))
+    }
+
+    /// Applies all operations or none of them. Returns the previous live entry and the new
+    /// version of each key, in the order of the operations.
+    pub fn apply_key_value_operations(
+        &mut self,
+        namespace_id: NamespaceId,
+        operations: Vec<KeyValueOperation>,
+        current_time: SystemTime,
+    ) -> Result<Vec<(Option<KeyValueEntry>, Option<u64>)>, NamespaceErrors> {
+        let unique_keys: BTreeSet<&String> = operations.iter().map(|op| &op.key).collect();
+        if unique_keys.len() != operations.len() {
+            return Err(NamespaceErrors::InvalidTransaction);
+        }
+
+        let mut settings = self.get_namespace_settings(namespace_id);
+        let limits = settings.limits;
+        let mut usage = settings.usage;
+        let mut stored_entries = Vec::with_capacity(operations.len());
+
+        for operation in operations.iter() {
+            if let Some((value, expires_at)) = written_value(&operation.action) {
+                if value.len() as u64 > limits.max_value_size() {
+                    return Err(NamespaceErrors::ValueTooBig);
+                }
+                if expires_at.is_some_and(|expires_at| expires_at <= current_time) {
+                    return Err(NamespaceErrors::InvalidExpiry);
+                }
+            }
+
+            let stored_entry = self.namespace_key_value.get(&NameSpaceKey {
+                namespace_id,
+                key: operation.key.clone(),
+            });
+            let live_version = stored_entry
+                .as_ref()
+                .filter(|entry| !entry.is_expired(current_time))
+                .map(|entry| entry.version);
+            let is_condition_met = match operation.condition {
+                KeyValueCondition::Any => true,
+                KeyValueCondition::Absent => live_version.is_none(),
+                KeyValueCondition::Version(version) => live_version == Some(version),
+            };
+            if !is_condition_met {
+                return Err(NamespaceErrors::VersionMismatch);
+            }
+
+            if let Some(stored_entry) = stored_entry.as_ref() {
+                usage.number_of_keys = usage.number_of_keys.saturating_sub(1);
+                usage.total_size_in_bytes = usage
+                    .total_size_in_bytes
+                    .saturating_sub(entry_size_in_bytes(&operation.key, &stored_entry.value));
+            }
+            if let Some((value, _)) = written_value(&operation.action) {
+                usage.number_of_keys += 1;
+                usage.total_size_in_bytes += entry_size_in_bytes(&operation.key, value);
+            }
+
+            stored_entries.push(stored_entry);
+        }
+
+        if limits
+            .max_number_of_keys
+            .is_some_and(|max_number_of_keys| usage.number_of_keys > max_number_of_keys)
+        {
+            return Err(NamespaceErrors::KeyLimitReached);
+        }
+        if limits
+            .max_total_size_in_bytes
+            .is_some_and(|max_total_size| usage.total_size_in_bytes > max_total_size)
+        {
+            return Err(NamespaceErrors::StorageLimitReached);
+        }
+
+        let mut last_version = settings.last_version;
+        let outcomes = operations
+            .into_iter()
+            .zip(stored_entries)
+            .map(|(operation, stored_entry)| {
+                let namespace_key = NameSpaceKey {
+                    namespace_id,
+                    key: operation.key,
+                };
+                if let Some(expires_at) = stored_entry.as_ref().and_then(|entry| entry.expires_at) {
+                    self.key_expiries
+                        .remove(&(expiry_index_time(expires_at), namespace_key.clone()));
+                }
+
+                let new_version = match into_written_value(operation.action) {
+                    Some((value, content_type, expires_at)) => {
+                        // Entries written before the namespace counted versions may be ahead of
+                        // the counter
+                        let version = stored_entry
+                            .as_ref()
+                            .map_or(last_version, |entry| entry.version.max(last_version))
+                            + 1;
+                        last_version = version;
+                        if let Some(expires_at) = expires_at {
+                            self.key_expiries
+                                .insert((expiry_index_time(expires_at), namespace_key.clone()), ());
+                        }
+                        self.namespace_key_value.insert(
+                            namespace_key,
+                            KeyValueEntry {
+                                value,
+                                content_type,
+                                version,
+                                expires_at,
+                            },
+                        );
+                        Some(version)
+                    }
+                    None => {
+                        self.namespace_key_value.remove(&namespace_key);
+                        None
+                    }
+                };
+
+                let prev_live_entry = stored_entry.filter(|entry| !entry.is_expired(current_time));
+                (prev_live_entry, new_version)
+            })
+            .collect();
+
+        if settings.usage != usage || settings.last_version != last_version {
+            settings.usage = usage;
+            settings.last_version = last_version;
+            self.namespace_settings.insert(namespace_id, settings);
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Writes all pairs or none of them, returning the previous values in the order of the keys.
+    pub fn write_key_value_pairs(
+        &mut self,
+        namespace_id: NamespaceId,
+        pairs: BTreeMap<String, String>,
+        current_time: SystemTime,
+    ) -> Result<Vec<Option<String>>, NamespaceErrors> {
+        let operations = pairs
+            .into_iter()
+            .map(|(key, value)| KeyValueOperation {
+                key,
+                condition: KeyValueCondition::Any,
+                action: KeyValueAction::Write {
+                    value,
+                    expires_at: None,
+                },
+            })
+            .collect();
+
+        let outcomes = self.apply_key_value_operations(namespace_id, operations, current_time)?;
+        Ok(outcomes
+            .into_iter()
+            .map(|(prev_entry, _)| prev_entry.map(KeyValueEntry::into_text_lossy))
+            .collect())
+    }
+
+    /// Returns the removed values in the order of the deduplicated keys.
+    pub fn delete_keys(
+        &mut self,
+        namespace_id: NamespaceId,
+        keys: Vec<String>,
+        current_time: SystemTime,
+    ) -> Result<Vec<Option<String>>, NamespaceErrors> {
+        let operations = keys
+            .into_iter()
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .map(|key| KeyValueOperation {
+                key,
+                condition: KeyValueCondition::Any,
+                action: KeyValueAction::Delete,
+            })
+            .collect();
+
+        let outcomes = self.apply_key_value_operations(namespace_id, operations, current_time)?;
+        Ok(outcomes
+            .into_iter()
+            .map(|(prev_entry, _)| prev_entry.map(KeyValueEntry::into_text_lossy))
+            .collect())
+    }
+
+    pub fn has_expiring_entries(&self) -> bool {
+        !self.key_expiries.is_empty()
+    }
+
+    /// Removes up to `limit` entries that expired by the current time and returns how many were
+    /// removed.
+    pub fn remove_expired_entries(&mut self, current_time: SystemTime, limit: usize) -> usize {
+        let current_index_time = expiry_index_time(current_time);
+        let expired_keys: Vec<(u64, NameSpaceKey)> = self
+            .key_expiries
+            .iter()
+            .map(|(expiry_key, _)| expiry_key)
+            .take_while(|(expires_at, _)| *expires_at <= current_index_time)
+            .take(limit)
+            .collect();
+
+        for expiry_key in expired_keys.iter() {
+            self.key_expiries.remove(expiry_key);
+
+            let namespace_key = &expiry_key.1;
+            let Some(entry) = self.namespace_key_value.remove(namespace_key) else {
+                continue;
+            };
+            let mut settings = self.get_namespace_settings(namespace_key.namespace_id);
+            settings.usage.number_of_keys = settings.usage.number_of_keys.saturating_sub(1);
+            settings.usage.total_size_in_bytes = settings
+                .usage
+                .total_size_in_bytes
+                .saturating_sub(entry_size_in_bytes(&namespace_key.key, &entry.value));
+            self.namespace_settings
+                .insert(namespace_key.namespace_id, settings);
+        }
+
+        expired_keys.len()
+    }
+
+    /// Uploads are refused when the namespace could not hold them along with its keys and its
+    /// other pending uploads. The value an upload replaces counts as freed.
+    pub fn start_upload(
+        &mut self,
+        namespace_id: NamespaceId,
+        key: String,
+        content_type: Option<String>,
+        total_size_in_bytes: u64,
+        uploaded_by: Principal,
+        current_time: SystemTime,
+    ) -> Result<u64, NamespaceErrors> {
+        let settings = self.get_namespace_settings(namespace_id);
+        if total_size_in_bytes > settings.limits.max_value_size() {
+            return Err(NamespaceErrors::ValueTooBig);
+        }
+
+        let pending_uploads: Vec<KeyValueUpload> = self
+            .key_value_uploads
+            .iter()
+            .map(|(_, upload)| upload)
+            .filter(|upload| upload.namespace_id == namespace_id)
+            .collect();
+        if pending_uploads.len() >= MAX_PENDING_UPLOADS_PER_NAMESPACE {
+            return Err(NamespaceErrors::StorageLimitReached);
+        }
+
+        if let Some(max_total_size) = settings.limits.max_total_size_in_bytes {
+            let replaced_size = self
+                .namespace_key_value
+                .get(&NameSpaceKey {
+                    namespace_id,
+                    key: key.clone(),
+                })
+                .map_or(0, |entry| entry_size_in_bytes(&key, &entry.value));
+            let pending_size: u64 = pending_uploads
+                .iter()
+                .map(|upload| upload_size_in_bytes(&upload.key, upload.total_size_in_bytes))
+                .sum();
+            let projected_size = settings
+                .usage
+                .total_size_in_bytes
+                .saturating_sub(replaced_size)
+                + pending_size
+                + upload_size_in_bytes(&key, total_size_in_bytes);
+            if projected_size > max_total_size {
+                return Err(NamespaceErrors::StorageLimitReached);
+            }
+        }
+
+        let upload_id = self.next_key_value_upload_id;
+        self.next_key_value_upload_id += 1;
+        self.key_value_uploads.insert(
+            upload_id,
+            KeyValueUpload {
+                namespace_id,
+                key,
+                content_type,
+                total_size_in_bytes,
+                received_size_in_bytes: 0,
+                uploaded_by,
+                started_at: current_time,
+            },
+        );
+
+        Ok(upload_id)
+    }
+
+    fn get_upload(
+        &self,
+        namespace_id: NamespaceId,
+        upload_id: u64,
+        caller: Principal,
+    ) -> Result<KeyValueUpload, NamespaceErrors> {
+        let upload = self
+            .key_value_uploads
+            .get(&upload_id)
+            .filter(|upload| upload.namespace_id == namespace_id)
+            .ok_or(NamespaceErrors::UploadNotFound)?;
+        if upload.uploaded_by != caller {
+            return Err(NamespaceErrors::Unauthorized);
+        }
+
+        Ok(upload)
+    }
+
+    /// Chunks are appended in order, so the offset of a chunk is the number of bytes received
+    /// before it.
+    pub fn upload_chunk(
+        &mut self,
+        namespace_id: NamespaceId,
+        upload_id: u64,
+        caller: Principal,
+        offset: u64,
+        chunk: Vec<u8>,
+    ) -> Result<(), NamespaceErrors> {
+        let mut upload = self.get_upload(namespace_id, upload_id, caller)?;
+        let chunk_size = chunk.len() as u64;
+        if chunk.is_empty()
+            || offset != upload.received_size_in_bytes
+            || offset + chunk_size > upload.total_size_in_bytes
+        {
+            return Err(NamespaceErrors::InvalidChunk);
+        }
+
+        self.key_value_upload_chunks
+            .insert((upload_id, offset), chunk);
+        upload.received_size_in_bytes += chunk_size;
+        self.key_value_uploads.insert(upload_id, upload);
+
+        Ok(())
+    }
+
+    /// Writes the uploaded value to its key if the key is in the expected state and returns its
+    /// new version. A failed commit keeps the upload, so it can be committed again.
+    pub fn commit_upload(
+        &mut self,
+        namespace_id: NamespaceId,
+        upload_id: u64,
+        caller: Principal,
+        condition: KeyValueCondition,
+        expires_at: Option<SystemTime>,
+        current_time: SystemTime,
+    ) -> Result<u64, NamespaceErrors> {
+        let upload = self.get_upload(namespace_id, upload_id, caller)?;
+        if upload.received_size_in_bytes != upload.total_size_in_bytes {
+            return Err(NamespaceErrors::UploadIncomplete);
+        }
+
+        let value: Vec<u8> = self
+            .key_value_upload_chunks
+            .range((upload_id, 0)..=(upload_id, u64::MAX))
+            .flat_map(|(_, chunk)| chunk)
+            .collect();
+        let outcomes = self.apply_key_value_operations(
+            namespace_id,
+            vec![KeyValueOperation {
+                key: upload.key,
+                condition,
+                action: KeyValueAction::WriteBlob {
+                    value,
+                    content_type: upload.content_type,
+                    expires_at,
+                },
+            }],
+            current_time,
+        )?;
+        self.remove_upload(upload_id);
+
+        Ok(outcomes
+            .into_iter()
+            .find_map(|(_, new_version)| new_version)
+            .unwrap_or_default())
+    }
+
+    pub fn cancel_upload(
+        &mut self,
+        namespace_id: NamespaceId,
+        upload_id: u64,
+        caller: Principal,
+    ) -> Result<(), NamespaceErrors> {
+        self.get_upload(namespace_id, upload_id, caller)?;
+        self.remove_upload(upload_id);
+        Ok(())
+    }
+
+    fn remove_upload(&mut self, upload_id: u64) {
+        let chunk_keys: Vec<(u64, u64)> = self
+            .key_value_upload_chunks
+            .range((upload_id, 0)..=(upload_id, u64::MAX))
+            .map(|(chunk_key, _)| chunk_key)
+            .collect();
+        for chunk_key in chunk_keys.iter() {
+            self.key_value_upload_chunks.remove(chunk_key);
+        }
+        self.key_value_uploads.remove(&upload_id);
+    }
+
+    pub fn has_pending_uploads(&self) -> bool {
+        !self.key_value_uploads.is_empty()
+    }
+
+    /// Removes uploads that were not committed within the upload timeout and returns how many
+    /// were removed.
+    pub fn remove_timed_out_uploads(&mut self, current_time: SystemTime) -> usize {
+        let timed_out_upload_ids: Vec<u64> = self
+            .key_value_uploads
+            .iter()
+            .filter(|(_, upload)| upload.is_timed_out(current_time))
+            .map(|(upload_id, _)| upload_id)
+            .collect();
+        for upload_id in timed_out_upload_ids.iter() {
+            self.remove_upload(*upload_id);
+        }
+
+        timed_out_upload_ids.len()
+    }
+
+    /// Entries of the namespace whose key starts with the prefix, in ascending key order and
+    /// starting after the cursor key if there is one.
+    fn namespace_entries<'a>(
+        &'a self,
+        namespace_id: NamespaceId,
+        prefix: &'a str,
+        cursor: Option<String>,
+    ) -> impl Iterator<Item = (NameSpaceKey, KeyValueEntry)> + 'a {
+        let start = match cursor {
+            Some(cursor) if cursor.as_str() >= prefix => ops::Bound::Excluded(NameSpaceKey {
+                namespace_id,
+                key: cursor,
+            }),
+            _ => ops::Bound::Included(NameSpaceKey {
+                namespace_id,
+                key: prefix.to_owned(),
+            }),
+        };
+
+        self.namespace_key_value
+            .range((start, ops::Bound::Unbounded))
+            .take_while(move |(namespace_key, _)| {
+                namespace_key.namespace_id == namespace_id && namespace_key.key.starts_with(prefix)
+            })
+    }
+
+    pub fn list_keys_paginated(
+        &self,
+        namespace_id: NamespaceId,
+        prefix: &str,
+        cursor: Option<String>,
+        limit: usize,
+        current_time: SystemTime,
+    ) -> NamespaceKeysPage {
+        let mut keys: Vec<String> = self
+            .namespace_entries(namespace_id, prefix, cursor)
+            .filter(|(_, entry)| !entry.is_expired(current_time))
+            .map(|(namespace_key, _)| namespace_key.key)
+            .take(limit + 1)
+            .collect();
+
+        let next_cursor = if keys.len() > limit {
+            keys.truncate(limit);
+            keys.last().cloned()
+        } else {
+            None
+        };
+
+        NamespaceKeysPage { keys, next_cursor }
+    }
+
+    /// Missing keys are left out of the result.
+    pub fn read_key_value_pairs(
+        &self,
+        namespace_id: NamespaceId,
+        keys: Vec<String>,
+        current_time: SystemTime,
+    ) -> Result<BTreeMap<String, String>, NamespaceErrors> {
+        keys.into_iter()
+            .filter_map(|key| {
+                let entry = self.get_live_entry(namespace_id, key.clone(), current_time)?;
+                Some(entry.into_text().map(|value| (key, value)))
+            })
+            .collect()
+    }
+
+    /// Removes the namespace so that it can no longer be reached. Its keys, uploads, settings
+    /// and usage are left for the sweeper to remove in batches.
+    pub fn remove_namespace(&mut self, namespace_id: NamespaceId) {
+        self.namespace_list.remove(&namespace_id);
+        self.deleted_namespace_ids.insert(namespace_id, ());
+    }
+
+    pub fn has_deleted_namespaces(&self) -> bool {
+        !self.deleted_namespace_ids.is_empty()
+    }
+
+    /// Removes up to `limit` keys of deleted namespaces and returns how many were removed. The
+    /// uploads and settings of a namespace are removed once none of its keys are left.
+    pub fn remove_deleted_namespace_entries(&mut self, limit: usize) -> usize {
+        let mut number_of_removed_entries = 0;
+
+        while number_of_removed_entries < limit {
+            let Some((namespace_id, _)) = self.deleted_namespace_ids.first_key_value() else {
+                break;
+            };
+
+            let batch_limit = limit - number_of_removed_entries;
+            let namespace_entries: Vec<(NameSpaceKey, KeyValueEntry)> = self
+                .namespace_entries(namespace_id, "", None)
+                .take(batch_limit)
+                .collect();
+            number_of_removed_entries += namespace_entries.len();
+            let has_entries_left = namespace_entries.len() == batch_limit;
+
+            for (namespace_key, entry) in namespace_entries {
+                if let Some(expires_at) = entry.expires_at {
+                    self.key_expiries
+                        .remove(&(expiry_index_time(expires_at), namespace_key.clone()));
+                }
+                self.namespace_key_value.remove(&namespace_key);
+            }
+
+            if !has_entries_left {
+                let upload_ids: Vec<u64> = self
+                    .key_value_uploads
+                    .iter()
+                    .filter(|(_, upload)| upload.namespace_id == namespace_id)
+                    .map(|(upload_id, _)| upload_id)
+                    .collect();
+                for upload_id in upload_ids {
+                    self.remove_upload(upload_id);
+                }
+                self.namespace_settings.remove(&namespace_id);
+                self.deleted_namespace_ids.remove(&namespace_id);
+            }
+        }
+
+        number_of_removed_entries
+    }
+
+    /// Ids are never reused, so that clients and grants that still refer to a deleted namespace
+    /// cannot reach a namespace created after it.
+    pub fn allocate_namespace_id(&mut self) -> NamespaceId {
+        let namespace_id = *self.next_namespace_id.get();
+        self.next_namespace_id
+            .set(namespace_id + 1)
+            .expect("failed to save the next namespace id");
+        namespace_id
+    }
+
+    /// Namespaces were given the number of namespaces as their id, which could hand out an id
+    /// that is already in use. A namespace whose id does not match the id it is stored under
+    /// would read and write the keys of another namespace, so its id is reset to the stored one.
+    /// The id counter is then moved past every id that namespaces, keys, settings or uploads
+    /// still refer to. Returns the number of namespaces whose id was repaired.
+    pub fn repair_namespace_ids(&mut self) -> usize {
+        let mismatched_namespaces: Vec<(NamespaceId, Namespace)> = self
+            .namespace_list
+            .iter()
+            .filter(|(namespace_id, namespace)| namespace.id != *namespace_id)
+            .collect();
+        for (namespace_id, mut namespace) in mismatched_namespaces.iter().cloned() {
+            namespace.id = namespace_id;
+            self.namespace_list.insert(namespace_id, namespace);
+        }
+
+        let highest_namespace_id_in_use = [
+            self.namespace_list.last_key_value().map(|(id, _)| id),
+            self.namespace_key_value
+                .last_key_value()
+                .map(|(namespace_key, _)| namespace_key.namespace_id),
+            self.namespace_settings.last_key_value().map(|(id, _)| id),
+            self.key_value_uploads
+                .iter()
+                .map(|(_, upload)| upload.namespace_id)
+                .max(),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+        if let Some(highest_namespace_id_in_use) = highest_namespace_id_in_use {
+            if *self.next_namespace_id.get() <= highest_namespace_id_in_use {
+                self.next_namespace_id
+                    .set(highest_namespace_id_in_use + 1)
+                    .expect("failed to save the next namespace id");
+            }
+        }
+
+        mismatched_namespaces.len()
+    }
+
+    /// Namespaces created before usage was tracked have no settings. Their usage is counted from
+    /// the stored keys once, so that quotas apply to them as well.
+    pub fn backfill_namespace_usage(&mut self) {
+        let untracked_namespace_ids: Vec<NamespaceId> = self
+            .namespace_list
+            .iter()
+            .map(|(namespace_id, _)| namespace_id)
+            .filter(|namespace_id| !self.namespace_settings.contains_key(namespace_id))
+            .collect();
+
+        for namespace_id in untracked_namespace_ids {
+            let usage = self.namespace_entries(namespace_id, "", None).fold(
+                NamespaceUsage::default(),
+                |mut usage, (namespace_key, entry)| {
+                    usage.number_of_keys += 1;
+                    usage.total_size_in_bytes +=
+                        entry_size_in_bytes(&namespace_key.key, &entry.value);
+                    usage
+                },
+            );
+
+            self.namespace_settings.insert(
+                namespace_id,
+                NamespaceSettings {
+                    usage,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
     pub fn list_namespaces(start_index: usize, limit: usize) -> Vec<NamespaceForFrontend> {
         CANISTER_DATA.with_borrow(|canister_data| {
             canister_data
@@ -276,6 +1272,701 @@ pub fn _default_namespace_list() -> StableBTreeMap<u64, Namespace, Memory> {
     ic_stable_structures::StableBTreeMap::init(get_kv_storage_namespace_memory())
 }
 
-pub fn _default_namespace_key_value() -> StableBTreeMap<NameSpaceKey, String, Memory> {
+pub fn _default_namespace_key_value() -> StableBTreeMap<NameSpaceKey, KeyValueEntry, Memory> {
     ic_stable_structures::StableBTreeMap::init(get_kv_storage_namespace_key_value_memory())
 }
+
+pub fn _default_namespace_settings() -> StableBTreeMap<NamespaceId, NamespaceSettings, Memory> {
+    ic_stable_structures::StableBTreeMap::init(get_kv_storage_namespace_settings_memory())
+}
+
+pub fn _default_key_expiries() -> StableBTreeMap<(u64, NameSpaceKey), (), Memory> {
+    ic_stable_structures::StableBTreeMap::init(get_kv_storage_key_expiry_memory())
+}
+
+pub fn _default_key_value_uploads() -> StableBTreeMap<u64, KeyValueUpload, Memory> {
+    ic_stable_structures::StableBTreeMap::init(get_kv_storage_upload_memory())
+}
+
+pub fn _default_key_value_upload_chunks() -> StableBTreeMap<(u64, u64), Vec<u8>, Memory> {
+    ic_stable_structures::StableBTreeMap::init(get_kv_storage_upload_chunk_memory())
+}
+
+pub fn _default_next_namespace_id() -> StableCell<NamespaceId, Memory> {
+    ic_stable_structures::StableCell::init(get_kv_storage_next_namespace_id_memory(), 0)
+        .expect("failed to initialize the next namespace id")
+}
+
+pub fn _default_deleted_namespace_ids() -> StableBTreeMap<NamespaceId, (), Memory> {
+    ic_stable_structures::StableBTreeMap::init(get_kv_storage_deleted_namespace_memory())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use shared_utils::canister_specific::individual_user_template::types::kv_storage::DEFAULT_MAX_VALUE_SIZE_IN_BYTES;
+    use test_utils::setup::test_constants::get_mock_user_alice_principal_id;
+
+    use super::*;
+
+    fn insert_namespace(app_storage: &mut AppStorage, id: NamespaceId) {
+        app_storage.namespace_list.insert(
+            id,
+            Namespace {
+                id,
+                title: format!("namespace_{id}"),
+                namespace_owner_id: get_mock_user_alice_principal_id(),
+            },
+        );
+    }
+
+    #[test]
+    fn test_writes_are_limited_by_the_namespace_quota() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        app_storage
+            .set_namespace_limits(
+                0,
+                NamespaceLimits {
+                    max_value_size_in_bytes: 10,
+                    max_number_of_keys: Some(2),
+                    max_total_size_in_bytes: Some(15),
+                },
+            )
+            .unwrap();
+
+        let result = app_storage.write_key_value_pairs(
+            0,
+            BTreeMap::from([("a".into(), "01234567890".into())]),
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::ValueTooBig)));
+
+        app_storage
+            .write_key_value_pairs(
+                0,
+                BTreeMap::from([("a".into(), "0123".into()), ("b".into(), "0123".into())]),
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(
+            app_storage.get_namespace_settings(0).usage,
+            NamespaceUsage {
+                number_of_keys: 2,
+                total_size_in_bytes: 10
+            }
+        );
+
+        let result = app_storage.write_key_value_pairs(
+            0,
+            BTreeMap::from([("c".into(), "0".into())]),
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::KeyLimitReached)));
+
+        let result = app_storage.write_key_value_pairs(
+            0,
+            BTreeMap::from([("a".into(), "0123456789".into())]),
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::StorageLimitReached)));
+
+        let prev_values = app_storage
+            .write_key_value_pairs(
+                0,
+                BTreeMap::from([("a".into(), "01234".into())]),
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(prev_values, vec![Some("0123".to_string())]);
+        assert_eq!(
+            app_storage
+                .get_namespace_settings(0)
+                .usage
+                .total_size_in_bytes,
+            11
+        );
+
+        app_storage
+            .delete_keys(0, vec!["a".into(), "missing".into()], UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(
+            app_storage.get_namespace_settings(0).usage,
+            NamespaceUsage {
+                number_of_keys: 1,
+                total_size_in_bytes: 5
+            }
+        );
+        assert!(matches!(
+            app_storage.set_namespace_limits(
+                0,
+                NamespaceLimits {
+                    max_total_size_in_bytes: Some(4),
+                    ..Default::default()
+                }
+            ),
+            Err(NamespaceErrors::InvalidLimits)
+        ));
+    }
+
+    #[test]
+    fn test_removing_a_namespace_keeps_the_keys_of_other_namespaces() {
+        let mut app_storage = AppStorage::default();
+        for id in 0..3 {
+            insert_namespace(&mut app_storage, id);
+            app_storage
+                .write_key_value_pairs(
+                    id,
+                    BTreeMap::from([("a".into(), "1".into()), ("b".into(), "2".into())]),
+                    UNIX_EPOCH,
+                )
+                .unwrap();
+        }
+        app_storage.set_namespace_permission(
+            1,
+            Principal::anonymous(),
+            Some(NamespacePermission::Read),
+        );
+
+        app_storage.remove_namespace(1);
+
+        // * the namespace is gone at once and its keys are removed in batches
+        assert!(app_storage.namespace_list.get(&1).is_none());
+        assert!(app_storage.has_deleted_namespaces());
+        assert_eq!(app_storage.remove_deleted_namespace_entries(1), 1);
+        assert_eq!(app_storage.namespace_key_value.len(), 5);
+        assert!(app_storage.namespace_settings.get(&1).is_some());
+
+        assert_eq!(app_storage.remove_deleted_namespace_entries(10), 1);
+        assert!(!app_storage.has_deleted_namespaces());
+        assert!(app_storage.namespace_settings.get(&1).is_none());
+        assert_eq!(app_storage.namespace_key_value.len(), 4);
+        assert!(app_storage
+            .namespace_key_value
+            .iter()
+            .all(|(namespace_key, _)| namespace_key.namespace_id != 1));
+    }
+
+    #[test]
+    fn test_usage_of_untracked_namespaces_is_backfilled() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        insert_namespace(&mut app_storage, 1);
+        app_storage.namespace_key_value.insert(
+            NameSpaceKey {
+                namespace_id: 1,
+                key: "key".into(),
+            },
+            KeyValueEntry::from_bytes(Cow::Borrowed("value".as_bytes())),
+        );
+
+        app_storage.backfill_namespace_usage();
+
+        assert_eq!(
+            app_storage.get_namespace_settings(0).usage,
+            NamespaceUsage::default()
+        );
+        assert_eq!(
+            app_storage.get_namespace_settings(1).usage,
+            NamespaceUsage {
+                number_of_keys: 1,
+                total_size_in_bytes: 8
+            }
+        );
+    }
+
+    #[test]
+    fn test_keys_are_listed_and_read_per_namespace() {
+        let mut app_storage = AppStorage::default();
+        for id in 0..3 {
+            insert_namespace(&mut app_storage, id);
+            app_storage
+                .write_key_value_pairs(
+                    id,
+                    BTreeMap::from([
+                        ("shared".into(), format!("value_{id}")),
+                        (format!("only_in_{id}"), "value".into()),
+                    ]),
+                    UNIX_EPOCH,
+                )
+                .unwrap();
+        }
+
+        for id in 0..3 {
+            let page = app_storage.list_keys_paginated(id, "", None, 10, UNIX_EPOCH);
+            assert_eq!(
+                page.keys,
+                vec![format!("only_in_{id}"), "shared".to_string()]
+            );
+            assert_eq!(page.next_cursor, None);
+
+            let values = app_storage.read_key_value_pairs(
+                id,
+                vec!["shared".into(), "only_in_0".into(), "missing".into()],
+                UNIX_EPOCH,
+            );
+            let mut expected = BTreeMap::from([("shared".to_string(), format!("value_{id}"))]);
+            if id == 0 {
+                expected.insert("only_in_0".into(), "value".into());
+            }
+            assert_eq!(values.unwrap(), expected);
+        }
+
+        assert_eq!(
+            app_storage.list_keys_paginated(3, "", None, 10, UNIX_EPOCH),
+            NamespaceKeysPage::default()
+        );
+    }
+
+    #[test]
+    fn test_keys_are_paginated_by_prefix_and_cursor() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        insert_namespace(&mut app_storage, 1);
+        app_storage
+            .write_key_value_pairs(
+                0,
+                ["a", "user/1", "user/2", "user/3", "users", "z"]
+                    .into_iter()
+                    .map(|key| (key.to_string(), "value".to_string()))
+                    .collect(),
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        app_storage
+            .write_key_value_pairs(
+                1,
+                BTreeMap::from([("user/0".into(), "value".into())]),
+                UNIX_EPOCH,
+            )
+            .unwrap();
+
+        let first_page = app_storage.list_keys_paginated(0, "user/", None, 2, UNIX_EPOCH);
+        assert_eq!(first_page.keys, vec!["user/1", "user/2"]);
+        assert_eq!(first_page.next_cursor, Some("user/2".to_string()));
+
+        let second_page =
+            app_storage.list_keys_paginated(0, "user/", first_page.next_cursor, 2, UNIX_EPOCH);
+        assert_eq!(second_page.keys, vec!["user/3"]);
+        assert_eq!(second_page.next_cursor, None);
+
+        let all_keys =
+            app_storage.list_keys_paginated(0, "", Some("user/3".into()), 10, UNIX_EPOCH);
+        assert_eq!(all_keys.keys, vec!["users", "z"]);
+
+        // A cursor before the prefix starts the page at the prefix
+        let from_prefix =
+            app_storage.list_keys_paginated(0, "user/", Some("a".into()), 10, UNIX_EPOCH);
+        assert_eq!(from_prefix.keys, vec!["user/1", "user/2", "user/3"]);
+    }
+
+    #[test]
+    fn test_values_stored_as_plain_strings_are_read_as_first_versions() {
+        let entry = KeyValueEntry::from_bytes(Cow::Borrowed("value".as_bytes()));
+        assert_eq!(
+            entry,
+            KeyValueEntry {
+                value: "value".into(),
+                content_type: None,
+                version: 1,
+                expires_at: None
+            }
+        );
+        assert_eq!(KeyValueEntry::from_bytes(entry.to_bytes()), entry);
+    }
+
+    #[test]
+    fn test_text_entries_are_read_as_binary_values() {
+        #[derive(Serialize)]
+        struct TextEntry {
+            value: String,
+            version: u64,
+            expires_at: Option<SystemTime>,
+        }
+
+        let mut bytes = vec![TEXT_KEY_VALUE_ENTRY_MARKER];
+        ciborium::ser::into_writer(
+            &TextEntry {
+                value: "value".into(),
+                version: 3,
+                expires_at: None,
+            },
+            &mut bytes,
+        )
+        .unwrap();
+        let entry = KeyValueEntry::from_bytes(Cow::Owned(bytes));
+        assert_eq!(entry.value, b"value");
+        assert_eq!(entry.version, 3);
+        assert_eq!(entry.into_text().unwrap(), "value");
+
+        let entry = KeyValueEntry {
+            value: vec![0xFF, 0x00],
+            content_type: Some("image/png".into()),
+            version: 1,
+            expires_at: None,
+        };
+        assert_eq!(KeyValueEntry::from_bytes(entry.to_bytes()), entry);
+        assert!(matches!(
+            entry.into_text(),
+            Err(NamespaceErrors::ValueNotText)
+        ));
+    }
+
+    #[test]
+    fn test_chunked_uploads_are_written_once_complete() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        let alice = get_mock_user_alice_principal_id();
+
+        let result = app_storage.start_upload(
+            0,
+            "image".into(),
+            None,
+            DEFAULT_MAX_VALUE_SIZE_IN_BYTES + 1,
+            alice,
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::ValueTooBig)));
+
+        let upload_id = app_storage
+            .start_upload(
+                0,
+                "image".into(),
+                Some("image/png".into()),
+                4,
+                alice,
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        app_storage
+            .upload_chunk(0, upload_id, alice, 0, vec![1, 2])
+            .unwrap();
+        assert!(matches!(
+            app_storage.upload_chunk(0, upload_id, alice, 0, vec![3, 4]),
+            Err(NamespaceErrors::InvalidChunk)
+        ));
+        assert!(matches!(
+            app_storage.upload_chunk(0, upload_id, Principal::anonymous(), 2, vec![3, 4]),
+            Err(NamespaceErrors::Unauthorized)
+        ));
+        assert!(matches!(
+            app_storage.commit_upload(
+                0,
+                upload_id,
+                alice,
+                KeyValueCondition::Any,
+                None,
+                UNIX_EPOCH
+            ),
+            Err(NamespaceErrors::UploadIncomplete)
+        ));
+
+        app_storage
+            .upload_chunk(0, upload_id, alice, 2, vec![3, 4])
+            .unwrap();
+        let version = app_storage
+            .commit_upload(
+                0,
+                upload_id,
+                alice,
+                KeyValueCondition::Absent,
+                None,
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(version, 1);
+        assert!(!app_storage.has_pending_uploads());
+        assert!(app_storage.key_value_upload_chunks.is_empty());
+
+        let entry = app_storage
+            .get_live_entry(0, "image".into(), UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(entry.value, vec![1, 2, 3, 4]);
+        assert_eq!(entry.content_type, Some("image/png".into()));
+        assert_eq!(
+            app_storage
+                .get_namespace_settings(0)
+                .usage
+                .total_size_in_bytes,
+            9
+        );
+
+        app_storage
+            .start_upload(0, "stale".into(), None, 1, alice, UNIX_EPOCH)
+            .unwrap();
+        let timed_out_at = UNIX_EPOCH + Duration::from_secs(KEY_VALUE_UPLOAD_TIMEOUT_IN_SECONDS);
+        assert_eq!(app_storage.remove_timed_out_uploads(UNIX_EPOCH), 0);
+        assert_eq!(app_storage.remove_timed_out_uploads(timed_out_at), 1);
+        assert!(!app_storage.has_pending_uploads());
+    }
+
+    #[test]
+    fn test_uploads_are_limited_by_the_namespace_quota() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        let alice = get_mock_user_alice_principal_id();
+        app_storage
+            .set_namespace_limits(
+                0,
+                NamespaceLimits {
+                    max_total_size_in_bytes: Some(20),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        app_storage
+            .write_key_value_pairs(0, BTreeMap::from([("a".into(), "0123".into())]), UNIX_EPOCH)
+            .unwrap();
+
+        app_storage
+            .start_upload(0, "image".into(), None, 10, alice, UNIX_EPOCH)
+            .unwrap();
+        // * the pending upload already takes the rest of the quota
+        assert!(matches!(
+            app_storage.start_upload(0, "other".into(), None, 1, alice, UNIX_EPOCH),
+            Err(NamespaceErrors::StorageLimitReached)
+        ));
+        // * replacing a value frees its size
+        app_storage
+            .start_upload(0, "a".into(), None, 4, alice, UNIX_EPOCH)
+            .unwrap();
+        assert!(matches!(
+            app_storage.start_upload(0, "a".into(), None, 5, alice, UNIX_EPOCH),
+            Err(NamespaceErrors::StorageLimitReached)
+        ));
+    }
+
+    #[test]
+    fn test_writes_are_applied_only_when_the_version_matches() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        let write = |key: &str, condition: KeyValueCondition, value: &str| KeyValueOperation {
+            key: key.into(),
+            condition,
+            action: KeyValueAction::Write {
+                value: value.into(),
+                expires_at: None,
+            },
+        };
+
+        let outcomes = app_storage
+            .apply_key_value_operations(
+                0,
+                vec![write("settings", KeyValueCondition::Absent, "light")],
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(outcomes, vec![(None, Some(1))]);
+
+        let result = app_storage.apply_key_value_operations(
+            0,
+            vec![write("settings", KeyValueCondition::Absent, "dark")],
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::VersionMismatch)));
+
+        app_storage
+            .apply_key_value_operations(
+                0,
+                vec![write("settings", KeyValueCondition::Version(1), "dark")],
+                UNIX_EPOCH,
+            )
+            .unwrap();
+
+        // A stale version fails the whole transaction
+        let result = app_storage.apply_key_value_operations(
+            0,
+            vec![
+                write("other", KeyValueCondition::Any, "value"),
+                write("settings", KeyValueCondition::Version(1), "light"),
+            ],
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::VersionMismatch)));
+        assert_eq!(
+            app_storage.get_live_entry(0, "other".into(), UNIX_EPOCH),
+            None
+        );
+
+        let outcomes = app_storage
+            .apply_key_value_operations(
+                0,
+                vec![
+                    write("other", KeyValueCondition::Absent, "value"),
+                    KeyValueOperation {
+                        key: "settings".into(),
+                        condition: KeyValueCondition::Version(2),
+                        action: KeyValueAction::Delete,
+                    },
+                ],
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(outcomes[0].1, Some(3));
+        assert_eq!(outcomes[1].0.as_ref().map(|entry| entry.version), Some(2));
+        assert_eq!(outcomes[1].1, None);
+
+        let result = app_storage.apply_key_value_operations(
+            0,
+            vec![
+                write("other", KeyValueCondition::Any, "a"),
+                write("other", KeyValueCondition::Any, "b"),
+            ],
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::InvalidTransaction)));
+    }
+
+    #[test]
+    fn test_versions_are_not_reused_after_a_delete_or_expiry() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        let expires_at = UNIX_EPOCH + Duration::from_secs(10);
+        let write = |condition: KeyValueCondition, expires_at: Option<SystemTime>| {
+            vec![KeyValueOperation {
+                key: "session".into(),
+                condition,
+                action: KeyValueAction::Write {
+                    value: "token".into(),
+                    expires_at,
+                },
+            }]
+        };
+
+        let outcomes = app_storage
+            .apply_key_value_operations(0, write(KeyValueCondition::Absent, None), UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(outcomes[0].1, Some(1));
+
+        app_storage
+            .delete_keys(0, vec!["session".into()], UNIX_EPOCH)
+            .unwrap();
+        let outcomes = app_storage
+            .apply_key_value_operations(
+                0,
+                write(KeyValueCondition::Absent, Some(expires_at)),
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(outcomes[0].1, Some(2));
+
+        // * a version read before the delete does not match the key written again
+        let result = app_storage.apply_key_value_operations(
+            0,
+            write(KeyValueCondition::Version(1), None),
+            UNIX_EPOCH,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::VersionMismatch)));
+
+        assert_eq!(app_storage.remove_expired_entries(expires_at, 10), 1);
+        let outcomes = app_storage
+            .apply_key_value_operations(0, write(KeyValueCondition::Absent, None), expires_at)
+            .unwrap();
+        assert_eq!(outcomes[0].1, Some(3));
+        assert_eq!(app_storage.get_namespace_settings(0).last_version, 3);
+    }
+
+    #[test]
+    fn test_expired_entries_are_hidden_and_swept() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        let expires_at = UNIX_EPOCH + Duration::from_secs(10);
+
+        let result = app_storage.apply_key_value_operations(
+            0,
+            vec![KeyValueOperation {
+                key: "session".into(),
+                condition: KeyValueCondition::Any,
+                action: KeyValueAction::Write {
+                    value: "token".into(),
+                    expires_at: Some(expires_at),
+                },
+            }],
+            expires_at,
+        );
+        assert!(matches!(result, Err(NamespaceErrors::InvalidExpiry)));
+
+        app_storage
+            .apply_key_value_operations(
+                0,
+                vec![KeyValueOperation {
+                    key: "session".into(),
+                    condition: KeyValueCondition::Any,
+                    action: KeyValueAction::Write {
+                        value: "token".into(),
+                        expires_at: Some(expires_at),
+                    },
+                }],
+                UNIX_EPOCH,
+            )
+            .unwrap();
+        assert!(app_storage
+            .get_live_entry(0, "session".into(), UNIX_EPOCH)
+            .is_some());
+        assert!(app_storage
+            .get_live_entry(0, "session".into(), expires_at)
+            .is_none());
+        assert!(app_storage
+            .list_keys_paginated(0, "", None, 10, expires_at)
+            .keys
+            .is_empty());
+
+        assert_eq!(app_storage.remove_expired_entries(UNIX_EPOCH, 10), 0);
+        assert_eq!(app_storage.remove_expired_entries(expires_at, 10), 1);
+        assert!(!app_storage.has_expiring_entries());
+        assert_eq!(
+            app_storage.get_namespace_settings(0).usage,
+            NamespaceUsage::default()
+        );
+    }
+
+    #[test]
+    fn test_namespace_ids_are_not_reused() {
+        let mut app_storage = AppStorage::default();
+        let first_id = app_storage.allocate_namespace_id();
+        insert_namespace(&mut app_storage, first_id);
+        let second_id = app_storage.allocate_namespace_id();
+        insert_namespace(&mut app_storage, second_id);
+
+        app_storage.remove_namespace(second_id);
+        let third_id = app_storage.allocate_namespace_id();
+        assert_eq!((first_id, second_id, third_id), (0, 1, 2));
+    }
+
+    #[test]
+    fn test_repairing_namespace_ids() {
+        let mut app_storage = AppStorage::default();
+        insert_namespace(&mut app_storage, 0);
+        app_storage.namespace_list.insert(
+            1,
+            Namespace {
+                id: 0,
+                title: "duplicate".into(),
+                namespace_owner_id: get_mock_user_alice_principal_id(),
+            },
+        );
+        // Keys left behind by a namespace that is no longer listed
+        app_storage.namespace_key_value.insert(
+            NameSpaceKey {
+                namespace_id: 4,
+                key: "orphan".into(),
+            },
+            KeyValueEntry::from_bytes(Cow::Borrowed("value".as_bytes())),
+        );
+
+        assert_eq!(app_storage.repair_namespace_ids(), 1);
+        assert_eq!(app_storage.namespace_list.get(&1).unwrap().id, 1);
+        assert_eq!(app_storage.allocate_namespace_id(), 5);
+
+        assert_eq!(app_storage.repair_namespace_ids(), 0);
+        assert_eq!(app_storage.allocate_namespace_id(), 6);
+    }
+
+    #[test]
+    fn test_read_permission_does_not_allow_writes() {
+        assert!(NamespacePermission::Read.allows(NamespacePermission::Read));
+        assert!(!NamespacePermission::Read.allows(NamespacePermission::ReadWrite));
+        assert!(NamespacePermission::ReadWrite.allows(NamespacePermission::ReadWrite));
+    }
+}
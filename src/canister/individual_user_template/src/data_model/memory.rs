@@ -19,6 +19,19 @@ const KV_STORAGE_NAMESPACE_KEY_VALUE_MEMORY: MemoryId = MemoryId::new(6);
 const WATCH_HISTORY_MEMORY: MemoryId = MemoryId::new(7);
 const SUCCESS_HISTORY_MEMORY: MemoryId = MemoryId::new(8);
 const TOKEN_LIST_MEMORY: MemoryId = MemoryId::new(9);
+const NOTIFICATION_INBOX_MEMORY: MemoryId = MemoryId::new(10);
+const UTILITY_TOKEN_TRANSACTION_HISTORY_MEMORY: MemoryId = MemoryId::new(11);
+const AIRDROP_CLAIMS_MEMORY: MemoryId = MemoryId::new(12);
+const KV_STORAGE_NAMESPACE_SETTINGS_MEMORY: MemoryId = MemoryId::new(13);
+const KV_STORAGE_KEY_EXPIRY_MEMORY: MemoryId = MemoryId::new(14);
+const KV_STORAGE_UPLOAD_MEMORY: MemoryId = MemoryId::new(15);
+const KV_STORAGE_UPLOAD_CHUNK_MEMORY: MemoryId = MemoryId::new(16);
+const KV_STORAGE_NEXT_NAMESPACE_ID_MEMORY: MemoryId = MemoryId::new(17);
+const UTILITY_TOKEN_OUTGOING_TRANSFERS_MEMORY: MemoryId = MemoryId::new(18);
+const UTILITY_TOKEN_RECEIVED_TRANSFERS_MEMORY: MemoryId = MemoryId::new(19);
+const AIRDROP_CAMPAIGN_CLAIMS_MEMORY: MemoryId = MemoryId::new(20);
+const NOTIFIED_POST_LIKES_MEMORY: MemoryId = MemoryId::new(21);
+const KV_STORAGE_DELETED_NAMESPACE_MEMORY: MemoryId = MemoryId::new(22);
 pub type Memory = VirtualMemory<DefaultMemoryImpl>;
 
 thread_local! {
@@ -68,6 +81,58 @@ pub fn get_token_list_memory() -> Memory {
     MEMORY_MANAGER.with(|m| m.borrow_mut().get(TOKEN_LIST_MEMORY))
 }
 
+pub fn get_notification_inbox_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(NOTIFICATION_INBOX_MEMORY))
+}
+
+pub fn get_utility_token_transaction_history_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(UTILITY_TOKEN_TRANSACTION_HISTORY_MEMORY))
+}
+
+pub fn get_airdrop_claims_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(AIRDROP_CLAIMS_MEMORY))
+}
+
+pub fn get_kv_storage_namespace_settings_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(KV_STORAGE_NAMESPACE_SETTINGS_MEMORY))
+}
+
+pub fn get_kv_storage_key_expiry_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(KV_STORAGE_KEY_EXPIRY_MEMORY))
+}
+
+pub fn get_kv_storage_upload_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(KV_STORAGE_UPLOAD_MEMORY))
+}
+
+pub fn get_kv_storage_upload_chunk_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(KV_STORAGE_UPLOAD_CHUNK_MEMORY))
+}
+
+pub fn get_kv_storage_next_namespace_id_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(KV_STORAGE_NEXT_NAMESPACE_ID_MEMORY))
+}
+
+pub fn get_utility_token_outgoing_transfers_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(UTILITY_TOKEN_OUTGOING_TRANSFERS_MEMORY))
+}
+
+pub fn get_utility_token_received_transfers_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(UTILITY_TOKEN_RECEIVED_TRANSFERS_MEMORY))
+}
+
+pub fn get_airdrop_campaign_claims_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(AIRDROP_CAMPAIGN_CLAIMS_MEMORY))
+}
+
+pub fn get_notified_post_likes_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(NOTIFIED_POST_LIKES_MEMORY))
+}
+
+pub fn get_kv_storage_deleted_namespace_memory() -> Memory {
+    MEMORY_MANAGER.with(|m| m.borrow_mut().get(KV_STORAGE_DELETED_NAMESPACE_MEMORY))
+}
+
 pub fn init_memory_manager() {
     MEMORY_MANAGER.with(|m| {
         *m.borrow_mut() = MemoryManager::init_with_bucket_size(DefaultMemoryImpl::default(), 1);
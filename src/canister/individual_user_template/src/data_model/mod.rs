@@ -5,13 +5,17 @@ use std::{
 
 use candid::{Deserialize, Principal};
 use ic_cdk::api::management_canister::provisional::CanisterId;
-use memory::{get_success_history_memory, get_token_list_memory, get_watch_history_memory};
+use memory::{
+    get_success_history_memory, get_token_list_memory,
+    get_utility_token_transaction_history_memory, get_watch_history_memory,
+};
 use serde::Serialize;
 use shared_utils::{
     canister_specific::individual_user_template::types::{
-        cdao::DeployedCdaoCanisters,
+        cdao::{CreatorDaoTokenStatus, DeployedCdaoCanisters},
         configuration::IndividualUserConfiguration,
         device_id::DeviceIdentity,
+        error::TokenError,
         follow::FollowData,
         hot_or_not::{
             BetDetails, GlobalBetId, GlobalRoomId, PlacedBetDetail, RoomDetailsV1, RoomId,
@@ -24,14 +28,17 @@ use shared_utils::{
         post::{FeedScore, Post, PostViewStatistics},
         profile::UserProfile,
         session::SessionType,
-        token::TokenBalance,
+        sns_neuron::PendingSnsNeuronStake,
+        token::{TokenBalance, TokenBalanceReconciliation, TokenBalanceReconciliationCheckpoint},
     },
     common::types::{
         app_primitive_type::PostId,
         known_principal::KnownPrincipalMap,
         top_posts::{post_score_index::PostScoreIndex, post_score_index_item::PostStatus},
+        utility_token::token_event::TokenEvent,
         version_details::VersionDetails,
     },
+    types::utility_token_supply_stats::UtilityTokenSupplyStatsCheckpoint,
 };
 
 use self::memory::{
@@ -39,10 +46,32 @@ use self::memory::{
     get_slot_details_memory, Memory,
 };
 
+use airdrop_campaign::AirdropCampaigns;
+use airdrop_claim::AirdropClaims;
+use cdao_deployment::CdaoDeployments;
+use creator_token_quota::CreatorTokenQuotaSettings;
+use engagement_reward::EngagementRewards;
 use kv_storage::AppStorage;
+use notification::NotificationInbox;
+use referral::Referrals;
+use swap_participation::SwapCommitments;
+use utility_token_ledger_mirror::UtilityTokenLedgerMirror;
+use utility_token_transfer::UtilityTokenTransfers;
+use wallet::Wallet;
 
+pub mod airdrop_campaign;
+pub mod airdrop_claim;
+pub mod cdao_deployment;
+pub mod creator_token_quota;
+pub mod engagement_reward;
 pub mod kv_storage;
 pub mod memory;
+pub mod notification;
+pub mod referral;
+pub mod swap_participation;
+pub mod utility_token_ledger_mirror;
+pub mod utility_token_transfer;
+pub mod wallet;
 
 #[derive(Deserialize, Serialize)]
 pub struct CanisterData {
@@ -76,6 +105,9 @@ pub struct CanisterData {
     pub last_access_time: Option<SystemTime>,
     #[serde(default)]
     pub last_canister_functionality_access_time: Option<SystemTime>,
+    /// Backfilled on upgrade for accounts created before it was recorded
+    #[serde(default)]
+    pub account_created_at: Option<SystemTime>,
     #[serde(default)]
     pub migration_info: MigrationInfo,
     #[serde(default)]
@@ -90,13 +122,128 @@ pub struct CanisterData {
     pub ml_feed_cache: Vec<MLFeedCacheItem>,
     #[serde(default)]
     pub cdao_canisters: Vec<DeployedCdaoCanisters>,
+    // Key is the root canister id of the creator token
+    #[serde(default)]
+    pub cdao_token_statuses: BTreeMap<Principal, CreatorDaoTokenStatus>,
+    #[serde(default)]
+    pub cdao_deployments: CdaoDeployments,
+    #[serde(default)]
+    pub creator_token_quota: CreatorTokenQuotaSettings,
+    #[serde(default)]
+    pub airdrop_campaigns: AirdropCampaigns,
+    #[serde(default)]
+    pub airdrop_claims: AirdropClaims,
     // list of root token canisters
     #[serde(skip, default = "_default_token_list")]
     pub token_roots: ic_stable_structures::btreemap::BTreeMap<Principal, (), Memory>,
     #[serde(default)]
+    pub wallet: Wallet,
+    /// Memo of the next neuron staked on a creator token governance canister
+    #[serde(default)]
+    pub next_sns_neuron_memo: u64,
+    // Key is the memo of the stake
+    #[serde(default)]
+    pub pending_sns_neuron_stakes: BTreeMap<u64, PendingSnsNeuronStake>,
+    #[serde(default)]
+    pub swap_commitments: SwapCommitments,
+    #[serde(default)]
     pub ml_data: MLData,
     #[serde(default)]
     pub empty_canisters: AllotedEmptyCanister,
+    #[serde(default)]
+    pub notification_inbox: NotificationInbox,
+    #[serde(default)]
+    pub utility_token_transfers: UtilityTokenTransfers,
+    #[serde(skip, default = "_default_utility_token_transaction_history")]
+    pub utility_token_transaction_history:
+        ic_stable_structures::btreemap::BTreeMap<u64, TokenEvent, Memory>,
+    #[serde(default)]
+    pub token_balance_reconciliation_checkpoint: TokenBalanceReconciliationCheckpoint,
+    #[serde(default)]
+    pub utility_token_supply_stats_checkpoint: UtilityTokenSupplyStatsCheckpoint,
+    #[serde(default)]
+    pub utility_token_ledger_mirror: UtilityTokenLedgerMirror,
+    #[serde(default)]
+    pub referrals: Referrals,
+    #[serde(default)]
+    pub engagement_rewards: EngagementRewards,
+}
+
+impl CanisterData {
+    /// Applies the event to the token balance and appends it to the transaction history. The
+    /// event is queued for the ledger when mirroring is enabled.
+    pub fn apply_token_event(&mut self, token_event: TokenEvent) -> Result<u64, TokenError> {
+        let token_event_id = self.my_token_balance.apply_token_event(
+            token_event.clone(),
+            &mut self.utility_token_transaction_history,
+        )?;
+
+        // * a checkpoint that lags behind is caught up on the next upgrade
+        let checkpoint = &mut self.token_balance_reconciliation_checkpoint;
+        if checkpoint.last_token_event_id + 1 == token_event_id {
+            checkpoint.fold_token_event(token_event_id, &token_event);
+        }
+
+        let supply_stats_checkpoint = &mut self.utility_token_supply_stats_checkpoint;
+        if supply_stats_checkpoint.last_token_event_id + 1 == token_event_id {
+            supply_stats_checkpoint.fold_token_event(token_event_id, &token_event);
+        }
+
+        self.utility_token_ledger_mirror
+            .record_token_event(token_event_id, &token_event);
+
+        Ok(token_event_id)
+    }
+
+    /// Recomputes the balance from the reconciliation checkpoint and the events recorded after it.
+    pub fn get_utility_token_balance_reconciliation(&self) -> TokenBalanceReconciliation {
+        let mut checkpoint = self.token_balance_reconciliation_checkpoint.clone();
+        self.fold_token_events_after_checkpoint(&mut checkpoint);
+
+        self.my_token_balance
+            .reconcile_balance_with_checkpoint(&checkpoint)
+    }
+
+    pub fn advance_token_balance_reconciliation_checkpoint(&mut self) {
+        let mut checkpoint = std::mem::take(&mut self.token_balance_reconciliation_checkpoint);
+        self.fold_token_events_after_checkpoint(&mut checkpoint);
+        self.token_balance_reconciliation_checkpoint = checkpoint;
+    }
+
+    /// Supply stats folded from the checkpoint and the events recorded after it.
+    pub fn get_utility_token_supply_stats_checkpoint(&self) -> UtilityTokenSupplyStatsCheckpoint {
+        let mut checkpoint = self.utility_token_supply_stats_checkpoint.clone();
+        self.fold_token_events_after_supply_stats_checkpoint(&mut checkpoint);
+        checkpoint
+    }
+
+    pub fn advance_utility_token_supply_stats_checkpoint(&mut self) {
+        let mut checkpoint = std::mem::take(&mut self.utility_token_supply_stats_checkpoint);
+        self.fold_token_events_after_supply_stats_checkpoint(&mut checkpoint);
+        self.utility_token_supply_stats_checkpoint = checkpoint;
+    }
+
+    fn fold_token_events_after_supply_stats_checkpoint(
+        &self,
+        checkpoint: &mut UtilityTokenSupplyStatsCheckpoint,
+    ) {
+        self.utility_token_transaction_history
+            .range(checkpoint.last_token_event_id + 1..)
+            .for_each(|(token_event_id, token_event)| {
+                checkpoint.fold_token_event(token_event_id, &token_event);
+            });
+    }
+
+    fn fold_token_events_after_checkpoint(
+        &self,
+        checkpoint: &mut TokenBalanceReconciliationCheckpoint,
+    ) {
+        self.utility_token_transaction_history
+            .range(checkpoint.last_token_event_id + 1..)
+            .for_each(|(token_event_id, token_event)| {
+                checkpoint.fold_token_event(token_event_id, &token_event);
+            });
+    }
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -174,6 +321,11 @@ pub fn _default_success_history_v1(
     ic_stable_structures::btreemap::BTreeMap::init(get_success_history_memory())
 }
 
+pub fn _default_utility_token_transaction_history(
+) -> ic_stable_structures::btreemap::BTreeMap<u64, TokenEvent, Memory> {
+    ic_stable_structures::btreemap::BTreeMap::init(get_utility_token_transaction_history_memory())
+}
+
 impl Default for CanisterData {
     fn default() -> Self {
         Self {
@@ -196,6 +348,7 @@ impl Default for CanisterData {
             session_type: None,
             last_access_time: None,
             last_canister_functionality_access_time: None,
+            account_created_at: None,
             migration_info: MigrationInfo::NotMigrated,
             app_storage: AppStorage::default(),
             watch_history: _default_watch_history(),
@@ -203,9 +356,26 @@ impl Default for CanisterData {
             device_identities: Vec::new(),
             ml_feed_cache: Vec::new(),
             cdao_canisters: Vec::new(),
+            cdao_token_statuses: BTreeMap::new(),
+            cdao_deployments: CdaoDeployments::default(),
+            creator_token_quota: CreatorTokenQuotaSettings::default(),
+            airdrop_campaigns: AirdropCampaigns::default(),
+            airdrop_claims: AirdropClaims::default(),
             token_roots: _default_token_list(),
+            wallet: Wallet::default(),
+            next_sns_neuron_memo: 0,
+            pending_sns_neuron_stakes: BTreeMap::new(),
+            swap_commitments: SwapCommitments::default(),
             ml_data: MLData::default(),
             empty_canisters: AllotedEmptyCanister::default(),
+            notification_inbox: NotificationInbox::default(),
+            utility_token_transfers: UtilityTokenTransfers::default(),
+            utility_token_transaction_history: _default_utility_token_transaction_history(),
+            token_balance_reconciliation_checkpoint: Default::default(),
+            utility_token_supply_stats_checkpoint: Default::default(),
+            utility_token_ledger_mirror: UtilityTokenLedgerMirror::default(),
+            referrals: Referrals::default(),
+            engagement_rewards: EngagementRewards::default(),
         }
     }
 }
@@ -0,0 +1,232 @@
+use std::time::SystemTime;
+
+use candid::Principal;
+use ic_stable_structures::StableBTreeMap;
+use serde::{Deserialize, Serialize};
+use shared_utils::{
+    canister_specific::individual_user_template::types::notification::{
+        Notification, NotificationId, NotificationType,
+    },
+    common::types::app_primitive_type::PostId,
+};
+
+use super::memory::{get_notification_inbox_memory, get_notified_post_likes_memory, Memory};
+
+pub const MAX_NOTIFICATIONS_IN_INBOX: u64 = 500;
+
+#[derive(Serialize, Deserialize)]
+pub struct NotificationInbox {
+    #[serde(skip, default = "_default_notifications")]
+    notifications: StableBTreeMap<NotificationId, Notification, Memory>,
+    // Likes that were notified, so that toggling a like does not notify it again
+    #[serde(skip, default = "_default_notified_post_likes")]
+    notified_post_likes: StableBTreeMap<(PostId, Principal), (), Memory>,
+}
+
+impl Default for NotificationInbox {
+    fn default() -> Self {
+        Self {
+            notifications: _default_notifications(),
+            notified_post_likes: _default_notified_post_likes(),
+        }
+    }
+}
+
+impl NotificationInbox {
+    /// Adds a notification and evicts the oldest ones once the inbox is full.
+    pub fn add_notification(
+        &mut self,
+        notification_type: NotificationType,
+        created_at: SystemTime,
+    ) -> NotificationId {
+        let notification_id = self
+            .notifications
+            .last_key_value()
+            .map_or(0, |(id, _)| id + 1);
+
+        self.notifications.insert(
+            notification_id,
+            Notification {
+                notification_type,
+                created_at,
+                read: false,
+            },
+        );
+
+        while self.notifications.len() > MAX_NOTIFICATIONS_IN_INBOX {
+            self.notifications.pop_first();
+        }
+
+        notification_id
+    }
+
+    /// Notifies a like once per post and liker. Returns `None` when the like was notified before.
+    pub fn add_post_liked_notification(
+        &mut self,
+        post_id: PostId,
+        liked_by_principal_id: Principal,
+        created_at: SystemTime,
+    ) -> Option<NotificationId> {
+        if self
+            .notified_post_likes
+            .insert((post_id, liked_by_principal_id), ())
+            .is_some()
+        {
+            return None;
+        }
+
+        Some(self.add_notification(
+            NotificationType::PostLiked {
+                post_id,
+                liked_by_principal_id,
+            },
+            created_at,
+        ))
+    }
+
+    /// Returns notifications newest first.
+    pub fn get_notifications(
+        &self,
+        from_inclusive_index: u64,
+        limit: u64,
+    ) -> Vec<(NotificationId, Notification)> {
+        self.notifications
+            .iter()
+            .rev()
+            .skip(from_inclusive_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    pub fn mark_as_read(&mut self, notification_ids: &[NotificationId]) {
+        notification_ids.iter().for_each(|notification_id| {
+            if let Some(mut notification) = self.notifications.get(notification_id) {
+                if !notification.read {
+                    notification.read = true;
+                    self.notifications.insert(*notification_id, notification);
+                }
+            }
+        });
+    }
+
+    pub fn mark_all_as_read(&mut self) {
+        let unread_notification_ids: Vec<NotificationId> = self
+            .notifications
+            .iter()
+            .filter(|(_, notification)| !notification.read)
+            .map(|(notification_id, _)| notification_id)
+            .collect();
+
+        self.mark_as_read(&unread_notification_ids);
+    }
+
+    pub fn unread_count(&self) -> u64 {
+        self.notifications
+            .iter()
+            .filter(|(_, notification)| !notification.read)
+            .count() as u64
+    }
+
+    pub fn len(&self) -> u64 {
+        self.notifications.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notifications.is_empty()
+    }
+}
+
+pub fn _default_notifications() -> StableBTreeMap<NotificationId, Notification, Memory> {
+    ic_stable_structures::StableBTreeMap::init(get_notification_inbox_memory())
+}
+
+pub fn _default_notified_post_likes() -> StableBTreeMap<(PostId, Principal), (), Memory> {
+    ic_stable_structures::StableBTreeMap::init(get_notified_post_likes_memory())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    fn new_follower_notification_type() -> NotificationType {
+        NotificationType::NewFollower {
+            follower_principal_id: get_mock_user_alice_principal_id(),
+            follower_canister_id: get_mock_user_alice_canister_id(),
+        }
+    }
+
+    #[test]
+    fn test_notification_inbox_is_bounded() {
+        let mut notification_inbox = NotificationInbox::default();
+
+        (0..MAX_NOTIFICATIONS_IN_INBOX + 10).for_each(|_| {
+            notification_inbox.add_notification(new_follower_notification_type(), UNIX_EPOCH);
+        });
+
+        assert_eq!(notification_inbox.len(), MAX_NOTIFICATIONS_IN_INBOX);
+
+        let newest = notification_inbox.get_notifications(0, 1);
+        assert_eq!(newest[0].0, MAX_NOTIFICATIONS_IN_INBOX + 9);
+
+        let oldest = notification_inbox.get_notifications(MAX_NOTIFICATIONS_IN_INBOX - 1, 10);
+        assert_eq!(oldest.len(), 1);
+        assert_eq!(oldest[0].0, 10);
+    }
+
+    #[test]
+    fn test_mark_notifications_as_read() {
+        let mut notification_inbox = NotificationInbox::default();
+
+        let ids: Vec<NotificationId> = (0..5)
+            .map(|_| {
+                notification_inbox.add_notification(new_follower_notification_type(), UNIX_EPOCH)
+            })
+            .collect();
+
+        assert_eq!(notification_inbox.unread_count(), 5);
+
+        notification_inbox.mark_as_read(&ids[0..2]);
+        assert_eq!(notification_inbox.unread_count(), 3);
+
+        // * unknown ids are ignored
+        notification_inbox.mark_as_read(&[100]);
+        assert_eq!(notification_inbox.unread_count(), 3);
+
+        notification_inbox.mark_all_as_read();
+        assert_eq!(notification_inbox.unread_count(), 0);
+        assert_eq!(notification_inbox.len(), 5);
+    }
+
+    #[test]
+    fn test_post_likes_are_notified_once_per_liker() {
+        let mut notification_inbox = NotificationInbox::default();
+        let alice = get_mock_user_alice_principal_id();
+        let bob = get_mock_user_bob_principal_id();
+
+        assert_eq!(
+            notification_inbox.add_post_liked_notification(0, alice, UNIX_EPOCH),
+            Some(0)
+        );
+        // * unliking and liking the post again
+        assert_eq!(
+            notification_inbox.add_post_liked_notification(0, alice, UNIX_EPOCH),
+            None
+        );
+        assert_eq!(
+            notification_inbox.add_post_liked_notification(1, alice, UNIX_EPOCH),
+            Some(1)
+        );
+        assert_eq!(
+            notification_inbox.add_post_liked_notification(0, bob, UNIX_EPOCH),
+            Some(2)
+        );
+        assert_eq!(notification_inbox.len(), 3);
+    }
+}
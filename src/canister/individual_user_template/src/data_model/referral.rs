@@ -0,0 +1,273 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::SystemTime,
+};
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::referral::{
+    ReferralConfig, ReferralDetails, ReferralRejectionReason, ReferralStatus,
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ReferralEntry {
+    referee_canister_id: Principal,
+    referee_device_ids: BTreeSet<String>,
+    status: ReferralStatus,
+    referred_at: SystemTime,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Referrals {
+    config: ReferralConfig,
+    // Key is the referee's principal
+    referrals: BTreeMap<Principal, ReferralEntry>,
+    /// Reward for being referred by the referrer in the profile
+    my_referral_status: Option<ReferralStatus>,
+}
+
+impl Referrals {
+    pub fn get_config(&self) -> ReferralConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: ReferralConfig) {
+        self.config = config;
+    }
+
+    pub fn get_my_referral_status(&self) -> Option<ReferralStatus> {
+        self.my_referral_status
+    }
+
+    pub fn set_my_referral_status(&mut self, status: Option<ReferralStatus>) {
+        self.my_referral_status = status;
+    }
+
+    pub fn get_referral_status(&self, referee_principal_id: Principal) -> Option<ReferralStatus> {
+        self.referrals
+            .get(&referee_principal_id)
+            .map(|referral_entry| referral_entry.status)
+    }
+
+    pub fn len(&self) -> u64 {
+        self.referrals.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.referrals.is_empty()
+    }
+
+    fn get_number_of_rewarded_referrals(&self) -> u64 {
+        self.referrals
+            .values()
+            .filter(|referral_entry| {
+                matches!(referral_entry.status, ReferralStatus::Rewarded { .. })
+            })
+            .count() as u64
+    }
+
+    /// Checks a new referral against the referrer's own identity and devices, the referrer's
+    /// earlier referees and the per referrer reward cap.
+    pub fn evaluate_referral<'a>(
+        &self,
+        my_principal_id: Principal,
+        my_referrer_principal_id: Option<Principal>,
+        my_device_ids: impl IntoIterator<Item = &'a str>,
+        referee_principal_id: Principal,
+        referee_device_ids: &BTreeSet<String>,
+    ) -> Result<(), ReferralRejectionReason> {
+        if referee_principal_id == my_principal_id
+            || my_device_ids
+                .into_iter()
+                .any(|device_id| referee_device_ids.contains(device_id))
+        {
+            return Err(ReferralRejectionReason::SelfReferral);
+        }
+
+        let shares_device_with_another_referee =
+            self.referrals
+                .iter()
+                .any(|(other_referee_principal_id, referral_entry)| {
+                    *other_referee_principal_id != referee_principal_id
+                        && !referral_entry
+                            .referee_device_ids
+                            .is_disjoint(referee_device_ids)
+                });
+
+        if my_referrer_principal_id == Some(referee_principal_id)
+            || shares_device_with_another_referee
+        {
+            return Err(ReferralRejectionReason::ReferralRing);
+        }
+
+        if self.get_number_of_rewarded_referrals()
+            >= self.config.max_rewarded_referrals_per_referrer
+        {
+            return Err(ReferralRejectionReason::ReferrerRewardCapReached);
+        }
+
+        Ok(())
+    }
+
+    pub fn record_referral(
+        &mut self,
+        referee_principal_id: Principal,
+        referee_canister_id: Principal,
+        referee_device_ids: BTreeSet<String>,
+        status: ReferralStatus,
+        referred_at: SystemTime,
+    ) {
+        self.referrals.insert(
+            referee_principal_id,
+            ReferralEntry {
+                referee_canister_id,
+                referee_device_ids,
+                status,
+                referred_at,
+            },
+        );
+    }
+
+    pub fn get_referrals(
+        &self,
+        from_inclusive_index: u64,
+        to_exclusive_index: u64,
+    ) -> Vec<ReferralDetails> {
+        self.referrals
+            .iter()
+            .skip(from_inclusive_index as usize)
+            .take((to_exclusive_index - from_inclusive_index) as usize)
+            .map(|(referee_principal_id, referral_entry)| ReferralDetails {
+                referee_principal_id: *referee_principal_id,
+                referee_canister_id: referral_entry.referee_canister_id,
+                status: referral_entry.status,
+                referred_at: referral_entry.referred_at,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_canister_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_canister_id,
+        get_mock_user_charlie_principal_id, get_mock_user_dan_principal_id,
+    };
+
+    use super::*;
+
+    fn device_ids(device_ids: &[&str]) -> BTreeSet<String> {
+        device_ids
+            .iter()
+            .map(|device_id| device_id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate_referral() {
+        let mut referrals = Referrals::default();
+        referrals.set_config(ReferralConfig {
+            max_rewarded_referrals_per_referrer: 2,
+            ..Default::default()
+        });
+
+        let alice = get_mock_user_alice_principal_id();
+        let alice_devices = ["alice-phone"];
+
+        assert_eq!(
+            referrals.evaluate_referral(
+                alice,
+                None,
+                alice_devices,
+                alice,
+                &device_ids(&["another-phone"])
+            ),
+            Err(ReferralRejectionReason::SelfReferral)
+        );
+        assert_eq!(
+            referrals.evaluate_referral(
+                alice,
+                None,
+                alice_devices,
+                get_mock_user_bob_principal_id(),
+                &device_ids(&["alice-phone"])
+            ),
+            Err(ReferralRejectionReason::SelfReferral)
+        );
+
+        // * bob referred alice, so alice cannot refer bob back
+        assert_eq!(
+            referrals.evaluate_referral(
+                alice,
+                Some(get_mock_user_bob_principal_id()),
+                alice_devices,
+                get_mock_user_bob_principal_id(),
+                &device_ids(&["bob-phone"])
+            ),
+            Err(ReferralRejectionReason::ReferralRing)
+        );
+
+        assert_eq!(
+            referrals.evaluate_referral(
+                alice,
+                None,
+                alice_devices,
+                get_mock_user_bob_principal_id(),
+                &device_ids(&["bob-phone"])
+            ),
+            Ok(())
+        );
+        referrals.record_referral(
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+            device_ids(&["bob-phone"]),
+            ReferralStatus::Rewarded { amount: 500 },
+            UNIX_EPOCH,
+        );
+
+        // * charlie signs in from bob's phone
+        assert_eq!(
+            referrals.evaluate_referral(
+                alice,
+                None,
+                alice_devices,
+                get_mock_user_charlie_principal_id(),
+                &device_ids(&["charlie-phone", "bob-phone"])
+            ),
+            Err(ReferralRejectionReason::ReferralRing)
+        );
+
+        referrals.record_referral(
+            get_mock_user_charlie_principal_id(),
+            get_mock_user_charlie_canister_id(),
+            device_ids(&["charlie-phone"]),
+            ReferralStatus::Rewarded { amount: 500 },
+            UNIX_EPOCH,
+        );
+
+        assert_eq!(
+            referrals.evaluate_referral(
+                alice,
+                None,
+                alice_devices,
+                get_mock_user_dan_principal_id(),
+                &device_ids(&["dan-phone"])
+            ),
+            Err(ReferralRejectionReason::ReferrerRewardCapReached)
+        );
+
+        assert_eq!(referrals.len(), 2);
+        let listed_referrals = referrals.get_referrals(0, 5);
+        assert_eq!(listed_referrals.len(), 2);
+        assert!(listed_referrals.contains(&ReferralDetails {
+            referee_principal_id: get_mock_user_charlie_principal_id(),
+            referee_canister_id: get_mock_user_charlie_canister_id(),
+            status: ReferralStatus::Rewarded { amount: 500 },
+            referred_at: UNIX_EPOCH,
+        }));
+        assert_eq!(referrals.get_referrals(1, 5).len(), 1);
+    }
+}
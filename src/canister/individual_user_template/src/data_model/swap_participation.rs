@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, time::SystemTime};
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::swap_participation::{
+    SwapCommitment, SwapCommitmentStatus,
+};
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct SwapCommitments {
+    // Key is the root canister id of the token
+    commitments: BTreeMap<Principal, SwapCommitment>,
+}
+
+impl SwapCommitments {
+    pub fn get_commitment(&self, token_root: Principal) -> Option<&SwapCommitment> {
+        self.commitments.get(&token_root)
+    }
+
+    pub fn get_commitments(&self) -> Vec<SwapCommitment> {
+        self.commitments.values().cloned().collect()
+    }
+
+    /// Marks the commitment as pending before ICP is sent to the swap, keeping the amount
+    /// accepted by earlier participations
+    pub fn record_pending_commitment(
+        &mut self,
+        token_root: Principal,
+        swap_canister: Principal,
+        current_time: SystemTime,
+    ) -> SwapCommitment {
+        let commitment = self
+            .commitments
+            .entry(token_root)
+            .or_insert_with(|| SwapCommitment {
+                token_root,
+                swap_canister,
+                amount_icp_e8s: 0,
+                status: SwapCommitmentStatus::Pending,
+                committed_at: current_time,
+                updated_at: current_time,
+            });
+        commitment.status = SwapCommitmentStatus::Pending;
+        commitment.updated_at = current_time;
+
+        commitment.clone()
+    }
+
+    /// Drops a pending commitment when its transfer failed. A commitment with ICP accepted by
+    /// an earlier participation goes back to committed.
+    pub fn revert_pending_commitment(&mut self, token_root: Principal, current_time: SystemTime) {
+        let Some(commitment) = self.commitments.get_mut(&token_root) else {
+            return;
+        };
+        if commitment.status != SwapCommitmentStatus::Pending {
+            return;
+        }
+
+        if commitment.amount_icp_e8s == 0 {
+            self.commitments.remove(&token_root);
+        } else {
+            commitment.status = SwapCommitmentStatus::Committed;
+            commitment.updated_at = current_time;
+        }
+    }
+
+    /// Records the total ICP the swap has accepted from this canister so far
+    pub fn record_commitment(
+        &mut self,
+        token_root: Principal,
+        swap_canister: Principal,
+        amount_icp_e8s: u64,
+        current_time: SystemTime,
+    ) -> SwapCommitment {
+        let commitment = self
+            .commitments
+            .entry(token_root)
+            .or_insert_with(|| SwapCommitment {
+                token_root,
+                swap_canister,
+                amount_icp_e8s,
+                status: SwapCommitmentStatus::Committed,
+                committed_at: current_time,
+                updated_at: current_time,
+            });
+        commitment.amount_icp_e8s = amount_icp_e8s;
+        commitment.status = SwapCommitmentStatus::Committed;
+        commitment.updated_at = current_time;
+
+        commitment.clone()
+    }
+
+    pub fn set_status(
+        &mut self,
+        token_root: Principal,
+        status: SwapCommitmentStatus,
+        current_time: SystemTime,
+    ) -> Option<SwapCommitment> {
+        let commitment = self.commitments.get_mut(&token_root)?;
+        commitment.status = status;
+        commitment.updated_at = current_time;
+
+        Some(commitment.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_record_commitment_keeps_the_first_commitment_time() {
+        let mut swap_commitments = SwapCommitments::default();
+        let token_root = get_mock_user_alice_canister_id();
+        let swap_canister = get_mock_user_bob_canister_id();
+        let later = UNIX_EPOCH + Duration::from_secs(10);
+
+        swap_commitments.record_commitment(token_root, swap_canister, 100, UNIX_EPOCH);
+        let commitment = swap_commitments.record_commitment(token_root, swap_canister, 300, later);
+
+        assert_eq!(commitment.amount_icp_e8s, 300);
+        assert_eq!(commitment.committed_at, UNIX_EPOCH);
+        assert_eq!(commitment.updated_at, later);
+        assert_eq!(commitment.status, SwapCommitmentStatus::Committed);
+
+        let commitment = swap_commitments
+            .set_status(token_root, SwapCommitmentStatus::Claimed, later)
+            .unwrap();
+        assert_eq!(commitment.status, SwapCommitmentStatus::Claimed);
+        assert_eq!(swap_commitments.get_commitments(), vec![commitment]);
+
+        assert!(swap_commitments
+            .set_status(swap_canister, SwapCommitmentStatus::Claimed, later)
+            .is_none());
+    }
+
+    #[test]
+    fn test_pending_commitment_is_confirmed_or_reverted() {
+        let mut swap_commitments = SwapCommitments::default();
+        let token_root = get_mock_user_alice_canister_id();
+        let swap_canister = get_mock_user_bob_canister_id();
+        let later = UNIX_EPOCH + Duration::from_secs(10);
+
+        let commitment =
+            swap_commitments.record_pending_commitment(token_root, swap_canister, UNIX_EPOCH);
+        assert_eq!(commitment.status, SwapCommitmentStatus::Pending);
+        assert_eq!(commitment.amount_icp_e8s, 0);
+
+        // * a failed first participation leaves nothing behind
+        swap_commitments.revert_pending_commitment(token_root, later);
+        assert!(swap_commitments.get_commitment(token_root).is_none());
+
+        swap_commitments.record_pending_commitment(token_root, swap_canister, UNIX_EPOCH);
+        let commitment = swap_commitments.record_commitment(token_root, swap_canister, 100, later);
+        assert_eq!(commitment.status, SwapCommitmentStatus::Committed);
+        assert_eq!(commitment.committed_at, UNIX_EPOCH);
+
+        // * a failed top up keeps the ICP accepted before
+        swap_commitments.record_pending_commitment(token_root, swap_canister, later);
+        swap_commitments.revert_pending_commitment(token_root, later);
+        let commitment = swap_commitments.get_commitment(token_root).unwrap();
+        assert_eq!(commitment.status, SwapCommitmentStatus::Committed);
+        assert_eq!(commitment.amount_icp_e8s, 100);
+    }
+}
@@ -0,0 +1,426 @@
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+use shared_utils::{
+    canister_specific::individual_user_template::types::token::{
+        get_recomputed_balance_change_for_token_event, UtilityTokenLedgerMirrorStatus,
+    },
+    common::types::utility_token::token_event::TokenEvent,
+};
+
+/// Key of the operation minting the balance held before mirroring was enabled. Token event ids
+/// start at 1 so it never collides with an event.
+pub const UTILITY_TOKEN_LEDGER_MIRROR_OPENING_BALANCE_OPERATION_ID: u64 = 0;
+/// Once this many operations are queued, new ones are merged into the last queued operation
+pub const MAX_PENDING_UTILITY_TOKEN_LEDGER_MIRROR_OPERATIONS: usize = 1000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UtilityTokenLedgerMirrorOperation {
+    Mint { amount: u64 },
+    Burn { amount: u64 },
+}
+
+impl UtilityTokenLedgerMirrorOperation {
+    fn from_balance_change(balance_change: i128) -> Option<Self> {
+        match balance_change {
+            0 => None,
+            change if change > 0 => Some(Self::Mint {
+                amount: change as u64,
+            }),
+            change => Some(Self::Burn {
+                amount: change.unsigned_abs() as u64,
+            }),
+        }
+    }
+
+    fn get_balance_change(&self) -> i128 {
+        match self {
+            Self::Mint { amount } => *amount as i128,
+            Self::Burn { amount } => -(*amount as i128),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingUtilityTokenLedgerMirrorOperation {
+    pub operation: UtilityTokenLedgerMirrorOperation,
+    /// Sent as the ledger `created_at_time` so a retried operation is deduplicated
+    pub created_at_time: SystemTime,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct UtilityTokenLedgerMirror {
+    ledger_canister_id: Option<Principal>,
+    // Key is the id of the mirrored token event
+    pending_operations: BTreeMap<u64, PendingUtilityTokenLedgerMirrorOperation>,
+    /// Balance of the mirror account on the ledger once every completed operation is applied
+    #[serde(default)]
+    mirrored_balance: u64,
+}
+
+impl UtilityTokenLedgerMirror {
+    pub fn get_ledger_canister_id(&self) -> Option<Principal> {
+        self.ledger_canister_id
+    }
+
+    pub fn get_status(&self) -> UtilityTokenLedgerMirrorStatus {
+        UtilityTokenLedgerMirrorStatus {
+            ledger_canister_id: self.ledger_canister_id,
+            number_of_pending_operations: self.pending_operations.len() as u64,
+        }
+    }
+
+    /// Starts mirroring to the given ledger, minting the current balance there first.
+    pub fn enable(
+        &mut self,
+        ledger_canister_id: Principal,
+        current_balance: u64,
+        current_time: SystemTime,
+    ) {
+        self.ledger_canister_id = Some(ledger_canister_id);
+        self.pending_operations.clear();
+        self.mirrored_balance = 0;
+
+        if current_balance > 0 {
+            self.pending_operations.insert(
+                UTILITY_TOKEN_LEDGER_MIRROR_OPENING_BALANCE_OPERATION_ID,
+                PendingUtilityTokenLedgerMirrorOperation {
+                    operation: UtilityTokenLedgerMirrorOperation::Mint {
+                        amount: current_balance,
+                    },
+                    created_at_time: current_time,
+                },
+            );
+        }
+    }
+
+    pub fn disable(&mut self) {
+        self.ledger_canister_id = None;
+        self.pending_operations.clear();
+        self.mirrored_balance = 0;
+    }
+
+    /// Queues the ledger operation for an applied token event. Events that do not change the
+    /// balance are not mirrored. When the queue is full the change is merged into the last queued
+    /// operation, which has never been sent as only the first operation is.
+    pub fn record_token_event(&mut self, token_event_id: u64, token_event: &TokenEvent) {
+        if self.ledger_canister_id.is_none() {
+            return;
+        }
+
+        let Some(created_at_time) = token_event.get_timestamp() else {
+            return;
+        };
+
+        let balance_change = get_recomputed_balance_change_for_token_event(token_event);
+
+        if self.pending_operations.len() >= MAX_PENDING_UTILITY_TOKEN_LEDGER_MIRROR_OPERATIONS {
+            self.merge_into_last_pending_operation(balance_change);
+            return;
+        }
+
+        let Some(operation) =
+            UtilityTokenLedgerMirrorOperation::from_balance_change(balance_change)
+        else {
+            return;
+        };
+
+        self.pending_operations.insert(
+            token_event_id,
+            PendingUtilityTokenLedgerMirrorOperation {
+                operation,
+                created_at_time,
+            },
+        );
+    }
+
+    pub fn get_next_pending_operation(
+        &self,
+    ) -> Option<(u64, PendingUtilityTokenLedgerMirrorOperation)> {
+        self.pending_operations
+            .first_key_value()
+            .map(|(operation_id, pending_operation)| (*operation_id, *pending_operation))
+    }
+
+    fn merge_into_last_pending_operation(&mut self, balance_change: i128) {
+        let Some((&last_operation_id, last_pending_operation)) =
+            self.pending_operations.last_key_value()
+        else {
+            return;
+        };
+
+        let merged_operation = UtilityTokenLedgerMirrorOperation::from_balance_change(
+            last_pending_operation.operation.get_balance_change() + balance_change,
+        );
+
+        match merged_operation {
+            Some(operation) => {
+                let created_at_time = last_pending_operation.created_at_time;
+                self.pending_operations.insert(
+                    last_operation_id,
+                    PendingUtilityTokenLedgerMirrorOperation {
+                        operation,
+                        created_at_time,
+                    },
+                );
+            }
+            None => {
+                self.pending_operations.remove(&last_operation_id);
+            }
+        }
+    }
+
+    /// Removes an operation the ledger has applied.
+    pub fn complete_operation(&mut self, operation_id: u64) {
+        if let Some(pending_operation) = self.pending_operations.remove(&operation_id) {
+            self.mirrored_balance = (self.mirrored_balance as i128
+                + pending_operation.operation.get_balance_change())
+            .clamp(0, u64::MAX as i128) as u64;
+        }
+    }
+
+    /// Removes an operation the ledger will never apply.
+    pub fn drop_operation(&mut self, operation_id: u64) {
+        self.pending_operations.remove(&operation_id);
+    }
+
+    /// Operations are applied one at a time and in order, so the ledger balance of the mirror
+    /// account tells whether the first pending operation was applied. Returns `None` when the
+    /// balance matches neither case.
+    pub fn is_operation_applied_on_ledger(
+        &self,
+        operation_id: u64,
+        mirror_account_ledger_balance: u64,
+    ) -> Option<bool> {
+        let pending_operation = self.pending_operations.get(&operation_id)?;
+        let mirrored_balance = self.mirrored_balance as i128;
+        let ledger_balance = mirror_account_ledger_balance as i128;
+
+        if ledger_balance == mirrored_balance + pending_operation.operation.get_balance_change() {
+            Some(true)
+        } else if ledger_balance == mirrored_balance {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// The ledger only deduplicates within its transaction window, an operation older than
+    /// that and not applied yet is resent with a fresh timestamp.
+    pub fn refresh_operation_created_at_time(
+        &mut self,
+        operation_id: u64,
+        current_time: SystemTime,
+    ) {
+        if let Some(pending_operation) = self.pending_operations.get_mut(&operation_id) {
+            pending_operation.created_at_time = current_time;
+        }
+    }
+}
+
+pub fn get_ledger_created_at_time(created_at_time: SystemTime) -> u64 {
+    created_at_time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use shared_utils::{
+        canister_specific::individual_user_template::types::hot_or_not::BetDirection,
+        common::types::utility_token::token_event::{MintEvent, StakeEvent},
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_record_token_event() {
+        let mut utility_token_ledger_mirror = UtilityTokenLedgerMirror::default();
+        let timestamp = UNIX_EPOCH + Duration::from_secs(10);
+        let mint = TokenEvent::Mint {
+            amount: 1000,
+            details: MintEvent::NewUserSignup {
+                new_user_principal_id: get_mock_user_alice_principal_id(),
+            },
+            timestamp,
+        };
+
+        // * nothing is queued while mirroring is disabled
+        utility_token_ledger_mirror.record_token_event(1, &mint);
+        assert_eq!(
+            utility_token_ledger_mirror.get_next_pending_operation(),
+            None
+        );
+
+        utility_token_ledger_mirror.enable(get_mock_user_bob_canister_id(), 1000, UNIX_EPOCH);
+        utility_token_ledger_mirror.record_token_event(2, &mint);
+        utility_token_ledger_mirror.record_token_event(
+            3,
+            &TokenEvent::Stake {
+                amount: 100,
+                details: StakeEvent::BetOnHotOrNotPost {
+                    post_canister_id: get_mock_user_alice_canister_id(),
+                    post_id: 0,
+                    bet_amount: 100,
+                    bet_direction: BetDirection::Hot,
+                },
+                timestamp,
+            },
+        );
+        utility_token_ledger_mirror.record_token_event(4, &TokenEvent::Burn);
+
+        assert_eq!(
+            utility_token_ledger_mirror.get_status(),
+            UtilityTokenLedgerMirrorStatus {
+                ledger_canister_id: Some(get_mock_user_bob_canister_id()),
+                number_of_pending_operations: 3,
+            }
+        );
+        assert_eq!(
+            utility_token_ledger_mirror.get_next_pending_operation(),
+            Some((
+                UTILITY_TOKEN_LEDGER_MIRROR_OPENING_BALANCE_OPERATION_ID,
+                PendingUtilityTokenLedgerMirrorOperation {
+                    operation: UtilityTokenLedgerMirrorOperation::Mint { amount: 1000 },
+                    created_at_time: UNIX_EPOCH,
+                }
+            ))
+        );
+
+        utility_token_ledger_mirror
+            .complete_operation(UTILITY_TOKEN_LEDGER_MIRROR_OPENING_BALANCE_OPERATION_ID);
+        utility_token_ledger_mirror.complete_operation(2);
+
+        assert_eq!(
+            utility_token_ledger_mirror.get_next_pending_operation(),
+            Some((
+                3,
+                PendingUtilityTokenLedgerMirrorOperation {
+                    operation: UtilityTokenLedgerMirrorOperation::Burn { amount: 100 },
+                    created_at_time: timestamp,
+                }
+            ))
+        );
+
+        utility_token_ledger_mirror.disable();
+        assert_eq!(
+            utility_token_ledger_mirror.get_next_pending_operation(),
+            None
+        );
+    }
+
+    fn receive(amount: u64) -> TokenEvent {
+        TokenEvent::Receive {
+            amount,
+            from_account: get_mock_user_alice_canister_id(),
+            timestamp: UNIX_EPOCH + Duration::from_secs(10),
+            memo: None,
+        }
+    }
+
+    fn transfer(amount: u64) -> TokenEvent {
+        TokenEvent::Transfer {
+            amount,
+            to_account: get_mock_user_alice_canister_id(),
+            timestamp: UNIX_EPOCH + Duration::from_secs(10),
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn test_record_token_event_merges_operations_into_a_full_queue() {
+        let mut utility_token_ledger_mirror = UtilityTokenLedgerMirror::default();
+        utility_token_ledger_mirror.enable(get_mock_user_bob_canister_id(), 0, UNIX_EPOCH);
+
+        (1..=MAX_PENDING_UTILITY_TOKEN_LEDGER_MIRROR_OPERATIONS as u64).for_each(
+            |token_event_id| {
+                utility_token_ledger_mirror.record_token_event(token_event_id, &receive(1));
+            },
+        );
+
+        let last_operation_id = MAX_PENDING_UTILITY_TOKEN_LEDGER_MIRROR_OPERATIONS as u64;
+        utility_token_ledger_mirror.record_token_event(last_operation_id + 1, &receive(10));
+        assert_eq!(
+            utility_token_ledger_mirror
+                .get_status()
+                .number_of_pending_operations,
+            MAX_PENDING_UTILITY_TOKEN_LEDGER_MIRROR_OPERATIONS as u64
+        );
+        assert_eq!(
+            utility_token_ledger_mirror
+                .pending_operations
+                .get(&last_operation_id)
+                .map(|pending_operation| pending_operation.operation),
+            Some(UtilityTokenLedgerMirrorOperation::Mint { amount: 11 })
+        );
+
+        utility_token_ledger_mirror.record_token_event(last_operation_id + 2, &transfer(15));
+        assert_eq!(
+            utility_token_ledger_mirror
+                .pending_operations
+                .get(&last_operation_id)
+                .map(|pending_operation| pending_operation.operation),
+            Some(UtilityTokenLedgerMirrorOperation::Burn { amount: 4 })
+        );
+
+        // * changes that cancel out leave nothing to send
+        utility_token_ledger_mirror.record_token_event(last_operation_id + 3, &receive(4));
+        assert_eq!(
+            utility_token_ledger_mirror
+                .get_status()
+                .number_of_pending_operations,
+            MAX_PENDING_UTILITY_TOKEN_LEDGER_MIRROR_OPERATIONS as u64 - 1
+        );
+    }
+
+    #[test]
+    fn test_is_operation_applied_on_ledger() {
+        let mut utility_token_ledger_mirror = UtilityTokenLedgerMirror::default();
+        utility_token_ledger_mirror.enable(get_mock_user_bob_canister_id(), 100, UNIX_EPOCH);
+        utility_token_ledger_mirror.record_token_event(1, &transfer(30));
+        utility_token_ledger_mirror.record_token_event(2, &transfer(5));
+
+        assert_eq!(
+            utility_token_ledger_mirror.is_operation_applied_on_ledger(
+                UTILITY_TOKEN_LEDGER_MIRROR_OPENING_BALANCE_OPERATION_ID,
+                100
+            ),
+            Some(true)
+        );
+        utility_token_ledger_mirror
+            .complete_operation(UTILITY_TOKEN_LEDGER_MIRROR_OPENING_BALANCE_OPERATION_ID);
+
+        assert_eq!(
+            utility_token_ledger_mirror.is_operation_applied_on_ledger(1, 100),
+            Some(false)
+        );
+        assert_eq!(
+            utility_token_ledger_mirror.is_operation_applied_on_ledger(1, 70),
+            Some(true)
+        );
+        assert_eq!(
+            utility_token_ledger_mirror.is_operation_applied_on_ledger(1, 50),
+            None
+        );
+
+        // * a dropped operation does not move the mirrored balance
+        utility_token_ledger_mirror.drop_operation(1);
+        assert_eq!(
+            utility_token_ledger_mirror.is_operation_applied_on_ledger(2, 95),
+            Some(true)
+        );
+        utility_token_ledger_mirror.complete_operation(2);
+        assert_eq!(utility_token_ledger_mirror.mirrored_balance, 95);
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use candid::Principal;
+use ic_stable_structures::{storable::Bound, StableBTreeMap, Storable};
+use serde::{Deserialize, Serialize};
+use shared_utils::types::canister_specific::individual_user_template::error_types::UtilityTokenTransferError;
+
+use super::memory::{
+    get_utility_token_outgoing_transfers_memory, get_utility_token_received_transfers_memory,
+    Memory,
+};
+
+pub type UtilityTokenTransferId = u64;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum OutgoingUtilityTokenTransferStatus {
+    /// Debited and waiting on the receiving canister
+    Sending,
+    Completed,
+    /// Refunded after the receiving canister did not accept it. It can be sent again with the
+    /// same id.
+    Reverted,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct OutgoingUtilityTokenTransfer {
+    pub to_canister_id: Principal,
+    pub amount: u64,
+    pub memo: Option<String>,
+    pub status: OutgoingUtilityTokenTransferStatus,
+    pub updated_at: SystemTime,
+}
+
+impl OutgoingUtilityTokenTransfer {
+    fn is_same_transfer(
+        &self,
+        to_canister_id: Principal,
+        amount: u64,
+        memo: &Option<String>,
+    ) -> bool {
+        self.to_canister_id == to_canister_id && self.amount == amount && &self.memo == memo
+    }
+}
+
+impl Storable for OutgoingUtilityTokenTransfer {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut bytes = vec![];
+        ciborium::ser::into_writer(self, &mut bytes).unwrap();
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let transfer: Self = ciborium::de::from_reader(bytes.as_ref()).unwrap();
+        transfer
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UtilityTokenTransfers {
+    // Key is the transfer id chosen by the sending user
+    #[serde(skip, default = "_default_outgoing_transfers")]
+    outgoing_transfers:
+        StableBTreeMap<UtilityTokenTransferId, OutgoingUtilityTokenTransfer, Memory>,
+    // Key is the sending user canister and the id of its transfer, value is when the transfer
+    // was credited in nanoseconds since the unix epoch
+    #[serde(skip, default = "_default_received_transfers")]
+    received_transfers: StableBTreeMap<(Principal, UtilityTokenTransferId), u64, Memory>,
+    // Transfers received before they were kept in stable memory
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    received_transfer_ids: BTreeMap<Principal, BTreeSet<UtilityTokenTransferId>>,
+}
+
+impl Default for UtilityTokenTransfers {
+    fn default() -> Self {
+        Self {
+            outgoing_transfers: _default_outgoing_transfers(),
+            received_transfers: _default_received_transfers(),
+            received_transfer_ids: BTreeMap::default(),
+        }
+    }
+}
+
+impl UtilityTokenTransfers {
+    pub fn get_outgoing_transfer(
+        &self,
+        transfer_id: UtilityTokenTransferId,
+    ) -> Option<OutgoingUtilityTokenTransfer> {
+        self.outgoing_transfers.get(&transfer_id)
+    }
+
+    /// Returns true if the transfer with this id was already completed. An id can only be reused
+    /// for the same receiver, amount and memo, and only once its earlier transfer was reverted.
+    pub fn check_outgoing_transfer(
+        &self,
+        transfer_id: UtilityTokenTransferId,
+        to_canister_id: Principal,
+        amount: u64,
+        memo: &Option<String>,
+    ) -> Result<bool, UtilityTokenTransferError> {
+        let Some(transfer) = self.outgoing_transfers.get(&transfer_id) else {
+            return Ok(false);
+        };
+
+        if !transfer.is_same_transfer(to_canister_id, amount, memo) {
+            return Err(UtilityTokenTransferError::TransferIdAlreadyUsed);
+        }
+
+        match transfer.status {
+            OutgoingUtilityTokenTransferStatus::Sending => {
+                Err(UtilityTokenTransferError::TransferInProgress)
+            }
+            OutgoingUtilityTokenTransferStatus::Completed => Ok(true),
+            OutgoingUtilityTokenTransferStatus::Reverted => Ok(false),
+        }
+    }
+
+    pub fn start_outgoing_transfer(
+        &mut self,
+        transfer_id: UtilityTokenTransferId,
+        to_canister_id: Principal,
+        amount: u64,
+        memo: Option<String>,
+        current_time: SystemTime,
+    ) {
+        self.outgoing_transfers.insert(
+            transfer_id,
+            OutgoingUtilityTokenTransfer {
+                to_canister_id,
+                amount,
+                memo,
+                status: OutgoingUtilityTokenTransferStatus::Sending,
+                updated_at: current_time,
+            },
+        );
+    }
+
+    pub fn finish_outgoing_transfer(
+        &mut self,
+        transfer_id: UtilityTokenTransferId,
+        status: OutgoingUtilityTokenTransferStatus,
+        current_time: SystemTime,
+    ) {
+        if let Some(mut transfer) = self.outgoing_transfers.get(&transfer_id) {
+            transfer.status = status;
+            transfer.updated_at = current_time;
+            self.outgoing_transfers.insert(transfer_id, transfer);
+        }
+    }
+
+    pub fn is_transfer_received(
+        &self,
+        from_canister_id: Principal,
+        transfer_id: UtilityTokenTransferId,
+    ) -> bool {
+        self.received_transfers
+            .contains_key(&(from_canister_id, transfer_id))
+            || self
+                .received_transfer_ids
+                .get(&from_canister_id)
+                .is_some_and(|transfer_ids| transfer_ids.contains(&transfer_id))
+    }
+
+    /// Returns false if the transfer was already marked as received.
+    pub fn mark_transfer_received(
+        &mut self,
+        from_canister_id: Principal,
+        transfer_id: UtilityTokenTransferId,
+        current_time: SystemTime,
+    ) -> bool {
+        if self.is_transfer_received(from_canister_id, transfer_id) {
+            return false;
+        }
+
+        self.received_transfers
+            .insert((from_canister_id, transfer_id), to_nanos(current_time));
+        true
+    }
+
+    pub fn migrate_received_transfer_ids_to_stable_memory(&mut self, current_time: SystemTime) {
+        let received_transfer_ids = std::mem::take(&mut self.received_transfer_ids);
+
+        for (from_canister_id, transfer_ids) in received_transfer_ids {
+            for transfer_id in transfer_ids {
+                self.received_transfers
+                    .insert((from_canister_id, transfer_id), to_nanos(current_time));
+            }
+        }
+    }
+}
+
+fn to_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+fn _default_outgoing_transfers(
+) -> StableBTreeMap<UtilityTokenTransferId, OutgoingUtilityTokenTransfer, Memory> {
+    StableBTreeMap::init(get_utility_token_outgoing_transfers_memory())
+}
+
+fn _default_received_transfers() -> StableBTreeMap<(Principal, UtilityTokenTransferId), u64, Memory>
+{
+    StableBTreeMap::init(get_utility_token_received_transfers_memory())
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_check_outgoing_transfer() {
+        let mut utility_token_transfers = UtilityTokenTransfers::default();
+        let bob_canister_id = get_mock_user_bob_canister_id();
+
+        assert_eq!(
+            utility_token_transfers.check_outgoing_transfer(7, bob_canister_id, 40, &None),
+            Ok(false)
+        );
+
+        utility_token_transfers.start_outgoing_transfer(7, bob_canister_id, 40, None, UNIX_EPOCH);
+        assert_eq!(
+            utility_token_transfers.check_outgoing_transfer(7, bob_canister_id, 40, &None),
+            Err(UtilityTokenTransferError::TransferInProgress)
+        );
+        assert_eq!(
+            utility_token_transfers.check_outgoing_transfer(7, bob_canister_id, 41, &None),
+            Err(UtilityTokenTransferError::TransferIdAlreadyUsed)
+        );
+
+        utility_token_transfers.finish_outgoing_transfer(
+            7,
+            OutgoingUtilityTokenTransferStatus::Reverted,
+            UNIX_EPOCH,
+        );
+        assert_eq!(
+            utility_token_transfers.check_outgoing_transfer(7, bob_canister_id, 40, &None),
+            Ok(false)
+        );
+
+        utility_token_transfers.finish_outgoing_transfer(
+            7,
+            OutgoingUtilityTokenTransferStatus::Completed,
+            UNIX_EPOCH,
+        );
+        assert_eq!(
+            utility_token_transfers.check_outgoing_transfer(7, bob_canister_id, 40, &None),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_migrate_received_transfer_ids_to_stable_memory() {
+        let mut utility_token_transfers = UtilityTokenTransfers::default();
+        let alice_canister_id = get_mock_user_alice_canister_id();
+        utility_token_transfers
+            .received_transfer_ids
+            .insert(alice_canister_id, BTreeSet::from([0, 1]));
+
+        assert!(!utility_token_transfers.mark_transfer_received(alice_canister_id, 1, UNIX_EPOCH));
+
+        utility_token_transfers.migrate_received_transfer_ids_to_stable_memory(UNIX_EPOCH);
+
+        assert!(utility_token_transfers.received_transfer_ids.is_empty());
+        assert!(utility_token_transfers.is_transfer_received(alice_canister_id, 0));
+        assert!(utility_token_transfers.is_transfer_received(alice_canister_id, 1));
+        assert!(utility_token_transfers.mark_transfer_received(alice_canister_id, 2, UNIX_EPOCH));
+        assert!(!utility_token_transfers.mark_transfer_received(alice_canister_id, 2, UNIX_EPOCH));
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use candid::{Nat, Principal};
+use serde::{Deserialize, Serialize};
+use shared_utils::canister_specific::individual_user_template::types::wallet::WalletConfig;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Wallet {
+    config: WalletConfig,
+    // Key is the root canister id of the token
+    ledgers: BTreeMap<Principal, Principal>,
+    // Key is the root canister id of the token
+    zero_balance_since: BTreeMap<Principal, SystemTime>,
+}
+
+impl Wallet {
+    pub fn get_config(&self) -> WalletConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: WalletConfig) {
+        self.config = config;
+    }
+
+    pub fn get_ledger(&self, token_root: Principal) -> Option<Principal> {
+        self.ledgers.get(&token_root).copied()
+    }
+
+    pub fn cache_ledger(&mut self, token_root: Principal, ledger: Principal) {
+        self.ledgers.insert(token_root, ledger);
+    }
+
+    /// Tracks since when the balance of the token has been zero. Returns that time, or `None`
+    /// when the balance is not zero.
+    pub fn record_balance(
+        &mut self,
+        token_root: Principal,
+        balance: &Nat,
+        current_time: SystemTime,
+    ) -> Option<SystemTime> {
+        if *balance != 0u64 {
+            self.zero_balance_since.remove(&token_root);
+            return None;
+        }
+
+        Some(
+            *self
+                .zero_balance_since
+                .entry(token_root)
+                .or_insert(current_time),
+        )
+    }
+
+    pub fn is_zero_balance_grace_period_over(
+        &self,
+        token_root: Principal,
+        current_time: SystemTime,
+    ) -> bool {
+        let grace_period = Duration::from_secs(self.config.zero_balance_grace_period_in_seconds);
+
+        self.zero_balance_since
+            .get(&token_root)
+            .is_some_and(|zero_balance_since| {
+                current_time
+                    .duration_since(*zero_balance_since)
+                    .is_ok_and(|elapsed| elapsed >= grace_period)
+            })
+    }
+
+    /// For tokens that stay in the wallet whatever their balance
+    pub fn clear_zero_balance(&mut self, token_root: Principal) {
+        self.zero_balance_since.remove(&token_root);
+    }
+
+    pub fn has_zero_balance_tokens(&self) -> bool {
+        !self.zero_balance_since.is_empty()
+    }
+
+    /// Tokens past the zero balance grace period, with their cached ledger
+    pub fn get_tokens_past_zero_balance_grace_period(
+        &self,
+        current_time: SystemTime,
+    ) -> Vec<(Principal, Option<Principal>)> {
+        self.zero_balance_since
+            .keys()
+            .filter(|token_root| self.is_zero_balance_grace_period_over(**token_root, current_time))
+            .map(|token_root| (*token_root, self.get_ledger(*token_root)))
+            .collect()
+    }
+
+    pub fn remove_token(&mut self, token_root: Principal) {
+        self.ledgers.remove(&token_root);
+        self.zero_balance_since.remove(&token_root);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_bob_canister_id,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_zero_balance_grace_period() {
+        let mut wallet = Wallet::default();
+        wallet.set_config(WalletConfig {
+            zero_balance_grace_period_in_seconds: 100,
+        });
+        let token_root = get_mock_user_alice_canister_id();
+        let zero = Nat::from(0u64);
+
+        assert_eq!(
+            wallet.record_balance(token_root, &zero, UNIX_EPOCH),
+            Some(UNIX_EPOCH)
+        );
+        // * the first time the balance was seen at zero is kept
+        let later = UNIX_EPOCH + Duration::from_secs(50);
+        assert_eq!(
+            wallet.record_balance(token_root, &zero, later),
+            Some(UNIX_EPOCH)
+        );
+        assert!(!wallet.is_zero_balance_grace_period_over(token_root, later));
+        assert!(wallet
+            .is_zero_balance_grace_period_over(token_root, UNIX_EPOCH + Duration::from_secs(100)));
+
+        // * a balance in between restarts the grace period
+        assert_eq!(
+            wallet.record_balance(token_root, &Nat::from(1u64), later),
+            None
+        );
+        assert!(!wallet
+            .is_zero_balance_grace_period_over(token_root, UNIX_EPOCH + Duration::from_secs(100)));
+        assert_eq!(wallet.record_balance(token_root, &zero, later), Some(later));
+    }
+
+    #[test]
+    fn test_get_tokens_past_zero_balance_grace_period() {
+        let mut wallet = Wallet::default();
+        wallet.set_config(WalletConfig {
+            zero_balance_grace_period_in_seconds: 100,
+        });
+        let token_root = get_mock_user_alice_canister_id();
+        let ledger = get_mock_user_bob_canister_id();
+        let zero = Nat::from(0u64);
+        assert!(!wallet.has_zero_balance_tokens());
+
+        wallet.cache_ledger(token_root, ledger);
+        wallet.record_balance(token_root, &zero, UNIX_EPOCH);
+        assert!(wallet.has_zero_balance_tokens());
+        assert!(wallet
+            .get_tokens_past_zero_balance_grace_period(UNIX_EPOCH + Duration::from_secs(50))
+            .is_empty());
+        assert_eq!(
+            wallet.get_tokens_past_zero_balance_grace_period(UNIX_EPOCH + Duration::from_secs(100)),
+            vec![(token_root, Some(ledger))]
+        );
+
+        wallet.remove_token(token_root);
+        assert!(!wallet.has_zero_balance_tokens());
+        assert_eq!(wallet.get_ledger(token_root), None);
+    }
+}
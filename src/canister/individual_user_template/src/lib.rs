@@ -6,49 +6,76 @@ use api::{
     profile::update_profile_display_details::UpdateProfileDetailsError,
 };
 use candid::{Nat, Principal};
-use data_model::CanisterData;
+use data_model::{utility_token_transfer::UtilityTokenTransferId, CanisterData};
 use ic_cdk::api::management_canister::provisional::CanisterId;
 use ic_cdk_macros::export_candid;
 use ic_nns_governance::pb::v1::{
     SettleNeuronsFundParticipationRequest, SettleNeuronsFundParticipationResponse,
 };
 use ic_sns_init::pb::v1::SnsInitPayload;
-use icrc_ledger_types::icrc1::transfer::Memo;
+use icrc_ledger_types::{
+    icrc::generic_metadata_value::MetadataValue,
+    icrc1::{account::Account, transfer::Memo},
+};
 use shared_utils::{
     canister_specific::individual_user_template::types::{
         arg::{FolloweeArg, IndividualUserTemplateInitArgs, PlaceBetArg},
-        cdao::DeployedCdaoCanisters,
+        cdao::{
+            AirdropCampaignConfig, AirdropCampaignDetails, AirdropClaimDetails,
+            AirdropClaimRecord, AirdropClaimant, CdaoDeploymentDetails, CdaoDeploymentStep,
+            CreatorDaoTokenStatus, CreatorDaoTokenStatusError, DeployedCdaoCanisters,
+        },
+        creator_token_quota::{CreatorTokenQuota, CreatorTokenQuotaConfig},
         device_id::DeviceIdentity,
         error::{
             AirdropError, BetOnCurrentlyViewingPostError, CdaoDeployError, CdaoTokenError,
-            FollowAnotherUserProfileError, GetPostsOfUserProfileError,
+            FollowAnotherUserProfileError, GetPostsOfUserProfileError, NotificationError,
+            SnsNeuronError, SwapParticipationError,
         },
-        follow::{FollowEntryDetail, FollowEntryId},
+        engagement_reward::{
+            EngagementRewardClaim, EngagementRewardError, EngagementRewardsConfig,
+            EngagementRewardsStatus,
+        },
+        follow::{FollowEntryDetail, FollowEntryId, SuggestedCreator},
         hot_or_not::{BetDetails, BetOutcomeForBetMaker, BettingStatus, PlacedBetDetail},
-        kv_storage::{NamespaceErrors, NamespaceForFrontend},
+        kv_storage::{
+            KeyValueBlobForFrontend, KeyValueCondition, KeyValueEntryForFrontend,
+            KeyValueOperation, NamespaceErrors, NamespaceForFrontend, NamespaceKeysPage,
+            NamespaceLimits, NamespacePermission, NamespaceSettingsForFrontend,
+        },
         migration::MigrationErrors,
         ml_data::{MLFeedCacheItem, SuccessHistoryItemV1, WatchHistoryItem},
+        notification::{Notification, NotificationId},
         post::{
             Post, PostDetailsForFrontend, PostDetailsFromFrontend, PostViewDetailsFromFrontend,
         },
         profile::{
-            UserCanisterDetails, UserProfile, UserProfileDetailsForFrontend,
-            UserProfileDetailsForFrontendV2, UserProfileUpdateDetailsFromFrontend,
+            UniqueUserNameHistoryEntry, UserCanisterDetails, UserProfile,
+            UserProfileDetailsForFrontend, UserProfileDetailsForFrontendV2,
+            UserProfileDetailsForFrontendV3, UserProfileUpdateDetailsFromFrontend,
+            UserProfileUpdateDetailsFromFrontendV2,
         },
+        referral::{ReferralConfig, ReferralDetails, ReferralError, ReferralStatus},
         session::SessionType,
+        sns_neuron::{PendingSnsNeuronStake, SnsNeuronDetails, SnsVote},
+        swap_participation::SwapCommitment,
+        token::{TokenBalanceReconciliation, UtilityTokenLedgerMirrorStatus},
+        wallet::{WalletConfig, WalletOverview},
     },
     common::types::{
         app_primitive_type::PostId,
         http::{HttpRequest, HttpResponse},
         known_principal::KnownPrincipalType,
         top_posts::post_score_index_item::PostStatus,
-        utility_token::token_event::TokenEvent,
+        utility_token::token_event::{FilteredTokenEventPage, TokenEvent, TokenEventFilter},
     },
     pagination::PaginationError,
     types::canister_specific::individual_user_template::error_types::{
-        GetUserUtilityTokenTransactionHistoryError, UpdateProfileSetUniqueUsernameError,
+        GetUserUtilityTokenTransactionHistoryError, UpdateProfileChangeUniqueUsernameError,
+        UpdateProfileSetUniqueUsernameError, UtilityTokenTransferError,
     },
     types::creator_dao_stats::IndividualUserCreatorDaoEntry,
+    types::utility_token_supply_stats::UtilityTokenSupplyStats,
 };
 
 mod api;
@@ -0,0 +1,432 @@
+use std::time::SystemTime;
+
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        engagement_reward::{
+            EngagementMilestone, EngagementMilestoneStatus, EngagementRewardClaim,
+            EngagementRewardsStatus,
+        },
+        notification::NotificationType,
+    },
+    common::types::utility_token::token_event::{MintEvent, TokenEvent},
+    types::utility_token_supply_stats::get_day_since_epoch,
+};
+
+use crate::data_model::CanisterData;
+
+/// Returns the details of the mint for the milestone when the user's activity reaches it today.
+fn get_milestone_mint_event(
+    canister_data: &CanisterData,
+    milestone: EngagementMilestone,
+    today: u64,
+) -> Option<MintEvent> {
+    let config = canister_data.engagement_rewards.get_config();
+
+    match milestone {
+        EngagementMilestone::DailyLoginStreak => {
+            let streak_days = canister_data
+                .engagement_rewards
+                .get_todays_login_streak_days(today);
+
+            (streak_days > 0 && streak_days >= config.min_login_streak_days).then_some(
+                MintEvent::DailyLoginStreak {
+                    day: today,
+                    streak_days,
+                },
+            )
+        }
+        EngagementMilestone::FirstPost => canister_data
+            .all_created_posts
+            .keys()
+            .next()
+            .map(|post_id| MintEvent::FirstPost { post_id: *post_id }),
+        EngagementMilestone::DailyPostsWatched => {
+            let posts_watched = canister_data.engagement_rewards.get_posts_watched(today);
+
+            (posts_watched > 0 && posts_watched >= config.min_posts_watched_per_day).then_some(
+                MintEvent::DailyPostsWatched {
+                    day: today,
+                    posts_watched,
+                },
+            )
+        }
+        EngagementMilestone::FirstBet => canister_data
+            .all_hot_or_not_bets_placed
+            .values()
+            .min_by_key(|placed_bet_detail| placed_bet_detail.bet_placed_at)
+            .map(|placed_bet_detail| MintEvent::FirstBet {
+                post_canister_id: placed_bet_detail.canister_id,
+                post_id: placed_bet_detail.post_id,
+            }),
+    }
+}
+
+/// Rewards of every milestone reached and not yet claimed, before the network caps are applied.
+pub fn get_claimable_engagement_rewards(
+    canister_data: &CanisterData,
+    current_time: SystemTime,
+) -> Vec<EngagementRewardClaim> {
+    let today = get_day_since_epoch(current_time);
+
+    EngagementMilestone::ALL
+        .into_iter()
+        .filter_map(|milestone| {
+            let amount = canister_data
+                .engagement_rewards
+                .get_claimable_amount(milestone, today);
+            if amount == 0 {
+                return None;
+            }
+
+            get_milestone_mint_event(canister_data, milestone, today)?;
+
+            Some(EngagementRewardClaim {
+                milestone,
+                day: today,
+                amount,
+            })
+        })
+        .collect()
+}
+
+/// Mints the rewards the user index granted out of the network caps. Claiming again on the
+/// same day mints nothing more.
+pub fn claim_engagement_rewards_impl(
+    canister_data: &mut CanisterData,
+    granted_claims: Vec<EngagementRewardClaim>,
+    current_time: SystemTime,
+) -> Vec<EngagementRewardClaim> {
+    let today = get_day_since_epoch(current_time);
+    let mut claims = vec![];
+
+    for granted_claim in granted_claims {
+        let milestone = granted_claim.milestone;
+        if granted_claim.day != today {
+            continue;
+        }
+
+        let amount = canister_data
+            .engagement_rewards
+            .get_claimable_amount(milestone, today)
+            .min(granted_claim.amount);
+        if amount == 0 {
+            continue;
+        }
+
+        let Some(details) = get_milestone_mint_event(canister_data, milestone, today) else {
+            continue;
+        };
+
+        let reward_result = canister_data.apply_token_event(TokenEvent::Mint {
+            amount,
+            details,
+            timestamp: current_time,
+        });
+
+        if let Err(e) = reward_result {
+            ic_cdk::println!("Failed to credit engagement reward: {:?}", e);
+            continue;
+        }
+
+        canister_data
+            .engagement_rewards
+            .record_claim(milestone, today, amount);
+        canister_data.notification_inbox.add_notification(
+            NotificationType::EngagementRewardCredited { milestone, amount },
+            current_time,
+        );
+
+        claims.push(EngagementRewardClaim {
+            milestone,
+            day: today,
+            amount,
+        });
+    }
+
+    claims
+}
+
+/// The part of each granted claim that was not minted, to be given back to the user index.
+pub fn get_unused_engagement_reward_claims(
+    granted_claims: &[EngagementRewardClaim],
+    minted_claims: &[EngagementRewardClaim],
+) -> Vec<EngagementRewardClaim> {
+    granted_claims
+        .iter()
+        .filter_map(|granted_claim| {
+            let minted_amount = minted_claims
+                .iter()
+                .find(|minted_claim| {
+                    minted_claim.milestone == granted_claim.milestone
+                        && minted_claim.day == granted_claim.day
+                })
+                .map_or(0, |minted_claim| minted_claim.amount);
+
+            let amount = granted_claim.amount.saturating_sub(minted_amount);
+            (amount > 0).then_some(EngagementRewardClaim {
+                amount,
+                ..*granted_claim
+            })
+        })
+        .collect()
+}
+
+pub fn get_engagement_rewards_status_impl(
+    canister_data: &CanisterData,
+    current_time: SystemTime,
+) -> EngagementRewardsStatus {
+    let today = get_day_since_epoch(current_time);
+    let engagement_rewards = &canister_data.engagement_rewards;
+
+    let milestones = EngagementMilestone::ALL
+        .into_iter()
+        .map(|milestone| {
+            let claims = engagement_rewards.get_claims(milestone);
+            let claimable_amount =
+                if get_milestone_mint_event(canister_data, milestone, today).is_some() {
+                    engagement_rewards.get_claimable_amount(milestone, today)
+                } else {
+                    0
+                };
+
+            EngagementMilestoneStatus {
+                milestone,
+                total_rewarded_amount: claims.total_rewarded_amount,
+                last_claimed_day: claims.last_claimed_day,
+                claimable_amount,
+            }
+        })
+        .collect();
+
+    EngagementRewardsStatus {
+        login_streak_days: engagement_rewards.get_login_streak_days(today),
+        milestones,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use shared_utils::canister_specific::individual_user_template::types::{
+        engagement_reward::{EngagementRewardRule, EngagementRewardsConfig},
+        ml_data::WatchHistoryItem,
+        post::{Post, PostDetailsFromFrontend},
+    };
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    const SECONDS_IN_A_DAY: u64 = 24 * 60 * 60;
+
+    fn get_time_on_day(day: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(day * SECONDS_IN_A_DAY + 60)
+    }
+
+    /// Claims with everything granted, as when the network caps are not reached
+    fn claim_all(
+        canister_data: &mut CanisterData,
+        current_time: SystemTime,
+    ) -> Vec<EngagementRewardClaim> {
+        let claimable_rewards = get_claimable_engagement_rewards(canister_data, current_time);
+        claim_engagement_rewards_impl(canister_data, claimable_rewards, current_time)
+    }
+
+    #[test]
+    fn test_claim_engagement_rewards_impl() {
+        let mut canister_data = CanisterData::default();
+        canister_data
+            .engagement_rewards
+            .set_config(EngagementRewardsConfig {
+                min_login_streak_days: 2,
+                min_posts_watched_per_day: 2,
+                daily_login_streak: EngagementRewardRule {
+                    reward_amount: 10,
+                    max_total_reward_amount: 15,
+                },
+                ..Default::default()
+            });
+
+        // * nothing is reached yet
+        assert!(claim_all(&mut canister_data, get_time_on_day(1)).is_empty());
+
+        canister_data.engagement_rewards.record_access(1);
+        canister_data.engagement_rewards.record_access(2);
+        canister_data.all_created_posts.insert(
+            0,
+            Post::new(
+                0,
+                &PostDetailsFromFrontend {
+                    is_nsfw: false,
+                    description: "My first post".into(),
+                    hashtags: vec![],
+                    video_uid: "video-1".into(),
+                    creator_consent_for_inclusion_in_hot_or_not: false,
+                },
+                &get_time_on_day(2),
+            ),
+        );
+        // * views only written to the watch history are not counted
+        canister_data.watch_history.insert(
+            WatchHistoryItem {
+                post_id: 3,
+                publisher_canister_id: get_mock_user_alice_canister_id(),
+                viewed_at: get_time_on_day(2),
+                cf_video_id: "video-4".into(),
+                percentage_watched: 90.0,
+            },
+            (),
+        );
+        for post_id in [1, 2, 2] {
+            canister_data.engagement_rewards.record_post_watched(
+                2,
+                get_mock_user_alice_canister_id(),
+                post_id,
+            );
+        }
+
+        let claims = claim_all(&mut canister_data, get_time_on_day(2));
+        assert_eq!(
+            claims,
+            vec![
+                EngagementRewardClaim {
+                    milestone: EngagementMilestone::DailyLoginStreak,
+                    day: 2,
+                    amount: 10,
+                },
+                EngagementRewardClaim {
+                    milestone: EngagementMilestone::FirstPost,
+                    day: 2,
+                    amount: 100,
+                },
+                EngagementRewardClaim {
+                    milestone: EngagementMilestone::DailyPostsWatched,
+                    day: 2,
+                    amount: 10,
+                },
+            ]
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 120);
+
+        // * claiming again on the same day mints nothing
+        assert!(claim_all(&mut canister_data, get_time_on_day(2)).is_empty());
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 120);
+
+        // * the streak reward is capped, and no posts were watched on day 3
+        canister_data.engagement_rewards.record_access(3);
+        let claims = claim_all(&mut canister_data, get_time_on_day(3));
+        assert_eq!(
+            claims,
+            vec![EngagementRewardClaim {
+                milestone: EngagementMilestone::DailyLoginStreak,
+                day: 3,
+                amount: 5,
+            }]
+        );
+
+        let status = get_engagement_rewards_status_impl(&canister_data, get_time_on_day(3));
+        assert_eq!(status.login_streak_days, 3);
+        assert!(status
+            .milestones
+            .iter()
+            .all(|milestone_status| milestone_status.claimable_amount == 0));
+    }
+
+    #[test]
+    fn test_claim_engagement_rewards_impl_mints_only_the_granted_amount() {
+        let mut canister_data = CanisterData::default();
+        canister_data.all_created_posts.insert(
+            0,
+            Post::new(
+                0,
+                &PostDetailsFromFrontend {
+                    is_nsfw: false,
+                    description: "My first post".into(),
+                    hashtags: vec![],
+                    video_uid: "video-1".into(),
+                    creator_consent_for_inclusion_in_hot_or_not: false,
+                },
+                &get_time_on_day(2),
+            ),
+        );
+
+        let claimable_rewards =
+            get_claimable_engagement_rewards(&canister_data, get_time_on_day(2));
+        assert_eq!(
+            claimable_rewards,
+            vec![EngagementRewardClaim {
+                milestone: EngagementMilestone::FirstPost,
+                day: 2,
+                amount: 100,
+            }]
+        );
+
+        // * the user index had only 40 left under the network cap
+        let claims = claim_engagement_rewards_impl(
+            &mut canister_data,
+            vec![EngagementRewardClaim {
+                milestone: EngagementMilestone::FirstPost,
+                day: 2,
+                amount: 40,
+            }],
+            get_time_on_day(2),
+        );
+        assert_eq!(claims[0].amount, 40);
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 40);
+        assert!(get_claimable_engagement_rewards(&canister_data, get_time_on_day(3)).is_empty());
+    }
+
+    #[test]
+    fn test_get_unused_engagement_reward_claims() {
+        let mut canister_data = CanisterData::default();
+        for day in 1..=3 {
+            canister_data.engagement_rewards.record_access(day);
+        }
+
+        // * granted for a day that has passed, more than is claimable and for a milestone not reached
+        let granted_claims = vec![
+            EngagementRewardClaim {
+                milestone: EngagementMilestone::DailyLoginStreak,
+                day: 2,
+                amount: 10,
+            },
+            EngagementRewardClaim {
+                milestone: EngagementMilestone::DailyLoginStreak,
+                day: 3,
+                amount: 1000,
+            },
+            EngagementRewardClaim {
+                milestone: EngagementMilestone::FirstPost,
+                day: 3,
+                amount: 100,
+            },
+        ];
+        let minted_claims = claim_engagement_rewards_impl(
+            &mut canister_data,
+            granted_claims.clone(),
+            get_time_on_day(3),
+        );
+        let claimable_amount = minted_claims[0].amount;
+
+        assert_eq!(
+            get_unused_engagement_reward_claims(&granted_claims, &minted_claims),
+            vec![
+                EngagementRewardClaim {
+                    milestone: EngagementMilestone::DailyLoginStreak,
+                    day: 2,
+                    amount: 10,
+                },
+                EngagementRewardClaim {
+                    milestone: EngagementMilestone::DailyLoginStreak,
+                    day: 3,
+                    amount: 1000 - claimable_amount,
+                },
+                EngagementRewardClaim {
+                    milestone: EngagementMilestone::FirstPost,
+                    day: 3,
+                    amount: 100,
+                },
+            ]
+        );
+    }
+}
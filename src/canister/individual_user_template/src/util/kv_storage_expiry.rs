@@ -0,0 +1,61 @@
+use std::{cell::RefCell, time::Duration};
+
+use ic_cdk_timers::TimerId;
+use shared_utils::common::utils::system_time::get_current_system_time_from_ic;
+
+use crate::CANISTER_DATA;
+
+const KV_STORAGE_EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_EXPIRED_ENTRIES_REMOVED_PER_SWEEP: usize = 500;
+const MAX_DELETED_NAMESPACE_ENTRIES_REMOVED_PER_SWEEP: usize = 500;
+
+thread_local! {
+    static KV_STORAGE_EXPIRY_SWEEPER_TIMER: RefCell<Option<TimerId>> = const { RefCell::new(None) };
+}
+
+fn has_anything_to_sweep() -> bool {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data.app_storage.has_expiring_entries()
+            || canister_data.app_storage.has_pending_uploads()
+            || canister_data.app_storage.has_deleted_namespaces()
+    })
+}
+
+/// Starts the sweeper when there are expiring entries, pending uploads or deleted namespaces and
+/// it is not running yet. Timers do not survive upgrades, so this is also called from `post_upgrade`.
+pub fn start_kv_storage_expiry_sweeper_timer_if_needed() {
+    let is_running = KV_STORAGE_EXPIRY_SWEEPER_TIMER.with_borrow(Option::is_some);
+    if is_running || !has_anything_to_sweep() {
+        return;
+    }
+
+    let timer_id = ic_cdk_timers::set_timer_interval(
+        KV_STORAGE_EXPIRY_SWEEP_INTERVAL,
+        sweep_expired_kv_storage_entries,
+    );
+
+    KV_STORAGE_EXPIRY_SWEEPER_TIMER.with_borrow_mut(|timer| *timer = Some(timer_id));
+}
+
+/// Removes a batch of expired entries, the timed out uploads and a batch of the keys of deleted
+/// namespaces, and stops the sweeper once none are left.
+fn sweep_expired_kv_storage_entries() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let current_time = get_current_system_time_from_ic();
+        canister_data
+            .app_storage
+            .remove_expired_entries(current_time, MAX_EXPIRED_ENTRIES_REMOVED_PER_SWEEP);
+        canister_data
+            .app_storage
+            .remove_timed_out_uploads(current_time);
+        canister_data
+            .app_storage
+            .remove_deleted_namespace_entries(MAX_DELETED_NAMESPACE_ENTRIES_REMOVED_PER_SWEEP);
+    });
+
+    if !has_anything_to_sweep() {
+        if let Some(timer_id) = KV_STORAGE_EXPIRY_SWEEPER_TIMER.with_borrow_mut(Option::take) {
+            ic_cdk_timers::clear_timer(timer_id);
+        }
+    }
+}
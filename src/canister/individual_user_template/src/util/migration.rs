@@ -150,44 +150,62 @@ impl IndividualUser {
         Ok(())
     }
 
+    /// The balance is debited before the receiving canister credits it, so the tokens can't be
+    /// spent while the call is in flight. The debit is reverted if the receiving canister does
+    /// not credit them.
     async fn transfer_tokens(
         &self,
         caller: Principal,
         to_individual_user: IndividualUser,
     ) -> Result<(), MigrationErrors> {
-        let token =
-            CANISTER_DATA.with_borrow(|canister_data| canister_data.my_token_balance.clone());
+        let amount = CANISTER_DATA.with_borrow_mut(|canister_data| {
+            let amount = canister_data.my_token_balance.utility_token_balance;
+            canister_data
+                .apply_token_event(TokenEvent::Transfer {
+                    amount,
+                    to_account: to_individual_user.profile_principal,
+                    timestamp: get_current_system_time(),
+                    memo: None,
+                })
+                .map(|_| amount)
+                .map_err(|_| MigrationErrors::InsufficientTokenBalance)
+        })?;
 
-        let (transfer_res,): (Result<(), MigrationErrors>,) = call(
+        let transfer_res = call::<_, (Result<(), MigrationErrors>,)>(
             to_individual_user.canister_id,
             "receive_data_from_hotornot",
-            (
-                self.profile_principal,
-                token.utility_token_balance,
-                Vec::<Post>::new(),
-            ),
+            (self.profile_principal, amount, Vec::<Post>::new()),
         )
         .await
-        .map_err(|e| MigrationErrors::TransferToCanisterCallFailed(e.1))?;
-
-        match transfer_res {
-            Ok(()) => CANISTER_DATA.with_borrow_mut(|canister_data| {
-                canister_data
-                    .my_token_balance
-                    .handle_token_event(TokenEvent::Transfer {
-                        amount: token.utility_token_balance,
-                        to_account: to_individual_user.profile_principal,
-                        timestamp: get_current_system_time(),
-                    });
+        .map_err(|e| MigrationErrors::TransferToCanisterCallFailed(e.1))
+        .and_then(|(transfer_res,)| transfer_res);
 
+        CANISTER_DATA.with_borrow_mut(|canister_data| match transfer_res {
+            Ok(()) => {
                 canister_data.migration_info = MigrationInfo::MigratedToYral {
                     account_principal: to_individual_user.profile_principal,
                 };
 
                 Ok(())
-            }),
-            Err(e) => Err(e),
-        }
+            }
+            Err(e) => {
+                if let Err(revert_error) =
+                    canister_data.apply_token_event(TokenEvent::TransferReverted {
+                        amount,
+                        to_account: to_individual_user.profile_principal,
+                        timestamp: get_current_system_time(),
+                    })
+                {
+                    ic_cdk::println!(
+                        "Failed to revert the debit of {} migrated tokens: {:?}",
+                        amount,
+                        revert_error
+                    );
+                }
+
+                Err(e)
+            }
+        })
     }
     fn transfer_posts(&self, to_individual_user: IndividualUser) -> Result<(), MigrationErrors> {
         ic_cdk::spawn(transfer_posts_task(
@@ -223,12 +241,13 @@ impl IndividualUser {
             }
 
             canister_data
-                .my_token_balance
-                .handle_token_event(TokenEvent::Receive {
+                .apply_token_event(TokenEvent::Receive {
                     amount: token_amount,
                     from_account: from_individual_user.profile_principal,
                     timestamp: get_current_system_time_from_ic(),
-                });
+                    memo: None,
+                })
+                .map_err(|_| MigrationErrors::TokenBalanceOverflow)?;
 
             canister_data.migration_info = MigrationInfo::MigratedFromHotOrNot {
                 account_principal: from_individual_user.profile_principal,
@@ -1,5 +1,10 @@
 pub mod cycles;
+pub mod engagement_reward;
+pub mod kv_storage_expiry;
 pub mod migration;
 pub mod periodic_update;
+pub mod referral;
 pub mod score_ranking;
 pub mod subnet_orchestrator;
+pub mod utility_token_ledger_mirror;
+pub mod wallet_zero_balance_sweeper;
@@ -0,0 +1,407 @@
+use std::{collections::BTreeSet, time::SystemTime};
+
+use candid::Principal;
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        notification::NotificationType,
+        profile::UserCanisterDetails,
+        referral::{ReferralError, ReferralStatus},
+        session::SessionType,
+    },
+    common::{
+        types::{
+            known_principal::KnownPrincipalType,
+            utility_token::token_event::{MintEvent, TokenEvent},
+        },
+        utils::system_time::get_current_system_time_from_ic,
+    },
+};
+
+use crate::{data_model::CanisterData, CANISTER_DATA};
+
+/// Claims the referral rewards once the user is registered and has a referrer. A referral is
+/// only claimed again if the referrer's canister could not be reached.
+pub fn claim_referral_rewards_if_eligible() {
+    let referral_reward_claim = CANISTER_DATA.with_borrow_mut(start_referral_reward_claim);
+
+    if let Some((referrer, my_principal_id, my_device_ids)) = referral_reward_claim {
+        ic_cdk::spawn(claim_referral_rewards(
+            referrer,
+            my_principal_id,
+            my_device_ids,
+        ));
+    }
+}
+
+fn start_referral_reward_claim(
+    canister_data: &mut CanisterData,
+) -> Option<(UserCanisterDetails, Principal, Vec<String>)> {
+    if canister_data.session_type != Some(SessionType::RegisteredSession)
+        || canister_data.referrals.get_my_referral_status().is_some()
+    {
+        return None;
+    }
+
+    let referrer = canister_data.profile.referrer_details.clone()?;
+    let my_principal_id = canister_data.profile.principal_id?;
+    let my_device_ids = canister_data
+        .device_identities
+        .iter()
+        .map(|device_identity| device_identity.device_id.clone())
+        .collect();
+
+    canister_data
+        .referrals
+        .set_my_referral_status(Some(ReferralStatus::Pending));
+
+    Some((referrer, my_principal_id, my_device_ids))
+}
+
+async fn claim_referral_rewards(
+    referrer: UserCanisterDetails,
+    my_principal_id: Principal,
+    my_device_ids: Vec<String>,
+) {
+    let referral_result = ic_cdk::call::<_, (Result<(), ReferralError>,)>(
+        referrer.user_canister_id,
+        "receive_referral_from_referee_canister",
+        (my_principal_id, my_device_ids),
+    )
+    .await
+    .map_err(|e| ReferralError::ReferrerCrossCanisterCallFailed(e.1))
+    .and_then(|res| res.0);
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        complete_referral_reward_claim(
+            canister_data,
+            referrer.profile_owner,
+            my_principal_id,
+            referral_result,
+            get_current_system_time_from_ic(),
+        )
+    });
+}
+
+fn complete_referral_reward_claim(
+    canister_data: &mut CanisterData,
+    referrer_principal_id: Principal,
+    my_principal_id: Principal,
+    referral_result: Result<(), ReferralError>,
+    current_time: SystemTime,
+) {
+    let my_referral_status = match referral_result {
+        Ok(()) => {
+            let referee_reward_amount = canister_data.referrals.get_config().referee_reward_amount;
+            let amount = credit_referral_reward(
+                canister_data,
+                referrer_principal_id,
+                my_principal_id,
+                referee_reward_amount,
+                current_time,
+            );
+            Some(ReferralStatus::Rewarded { amount })
+        }
+        Err(ReferralError::Rejected(reason)) => Some(ReferralStatus::Rejected { reason }),
+        Err(e) => {
+            ic_cdk::println!("Failed to claim referral rewards: {:?}", e);
+            None
+        }
+    };
+
+    canister_data
+        .referrals
+        .set_my_referral_status(my_referral_status);
+}
+
+/// Looks up the canister the user index has on record for the user.
+pub async fn get_user_canister_id_from_user_index(
+    user_principal_id: Principal,
+) -> Result<Option<Principal>, ReferralError> {
+    let user_index_canister_id = CANISTER_DATA
+        .with_borrow(|canister_data| {
+            canister_data
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdUserIndex)
+                .copied()
+        })
+        .ok_or(ReferralError::UserIndexCanisterIdNotFound)?;
+
+    let (user_canister_id,): (Option<Principal>,) = ic_cdk::call(
+        user_index_canister_id,
+        "get_user_canister_id_from_user_principal_id",
+        (user_principal_id,),
+    )
+    .await
+    .map_err(|_| ReferralError::UserIndexCrossCanisterCallFailed)?;
+
+    Ok(user_canister_id)
+}
+
+fn get_result_for_referral_status(referral_status: ReferralStatus) -> Result<(), ReferralError> {
+    match referral_status {
+        ReferralStatus::Rejected { reason } => Err(ReferralError::Rejected(reason)),
+        ReferralStatus::Pending | ReferralStatus::Rewarded { .. } => Ok(()),
+    }
+}
+
+/// Decides a referral of `referee_principal_id` to this user and rewards this user as the
+/// referrer if it is accepted. Every referral is recorded, so a referral that was already
+/// decided gets the same answer again and is never rewarded twice.
+pub fn evaluate_and_record_referral(
+    canister_data: &mut CanisterData,
+    referee_principal_id: Principal,
+    referee_canister_id: Principal,
+    referee_device_ids: BTreeSet<String>,
+    current_time: SystemTime,
+) -> Result<(), ReferralError> {
+    if let Some(referral_status) = canister_data
+        .referrals
+        .get_referral_status(referee_principal_id)
+    {
+        return get_result_for_referral_status(referral_status);
+    }
+
+    let my_principal_id = canister_data
+        .profile
+        .principal_id
+        .ok_or(ReferralError::ReferrerNotSignedUp)?;
+
+    let referral_evaluation = canister_data.referrals.evaluate_referral(
+        my_principal_id,
+        canister_data
+            .profile
+            .referrer_details
+            .as_ref()
+            .map(|referrer| referrer.profile_owner),
+        canister_data
+            .device_identities
+            .iter()
+            .map(|device_identity| device_identity.device_id.as_str()),
+        referee_principal_id,
+        &referee_device_ids,
+    );
+
+    let referral_status = match referral_evaluation {
+        Ok(()) => {
+            let referrer_reward_amount =
+                canister_data.referrals.get_config().referrer_reward_amount;
+            let amount = credit_referral_reward(
+                canister_data,
+                my_principal_id,
+                referee_principal_id,
+                referrer_reward_amount,
+                current_time,
+            );
+            ReferralStatus::Rewarded { amount }
+        }
+        Err(reason) => ReferralStatus::Rejected { reason },
+    };
+
+    canister_data.referrals.record_referral(
+        referee_principal_id,
+        referee_canister_id,
+        referee_device_ids,
+        referral_status,
+        current_time,
+    );
+
+    get_result_for_referral_status(referral_status)
+}
+
+/// Mints a referral reward for the user of this canister and returns the amount credited.
+pub fn credit_referral_reward(
+    canister_data: &mut CanisterData,
+    referrer_principal_id: Principal,
+    referee_principal_id: Principal,
+    amount: u64,
+    current_time: SystemTime,
+) -> u64 {
+    let reward_result = canister_data.apply_token_event(TokenEvent::Mint {
+        amount,
+        details: MintEvent::Referral {
+            referrer_user_principal_id: referrer_principal_id,
+            referee_user_principal_id: referee_principal_id,
+        },
+        timestamp: current_time,
+    });
+
+    if let Err(e) = reward_result {
+        ic_cdk::println!("Failed to credit referral reward: {:?}", e);
+        return 0;
+    }
+
+    canister_data.notification_inbox.add_notification(
+        NotificationType::ReferralRewardCredited {
+            referrer_user_principal_id: referrer_principal_id,
+            referee_user_principal_id: referee_principal_id,
+            amount,
+        },
+        current_time,
+    );
+
+    amount
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::UNIX_EPOCH;
+
+    use shared_utils::canister_specific::individual_user_template::types::{
+        device_id::DeviceIdentity, referral::ReferralRejectionReason,
+    };
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+        get_mock_user_charlie_canister_id, get_mock_user_charlie_principal_id,
+    };
+
+    use super::*;
+
+    fn get_registered_referee_canister_data() -> CanisterData {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_bob_principal_id());
+        canister_data.profile.referrer_details = Some(UserCanisterDetails {
+            profile_owner: get_mock_user_alice_principal_id(),
+            user_canister_id: get_mock_user_alice_canister_id(),
+        });
+        canister_data.session_type = Some(SessionType::RegisteredSession);
+        canister_data
+    }
+
+    #[test]
+    fn test_referral_reward_claim() {
+        let mut canister_data = get_registered_referee_canister_data();
+        canister_data.session_type = Some(SessionType::AnonymousSession);
+
+        // * nothing is claimed before the referee registers
+        assert!(start_referral_reward_claim(&mut canister_data).is_none());
+
+        canister_data.session_type = Some(SessionType::RegisteredSession);
+        let (referrer, my_principal_id, _) =
+            start_referral_reward_claim(&mut canister_data).unwrap();
+        assert_eq!(referrer.profile_owner, get_mock_user_alice_principal_id());
+        assert_eq!(my_principal_id, get_mock_user_bob_principal_id());
+
+        // * a pending claim is not started twice
+        assert!(start_referral_reward_claim(&mut canister_data).is_none());
+
+        complete_referral_reward_claim(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+            Err(ReferralError::ReferrerCrossCanisterCallFailed(
+                "unreachable".into(),
+            )),
+            UNIX_EPOCH,
+        );
+        assert_eq!(canister_data.referrals.get_my_referral_status(), None);
+
+        start_referral_reward_claim(&mut canister_data).unwrap();
+        complete_referral_reward_claim(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+            Ok(()),
+            UNIX_EPOCH,
+        );
+        assert_eq!(
+            canister_data.referrals.get_my_referral_status(),
+            Some(ReferralStatus::Rewarded { amount: 500 })
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 500);
+        assert!(start_referral_reward_claim(&mut canister_data).is_none());
+    }
+
+    #[test]
+    fn test_rejected_referral_reward_claim() {
+        let mut canister_data = get_registered_referee_canister_data();
+
+        start_referral_reward_claim(&mut canister_data).unwrap();
+        complete_referral_reward_claim(
+            &mut canister_data,
+            get_mock_user_alice_principal_id(),
+            get_mock_user_bob_principal_id(),
+            Err(ReferralError::Rejected(
+                ReferralRejectionReason::ReferralRing,
+            )),
+            UNIX_EPOCH,
+        );
+
+        assert_eq!(
+            canister_data.referrals.get_my_referral_status(),
+            Some(ReferralStatus::Rejected {
+                reason: ReferralRejectionReason::ReferralRing
+            })
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 0);
+        assert!(start_referral_reward_claim(&mut canister_data).is_none());
+    }
+
+    #[test]
+    fn test_evaluate_and_record_referral() {
+        let mut canister_data = CanisterData::default();
+        canister_data.profile.principal_id = Some(get_mock_user_alice_principal_id());
+        canister_data.device_identities.push(DeviceIdentity {
+            device_id: "alice-phone".into(),
+            timestamp: 0,
+        });
+
+        let receive_referral = |canister_data: &mut CanisterData,
+                                referee_principal_id: Principal,
+                                referee_canister_id: Principal,
+                                referee_device_id: &str| {
+            evaluate_and_record_referral(
+                canister_data,
+                referee_principal_id,
+                referee_canister_id,
+                [referee_device_id.to_string()].into_iter().collect(),
+                UNIX_EPOCH,
+            )
+        };
+
+        assert_eq!(
+            receive_referral(
+                &mut canister_data,
+                get_mock_user_bob_principal_id(),
+                get_mock_user_bob_canister_id(),
+                "bob-phone"
+            ),
+            Ok(())
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 500);
+
+        // * the same referral is not rewarded twice
+        assert_eq!(
+            receive_referral(
+                &mut canister_data,
+                get_mock_user_bob_principal_id(),
+                get_mock_user_bob_canister_id(),
+                "bob-phone"
+            ),
+            Ok(())
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 500);
+
+        assert_eq!(
+            receive_referral(
+                &mut canister_data,
+                get_mock_user_charlie_principal_id(),
+                get_mock_user_charlie_canister_id(),
+                "alice-phone"
+            ),
+            Err(ReferralError::Rejected(
+                ReferralRejectionReason::SelfReferral
+            ))
+        );
+        assert_eq!(canister_data.my_token_balance.utility_token_balance, 500);
+        assert_eq!(
+            canister_data
+                .referrals
+                .get_referral_status(get_mock_user_charlie_principal_id()),
+            Some(ReferralStatus::Rejected {
+                reason: ReferralRejectionReason::SelfReferral
+            })
+        );
+        assert_eq!(canister_data.referrals.len(), 2);
+    }
+}
@@ -5,8 +5,9 @@ use ic_cdk::{
     api::management_canister::main::{update_settings, CanisterSettings, UpdateSettingsArgument},
     notify,
 };
-use shared_utils::common::{
-    types::known_principal::KnownPrincipalType, utils::task::run_task_concurrently,
+use shared_utils::{
+    canister_specific::individual_user_template::types::engagement_reward::EngagementRewardClaim,
+    common::{types::known_principal::KnownPrincipalType, utils::task::run_task_concurrently},
 };
 
 use crate::{
@@ -135,4 +136,49 @@ impl SubnetOrchestrator {
             )
         })
     }
+
+    pub async fn is_user_canister_in_the_network(
+        &self,
+        user_principal: Principal,
+        user_canister: Principal,
+    ) -> Result<bool, String> {
+        ic_cdk::call::<_, (Result<bool, String>,)>(
+            self.canister_id,
+            "is_user_canister_in_the_network",
+            (user_principal, user_canister),
+        )
+        .await
+        .map_err(|e| e.1)?
+        .0
+    }
+
+    /// Gives back the part of reserved rewards that was not minted.
+    pub async fn release_engagement_rewards(
+        &self,
+        engagement_reward_claims: Vec<EngagementRewardClaim>,
+    ) -> Result<(), String> {
+        ic_cdk::call::<_, (Result<(), String>,)>(
+            self.canister_id,
+            "release_engagement_rewards",
+            (engagement_reward_claims,),
+        )
+        .await
+        .map_err(|e| e.1)?
+        .0
+    }
+
+    /// Returns the part of each reward that fits under what is left of the network caps.
+    pub async fn reserve_engagement_rewards(
+        &self,
+        engagement_reward_claims: Vec<EngagementRewardClaim>,
+    ) -> Result<Vec<EngagementRewardClaim>, String> {
+        ic_cdk::call::<_, (Result<Vec<EngagementRewardClaim>, String>,)>(
+            self.canister_id,
+            "reserve_engagement_rewards",
+            (engagement_reward_claims,),
+        )
+        .await
+        .map_err(|e| e.1)?
+        .0
+    }
 }
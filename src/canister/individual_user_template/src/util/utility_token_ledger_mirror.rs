@@ -0,0 +1,275 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::Duration,
+};
+
+use candid::{Nat, Principal};
+use ic_cdk::api::call::CallResult;
+use ic_cdk_timers::TimerId;
+use icrc_ledger_types::icrc1::{
+    account::Account,
+    transfer::{Memo, TransferArg, TransferError},
+};
+use shared_utils::{
+    canister_specific::individual_user_template::types::token::UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT,
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{
+    data_model::utility_token_ledger_mirror::{
+        get_ledger_created_at_time, PendingUtilityTokenLedgerMirrorOperation,
+        UtilityTokenLedgerMirrorOperation,
+    },
+    CANISTER_DATA,
+};
+
+const UTILITY_TOKEN_LEDGER_MIRROR_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+thread_local! {
+    static UTILITY_TOKEN_LEDGER_MIRROR_TIMER: RefCell<Option<TimerId>> = const { RefCell::new(None) };
+    static UTILITY_TOKEN_LEDGER_MIRROR_FLUSH_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Timers do not survive upgrades, so this is also called from `post_upgrade` when mirroring
+/// is enabled.
+pub fn start_utility_token_ledger_mirror_timer() {
+    stop_utility_token_ledger_mirror_timer();
+
+    let timer_id =
+        ic_cdk_timers::set_timer_interval(UTILITY_TOKEN_LEDGER_MIRROR_FLUSH_INTERVAL, || {
+            ic_cdk::spawn(flush_utility_token_ledger_mirror())
+        });
+
+    UTILITY_TOKEN_LEDGER_MIRROR_TIMER.with_borrow_mut(|timer| *timer = Some(timer_id));
+}
+
+pub fn stop_utility_token_ledger_mirror_timer() {
+    if let Some(timer_id) = UTILITY_TOKEN_LEDGER_MIRROR_TIMER.with_borrow_mut(Option::take) {
+        ic_cdk_timers::clear_timer(timer_id);
+    }
+}
+
+/// Sends pending operations to the ledger in order and stops at the first one the ledger does
+/// not accept, leaving it queued for the next run.
+pub async fn flush_utility_token_ledger_mirror() {
+    if UTILITY_TOKEN_LEDGER_MIRROR_FLUSH_IN_PROGRESS.replace(true) {
+        return;
+    }
+
+    while let Some((ledger_canister_id, operation_id, pending_operation)) = CANISTER_DATA
+        .with_borrow(|canister_data| {
+            let utility_token_ledger_mirror = &canister_data.utility_token_ledger_mirror;
+            let ledger_canister_id = utility_token_ledger_mirror.get_ledger_canister_id()?;
+            utility_token_ledger_mirror
+                .get_next_pending_operation()
+                .map(|(operation_id, pending_operation)| {
+                    (ledger_canister_id, operation_id, pending_operation)
+                })
+        })
+    {
+        let transfer_arg =
+            get_transfer_arg_for_operation(ic_cdk::id(), operation_id, &pending_operation);
+
+        let transfer_result: CallResult<(Result<Nat, TransferError>,)> =
+            ic_cdk::call(ledger_canister_id, "icrc1_transfer", (transfer_arg,)).await;
+
+        let mirrored_to_same_ledger = CANISTER_DATA.with_borrow(|canister_data| {
+            canister_data
+                .utility_token_ledger_mirror
+                .get_ledger_canister_id()
+                == Some(ledger_canister_id)
+        });
+        if !mirrored_to_same_ledger {
+            break;
+        }
+
+        match transfer_result {
+            Ok((Ok(_),)) | Ok((Err(TransferError::Duplicate { .. }),)) => {
+                CANISTER_DATA.with_borrow_mut(|canister_data| {
+                    canister_data
+                        .utility_token_ledger_mirror
+                        .complete_operation(operation_id)
+                });
+            }
+            Ok((Err(TransferError::TooOld),)) => {
+                // * an earlier attempt may have reached the ledger without a reply, so the
+                // ledger balance decides whether the operation is resent
+                let mirror_account_balance =
+                    get_mirror_account_balance(ledger_canister_id, ic_cdk::id()).await;
+
+                let is_operation_applied = CANISTER_DATA.with_borrow(|canister_data| {
+                    let utility_token_ledger_mirror = &canister_data.utility_token_ledger_mirror;
+                    if utility_token_ledger_mirror.get_ledger_canister_id()
+                        != Some(ledger_canister_id)
+                    {
+                        return None;
+                    }
+
+                    let mirror_account_balance = mirror_account_balance
+                        .as_ref()
+                        .ok()
+                        .and_then(|balance| u64::try_from(&balance.0).ok())?;
+                    utility_token_ledger_mirror
+                        .is_operation_applied_on_ledger(operation_id, mirror_account_balance)
+                });
+
+                match is_operation_applied {
+                    Some(true) => CANISTER_DATA.with_borrow_mut(|canister_data| {
+                        canister_data
+                            .utility_token_ledger_mirror
+                            .complete_operation(operation_id)
+                    }),
+                    Some(false) => CANISTER_DATA.with_borrow_mut(|canister_data| {
+                        canister_data
+                            .utility_token_ledger_mirror
+                            .refresh_operation_created_at_time(
+                                operation_id,
+                                get_current_system_time_from_ic(),
+                            )
+                    }),
+                    None => {
+                        ic_cdk::println!(
+                            "Could not tell from the ledger balance {:?} whether mirrored operation {} was applied",
+                            mirror_account_balance,
+                            operation_id
+                        );
+                        break;
+                    }
+                }
+            }
+            Ok((Err(TransferError::BadBurn { min_burn_amount }),)) => {
+                ic_cdk::println!(
+                    "Dropping mirrored burn {} below the ledger minimum of {}",
+                    operation_id,
+                    min_burn_amount
+                );
+                CANISTER_DATA.with_borrow_mut(|canister_data| {
+                    canister_data
+                        .utility_token_ledger_mirror
+                        .drop_operation(operation_id)
+                });
+            }
+            Ok((Err(e),)) => {
+                ic_cdk::println!(
+                    "Ledger rejected mirrored operation {}: {:?}",
+                    operation_id,
+                    e
+                );
+                break;
+            }
+            Err((_, message)) => {
+                ic_cdk::println!("Failed to mirror operation {}: {}", operation_id, message);
+                break;
+            }
+        }
+    }
+
+    UTILITY_TOKEN_LEDGER_MIRROR_FLUSH_IN_PROGRESS.set(false);
+}
+
+async fn get_mirror_account_balance(
+    ledger_canister_id: Principal,
+    my_canister_id: Principal,
+) -> CallResult<Nat> {
+    let mirror_account = Account {
+        owner: my_canister_id,
+        subaccount: Some(UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT),
+    };
+
+    let (balance,): (Nat,) =
+        ic_cdk::call(ledger_canister_id, "icrc1_balance_of", (mirror_account,)).await?;
+
+    Ok(balance)
+}
+
+/// Mints move tokens from the canister's default account, the ledger's minting account, to the
+/// mirror subaccount. Burns move them back.
+fn get_transfer_arg_for_operation(
+    my_canister_id: Principal,
+    operation_id: u64,
+    pending_operation: &PendingUtilityTokenLedgerMirrorOperation,
+) -> TransferArg {
+    let minting_account = Account {
+        owner: my_canister_id,
+        subaccount: None,
+    };
+    let mirror_account = Account {
+        owner: my_canister_id,
+        subaccount: Some(UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT),
+    };
+
+    let (from_subaccount, to, amount) = match pending_operation.operation {
+        UtilityTokenLedgerMirrorOperation::Mint { amount } => (None, mirror_account, amount),
+        UtilityTokenLedgerMirrorOperation::Burn { amount } => (
+            Some(UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT),
+            minting_account,
+            amount,
+        ),
+    };
+
+    TransferArg {
+        from_subaccount,
+        to,
+        fee: None,
+        created_at_time: Some(get_ledger_created_at_time(
+            pending_operation.created_at_time,
+        )),
+        memo: Some(Memo::from(operation_id)),
+        amount: Nat::from(amount),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    #[test]
+    fn test_get_transfer_arg_for_operation() {
+        let created_at_time = UNIX_EPOCH + Duration::from_secs(1);
+
+        let mint = get_transfer_arg_for_operation(
+            get_mock_user_alice_canister_id(),
+            7,
+            &PendingUtilityTokenLedgerMirrorOperation {
+                operation: UtilityTokenLedgerMirrorOperation::Mint { amount: 100 },
+                created_at_time,
+            },
+        );
+        assert_eq!(mint.from_subaccount, None);
+        assert_eq!(
+            mint.to,
+            Account {
+                owner: get_mock_user_alice_canister_id(),
+                subaccount: Some(UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT),
+            }
+        );
+        assert_eq!(mint.amount, Nat::from(100_u64));
+        assert_eq!(mint.created_at_time, Some(1_000_000_000));
+        assert_eq!(mint.memo, Some(Memo::from(7_u64)));
+
+        let burn = get_transfer_arg_for_operation(
+            get_mock_user_alice_canister_id(),
+            8,
+            &PendingUtilityTokenLedgerMirrorOperation {
+                operation: UtilityTokenLedgerMirrorOperation::Burn { amount: 40 },
+                created_at_time,
+            },
+        );
+        assert_eq!(
+            burn.from_subaccount,
+            Some(UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT)
+        );
+        assert_eq!(
+            burn.to,
+            Account {
+                owner: get_mock_user_alice_canister_id(),
+                subaccount: None,
+            }
+        );
+        assert_eq!(burn.amount, Nat::from(40_u64));
+    }
+}
@@ -0,0 +1,111 @@
+use std::{cell::RefCell, time::Duration};
+
+use candid::Nat;
+use futures::StreamExt;
+use ic_cdk_timers::TimerId;
+use icrc_ledger_types::icrc1::account::Account;
+use shared_utils::common::utils::system_time::get_current_system_time_from_ic;
+
+use crate::CANISTER_DATA;
+
+const WALLET_ZERO_BALANCE_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_BALANCES_TO_FETCH_CONCURRENTLY: usize = 10;
+
+thread_local! {
+    static WALLET_ZERO_BALANCE_SWEEPER_TIMER: RefCell<Option<TimerId>> = const { RefCell::new(None) };
+}
+
+fn has_anything_to_sweep() -> bool {
+    CANISTER_DATA.with_borrow(|canister_data| canister_data.wallet.has_zero_balance_tokens())
+}
+
+/// Starts the sweeper when tokens were seen at a zero balance and it is not running yet. Timers
+/// do not survive upgrades, so this is also called from `post_upgrade`.
+pub fn start_wallet_zero_balance_sweeper_timer_if_needed() {
+    let is_running = WALLET_ZERO_BALANCE_SWEEPER_TIMER.with_borrow(Option::is_some);
+    if is_running || !has_anything_to_sweep() {
+        return;
+    }
+
+    let timer_id = ic_cdk_timers::set_timer_interval(WALLET_ZERO_BALANCE_SWEEP_INTERVAL, || {
+        ic_cdk::spawn(sweep_zero_balance_tokens())
+    });
+
+    WALLET_ZERO_BALANCE_SWEEPER_TIMER.with_borrow_mut(|timer| *timer = Some(timer_id));
+}
+
+/// Removes the tokens that have stayed at a zero balance past the grace period, and stops the
+/// sweeper once no token is at a zero balance. Balances are only recorded when the wallet
+/// overview is fetched, so they are fetched again before a token is removed.
+async fn sweep_zero_balance_tokens() {
+    let (my_principal_id, tokens_to_check) = CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let current_time = get_current_system_time_from_ic();
+        let mut tokens_to_check = vec![];
+
+        for (token_root, ledger) in canister_data
+            .wallet
+            .get_tokens_past_zero_balance_grace_period(current_time)
+        {
+            // * tokens of the creator stay in the wallet
+            let is_token_of_creator = canister_data
+                .cdao_canisters
+                .iter()
+                .any(|cdao| cdao.root == token_root);
+            match ledger {
+                Some(ledger)
+                    if !is_token_of_creator
+                        && canister_data.token_roots.contains_key(&token_root) =>
+                {
+                    tokens_to_check.push((token_root, ledger))
+                }
+                _ => canister_data.wallet.clear_zero_balance(token_root),
+            }
+        }
+
+        (canister_data.profile.principal_id, tokens_to_check)
+    });
+
+    if let Some(my_principal_id) = my_principal_id {
+        let account = Account {
+            owner: my_principal_id,
+            subaccount: None,
+        };
+        let balance_futures = tokens_to_check
+            .into_iter()
+            .map(|(token_root, ledger)| async move {
+                let balance_res =
+                    ic_cdk::call::<_, (Nat,)>(ledger, "icrc1_balance_of", (account,)).await;
+                (token_root, balance_res)
+            });
+        let balances: Vec<_> = futures::stream::iter(balance_futures)
+            .buffer_unordered(MAX_BALANCES_TO_FETCH_CONCURRENTLY)
+            .collect()
+            .await;
+
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            let current_time = get_current_system_time_from_ic();
+
+            for (token_root, balance_res) in balances {
+                let Ok((balance,)) = balance_res else {
+                    continue;
+                };
+                canister_data
+                    .wallet
+                    .record_balance(token_root, &balance, current_time);
+                if canister_data
+                    .wallet
+                    .is_zero_balance_grace_period_over(token_root, current_time)
+                {
+                    canister_data.token_roots.remove(&token_root);
+                    canister_data.wallet.remove_token(token_root);
+                }
+            }
+        });
+    }
+
+    if !has_anything_to_sweep() {
+        if let Some(timer_id) = WALLET_ZERO_BALANCE_SWEEPER_TIMER.with_borrow_mut(Option::take) {
+            ic_cdk_timers::clear_timer(timer_id);
+        }
+    }
+}
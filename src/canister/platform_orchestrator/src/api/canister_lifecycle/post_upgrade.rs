@@ -7,12 +7,23 @@ use shared_utils::{
     common::utils::system_time,
 };
 
-use crate::{data_model::memory, CANISTER_DATA};
+use crate::{
+    data_model::memory,
+    utils::utility_token_supply_snapshot::start_utility_token_supply_snapshot_timer, CANISTER_DATA,
+};
 
 #[post_upgrade]
 pub fn post_upgrade() {
     restore_data_from_stable_memory();
     update_version_from_args();
+    migrate_global_unique_user_name_registry_to_stable_memory();
+    start_utility_token_supply_snapshot_timer();
+}
+
+fn migrate_global_unique_user_name_registry_to_stable_memory() {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.migrate_global_unique_user_name_registry_to_stable_memory();
+    })
 }
 
 fn restore_data_from_stable_memory() {
@@ -27,7 +27,11 @@ pub mod start_subnet_orchestrator_canister;
 mod stop_upgrades_for_individual_user_canisters;
 mod subnet_orchestrator_maxed_out;
 mod update_canisters_last_access_time;
+pub mod update_creator_token_quota_config_in_the_network;
+pub mod update_creator_token_quota_override_for_individual_canister;
+pub mod update_engagement_reward_caps_in_the_network;
 mod update_profile_owner_for_individual_users;
+pub mod update_referral_config_in_the_network;
 pub mod update_timers_for_hon_game;
 pub mod upgrade_all_creator_dao_governance_canisters_in_the_network;
 pub mod upgrade_canisters_in_network;
@@ -0,0 +1,38 @@
+use candid::Principal;
+use ic_cdk::api::management_canister::main::{canister_info, CanisterInfoRequest};
+use ic_cdk_macros::update;
+
+use crate::{
+    guard::is_caller::is_caller_global_admin_or_controller,
+    utils::registered_subnet_orchestrator::RegisteredSubnetOrchestrator,
+};
+
+/// Overrides the creator token quota of one user, or restores the network wide one with `None`.
+#[update(guard = "is_caller_global_admin_or_controller")]
+pub async fn update_creator_token_quota_override_for_individual_canister(
+    individual_canister_id: Principal,
+    quota_override: Option<u64>,
+) -> Result<(), String> {
+    let (individual_canister_info,) = canister_info(CanisterInfoRequest {
+        canister_id: individual_canister_id,
+        num_requested_changes: None,
+    })
+    .await
+    .map_err(|e| e.1)?;
+
+    let subnet_orchestrator_canister_id = individual_canister_info
+        .controllers
+        .first()
+        .copied()
+        .ok_or("Subnet orchestrator canister id not found")?;
+
+    let registered_subnet_orchestrator =
+        RegisteredSubnetOrchestrator::new(subnet_orchestrator_canister_id)?;
+
+    registered_subnet_orchestrator
+        .update_creator_token_quota_override_for_individual_canister(
+            individual_canister_id,
+            quota_override,
+        )
+        .await
+}
@@ -0,0 +1,42 @@
+use ic_cdk::{api::call::CallResult, call};
+use ic_cdk_macros::update;
+use shared_utils::canister_specific::individual_user_template::types::engagement_reward::EngagementRewardCaps;
+
+use crate::{guard::is_caller::is_caller_global_admin_or_controller, CANISTER_DATA};
+
+/// Splits the caps evenly between the subnets and gives each subnet orchestrator its share to
+/// enforce. The caps have to be pushed again once a subnet is added.
+#[update(guard = "is_caller_global_admin_or_controller")]
+async fn update_engagement_reward_caps_in_the_network(
+    engagement_reward_caps: EngagementRewardCaps,
+) -> Result<String, String> {
+    let subnet_orchestrator_list = CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.all_subnet_orchestrator_canisters_list.clone());
+
+    let engagement_reward_caps_per_subnet =
+        engagement_reward_caps.get_share_per_subnet(subnet_orchestrator_list.len() as u64);
+
+    let mut errors = vec![];
+
+    for subnet_orchestrator in subnet_orchestrator_list {
+        let result: CallResult<()> = call(
+            subnet_orchestrator,
+            "update_engagement_reward_caps",
+            (engagement_reward_caps_per_subnet,),
+        )
+        .await;
+
+        if let Err(e) = result {
+            errors.push(format!(
+                "failed to update the engagement reward caps in subnet {} {}",
+                subnet_orchestrator, e.1
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    Ok("Success".into())
+}
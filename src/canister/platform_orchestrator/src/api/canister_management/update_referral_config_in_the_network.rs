@@ -0,0 +1,40 @@
+use ic_cdk::{api::call::CallResult, call};
+use ic_cdk_macros::update;
+use shared_utils::canister_specific::individual_user_template::types::referral::ReferralConfig;
+
+use crate::{guard::is_caller::is_caller_global_admin_or_controller, CANISTER_DATA};
+
+/// Pushes the config to every subnet and reports each subnet that could not update all of its
+/// individual canisters. Pushing the same config again retries the failed ones.
+#[update(guard = "is_caller_global_admin_or_controller")]
+async fn update_referral_config_in_the_network(
+    referral_config: ReferralConfig,
+) -> Result<String, String> {
+    let subnet_orchestrator_list = CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.all_subnet_orchestrator_canisters_list.clone());
+
+    let mut errors = vec![];
+
+    for subnet_orchestrator in subnet_orchestrator_list {
+        let result: CallResult<(Result<String, String>,)> = call(
+            subnet_orchestrator,
+            "update_referral_config_for_individual_canisters",
+            (referral_config,),
+        )
+        .await;
+
+        let result = result.map_err(|e| e.1).and_then(|res| res.0);
+        if let Err(e) = result {
+            errors.push(format!(
+                "failed to update the referral config in subnet {} {}",
+                subnet_orchestrator, e
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    Ok("Success".into())
+}
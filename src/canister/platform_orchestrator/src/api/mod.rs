@@ -3,5 +3,6 @@ pub mod canister_management;
 pub mod cycle_management;
 pub mod generic_proposal;
 pub mod monitoring;
+pub mod snapshot;
 pub mod stats;
-pub mod snapshot;
\ No newline at end of file
+pub mod unique_user_name;
@@ -0,0 +1,58 @@
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::cdao::CreatorDaoTokenStatus,
+    common::utils::task::run_task_concurrently,
+};
+
+use crate::{guard::is_caller::is_caller_global_admin_or_controller, CANISTER_DATA};
+
+/// Refreshes the creator token statuses kept alongside the creator dao stats.
+#[update(guard = "is_caller_global_admin_or_controller")]
+pub fn collect_creator_dao_token_statuses_in_the_network() {
+    let subnet_orchestrators = CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.all_subnet_orchestrator_canisters_list.clone());
+
+    let creator_dao_token_statuses_task =
+        subnet_orchestrators
+            .into_iter()
+            .map(|subnet_orchestrator_canister_id| async move {
+                ic_cdk::call::<_, (Result<Vec<CreatorDaoTokenStatus>, String>,)>(
+                    subnet_orchestrator_canister_id,
+                    "collect_creator_dao_token_statuses_in_the_network",
+                    (),
+                )
+                .await
+                .map_err(|e| e.1)
+                .and_then(|res| res.0)
+            });
+
+    let creator_dao_token_statuses_result_callback =
+        |creator_dao_token_statuses_result: Result<Vec<CreatorDaoTokenStatus>, String>| {
+            match creator_dao_token_statuses_result {
+                Ok(creator_dao_token_statuses) => {
+                    CANISTER_DATA.with_borrow_mut(|canister_data| {
+                        creator_dao_token_statuses
+                            .into_iter()
+                            .for_each(|token_status| {
+                                canister_data
+                                    .creator_dao_stats
+                                    .update_token_status(token_status)
+                            });
+                    });
+                }
+                Err(e) => {
+                    ic_cdk::println!(
+                        "Error retrieving creator dao token statuses from subnet orchestrator. {}",
+                        e
+                    );
+                }
+            }
+        };
+
+    ic_cdk::spawn(run_task_concurrently(
+        creator_dao_token_statuses_task,
+        5,
+        creator_dao_token_statuses_result_callback,
+        || false,
+    ));
+}
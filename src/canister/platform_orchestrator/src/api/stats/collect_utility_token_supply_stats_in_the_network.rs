@@ -0,0 +1,90 @@
+use std::{cell::Cell, collections::BTreeMap};
+
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    common::utils::{system_time, task::run_task_concurrently},
+    types::utility_token_supply_stats::UtilityTokenSupplyStats,
+};
+
+use crate::{guard::is_caller::is_caller_global_admin_or_controller, CANISTER_DATA};
+
+thread_local! {
+    static UTILITY_TOKEN_SUPPLY_STATS_COLLECTION_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Collects the utility token totals of every subnet and records them as today's snapshot. A
+/// timer already does this once a day, so this is only needed to refresh today's snapshot.
+#[update(guard = "is_caller_global_admin_or_controller")]
+pub fn collect_utility_token_supply_stats_in_the_network() {
+    ic_cdk::spawn(collect_utility_token_supply_stats_in_the_network_impl());
+}
+
+pub async fn collect_utility_token_supply_stats_in_the_network_impl() {
+    if UTILITY_TOKEN_SUPPLY_STATS_COLLECTION_IN_PROGRESS.replace(true) {
+        return;
+    }
+
+    let subnet_orchestrators = CANISTER_DATA
+        .with_borrow(|canister_data| canister_data.all_subnet_orchestrator_canisters_list.clone());
+
+    let mut stats_by_subnet = BTreeMap::<Principal, UtilityTokenSupplyStats>::new();
+
+    let utility_token_supply_stats_task =
+        subnet_orchestrators
+            .into_iter()
+            .map(|subnet_orchestrator_canister_id| async move {
+                let utility_token_supply_stats_call_result =
+                    ic_cdk::call::<_, (Result<UtilityTokenSupplyStats, String>,)>(
+                        subnet_orchestrator_canister_id,
+                        "collect_utility_token_supply_stats_in_the_network",
+                        (),
+                    )
+                    .await
+                    .map_err(|e| e.1)
+                    .and_then(|res| res.0);
+
+                (
+                    subnet_orchestrator_canister_id,
+                    utility_token_supply_stats_call_result,
+                )
+            });
+
+    let utility_token_supply_stats_result_callback = |(
+        subnet_orchestrator_canister_id,
+        utility_token_supply_stats_result,
+    ): (
+        Principal,
+        Result<UtilityTokenSupplyStats, String>,
+    )| {
+        match utility_token_supply_stats_result {
+            Ok(utility_token_supply_stats) => {
+                stats_by_subnet.insert(subnet_orchestrator_canister_id, utility_token_supply_stats);
+            }
+            Err(e) => {
+                ic_cdk::println!(
+                    "Error retrieving utility token supply stats from subnet orchestrator {}. {}",
+                    subnet_orchestrator_canister_id,
+                    e
+                );
+            }
+        }
+    };
+
+    run_task_concurrently(
+        utility_token_supply_stats_task,
+        5,
+        utility_token_supply_stats_result_callback,
+        || false,
+    )
+    .await;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.add_utility_token_supply_stats_received_from_subnet_orchestrators(
+            stats_by_subnet,
+            system_time::get_current_system_time(),
+        )
+    });
+
+    UTILITY_TOKEN_SUPPLY_STATS_COLLECTION_IN_PROGRESS.set(false);
+}
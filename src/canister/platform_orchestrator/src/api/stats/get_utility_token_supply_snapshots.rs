@@ -0,0 +1,27 @@
+use ic_cdk_macros::query;
+use shared_utils::types::utility_token_supply_stats::UtilityTokenSupplySnapshot;
+
+use crate::{guard::is_caller::is_caller_global_admin_or_controller, CANISTER_DATA};
+
+const MAX_UTILITY_TOKEN_SUPPLY_SNAPSHOTS_PER_REQUEST: usize = 100;
+
+/// Returns the daily snapshots for days since the unix epoch in the given range, oldest first
+/// and at most 100 of them.
+#[query(guard = "is_caller_global_admin_or_controller")]
+pub fn get_utility_token_supply_snapshots(
+    from_inclusive_day: u64,
+    to_exclusive_day: u64,
+) -> Vec<UtilityTokenSupplySnapshot> {
+    if from_inclusive_day >= to_exclusive_day {
+        return vec![];
+    }
+
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .utility_token_supply_snapshots
+            .range(from_inclusive_day..to_exclusive_day)
+            .take(MAX_UTILITY_TOKEN_SUPPLY_SNAPSHOTS_PER_REQUEST)
+            .map(|(_, snapshot)| snapshot.clone())
+            .collect()
+    })
+}
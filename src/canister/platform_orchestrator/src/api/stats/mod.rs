@@ -1,3 +1,6 @@
 pub mod collect_creator_dao_stats_in_the_network;
+pub mod collect_creator_dao_token_statuses_in_the_network;
+pub mod collect_utility_token_supply_stats_in_the_network;
 pub mod get_creator_dao_stats;
+pub mod get_utility_token_supply_snapshots;
 pub mod receive_creator_dao_stats_from_subnet_orchestrator;
@@ -0,0 +1,13 @@
+use candid::Principal;
+use ic_cdk_macros::query;
+
+use crate::CANISTER_DATA;
+
+#[query]
+pub fn get_user_canister_id_from_global_unique_user_name(
+    unique_user_name: String,
+) -> Option<Principal> {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data.get_user_canister_id_from_global_unique_user_name(&unique_user_name)
+    })
+}
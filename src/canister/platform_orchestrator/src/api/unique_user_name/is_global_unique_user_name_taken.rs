@@ -0,0 +1,13 @@
+use ic_cdk_macros::query;
+use shared_utils::common::utils::system_time::get_current_system_time_from_ic;
+
+use crate::CANISTER_DATA;
+
+#[query]
+pub fn is_global_unique_user_name_taken(unique_user_name: String) -> bool {
+    let current_time = get_current_system_time_from_ic();
+
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data.is_global_unique_user_name_taken(&unique_user_name, current_time)
+    })
+}
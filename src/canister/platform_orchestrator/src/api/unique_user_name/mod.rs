@@ -0,0 +1,5 @@
+pub mod get_user_canister_id_from_global_unique_user_name;
+pub mod is_global_unique_user_name_taken;
+pub mod receive_unique_user_names_from_subnet_orchestrator;
+pub mod register_global_unique_user_name;
+pub mod revert_global_unique_user_name_registration;
@@ -0,0 +1,51 @@
+use candid::Principal;
+use ic_cdk::caller;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::platform_orchestrator::types::GlobalUniqueUserNameEntry,
+    common::utils::validation::normalize_unique_user_name,
+};
+
+use crate::{utils::registered_subnet_orchestrator::RegisteredSubnetOrchestrator, CANISTER_DATA};
+
+/// Backfills the registry with names a subnet orchestrator assigned before the registry existed.
+/// Returns the names that are already held by a different user on the platform.
+#[update]
+pub fn receive_unique_user_names_from_subnet_orchestrator(
+    unique_user_names: Vec<(String, Principal, Principal)>,
+) -> Result<Vec<String>, String> {
+    let registered_subnet_orchestrator = RegisteredSubnetOrchestrator::new(caller())?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        let conflicting_unique_user_names = unique_user_names
+            .into_iter()
+            .filter_map(|(unique_user_name, user_principal_id, user_canister_id)| {
+                let unique_user_name = normalize_unique_user_name(&unique_user_name);
+                match canister_data
+                    .global_unique_user_name_registry
+                    .get(&unique_user_name)
+                {
+                    Some(entry) if entry.user_principal_id != user_principal_id => {
+                        Some(unique_user_name)
+                    }
+                    Some(_) => None,
+                    None => {
+                        canister_data.global_unique_user_name_registry.insert(
+                            unique_user_name,
+                            GlobalUniqueUserNameEntry {
+                                user_principal_id,
+                                user_canister_id,
+                                subnet_orchestrator_canister_id: registered_subnet_orchestrator
+                                    .get_canister_id(),
+                                released_claimable_by_others_after: None,
+                            },
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(conflicting_unique_user_names)
+    })
+}
@@ -0,0 +1,34 @@
+use candid::Principal;
+use ic_cdk::caller;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::platform_orchestrator::types::GlobalUniqueUserNameRegistryError,
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{utils::registered_subnet_orchestrator::RegisteredSubnetOrchestrator, CANISTER_DATA};
+
+/// Called by a subnet orchestrator before it sets or changes a username in its own index, so that
+/// names stay unique across all subnets.
+#[update]
+pub fn register_global_unique_user_name(
+    unique_user_name: String,
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+    old_unique_user_name: Option<String>,
+) -> Result<(), GlobalUniqueUserNameRegistryError> {
+    let registered_subnet_orchestrator = RegisteredSubnetOrchestrator::new(caller())
+        .map_err(|_| GlobalUniqueUserNameRegistryError::CallerIsNotASubnetOrchestrator)?;
+    let current_time = get_current_system_time_from_ic();
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.register_global_unique_user_name(
+            unique_user_name,
+            user_principal_id,
+            user_canister_id,
+            registered_subnet_orchestrator.get_canister_id(),
+            old_unique_user_name,
+            current_time,
+        )
+    })
+}
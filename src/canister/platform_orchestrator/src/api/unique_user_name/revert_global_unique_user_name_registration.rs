@@ -0,0 +1,24 @@
+use candid::Principal;
+use ic_cdk::caller;
+use ic_cdk_macros::update;
+use shared_utils::canister_specific::platform_orchestrator::types::GlobalUniqueUserNameRegistryError;
+
+use crate::{utils::registered_subnet_orchestrator::RegisteredSubnetOrchestrator, CANISTER_DATA};
+
+#[update]
+pub fn revert_global_unique_user_name_registration(
+    unique_user_name: String,
+    user_principal_id: Principal,
+    old_unique_user_name: Option<String>,
+) -> Result<(), GlobalUniqueUserNameRegistryError> {
+    RegisteredSubnetOrchestrator::new(caller())
+        .map_err(|_| GlobalUniqueUserNameRegistryError::CallerIsNotASubnetOrchestrator)?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.revert_global_unique_user_name_registration(
+            &unique_user_name,
+            user_principal_id,
+            old_unique_user_name,
+        )
+    })
+}
@@ -14,6 +14,12 @@ const CANISTER_UPGRADE_LOG_INDEX: MemoryId = MemoryId::new(2);
 //A memory for canister upgrade log 
 const CANISTER_UPGRADE_LOG: MemoryId = MemoryId::new(3);
 
+// A memory for the global unique user name registry
+const GLOBAL_UNIQUE_USER_NAME_REGISTRY: MemoryId = MemoryId::new(4);
+
+// A memory for the released global unique user names ordered by when they become claimable by others
+const RELEASED_GLOBAL_UNIQUE_USER_NAMES: MemoryId = MemoryId::new(5);
+
 // A memory for the StableBTreeMap we're using. A new memory should be created for
 // every additional stable structure.
 
@@ -43,6 +49,14 @@ pub fn get_canister_upgrade_log_memory() -> Memory {
     MEMORY_MANAGER.with_borrow_mut(|memory_manager| memory_manager.get(CANISTER_UPGRADE_LOG))
 }
 
+pub fn get_global_unique_user_name_registry_memory() -> Memory {
+    MEMORY_MANAGER.with_borrow_mut(|memory_manager| memory_manager.get(GLOBAL_UNIQUE_USER_NAME_REGISTRY))
+}
+
+pub fn get_released_global_unique_user_names_memory() -> Memory {
+    MEMORY_MANAGER.with_borrow_mut(|memory_manager| memory_manager.get(RELEASED_GLOBAL_UNIQUE_USER_NAMES))
+}
+
 pub fn init_memory_manager() {
     MEMORY_MANAGER.with(|m| {
         *m.borrow_mut() = MemoryManager::init_with_bucket_size(DefaultMemoryImpl::default(), 1);
@@ -2,8 +2,8 @@ use ciborium::de;
 use ic_stable_structures::{storable::Bound, StableBTreeMap, StableLog, Storable};
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
-    time::{SystemTime, UNIX_EPOCH},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use candid::{CandidType, Principal};
@@ -13,15 +13,23 @@ use shared_utils::{
         individual_user_template::types::{cdao::DeployedCdaoCanisters, session::SessionType},
         platform_orchestrator::types::{
             args::UpgradeCanisterArg, well_known_principal::PlatformOrchestratorKnownPrincipal,
-            SubnetUpgradeReport,
+            GlobalUniqueUserNameEntry, GlobalUniqueUserNameRegistryError, SubnetUpgradeReport,
         },
     },
-    common::types::wasm::{CanisterWasm, WasmType},
-    types::creator_dao_stats::CreatorDaoTokenStats,
+    common::{
+        types::wasm::{CanisterWasm, WasmType},
+        utils::validation::normalize_unique_user_name,
+    },
+    constant::UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS,
+    types::{
+        creator_dao_stats::CreatorDaoTokenStats,
+        utility_token_supply_stats::{UtilityTokenSupplySnapshot, UtilityTokenSupplyStats},
+    },
 };
 
 use self::memory::{
     get_canister_upgrade_log_index_memory, get_canister_upgrade_log_memory,
+    get_global_unique_user_name_registry_memory, get_released_global_unique_user_names_memory,
     get_subnet_orchestrator_wasm_memory, Memory,
 };
 
@@ -53,12 +61,34 @@ pub struct CanisterData {
     pub state_guard: StateGuard,
     #[serde(default)]
     pub creator_dao_stats: CreatorDaoTokenStats,
+    #[serde(skip, default = "_default_global_unique_user_name_registry")]
+    pub global_unique_user_name_registry: StableBTreeMap<String, GlobalUniqueUserNameEntry, Memory>,
+    // Released names ordered by when they become claimable by others, so that expired ones can be
+    // pruned without scanning the registry
+    #[serde(skip, default = "_default_released_global_unique_user_names")]
+    pub released_global_unique_user_names: StableBTreeMap<ReleasedGlobalUniqueUserName, (), Memory>,
+    // Registry kept on the heap before it moved to stable memory, only read to migrate it
+    #[serde(default, skip_serializing, rename = "global_unique_user_name_registry")]
+    legacy_global_unique_user_name_registry: BTreeMap<String, GlobalUniqueUserNameEntry>,
+    // Key is days since the unix epoch
+    #[serde(default)]
+    pub utility_token_supply_snapshots: BTreeMap<u64, UtilityTokenSupplySnapshot>,
 }
 
 fn _default_wasms() -> StableBTreeMap<WasmType, CanisterWasm, Memory> {
     StableBTreeMap::init(get_subnet_orchestrator_wasm_memory())
 }
 
+fn _default_global_unique_user_name_registry(
+) -> StableBTreeMap<String, GlobalUniqueUserNameEntry, Memory> {
+    StableBTreeMap::init(get_global_unique_user_name_registry_memory())
+}
+
+fn _default_released_global_unique_user_names(
+) -> StableBTreeMap<ReleasedGlobalUniqueUserName, (), Memory> {
+    StableBTreeMap::init(get_released_global_unique_user_names_memory())
+}
+
 fn _default_canister_upgrade_log() -> StableLog<CanisterUpgradeStatus, Memory, Memory> {
     StableLog::init(
         get_canister_upgrade_log_index_memory(),
@@ -82,6 +112,10 @@ impl Default for CanisterData {
             subnets_upgrade_report: SubnetUpgradeReport::default(),
             state_guard: StateGuard::default(),
             creator_dao_stats: CreatorDaoTokenStats::default(),
+            global_unique_user_name_registry: _default_global_unique_user_name_registry(),
+            released_global_unique_user_names: _default_released_global_unique_user_names(),
+            legacy_global_unique_user_name_registry: BTreeMap::default(),
+            utility_token_supply_snapshots: BTreeMap::default(),
         }
     }
 }
@@ -101,6 +135,40 @@ impl Default for VersionDetails {
     }
 }
 
+/// Key of the released names index. Stored as the big endian release time followed by the name
+/// so that keys sort by when the names become claimable by others.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReleasedGlobalUniqueUserName {
+    pub claimable_by_others_after: SystemTime,
+    pub unique_user_name: String,
+}
+
+impl Storable for ReleasedGlobalUniqueUserName {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let nanos_since_epoch = self
+            .claimable_by_others_after
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+
+        let mut bytes = nanos_since_epoch.to_be_bytes().to_vec();
+        bytes.extend_from_slice(self.unique_user_name.as_bytes());
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let (nanos_since_epoch, unique_user_name) = bytes.split_at(8);
+        let nanos_since_epoch = u64::from_be_bytes(nanos_since_epoch.try_into().unwrap());
+
+        Self {
+            claimable_by_others_after: UNIX_EPOCH + Duration::from_nanos(nanos_since_epoch),
+            unique_user_name: String::from_utf8(unique_user_name.to_vec()).unwrap(),
+        }
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
 // To store the upgrade arguments and the failed canisters list.
 
 #[derive(Serialize, Deserialize, CandidType, Clone)]
@@ -150,4 +218,557 @@ impl CanisterData {
                 .insert_new_entry(individual_user_profile_id, *root_canister_id);
         });
     }
+
+    /// Records the stats collected from the subnet orchestrators as the snapshot of the current
+    /// day, replacing an earlier snapshot taken the same day. Subnets that did not report keep
+    /// the stats from their last report and are marked as stale.
+    pub fn add_utility_token_supply_stats_received_from_subnet_orchestrators(
+        &mut self,
+        mut stats_by_subnet: BTreeMap<Principal, UtilityTokenSupplyStats>,
+        current_time: SystemTime,
+    ) {
+        let mut stale_subnets = BTreeSet::new();
+        if let Some((_, last_snapshot)) = self.utility_token_supply_snapshots.last_key_value() {
+            last_snapshot.stats_by_subnet.iter().for_each(
+                |(subnet_orchestrator_canister_id, subnet_stats)| {
+                    if !stats_by_subnet.contains_key(subnet_orchestrator_canister_id) {
+                        stats_by_subnet.insert(*subnet_orchestrator_canister_id, *subnet_stats);
+                        stale_subnets.insert(*subnet_orchestrator_canister_id);
+                    }
+                },
+            );
+        }
+
+        stats_by_subnet.retain(|subnet_orchestrator_canister_id, _| {
+            self.all_subnet_orchestrator_canisters_list
+                .contains(subnet_orchestrator_canister_id)
+        });
+        stale_subnets.retain(|subnet_orchestrator_canister_id| {
+            stats_by_subnet.contains_key(subnet_orchestrator_canister_id)
+        });
+
+        let snapshot =
+            UtilityTokenSupplySnapshot::new(current_time, stats_by_subnet, stale_subnets);
+        self.utility_token_supply_snapshots
+            .insert(snapshot.day, snapshot);
+    }
+
+    /// Claims `unique_user_name` for a user and, on a rename, releases `old_unique_user_name`
+    /// with a cooldown during which only the same user can claim it back.
+    pub fn register_global_unique_user_name(
+        &mut self,
+        unique_user_name: String,
+        user_principal_id: Principal,
+        user_canister_id: Principal,
+        subnet_orchestrator_canister_id: Principal,
+        old_unique_user_name: Option<String>,
+        current_time: SystemTime,
+    ) -> Result<(), GlobalUniqueUserNameRegistryError> {
+        let unique_user_name = normalize_unique_user_name(&unique_user_name);
+        let old_unique_user_name = old_unique_user_name
+            .as_deref()
+            .map(normalize_unique_user_name);
+
+        self.prune_released_global_unique_user_names(current_time);
+
+        if let Some(entry) = self.global_unique_user_name_registry.get(&unique_user_name) {
+            if entry.user_principal_id != user_principal_id {
+                match entry.released_claimable_by_others_after {
+                    None => return Err(GlobalUniqueUserNameRegistryError::UsernameAlreadyTaken),
+                    Some(claimable_by_others_after) if current_time < claimable_by_others_after => {
+                        return Err(GlobalUniqueUserNameRegistryError::UsernameInCooldown)
+                    }
+                    Some(_) => {}
+                }
+            } else if entry.released_claimable_by_others_after.is_none() {
+                // * already registered for this user, e.g. a retried call
+                return Ok(());
+            }
+        }
+
+        if let Some(old_unique_user_name) = old_unique_user_name {
+            let old_entry = self
+                .global_unique_user_name_registry
+                .get(&old_unique_user_name)
+                .filter(|entry| {
+                    entry.user_principal_id == user_principal_id
+                        && entry.released_claimable_by_others_after.is_none()
+                })
+                .ok_or(GlobalUniqueUserNameRegistryError::UsernameNotOwnedByUser)?;
+
+            let claimable_by_others_after =
+                current_time + Duration::from_secs(UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS);
+            self.global_unique_user_name_registry.insert(
+                old_unique_user_name.clone(),
+                GlobalUniqueUserNameEntry {
+                    released_claimable_by_others_after: Some(claimable_by_others_after),
+                    ..old_entry
+                },
+            );
+            self.released_global_unique_user_names.insert(
+                ReleasedGlobalUniqueUserName {
+                    claimable_by_others_after,
+                    unique_user_name: old_unique_user_name,
+                },
+                (),
+            );
+        }
+
+        self.global_unique_user_name_registry.insert(
+            unique_user_name,
+            GlobalUniqueUserNameEntry {
+                user_principal_id,
+                user_canister_id,
+                subnet_orchestrator_canister_id,
+                released_claimable_by_others_after: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Undoes `register_global_unique_user_name` when the subnet orchestrator could not apply the
+    /// change locally.
+    pub fn revert_global_unique_user_name_registration(
+        &mut self,
+        unique_user_name: &str,
+        user_principal_id: Principal,
+        old_unique_user_name: Option<String>,
+    ) -> Result<(), GlobalUniqueUserNameRegistryError> {
+        let unique_user_name = normalize_unique_user_name(unique_user_name);
+        let old_unique_user_name = old_unique_user_name
+            .as_deref()
+            .map(normalize_unique_user_name);
+
+        let is_owned_by_user = self
+            .global_unique_user_name_registry
+            .get(&unique_user_name)
+            .is_some_and(|entry| entry.user_principal_id == user_principal_id);
+
+        if !is_owned_by_user {
+            // * a retried revert finds the registration already undone
+            let is_already_reverted = match old_unique_user_name.as_ref() {
+                Some(old_unique_user_name) => self
+                    .global_unique_user_name_registry
+                    .get(old_unique_user_name)
+                    .is_some_and(|entry| {
+                        entry.user_principal_id == user_principal_id
+                            && entry.released_claimable_by_others_after.is_none()
+                    }),
+                None => !self
+                    .global_unique_user_name_registry
+                    .contains_key(&unique_user_name),
+            };
+
+            return if is_already_reverted {
+                Ok(())
+            } else {
+                Err(GlobalUniqueUserNameRegistryError::UsernameNotOwnedByUser)
+            };
+        }
+
+        self.global_unique_user_name_registry
+            .remove(&unique_user_name);
+
+        if let Some(old_unique_user_name) = old_unique_user_name {
+            if let Some(old_entry) = self
+                .global_unique_user_name_registry
+                .get(&old_unique_user_name)
+                .filter(|entry| entry.user_principal_id == user_principal_id)
+            {
+                if let Some(claimable_by_others_after) =
+                    old_entry.released_claimable_by_others_after
+                {
+                    self.released_global_unique_user_names
+                        .remove(&ReleasedGlobalUniqueUserName {
+                            claimable_by_others_after,
+                            unique_user_name: old_unique_user_name.clone(),
+                        });
+                }
+
+                self.global_unique_user_name_registry.insert(
+                    old_unique_user_name,
+                    GlobalUniqueUserNameEntry {
+                        released_claimable_by_others_after: None,
+                        ..old_entry
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes released names whose cooldown has passed and returns how many were removed. Names
+    /// claimed again since their release are kept.
+    pub fn prune_released_global_unique_user_names(&mut self, current_time: SystemTime) -> usize {
+        let mut number_of_pruned_names = 0;
+
+        while let Some((released_global_unique_user_name, ())) =
+            self.released_global_unique_user_names.first_key_value()
+        {
+            if current_time < released_global_unique_user_name.claimable_by_others_after {
+                break;
+            }
+            self.released_global_unique_user_names.pop_first();
+
+            let is_still_released = self
+                .global_unique_user_name_registry
+                .get(&released_global_unique_user_name.unique_user_name)
+                .is_some_and(|entry| {
+                    entry.released_claimable_by_others_after
+                        == Some(released_global_unique_user_name.claimable_by_others_after)
+                });
+            if is_still_released {
+                self.global_unique_user_name_registry
+                    .remove(&released_global_unique_user_name.unique_user_name);
+                number_of_pruned_names += 1;
+            }
+        }
+
+        number_of_pruned_names
+    }
+
+    /// Moves the registry that was kept on the heap before into stable memory, indexing its
+    /// released names on the way. Does nothing once the heap registry is empty.
+    pub fn migrate_global_unique_user_name_registry_to_stable_memory(&mut self) {
+        let legacy_global_unique_user_name_registry =
+            std::mem::take(&mut self.legacy_global_unique_user_name_registry);
+
+        for (unique_user_name, entry) in legacy_global_unique_user_name_registry {
+            if let Some(claimable_by_others_after) = entry.released_claimable_by_others_after {
+                self.released_global_unique_user_names.insert(
+                    ReleasedGlobalUniqueUserName {
+                        claimable_by_others_after,
+                        unique_user_name: unique_user_name.clone(),
+                    },
+                    (),
+                );
+            }
+
+            self.global_unique_user_name_registry
+                .insert(unique_user_name, entry);
+        }
+    }
+
+    /// Resolves a name to the canister of the user currently holding it.
+    pub fn get_user_canister_id_from_global_unique_user_name(
+        &self,
+        unique_user_name: &str,
+    ) -> Option<Principal> {
+        self.global_unique_user_name_registry
+            .get(&normalize_unique_user_name(unique_user_name))
+            .filter(|entry| entry.released_claimable_by_others_after.is_none())
+            .map(|entry| entry.user_canister_id)
+    }
+
+    /// Names in their release cooldown count as taken.
+    pub fn is_global_unique_user_name_taken(
+        &self,
+        unique_user_name: &str,
+        current_time: SystemTime,
+    ) -> bool {
+        self.global_unique_user_name_registry
+            .get(&normalize_unique_user_name(unique_user_name))
+            .is_some_and(|entry| {
+                entry
+                    .released_claimable_by_others_after
+                    .is_none_or(|claimable_by_others_after| {
+                        current_time < claimable_by_others_after
+                    })
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    fn register_for_alice(
+        canister_data: &mut CanisterData,
+        unique_user_name: &str,
+        old_unique_user_name: Option<&str>,
+        current_time: SystemTime,
+    ) -> Result<(), GlobalUniqueUserNameRegistryError> {
+        canister_data.register_global_unique_user_name(
+            unique_user_name.to_string(),
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+            Principal::anonymous(),
+            old_unique_user_name.map(str::to_string),
+            current_time,
+        )
+    }
+
+    fn register_for_bob(
+        canister_data: &mut CanisterData,
+        unique_user_name: &str,
+        current_time: SystemTime,
+    ) -> Result<(), GlobalUniqueUserNameRegistryError> {
+        canister_data.register_global_unique_user_name(
+            unique_user_name.to_string(),
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+            Principal::anonymous(),
+            None,
+            current_time,
+        )
+    }
+
+    #[test]
+    fn test_register_global_unique_user_name() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        assert_eq!(
+            register_for_alice(&mut canister_data, "alice", None, now),
+            Ok(())
+        );
+        // * a retried call succeeds
+        assert_eq!(
+            register_for_alice(&mut canister_data, "alice", None, now),
+            Ok(())
+        );
+        assert_eq!(
+            register_for_bob(&mut canister_data, "alice", now),
+            Err(GlobalUniqueUserNameRegistryError::UsernameAlreadyTaken)
+        );
+        assert!(canister_data.is_global_unique_user_name_taken("alice", now));
+        assert_eq!(
+            canister_data.get_user_canister_id_from_global_unique_user_name("alice"),
+            Some(get_mock_user_alice_canister_id())
+        );
+        assert_eq!(
+            register_for_alice(&mut canister_data, "alice2", Some("bob"), now),
+            Err(GlobalUniqueUserNameRegistryError::UsernameNotOwnedByUser)
+        );
+    }
+
+    #[test]
+    fn test_global_unique_user_names_differing_only_in_case_collide() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        register_for_alice(&mut canister_data, "Alice", None, now).unwrap();
+
+        assert_eq!(
+            register_for_bob(&mut canister_data, "aLiCe", now),
+            Err(GlobalUniqueUserNameRegistryError::UsernameAlreadyTaken)
+        );
+        assert!(canister_data.is_global_unique_user_name_taken("ALICE", now));
+        assert_eq!(
+            canister_data.get_user_canister_id_from_global_unique_user_name("alice"),
+            Some(get_mock_user_alice_canister_id())
+        );
+        assert_eq!(
+            canister_data.revert_global_unique_user_name_registration(
+                "ALICE",
+                get_mock_user_alice_principal_id(),
+                None
+            ),
+            Ok(())
+        );
+        assert!(!canister_data.is_global_unique_user_name_taken("Alice", now));
+    }
+
+    #[test]
+    fn test_released_global_unique_user_name_is_held_during_cooldown() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        let cooldown = Duration::from_secs(UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS);
+
+        register_for_alice(&mut canister_data, "alice", None, now).unwrap();
+        register_for_alice(&mut canister_data, "alice2", Some("alice"), now).unwrap();
+
+        assert!(!canister_data.is_global_unique_user_name_taken("alice", now + cooldown));
+        assert_eq!(
+            register_for_bob(&mut canister_data, "alice", now),
+            Err(GlobalUniqueUserNameRegistryError::UsernameInCooldown)
+        );
+
+        // * the previous owner can take the name back during the cooldown
+        assert_eq!(
+            register_for_alice(&mut canister_data, "alice", Some("alice2"), now),
+            Ok(())
+        );
+        assert_eq!(
+            register_for_bob(&mut canister_data, "alice", now + cooldown),
+            Err(GlobalUniqueUserNameRegistryError::UsernameAlreadyTaken)
+        );
+
+        assert_eq!(
+            register_for_bob(&mut canister_data, "alice2", now + cooldown),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_revert_global_unique_user_name_registration() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+
+        register_for_alice(&mut canister_data, "alice", None, now).unwrap();
+        register_for_alice(&mut canister_data, "alice2", Some("alice"), now).unwrap();
+
+        let alice = get_mock_user_alice_principal_id();
+        assert_eq!(
+            canister_data.revert_global_unique_user_name_registration(
+                "alice2",
+                alice,
+                Some("alice".to_string())
+            ),
+            Ok(())
+        );
+        assert!(canister_data.is_global_unique_user_name_taken("alice", now));
+        assert!(!canister_data.is_global_unique_user_name_taken("alice2", now));
+
+        // * a retried revert succeeds
+        assert_eq!(
+            canister_data.revert_global_unique_user_name_registration(
+                "alice2",
+                alice,
+                Some("alice".to_string())
+            ),
+            Ok(())
+        );
+
+        register_for_bob(&mut canister_data, "bob", now).unwrap();
+        assert_eq!(
+            canister_data.revert_global_unique_user_name_registration("bob", alice, None),
+            Err(GlobalUniqueUserNameRegistryError::UsernameNotOwnedByUser)
+        );
+    }
+
+    #[test]
+    fn test_prune_released_global_unique_user_names() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        let cooldown = Duration::from_secs(UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS);
+
+        register_for_alice(&mut canister_data, "alice", None, now).unwrap();
+        register_for_alice(&mut canister_data, "alice2", Some("alice"), now).unwrap();
+        register_for_alice(&mut canister_data, "alice3", Some("alice2"), now).unwrap();
+        // * reclaimed names are kept
+        register_for_alice(&mut canister_data, "alice", Some("alice3"), now).unwrap();
+
+        assert_eq!(
+            canister_data.prune_released_global_unique_user_names(now),
+            0
+        );
+        assert_eq!(
+            canister_data.prune_released_global_unique_user_names(now + cooldown),
+            2
+        );
+        assert!(canister_data.released_global_unique_user_names.is_empty());
+        assert_eq!(
+            canister_data
+                .global_unique_user_name_registry
+                .iter()
+                .map(|(unique_user_name, _)| unique_user_name)
+                .collect::<Vec<_>>(),
+            vec!["alice".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_migrate_global_unique_user_name_registry_to_stable_memory() {
+        let mut canister_data = CanisterData::default();
+        let now = SystemTime::now();
+        let cooldown = Duration::from_secs(UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS);
+        let entry = GlobalUniqueUserNameEntry {
+            user_principal_id: get_mock_user_alice_principal_id(),
+            user_canister_id: get_mock_user_alice_canister_id(),
+            subnet_orchestrator_canister_id: Principal::anonymous(),
+            released_claimable_by_others_after: None,
+        };
+        canister_data.legacy_global_unique_user_name_registry = BTreeMap::from([
+            (
+                "alice".to_string(),
+                GlobalUniqueUserNameEntry {
+                    released_claimable_by_others_after: Some(now + cooldown),
+                    ..entry.clone()
+                },
+            ),
+            ("alice2".to_string(), entry),
+        ]);
+
+        canister_data.migrate_global_unique_user_name_registry_to_stable_memory();
+
+        assert!(canister_data
+            .legacy_global_unique_user_name_registry
+            .is_empty());
+        assert_eq!(canister_data.global_unique_user_name_registry.len(), 2);
+        assert_eq!(canister_data.released_global_unique_user_names.len(), 1);
+        assert_eq!(
+            canister_data.prune_released_global_unique_user_names(now + cooldown),
+            1
+        );
+        assert!(!canister_data
+            .global_unique_user_name_registry
+            .contains_key(&"alice".to_string()));
+    }
+
+    #[test]
+    fn test_released_global_unique_user_name_keys_sort_by_release_time() {
+        let now = SystemTime::now();
+        let released_first = ReleasedGlobalUniqueUserName {
+            claimable_by_others_after: now,
+            unique_user_name: "zoe".to_string(),
+        };
+        let released_later = ReleasedGlobalUniqueUserName {
+            claimable_by_others_after: now + Duration::from_nanos(1),
+            unique_user_name: "alice".to_string(),
+        };
+
+        assert!(released_first.to_bytes() < released_later.to_bytes());
+        assert_eq!(
+            ReleasedGlobalUniqueUserName::from_bytes(released_first.to_bytes()),
+            released_first
+        );
+    }
+
+    #[test]
+    fn test_add_utility_token_supply_stats_marks_subnets_that_did_not_report_as_stale() {
+        let mut canister_data = CanisterData::default();
+        let first_subnet_orchestrator = get_mock_user_alice_canister_id();
+        let second_subnet_orchestrator = get_mock_user_bob_canister_id();
+        canister_data
+            .all_subnet_orchestrator_canisters_list
+            .extend([first_subnet_orchestrator, second_subnet_orchestrator]);
+        let subnet_stats = UtilityTokenSupplyStats {
+            minted: 1000,
+            circulating: 1000,
+            number_of_individual_canisters: 1,
+            ..Default::default()
+        };
+        let now = SystemTime::now();
+
+        canister_data.add_utility_token_supply_stats_received_from_subnet_orchestrators(
+            BTreeMap::from([
+                (first_subnet_orchestrator, subnet_stats),
+                (second_subnet_orchestrator, subnet_stats),
+            ]),
+            now,
+        );
+        canister_data.add_utility_token_supply_stats_received_from_subnet_orchestrators(
+            BTreeMap::from([(first_subnet_orchestrator, subnet_stats)]),
+            now + Duration::from_secs(24 * 60 * 60),
+        );
+
+        let (_, last_snapshot) = canister_data
+            .utility_token_supply_snapshots
+            .last_key_value()
+            .unwrap();
+        assert_eq!(canister_data.utility_token_supply_snapshots.len(), 2);
+        assert_eq!(last_snapshot.network_stats.minted, 2000);
+        assert_eq!(
+            last_snapshot.stale_subnets,
+            BTreeSet::from([second_subnet_orchestrator])
+        );
+    }
 }
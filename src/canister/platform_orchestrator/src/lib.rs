@@ -10,15 +10,22 @@ use crate::data_model::CanisterUpgradeStatus;
 use data_model::CanisterData;
 use ic_cdk_macros::export_candid;
 use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        creator_token_quota::CreatorTokenQuotaConfig, engagement_reward::EngagementRewardCaps,
+        referral::ReferralConfig,
+    },
     canister_specific::platform_orchestrator::types::args::{
         PlatformOrchestratorInitArgs, UpgradeCanisterArg,
     },
-    canister_specific::platform_orchestrator::types::SubnetUpgradeReport,
+    canister_specific::platform_orchestrator::types::{
+        GlobalUniqueUserNameRegistryError, SubnetUpgradeReport,
+    },
     canister_specific::user_index::types::UpgradeStatus,
     common::types::http::{HttpRequest, HttpResponse},
     common::types::known_principal::KnownPrincipalType,
     common::types::wasm::WasmType,
     types::creator_dao_stats::CreatorDaoTokenStats,
+    types::utility_token_supply_stats::UtilityTokenSupplySnapshot,
 };
 
 mod api;
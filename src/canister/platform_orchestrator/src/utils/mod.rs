@@ -1 +1,2 @@
 pub mod registered_subnet_orchestrator;
+pub mod utility_token_supply_snapshot;
@@ -179,6 +179,21 @@ impl RegisteredSubnetOrchestrator {
         .0
     }
 
+    pub async fn update_creator_token_quota_override_for_individual_canister(
+        &self,
+        individual_canister_id: Principal,
+        quota_override: Option<u64>,
+    ) -> Result<(), String> {
+        ic_cdk::call::<_, (Result<(), String>,)>(
+            self.canister_id,
+            "update_creator_token_quota_override_for_individual_canister",
+            (individual_canister_id, quota_override),
+        )
+        .await
+        .map_err(|e| e.1)?
+        .0
+    }
+
     pub async fn delete_all_sns_creator_token_in_the_network(&self) -> Result<(), String> {
         ic_cdk::call::<_, ()>(
             self.canister_id,
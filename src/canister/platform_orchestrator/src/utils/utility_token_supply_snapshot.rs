@@ -0,0 +1,26 @@
+use std::{cell::RefCell, time::Duration};
+
+use ic_cdk_timers::TimerId;
+
+use crate::api::stats::collect_utility_token_supply_stats_in_the_network::collect_utility_token_supply_stats_in_the_network_impl;
+
+const UTILITY_TOKEN_SUPPLY_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+thread_local! {
+    static UTILITY_TOKEN_SUPPLY_SNAPSHOT_TIMER: RefCell<Option<TimerId>> = const { RefCell::new(None) };
+}
+
+/// Takes the daily utility token supply snapshot. Timers do not survive upgrades, so this is
+/// called from both `init` and `post_upgrade`.
+pub fn start_utility_token_supply_snapshot_timer() {
+    if let Some(timer_id) = UTILITY_TOKEN_SUPPLY_SNAPSHOT_TIMER.with_borrow_mut(Option::take) {
+        ic_cdk_timers::clear_timer(timer_id);
+    }
+
+    let timer_id =
+        ic_cdk_timers::set_timer_interval(UTILITY_TOKEN_SUPPLY_SNAPSHOT_INTERVAL, || {
+            ic_cdk::spawn(collect_utility_token_supply_stats_in_the_network_impl())
+        });
+
+    UTILITY_TOKEN_SUPPLY_SNAPSHOT_TIMER.with_borrow_mut(|timer| *timer = Some(timer_id));
+}
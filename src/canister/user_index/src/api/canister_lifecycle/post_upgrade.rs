@@ -8,12 +8,19 @@ use shared_utils::{
     common::utils::system_time,
 };
 
-use crate::{data_model::memory, CANISTER_DATA};
+use crate::{
+    data_model::memory,
+    util::global_unique_user_name_registry::start_pending_global_unique_user_name_revert_retry_timer_if_needed,
+    CANISTER_DATA,
+};
 
 #[post_upgrade]
 fn post_upgrade() {
     restore_data_from_stable_memory();
     update_version_from_args();
+    CANISTER_DATA
+        .with_borrow_mut(|canister_data| canister_data.normalize_stored_unique_user_names());
+    start_pending_global_unique_user_name_revert_retry_timer_if_needed();
 }
 
 fn update_version_from_args() {
@@ -48,6 +48,7 @@ pub mod request_cycles;
 pub mod reset_user_canister_ml_feed_cache;
 pub mod start_upgrades_for_individual_canisters;
 pub mod update_canisters_access_time;
+pub mod update_creator_token_quota_override_for_individual_canister;
 pub mod update_user_canister_restart_timers;
 pub mod upgrade_all_creator_dao_governance_canisters_in_the_network;
 
@@ -0,0 +1,17 @@
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::common::utils::permissions::is_caller_controller;
+
+use crate::util::types::individual_user_canister::IndividualUserCanister;
+
+/// Called by the platform orchestrator, like the config update
+#[update(guard = "is_caller_controller")]
+pub async fn update_creator_token_quota_override_for_individual_canister(
+    individual_canister_id: Principal,
+    quota_override: Option<u64>,
+) -> Result<(), String> {
+    let individual_user_canister = IndividualUserCanister::new(individual_canister_id)?;
+    individual_user_canister
+        .update_creator_token_quota_override(quota_override)
+        .await
+}
@@ -0,0 +1,3 @@
+pub mod release_engagement_rewards;
+pub mod reserve_engagement_rewards;
+pub mod update_engagement_reward_caps;
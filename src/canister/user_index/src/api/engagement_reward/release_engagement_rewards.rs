@@ -0,0 +1,20 @@
+use ic_cdk::caller;
+use ic_cdk_macros::update;
+use shared_utils::canister_specific::individual_user_template::types::engagement_reward::EngagementRewardClaim;
+
+use crate::{util::types::individual_user_canister::IndividualUserCanister, CANISTER_DATA};
+
+/// Called by an individual canister with the part of its reserved rewards it did not mint, so
+/// that other users can claim it.
+#[update]
+fn release_engagement_rewards(
+    engagement_reward_claims: Vec<EngagementRewardClaim>,
+) -> Result<(), String> {
+    IndividualUserCanister::new(caller())?;
+
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.release_engagement_rewards(engagement_reward_claims)
+    });
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use ic_cdk::caller;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::engagement_reward::EngagementRewardClaim,
+    common::utils::system_time::get_current_system_time_from_ic,
+};
+
+use crate::{util::types::individual_user_canister::IndividualUserCanister, CANISTER_DATA};
+
+/// Called by an individual canister before it mints engagement rewards. Returns the part of
+/// each reward that fits under this subnet's daily caps, which the canister may then mint.
+#[update]
+fn reserve_engagement_rewards(
+    engagement_reward_claims: Vec<EngagementRewardClaim>,
+) -> Result<Vec<EngagementRewardClaim>, String> {
+    IndividualUserCanister::new(caller())?;
+
+    Ok(CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data
+            .reserve_engagement_rewards(engagement_reward_claims, get_current_system_time_from_ic())
+    }))
+}
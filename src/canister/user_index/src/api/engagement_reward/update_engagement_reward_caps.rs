@@ -0,0 +1,16 @@
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::engagement_reward::EngagementRewardCaps,
+    common::utils::permissions::is_caller_controller,
+};
+
+use crate::CANISTER_DATA;
+
+/// Sets this subnet's share of the network caps. Rewards already granted keep counting against
+/// the new caps.
+#[update(guard = "is_caller_controller")]
+fn update_engagement_reward_caps(engagement_reward_caps: EngagementRewardCaps) {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.engagement_reward_caps = Some(engagement_reward_caps)
+    });
+}
@@ -1,6 +1,7 @@
 pub mod canister_lifecycle;
 pub mod canister_management;
 pub mod cycle_management;
+pub mod engagement_reward;
 pub mod http;
 pub mod monitoring;
 pub mod stats;
@@ -0,0 +1,55 @@
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::cdao::CreatorDaoTokenStatus,
+    common::utils::{permissions::is_caller_controller, task::run_task_concurrently},
+};
+
+use crate::CANISTER_DATA;
+
+#[update(guard = "is_caller_controller")]
+pub async fn collect_creator_dao_token_statuses_in_the_network(
+) -> Result<Vec<CreatorDaoTokenStatus>, String> {
+    let individual_user_canisters: Vec<Principal> = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .values()
+            .copied()
+            .collect()
+    });
+
+    let mut subnet_creator_dao_token_statuses = vec![];
+
+    let creator_dao_token_statuses =
+        individual_user_canisters
+            .iter()
+            .map(|individual_user_canister_canister_id| async {
+                ic_cdk::call::<_, (Result<Vec<CreatorDaoTokenStatus>, String>,)>(
+                    *individual_user_canister_canister_id,
+                    "send_creator_dao_token_statuses_to_subnet_orchestrator",
+                    (),
+                )
+                .await
+                .map_err(|e| e.1)
+                .and_then(|val| val.0)
+            });
+
+    let result_callback =
+        |creator_dao_token_statuses_result: Result<Vec<CreatorDaoTokenStatus>, String>| {
+            match creator_dao_token_statuses_result {
+                Ok(creator_dao_token_statuses) => {
+                    subnet_creator_dao_token_statuses.extend(creator_dao_token_statuses);
+                }
+                Err(e) => {
+                    ic_cdk::println!(
+                        "Error retrieving creator dao token statuses from individual canister. {}",
+                        e
+                    );
+                }
+            }
+        };
+
+    run_task_concurrently(creator_dao_token_statuses, 10, result_callback, || false).await;
+
+    Ok(subnet_creator_dao_token_statuses)
+}
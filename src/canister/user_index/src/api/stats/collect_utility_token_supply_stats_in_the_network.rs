@@ -0,0 +1,57 @@
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    common::utils::{permissions::is_caller_controller, task::run_task_concurrently},
+    types::utility_token_supply_stats::UtilityTokenSupplyStats,
+};
+
+use crate::CANISTER_DATA;
+
+/// Sums the utility token totals of every individual canister in this subnet. Canisters that
+/// fail to respond are left out of the sum.
+#[update(guard = "is_caller_controller")]
+pub async fn collect_utility_token_supply_stats_in_the_network(
+) -> Result<UtilityTokenSupplyStats, String> {
+    let individual_user_canisters: Vec<Principal> = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .values()
+            .copied()
+            .collect()
+    });
+
+    let mut subnet_utility_token_supply_stats = UtilityTokenSupplyStats::default();
+
+    let utility_token_supply_stats =
+        individual_user_canisters
+            .iter()
+            .map(|individual_user_canister_canister_id| async {
+                ic_cdk::call::<_, (Result<UtilityTokenSupplyStats, String>,)>(
+                    *individual_user_canister_canister_id,
+                    "send_utility_token_supply_stats_to_subnet_orchestrator",
+                    (),
+                )
+                .await
+                .map_err(|e| e.1)
+                .and_then(|val| val.0)
+            });
+
+    let result_callback =
+        |utility_token_supply_stats_result: Result<UtilityTokenSupplyStats, String>| {
+            match utility_token_supply_stats_result {
+                Ok(utility_token_supply_stats) => {
+                    subnet_utility_token_supply_stats.merge(&utility_token_supply_stats);
+                }
+                Err(e) => {
+                    ic_cdk::println!(
+                        "Error retrieving utility token supply stats from individual canister. {}",
+                        e
+                    );
+                }
+            }
+        };
+
+    run_task_concurrently(utility_token_supply_stats, 10, result_callback, || false).await;
+
+    Ok(subnet_utility_token_supply_stats)
+}
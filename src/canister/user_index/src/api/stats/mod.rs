@@ -1,2 +1,4 @@
 pub mod collect_creator_dao_stats_in_the_network;
+pub mod collect_creator_dao_token_statuses_in_the_network;
+pub mod collect_utility_token_supply_stats_in_the_network;
 pub mod receive_creator_dao_stats_from_individual_canister;
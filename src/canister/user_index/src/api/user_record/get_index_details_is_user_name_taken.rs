@@ -1,13 +1,27 @@
 use ic_cdk_macros::query;
+use shared_utils::common::utils::{
+    system_time::get_current_system_time_from_ic, validation::normalize_unique_user_name,
+};
 
 use crate::CANISTER_DATA;
 
+/// Names in their release cooldown are reported as taken since only their previous owner can claim them.
 #[query]
 fn get_index_details_is_user_name_taken(user_name: String) -> bool {
+    let current_time = get_current_system_time_from_ic();
+    let user_name = normalize_unique_user_name(&user_name);
+
     CANISTER_DATA.with(|canister_data_ref_cell| {
-        canister_data_ref_cell
-            .borrow()
+        let canister_data = canister_data_ref_cell.borrow();
+
+        canister_data
             .unique_user_name_to_user_principal_id_map
             .contains_key(&user_name)
+            || canister_data
+                .released_unique_user_names
+                .get(&user_name)
+                .is_some_and(|released_unique_user_name| {
+                    current_time < released_unique_user_name.claimable_by_others_after
+                })
     })
 }
@@ -1,4 +1,8 @@
 use crate::{
+    api::user_record::{
+        update_creator_token_quota_config_for_individual_canisters::push_stored_creator_token_quota_config_to_individual_canister,
+        update_referral_config_for_individual_canisters::push_stored_referral_config_to_individual_canister,
+    },
     util::canister_management::{
         check_and_request_cycles_from_platform_orchestrator, create_empty_user_canister,
         install_canister_wasm, provision_number_of_empty_canisters, recharge_canister,
@@ -129,6 +133,12 @@ async fn new_user_signup(user_id: Principal) -> Result<Principal, String> {
             call::call(canister_id, "update_profile_owner", (user_id,))
                 .await
                 .map_err(|e| e.1)?;
+            push_stored_referral_config_to_individual_canister(canister_id)
+                .await
+                .map_err(|e| e.1)?;
+            push_stored_creator_token_quota_config_to_individual_canister(canister_id)
+                .await
+                .map_err(|e| e.1)?;
             CANISTER_DATA.with_borrow_mut(|canister_data| {
                 canister_data
                     .user_principal_id_to_canister_id_map
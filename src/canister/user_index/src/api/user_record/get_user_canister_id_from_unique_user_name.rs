@@ -1,5 +1,6 @@
 use candid::Principal;
 use ic_cdk_macros::query;
+use shared_utils::common::utils::validation::normalize_unique_user_name;
 
 use crate::{data_model::CanisterData, CANISTER_DATA};
 
@@ -16,7 +17,7 @@ fn get_user_canister_id_from_unique_user_name_impl(
 ) -> Option<Principal> {
     let profile_principal_id = canister_data
         .unique_user_name_to_user_principal_id_map
-        .get(&user_name)
+        .get(&normalize_unique_user_name(&user_name))
         .cloned()?;
 
     canister_data
@@ -0,0 +1,75 @@
+use candid::Principal;
+use ic_cdk::{
+    api::management_canister::main::{canister_info, CanisterInfoRequest},
+    caller, id,
+};
+use ic_cdk_macros::update;
+use shared_utils::common::types::known_principal::KnownPrincipalType;
+
+use crate::{util::types::individual_user_canister::IndividualUserCanister, CANISTER_DATA};
+
+/// Checks that `user_canister` is the canister of `user_principal` in this subnet or in any other
+/// subnet orchestrator registered with the platform orchestrator. Only callable by the individual
+/// canisters of this subnet, as the check may take a canister_info and two inter-canister calls.
+#[update]
+async fn is_user_canister_in_the_network(
+    user_principal: Principal,
+    user_canister: Principal,
+) -> Result<bool, String> {
+    IndividualUserCanister::new(caller())?;
+
+    let user_canister_in_this_subnet = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .get(&user_principal)
+            .copied()
+    });
+    if user_canister_in_this_subnet == Some(user_canister) {
+        return Ok(true);
+    }
+
+    let (canister_info_response,) = canister_info(CanisterInfoRequest {
+        canister_id: user_canister,
+        num_requested_changes: None,
+    })
+    .await
+    .map_err(|e| e.1)?;
+
+    let Some(subnet_orchestrator) = canister_info_response.controllers.first().copied() else {
+        return Ok(false);
+    };
+    if subnet_orchestrator == id() {
+        return Ok(false);
+    }
+
+    let platform_orchestrator_canister_id = CANISTER_DATA
+        .with_borrow(|canister_data| {
+            canister_data
+                .configuration
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+                .copied()
+        })
+        .ok_or("Platform orchestrator canister id not found")?;
+
+    let (subnet_orchestrators,): (Vec<Principal>,) = ic_cdk::call(
+        platform_orchestrator_canister_id,
+        "get_all_subnet_orchestrators",
+        (),
+    )
+    .await
+    .map_err(|e| e.1)?;
+    if !subnet_orchestrators.contains(&subnet_orchestrator) {
+        return Ok(false);
+    }
+
+    let (user_canister_in_subnet,): (Option<Principal>,) = ic_cdk::call(
+        subnet_orchestrator,
+        "get_user_canister_id_from_user_principal_id",
+        (user_principal,),
+    )
+    .await
+    .map_err(|e| e.1)?;
+
+    Ok(user_canister_in_subnet == Some(user_canister))
+}
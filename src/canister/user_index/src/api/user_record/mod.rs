@@ -4,6 +4,11 @@ pub mod get_user_canister_id_from_unique_user_name;
 pub mod get_user_canister_id_from_user_principal_id;
 pub mod get_user_canister_list;
 pub mod get_user_index_canister_count;
+pub mod is_user_canister_in_the_network;
+pub mod push_unique_user_names_to_platform_orchestrator;
+pub mod update_creator_token_quota_config_for_individual_canisters;
+pub mod update_index_with_changed_unique_user_name_for_user_principal_id;
 pub mod update_index_with_unique_user_name_corresponding_to_user_principal_id;
 pub mod update_profile_owner_for_individual_canisters;
+pub mod update_referral_config_for_individual_canisters;
 pub mod issue_rewards_for_referral;
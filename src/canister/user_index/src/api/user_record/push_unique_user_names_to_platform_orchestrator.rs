@@ -0,0 +1,60 @@
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::common::{
+    types::known_principal::KnownPrincipalType, utils::permissions::is_caller_controller,
+};
+
+use crate::CANISTER_DATA;
+
+const UNIQUE_USER_NAMES_PUSHED_PER_CALL: usize = 1000;
+
+/// Backfills the platform orchestrator's global registry with the names assigned on this subnet.
+/// Returns the names that are already held by a user on another subnet.
+#[update(guard = "is_caller_controller")]
+async fn push_unique_user_names_to_platform_orchestrator() -> Result<Vec<String>, String> {
+    let platform_orchestrator_canister_id = CANISTER_DATA
+        .with_borrow(|canister_data| {
+            canister_data
+                .configuration
+                .known_principal_ids
+                .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+                .copied()
+        })
+        .ok_or("Platform Orchestrator Canister Id not found")?;
+
+    let unique_user_names: Vec<(String, Principal, Principal)> =
+        CANISTER_DATA.with_borrow(|canister_data| {
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .iter()
+                .filter_map(|(unique_user_name, user_principal_id)| {
+                    canister_data
+                        .user_principal_id_to_canister_id_map
+                        .get(user_principal_id)
+                        .map(|user_canister_id| {
+                            (
+                                unique_user_name.clone(),
+                                *user_principal_id,
+                                *user_canister_id,
+                            )
+                        })
+                })
+                .collect()
+        });
+
+    let mut conflicting_unique_user_names = vec![];
+
+    for unique_user_names_chunk in unique_user_names.chunks(UNIQUE_USER_NAMES_PUSHED_PER_CALL) {
+        let (response,): (Result<Vec<String>, String>,) = ic_cdk::call(
+            platform_orchestrator_canister_id,
+            "receive_unique_user_names_from_subnet_orchestrator",
+            (unique_user_names_chunk.to_vec(),),
+        )
+        .await
+        .map_err(|e| format!("failed to push unique user names {:?}", e))?;
+
+        conflicting_unique_user_names.extend(response?);
+    }
+
+    Ok(conflicting_unique_user_names)
+}
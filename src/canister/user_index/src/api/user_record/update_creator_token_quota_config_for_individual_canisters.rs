@@ -0,0 +1,79 @@
+use candid::Principal;
+use futures::StreamExt;
+use ic_cdk::api::call::CallResult;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::individual_user_template::types::creator_token_quota::CreatorTokenQuotaConfig,
+    common::utils::permissions::is_caller_controller,
+};
+
+use crate::CANISTER_DATA;
+
+/// Stores the config so that canisters given to new users get it too, and pushes it to every
+/// individual canister in this subnet. Canisters that could not be updated are reported back.
+#[update(guard = "is_caller_controller")]
+async fn update_creator_token_quota_config_for_individual_canisters(
+    creator_token_quota_config: CreatorTokenQuotaConfig,
+) -> Result<String, String> {
+    CANISTER_DATA.with_borrow_mut(|canister_data| {
+        canister_data.creator_token_quota_config = Some(creator_token_quota_config)
+    });
+
+    let canisters = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .user_principal_id_to_canister_id_map
+            .values()
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    let futures = canisters.iter().map(|canister_id| async {
+        let result: CallResult<()> = ic_cdk::call(
+            *canister_id,
+            "update_creator_token_quota_config",
+            (creator_token_quota_config,),
+        )
+        .await;
+        result.map_err(|e| {
+            ic_cdk::println!(
+                "Failed to update the creator token quota config of {}. {}",
+                canister_id,
+                e.1
+            );
+        })
+    });
+
+    let stream = futures::stream::iter(futures).boxed().buffer_unordered(25);
+
+    let results = stream.collect::<Vec<Result<(), ()>>>().await;
+    let number_of_failed_canisters = results.iter().filter(|result| result.is_err()).count();
+
+    if number_of_failed_canisters > 0 {
+        return Err(format!(
+            "failed to update the creator token quota config of {} out of {} individual canisters",
+            number_of_failed_canisters,
+            canisters.len()
+        ));
+    }
+
+    Ok("Success".into())
+}
+
+/// Gives a canister that is handed out to a new user the creator token quota config stored for
+/// the network, if one was set.
+pub async fn push_stored_creator_token_quota_config_to_individual_canister(
+    canister_id: Principal,
+) -> CallResult<()> {
+    let Some(creator_token_quota_config) =
+        CANISTER_DATA.with_borrow(|canister_data| canister_data.creator_token_quota_config)
+    else {
+        return Ok(());
+    };
+
+    ic_cdk::call(
+        canister_id,
+        "update_creator_token_quota_config",
+        (creator_token_quota_config,),
+    )
+    .await
+}
@@ -0,0 +1,483 @@
+use std::time::{Duration, SystemTime};
+
+use candid::Principal;
+use ic_cdk_macros::update;
+use shared_utils::{
+    canister_specific::{
+        platform_orchestrator::types::GlobalUniqueUserNameRegistryError,
+        user_index::types::ReleasedUniqueUserName,
+    },
+    common::utils::{
+        system_time::get_current_system_time_from_ic,
+        validation::{normalize_unique_user_name, validate_unique_user_name},
+    },
+    constant::UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS,
+    types::canister_specific::user_index::error_types::ChangeUniqueUsernameError,
+};
+
+use crate::{
+    data_model::CanisterData,
+    util::global_unique_user_name_registry::{
+        register_unique_user_name_globally, revert_unique_user_name_registration_globally,
+        GlobalUniqueUserNameRegistryCallError,
+    },
+    CANISTER_DATA,
+};
+
+/// Moves a user from `old_unique_user_name` to `new_unique_user_name`. The platform orchestrator
+/// registry is updated first, then the local index swaps both names in one step. The old name is
+/// released with a cooldown during which only its previous owner can claim it back.
+#[update]
+async fn update_index_with_changed_unique_user_name_for_user_principal_id(
+    old_unique_user_name: String,
+    new_unique_user_name: String,
+    user_principal_id: Principal,
+) -> Result<(), ChangeUniqueUsernameError> {
+    let request_makers_canister_id = ic_cdk::caller();
+    let old_unique_user_name = normalize_unique_user_name(&old_unique_user_name);
+    let new_unique_user_name = normalize_unique_user_name(&new_unique_user_name);
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        validate_unique_user_name_change(
+            &old_unique_user_name,
+            &new_unique_user_name,
+            user_principal_id,
+            request_makers_canister_id,
+            get_current_system_time_from_ic(),
+            &canister_data_ref_cell.borrow(),
+        )
+    })?;
+
+    register_unique_user_name_globally(
+        &new_unique_user_name,
+        user_principal_id,
+        request_makers_canister_id,
+        Some(old_unique_user_name.clone()),
+    )
+    .await
+    .map_err(|e| match e {
+        GlobalUniqueUserNameRegistryCallError::Unavailable => {
+            ChangeUniqueUsernameError::GlobalUsernameRegistryUnavailable
+        }
+        GlobalUniqueUserNameRegistryCallError::Rejected(
+            GlobalUniqueUserNameRegistryError::UsernameInCooldown,
+        ) => ChangeUniqueUsernameError::UsernameInCooldown,
+        GlobalUniqueUserNameRegistryCallError::Rejected(
+            GlobalUniqueUserNameRegistryError::UsernameNotOwnedByUser,
+        ) => ChangeUniqueUsernameError::UsernameNotOwnedByUser,
+        GlobalUniqueUserNameRegistryCallError::Rejected(
+            GlobalUniqueUserNameRegistryError::UsernameAlreadyTaken,
+        ) => ChangeUniqueUsernameError::UsernameAlreadyTaken,
+        GlobalUniqueUserNameRegistryCallError::Rejected(e) => {
+            ChangeUniqueUsernameError::GlobalUsernameRegistryRejected(e)
+        }
+    })?;
+
+    // * the index may have changed while waiting on the platform orchestrator
+    let result = CANISTER_DATA.with(|canister_data_ref_cell| {
+        update_index_with_changed_unique_user_name_for_user_principal_id_impl(
+            old_unique_user_name.clone(),
+            new_unique_user_name.clone(),
+            user_principal_id,
+            request_makers_canister_id,
+            get_current_system_time_from_ic(),
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    });
+
+    if result.is_err() {
+        revert_unique_user_name_registration_globally(
+            &new_unique_user_name,
+            user_principal_id,
+            Some(old_unique_user_name),
+        )
+        .await
+        .map_err(map_revert_error)?;
+    }
+
+    result
+}
+
+/// Undoes a change made by `update_index_with_changed_unique_user_name_for_user_principal_id`.
+/// Called by the individual canister when it could not apply the change on its side.
+#[update]
+async fn revert_unique_user_name_change_for_user_principal_id(
+    old_unique_user_name: String,
+    new_unique_user_name: String,
+    user_principal_id: Principal,
+) -> Result<(), ChangeUniqueUsernameError> {
+    let request_makers_canister_id = ic_cdk::caller();
+    let old_unique_user_name = normalize_unique_user_name(&old_unique_user_name);
+    let new_unique_user_name = normalize_unique_user_name(&new_unique_user_name);
+
+    CANISTER_DATA.with(|canister_data_ref_cell| {
+        revert_unique_user_name_change_for_user_principal_id_impl(
+            old_unique_user_name.clone(),
+            new_unique_user_name.clone(),
+            user_principal_id,
+            request_makers_canister_id,
+            &mut canister_data_ref_cell.borrow_mut(),
+        )
+    })?;
+
+    revert_unique_user_name_registration_globally(
+        &new_unique_user_name,
+        user_principal_id,
+        Some(old_unique_user_name),
+    )
+    .await
+    .map_err(map_revert_error)
+}
+
+fn map_revert_error(error: GlobalUniqueUserNameRegistryCallError) -> ChangeUniqueUsernameError {
+    match error {
+        GlobalUniqueUserNameRegistryCallError::Unavailable => {
+            ChangeUniqueUsernameError::GlobalUsernameRegistryRevertPending
+        }
+        GlobalUniqueUserNameRegistryCallError::Rejected(e) => {
+            ChangeUniqueUsernameError::GlobalUsernameRegistryRejected(e)
+        }
+    }
+}
+
+fn validate_request_maker(
+    user_principal_id: Principal,
+    request_makers_canister_id: Principal,
+    canister_data: &CanisterData,
+) -> Result<(), ChangeUniqueUsernameError> {
+    match canister_data
+        .user_principal_id_to_canister_id_map
+        .get(&user_principal_id)
+    {
+        None => Err(ChangeUniqueUsernameError::UserCanisterEntryDoesNotExist),
+        Some(canister_id) if *canister_id != request_makers_canister_id => {
+            Err(ChangeUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId)
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+fn validate_unique_user_name_change(
+    old_unique_user_name: &str,
+    new_unique_user_name: &str,
+    user_principal_id: Principal,
+    request_makers_canister_id: Principal,
+    current_time: SystemTime,
+    canister_data: &CanisterData,
+) -> Result<(), ChangeUniqueUsernameError> {
+    validate_unique_user_name(new_unique_user_name)
+        .map_err(ChangeUniqueUsernameError::InvalidUsername)?;
+
+    validate_request_maker(user_principal_id, request_makers_canister_id, canister_data)?;
+
+    if canister_data
+        .unique_user_name_to_user_principal_id_map
+        .get(old_unique_user_name)
+        != Some(&user_principal_id)
+    {
+        return Err(ChangeUniqueUsernameError::UsernameNotOwnedByUser);
+    }
+
+    if old_unique_user_name == new_unique_user_name {
+        return Err(ChangeUniqueUsernameError::UsernameUnchanged);
+    }
+
+    if canister_data
+        .unique_user_name_to_user_principal_id_map
+        .contains_key(new_unique_user_name)
+    {
+        return Err(ChangeUniqueUsernameError::UsernameAlreadyTaken);
+    }
+
+    if !canister_data.is_unique_user_name_claimable_by(
+        new_unique_user_name,
+        user_principal_id,
+        current_time,
+    ) {
+        return Err(ChangeUniqueUsernameError::UsernameInCooldown);
+    }
+
+    Ok(())
+}
+
+fn update_index_with_changed_unique_user_name_for_user_principal_id_impl(
+    old_unique_user_name: String,
+    new_unique_user_name: String,
+    user_principal_id: Principal,
+    request_makers_canister_id: Principal,
+    current_time: SystemTime,
+    canister_data: &mut CanisterData,
+) -> Result<(), ChangeUniqueUsernameError> {
+    validate_unique_user_name_change(
+        &old_unique_user_name,
+        &new_unique_user_name,
+        user_principal_id,
+        request_makers_canister_id,
+        current_time,
+        canister_data,
+    )?;
+
+    // * names whose cooldown is over no longer need to be tracked
+    canister_data
+        .released_unique_user_names
+        .retain(|_, released_unique_user_name| {
+            current_time < released_unique_user_name.claimable_by_others_after
+        });
+
+    canister_data
+        .unique_user_name_to_user_principal_id_map
+        .remove(&old_unique_user_name);
+    canister_data
+        .released_unique_user_names
+        .remove(&new_unique_user_name);
+    canister_data
+        .unique_user_name_to_user_principal_id_map
+        .insert(new_unique_user_name, user_principal_id);
+    canister_data.released_unique_user_names.insert(
+        old_unique_user_name,
+        ReleasedUniqueUserName {
+            released_by_user_principal_id: user_principal_id,
+            claimable_by_others_after: current_time
+                + Duration::from_secs(UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS),
+        },
+    );
+
+    Ok(())
+}
+
+fn revert_unique_user_name_change_for_user_principal_id_impl(
+    old_unique_user_name: String,
+    new_unique_user_name: String,
+    user_principal_id: Principal,
+    request_makers_canister_id: Principal,
+    canister_data: &mut CanisterData,
+) -> Result<(), ChangeUniqueUsernameError> {
+    validate_request_maker(user_principal_id, request_makers_canister_id, canister_data)?;
+
+    let new_unique_user_name_is_owned_by_user = canister_data
+        .unique_user_name_to_user_principal_id_map
+        .get(&new_unique_user_name)
+        == Some(&user_principal_id);
+    let old_unique_user_name_is_owned_by_user = canister_data
+        .unique_user_name_to_user_principal_id_map
+        .get(&old_unique_user_name)
+        == Some(&user_principal_id);
+
+    // * a retried revert finds the change already undone
+    if old_unique_user_name_is_owned_by_user && !new_unique_user_name_is_owned_by_user {
+        return Ok(());
+    }
+
+    let old_unique_user_name_is_released_by_user = canister_data
+        .released_unique_user_names
+        .get(&old_unique_user_name)
+        .is_some_and(|released_unique_user_name| {
+            released_unique_user_name.released_by_user_principal_id == user_principal_id
+        });
+
+    if !new_unique_user_name_is_owned_by_user || !old_unique_user_name_is_released_by_user {
+        return Err(ChangeUniqueUsernameError::UsernameNotOwnedByUser);
+    }
+
+    // * the new name was never in use, so it is freed without a cooldown
+    canister_data
+        .unique_user_name_to_user_principal_id_map
+        .remove(&new_unique_user_name);
+    canister_data
+        .released_unique_user_names
+        .remove(&old_unique_user_name);
+    canister_data
+        .unique_user_name_to_user_principal_id_map
+        .insert(old_unique_user_name, user_principal_id);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use shared_utils::types::canister_specific::user_index::error_types::UsernameValidationError;
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_canister_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    fn setup_canister_data() -> CanisterData {
+        let mut canister_data = CanisterData::default();
+
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+        );
+        canister_data.user_principal_id_to_canister_id_map.insert(
+            get_mock_user_bob_principal_id(),
+            get_mock_user_bob_canister_id(),
+        );
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("cool_alice".to_string(), get_mock_user_alice_principal_id());
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .insert("cool_bob".to_string(), get_mock_user_bob_principal_id());
+
+        canister_data
+    }
+
+    fn change_alice(
+        old_unique_user_name: &str,
+        new_unique_user_name: &str,
+        current_time: SystemTime,
+        canister_data: &mut CanisterData,
+    ) -> Result<(), ChangeUniqueUsernameError> {
+        update_index_with_changed_unique_user_name_for_user_principal_id_impl(
+            old_unique_user_name.to_string(),
+            new_unique_user_name.to_string(),
+            get_mock_user_alice_principal_id(),
+            get_mock_user_alice_canister_id(),
+            current_time,
+            canister_data,
+        )
+    }
+
+    #[test]
+    fn test_update_index_with_changed_unique_user_name_for_user_principal_id_impl() {
+        let mut canister_data = setup_canister_data();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+
+        assert_eq!(
+            change_alice("cool_alice", "a!", now, &mut canister_data),
+            Err(ChangeUniqueUsernameError::InvalidUsername(
+                UsernameValidationError::TooShort
+            ))
+        );
+        assert_eq!(
+            update_index_with_changed_unique_user_name_for_user_principal_id_impl(
+                "cool_alice".to_string(),
+                "cooler_alice".to_string(),
+                get_mock_user_alice_principal_id(),
+                get_mock_user_bob_canister_id(),
+                now,
+                &mut canister_data,
+            ),
+            Err(ChangeUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId)
+        );
+        assert_eq!(
+            change_alice("cool_bob", "cooler_alice", now, &mut canister_data),
+            Err(ChangeUniqueUsernameError::UsernameNotOwnedByUser)
+        );
+        assert_eq!(
+            change_alice("cool_alice", "cool_alice", now, &mut canister_data),
+            Err(ChangeUniqueUsernameError::UsernameUnchanged)
+        );
+        assert_eq!(
+            change_alice("cool_alice", "cool_bob", now, &mut canister_data),
+            Err(ChangeUniqueUsernameError::UsernameAlreadyTaken)
+        );
+
+        assert_eq!(
+            change_alice("cool_alice", "cooler_alice", now, &mut canister_data),
+            Ok(())
+        );
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .get("cooler_alice"),
+            Some(&get_mock_user_alice_principal_id())
+        );
+        assert!(!canister_data
+            .unique_user_name_to_user_principal_id_map
+            .contains_key("cool_alice"));
+
+        // * bob cannot claim the released name during the cooldown
+        let bob_claims_old_name = |current_time: SystemTime, canister_data: &mut CanisterData| {
+            update_index_with_changed_unique_user_name_for_user_principal_id_impl(
+                "cool_bob".to_string(),
+                "cool_alice".to_string(),
+                get_mock_user_bob_principal_id(),
+                get_mock_user_bob_canister_id(),
+                current_time,
+                canister_data,
+            )
+        };
+        assert_eq!(
+            bob_claims_old_name(now, &mut canister_data),
+            Err(ChangeUniqueUsernameError::UsernameInCooldown)
+        );
+
+        // * alice can take it back during the cooldown
+        assert_eq!(
+            change_alice("cooler_alice", "cool_alice", now, &mut canister_data),
+            Ok(())
+        );
+        assert_eq!(
+            change_alice("cool_alice", "cooler_alice", now, &mut canister_data),
+            Ok(())
+        );
+
+        let after_cooldown =
+            now + Duration::from_secs(UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS);
+        assert_eq!(
+            bob_claims_old_name(after_cooldown, &mut canister_data),
+            Ok(())
+        );
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .get("cool_alice"),
+            Some(&get_mock_user_bob_principal_id())
+        );
+        assert!(canister_data
+            .released_unique_user_names
+            .contains_key("cool_bob"));
+        assert!(!canister_data
+            .released_unique_user_names
+            .contains_key("cool_alice"));
+    }
+
+    #[test]
+    fn test_revert_unique_user_name_change_for_user_principal_id_impl() {
+        let mut canister_data = setup_canister_data();
+        let now = SystemTime::UNIX_EPOCH;
+
+        let revert_alice = |canister_data: &mut CanisterData| {
+            revert_unique_user_name_change_for_user_principal_id_impl(
+                "cool_alice".to_string(),
+                "cooler_alice".to_string(),
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                canister_data,
+            )
+        };
+
+        assert_eq!(
+            revert_unique_user_name_change_for_user_principal_id_impl(
+                "never_alice".to_string(),
+                "cooler_alice".to_string(),
+                get_mock_user_alice_principal_id(),
+                get_mock_user_alice_canister_id(),
+                &mut canister_data,
+            ),
+            Err(ChangeUniqueUsernameError::UsernameNotOwnedByUser)
+        );
+
+        assert_eq!(
+            change_alice("cool_alice", "cooler_alice", now, &mut canister_data),
+            Ok(())
+        );
+        assert_eq!(revert_alice(&mut canister_data), Ok(()));
+        // * retries after a lost reply succeed without changing anything
+        assert_eq!(revert_alice(&mut canister_data), Ok(()));
+
+        assert_eq!(
+            canister_data
+                .unique_user_name_to_user_principal_id_map
+                .get("cool_alice"),
+            Some(&get_mock_user_alice_principal_id())
+        );
+        assert!(!canister_data
+            .unique_user_name_to_user_principal_id_map
+            .contains_key("cooler_alice"));
+        assert!(canister_data.released_unique_user_names.is_empty());
+    }
+}
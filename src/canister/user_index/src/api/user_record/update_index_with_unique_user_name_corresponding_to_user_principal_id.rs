@@ -1,32 +1,107 @@
+use std::time::SystemTime;
+
 use candid::Principal;
 use ic_cdk_macros::update;
-use shared_utils::types::canister_specific::user_index::error_types::SetUniqueUsernameError;
-
-use crate::{data_model::CanisterData, CANISTER_DATA};
-
+use shared_utils::{
+    canister_specific::platform_orchestrator::types::GlobalUniqueUserNameRegistryError,
+    common::utils::{
+        system_time::get_current_system_time_from_ic,
+        validation::{normalize_unique_user_name, validate_unique_user_name},
+    },
+    types::canister_specific::user_index::error_types::SetUniqueUsernameError,
+};
+
+use crate::{
+    data_model::CanisterData,
+    util::global_unique_user_name_registry::{
+        register_unique_user_name_globally, revert_unique_user_name_registration_globally,
+        GlobalUniqueUserNameRegistryCallError,
+    },
+    CANISTER_DATA,
+};
+
+/// # Access Control
+/// Only the individual canister of `user_principal_id` can set its username.
 #[update]
-fn update_index_with_unique_user_name_corresponding_to_user_principal_id(
+async fn update_index_with_unique_user_name_corresponding_to_user_principal_id(
     unique_user_name: String,
     user_principal_id: Principal,
 ) -> Result<(), SetUniqueUsernameError> {
     let request_makers_canister_id = ic_cdk::caller();
+    let unique_user_name = normalize_unique_user_name(&unique_user_name);
 
     CANISTER_DATA.with(|canister_data_ref_cell| {
+        validate_unique_user_name_claim(
+            &unique_user_name,
+            user_principal_id,
+            request_makers_canister_id,
+            get_current_system_time_from_ic(),
+            &canister_data_ref_cell.borrow(),
+        )
+    })?;
+
+    register_unique_user_name_globally(
+        &unique_user_name,
+        user_principal_id,
+        request_makers_canister_id,
+        None,
+    )
+    .await
+    .map_err(|e| match e {
+        GlobalUniqueUserNameRegistryCallError::Unavailable => {
+            SetUniqueUsernameError::GlobalUsernameRegistryUnavailable
+        }
+        GlobalUniqueUserNameRegistryCallError::Rejected(
+            GlobalUniqueUserNameRegistryError::UsernameAlreadyTaken,
+        ) => SetUniqueUsernameError::UsernameAlreadyTaken,
+        GlobalUniqueUserNameRegistryCallError::Rejected(e) => {
+            SetUniqueUsernameError::GlobalUsernameRegistryRejected(e)
+        }
+    })?;
+
+    // * the index may have changed while waiting on the platform orchestrator
+    let result = CANISTER_DATA.with(|canister_data_ref_cell| {
         update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
-            unique_user_name,
+            unique_user_name.clone(),
             user_principal_id,
             request_makers_canister_id,
+            get_current_system_time_from_ic(),
             &mut canister_data_ref_cell.borrow_mut(),
         )
-    })
+    });
+
+    let is_held_by_user = CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .unique_user_name_to_user_principal_id_map
+            .get(&unique_user_name)
+            == Some(&user_principal_id)
+    });
+
+    if result.is_err() && !is_held_by_user {
+        revert_unique_user_name_registration_globally(&unique_user_name, user_principal_id, None)
+            .await
+            .map_err(|e| match e {
+                GlobalUniqueUserNameRegistryCallError::Unavailable => {
+                    SetUniqueUsernameError::GlobalUsernameRegistryRevertPending
+                }
+                GlobalUniqueUserNameRegistryCallError::Rejected(e) => {
+                    SetUniqueUsernameError::GlobalUsernameRegistryRejected(e)
+                }
+            })?;
+    }
+
+    result
 }
 
-fn update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
-    unique_user_name: String,
+fn validate_unique_user_name_claim(
+    unique_user_name: &str,
     user_principal_id: Principal,
     request_makers_canister_id: Principal,
-    canister_data: &mut CanisterData,
+    current_time: SystemTime,
+    canister_data: &CanisterData,
 ) -> Result<(), SetUniqueUsernameError> {
+    validate_unique_user_name(unique_user_name).map_err(SetUniqueUsernameError::InvalidUsername)?;
+
     if !canister_data
         .user_principal_id_to_canister_id_map
         .contains_key(&user_principal_id)
@@ -42,13 +117,35 @@ fn update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
         return Err(SetUniqueUsernameError::SendingCanisterDoesNotMatchUserCanisterId);
     }
 
-    if canister_data
-        .unique_user_name_to_user_principal_id_map
-        .contains_key(&unique_user_name)
-    {
+    if !canister_data.is_unique_user_name_claimable_by(
+        unique_user_name,
+        user_principal_id,
+        current_time,
+    ) {
         return Err(SetUniqueUsernameError::UsernameAlreadyTaken);
     }
 
+    Ok(())
+}
+
+fn update_index_with_unique_user_name_corresponding_to_user_principal_id_impl(
+    unique_user_name: String,
+    user_principal_id: Principal,
+    request_makers_canister_id: Principal,
+    current_time: SystemTime,
+    canister_data: &mut CanisterData,
+) -> Result<(), SetUniqueUsernameError> {
+    validate_unique_user_name_claim(
+        &unique_user_name,
+        user_principal_id,
+        request_makers_canister_id,
+        current_time,
+        canister_data,
+    )?;
+
+    canister_data
+        .released_unique_user_names
+        .remove(&unique_user_name);
     canister_data
         .unique_user_name_to_user_principal_id_map
         .insert(unique_user_name.clone(), user_principal_id);
@@ -77,6 +174,7 @@ mod test {
             unique_user_name_1.clone(),
             user_principal_id,
             request_makers_canister_id,
+            SystemTime::UNIX_EPOCH,
             &mut canister_data,
         );
         assert!(result.is_err());
@@ -93,6 +191,7 @@ mod test {
             unique_user_name_1.clone(),
             user_principal_id,
             get_mock_user_bob_canister_id(),
+            SystemTime::UNIX_EPOCH,
             &mut canister_data,
         );
         assert!(result.is_err());
@@ -109,6 +208,7 @@ mod test {
             unique_user_name_1.clone(),
             user_principal_id,
             request_makers_canister_id,
+            SystemTime::UNIX_EPOCH,
             &mut canister_data,
         );
         assert!(result.is_err());
@@ -121,6 +221,7 @@ mod test {
             unique_user_name_2.clone(),
             user_principal_id,
             request_makers_canister_id,
+            SystemTime::UNIX_EPOCH,
             &mut canister_data,
         );
         assert!(result.is_ok());
@@ -1,12 +1,20 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::time::SystemTime;
 
 use candid::{Deserialize, Principal};
 use ic_stable_structures::StableBTreeMap;
 use serde::Serialize;
+use shared_utils::canister_specific::individual_user_template::types::creator_token_quota::CreatorTokenQuotaConfig;
+use shared_utils::canister_specific::individual_user_template::types::engagement_reward::{
+    EngagementMilestone, EngagementRewardCaps, EngagementRewardClaim,
+};
+use shared_utils::canister_specific::individual_user_template::types::referral::ReferralConfig;
 use shared_utils::canister_specific::user_index::types::{
-    BroadcastCallStatus, RecycleStatus, UpgradeStatus,
+    BroadcastCallStatus, RecycleStatus, ReleasedUniqueUserName, UpgradeStatus,
 };
 use shared_utils::common::types::wasm::{CanisterWasm, WasmType};
+use shared_utils::common::utils::validation::normalize_unique_user_name;
+use shared_utils::types::utility_token_supply_stats::get_day_since_epoch;
 
 use self::memory::get_wasm_memory;
 use self::{configuration::Configuration, memory::Memory};
@@ -32,12 +40,31 @@ pub struct CanisterData {
     pub backup_canister_pool: HashSet<Principal>,
     pub user_principal_id_to_canister_id_map: BTreeMap<Principal, Principal>,
     pub unique_user_name_to_user_principal_id_map: BTreeMap<String, Principal>,
+    #[serde(default)]
+    pub released_unique_user_names: BTreeMap<String, ReleasedUniqueUserName>,
+    /// Registrations in the platform orchestrator that could not be undone yet, retried by a timer
+    #[serde(default)]
+    pub pending_global_unique_user_name_reverts: BTreeSet<PendingGlobalUniqueUserNameRevert>,
     #[serde(skip, default = "_empty_wasms")]
     pub wasms: StableBTreeMap<WasmType, CanisterWasm, Memory>,
     #[serde(default)]
     pub recycle_status: RecycleStatus,
     #[serde(default)]
     pub last_broadcast_call_status: BroadcastCallStatus,
+    /// Last referral config pushed to the individual canisters, given to new users' canisters too
+    #[serde(default)]
+    pub referral_config: Option<ReferralConfig>,
+    /// This subnet's share of the network caps on engagement rewards, no cap until it is set
+    #[serde(default)]
+    pub engagement_reward_caps: Option<EngagementRewardCaps>,
+    /// Engagement rewards granted to the individual canisters of this subnet, by the day of the
+    /// claims. Only today and yesterday are kept.
+    #[serde(default)]
+    pub engagement_rewards_granted_by_day: BTreeMap<u64, BTreeMap<EngagementMilestone, u64>>,
+    /// Last creator token quota config pushed to the individual canisters, given to new users'
+    /// canisters too
+    #[serde(default)]
+    pub creator_token_quota_config: Option<CreatorTokenQuotaConfig>,
 }
 
 impl Default for CanisterData {
@@ -49,10 +76,152 @@ impl Default for CanisterData {
             available_canisters: Default::default(),
             user_principal_id_to_canister_id_map: Default::default(),
             unique_user_name_to_user_principal_id_map: Default::default(),
+            released_unique_user_names: Default::default(),
+            pending_global_unique_user_name_reverts: Default::default(),
             wasms: _empty_wasms(),
             backup_canister_pool: Default::default(),
             recycle_status: Default::default(),
             last_broadcast_call_status: Default::default(),
+            referral_config: Default::default(),
+            engagement_reward_caps: Default::default(),
+            engagement_rewards_granted_by_day: Default::default(),
+            creator_token_quota_config: Default::default(),
+        }
+    }
+}
+
+/// Arguments of a `revert_global_unique_user_name_registration` call that is still to be made.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PendingGlobalUniqueUserNameRevert {
+    pub unique_user_name: String,
+    pub user_principal_id: Principal,
+    pub old_unique_user_name: Option<String>,
+}
+
+impl CanisterData {
+    /// A name is claimable if nobody holds it and it is either not in its release cooldown or is
+    /// being reclaimed by the user who released it.
+    pub fn is_unique_user_name_claimable_by(
+        &self,
+        unique_user_name: &str,
+        user_principal_id: Principal,
+        current_time: SystemTime,
+    ) -> bool {
+        let unique_user_name = normalize_unique_user_name(unique_user_name);
+
+        if self
+            .unique_user_name_to_user_principal_id_map
+            .contains_key(&unique_user_name)
+        {
+            return false;
+        }
+
+        self.released_unique_user_names
+            .get(&unique_user_name)
+            .is_none_or(|released_unique_user_name| {
+                released_unique_user_name.released_by_user_principal_id == user_principal_id
+                    || current_time >= released_unique_user_name.claimable_by_others_after
+            })
+    }
+
+    /// Names stored before they were normalized are moved to their lowercase form once. A name
+    /// whose lowercase form is already held stays as it was, so that its owner can rename it.
+    pub fn normalize_stored_unique_user_names(&mut self) {
+        let unique_user_names_to_normalize: Vec<String> = self
+            .unique_user_name_to_user_principal_id_map
+            .keys()
+            .filter(|unique_user_name| {
+                **unique_user_name != normalize_unique_user_name(unique_user_name)
+            })
+            .cloned()
+            .collect();
+
+        for unique_user_name in unique_user_names_to_normalize {
+            let normalized_unique_user_name = normalize_unique_user_name(&unique_user_name);
+            if self
+                .unique_user_name_to_user_principal_id_map
+                .contains_key(&normalized_unique_user_name)
+            {
+                ic_cdk::println!(
+                    "Unique user name {} collides with {} and was not normalized",
+                    unique_user_name,
+                    normalized_unique_user_name
+                );
+                continue;
+            }
+
+            if let Some(user_principal_id) = self
+                .unique_user_name_to_user_principal_id_map
+                .remove(&unique_user_name)
+            {
+                self.unique_user_name_to_user_principal_id_map
+                    .insert(normalized_unique_user_name, user_principal_id);
+            }
+        }
+
+        let released_unique_user_names = std::mem::take(&mut self.released_unique_user_names);
+        for (unique_user_name, released_unique_user_name) in released_unique_user_names {
+            self.released_unique_user_names
+                .entry(normalize_unique_user_name(&unique_user_name))
+                .or_insert(released_unique_user_name);
+        }
+    }
+
+    /// Grants each claim as far as this subnet's daily cap for its milestone allows and counts the
+    /// grant against the cap of the claim's day right away. Claims for days before yesterday are
+    /// not granted.
+    pub fn reserve_engagement_rewards(
+        &mut self,
+        engagement_reward_claims: Vec<EngagementRewardClaim>,
+        current_time: SystemTime,
+    ) -> Vec<EngagementRewardClaim> {
+        let yesterday = get_day_since_epoch(current_time).saturating_sub(1);
+        self.engagement_rewards_granted_by_day =
+            self.engagement_rewards_granted_by_day.split_off(&yesterday);
+
+        engagement_reward_claims
+            .into_iter()
+            .map(|mut engagement_reward_claim| {
+                if engagement_reward_claim.day < yesterday {
+                    engagement_reward_claim.amount = 0;
+                    return engagement_reward_claim;
+                }
+
+                let granted = self
+                    .engagement_rewards_granted_by_day
+                    .entry(engagement_reward_claim.day)
+                    .or_default()
+                    .entry(engagement_reward_claim.milestone)
+                    .or_default();
+
+                if let Some(engagement_reward_caps) = self.engagement_reward_caps {
+                    let remaining = engagement_reward_caps
+                        .get_cap(engagement_reward_claim.milestone)
+                        .saturating_sub(*granted);
+                    engagement_reward_claim.amount = engagement_reward_claim.amount.min(remaining);
+                }
+
+                *granted = granted.saturating_add(engagement_reward_claim.amount);
+                engagement_reward_claim
+            })
+            .collect()
+    }
+
+    /// Gives back the part of earlier grants that an individual canister did not mint.
+    pub fn release_engagement_rewards(
+        &mut self,
+        engagement_reward_claims: Vec<EngagementRewardClaim>,
+    ) {
+        for engagement_reward_claim in engagement_reward_claims {
+            if let Some(granted) = self
+                .engagement_rewards_granted_by_day
+                .get_mut(&engagement_reward_claim.day)
+                .and_then(|granted_by_milestone| {
+                    granted_by_milestone.get_mut(&engagement_reward_claim.milestone)
+                })
+            {
+                *granted = granted.saturating_sub(engagement_reward_claim.amount);
+            }
         }
     }
 }
@@ -60,3 +229,125 @@ impl Default for CanisterData {
 fn _empty_wasms() -> StableBTreeMap<WasmType, CanisterWasm, Memory> {
     StableBTreeMap::init(get_wasm_memory())
 }
+
+#[cfg(test)]
+mod test {
+    use test_utils::setup::test_constants::{
+        get_mock_user_alice_principal_id, get_mock_user_bob_principal_id,
+    };
+
+    use super::*;
+
+    const SECONDS_IN_A_DAY: u64 = 24 * 60 * 60;
+
+    fn claim(milestone: EngagementMilestone, amount: u64) -> EngagementRewardClaim {
+        claim_on_day(milestone, 1, amount)
+    }
+
+    fn claim_on_day(
+        milestone: EngagementMilestone,
+        day: u64,
+        amount: u64,
+    ) -> EngagementRewardClaim {
+        EngagementRewardClaim {
+            milestone,
+            day,
+            amount,
+        }
+    }
+
+    fn get_time_on_day(day: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(day * SECONDS_IN_A_DAY)
+    }
+
+    #[test]
+    fn test_reserve_engagement_rewards() {
+        let mut canister_data = CanisterData::default();
+        let now = get_time_on_day(1);
+
+        // * nothing is held back before the caps are set
+        assert_eq!(
+            canister_data
+                .reserve_engagement_rewards(vec![claim(EngagementMilestone::FirstPost, 100)], now),
+            vec![claim(EngagementMilestone::FirstPost, 100)]
+        );
+
+        canister_data.engagement_reward_caps = Some(EngagementRewardCaps {
+            daily_login_streak: 15,
+            first_post: 150,
+            daily_posts_watched: 0,
+            first_bet: 50,
+        });
+
+        assert_eq!(
+            canister_data.reserve_engagement_rewards(
+                vec![
+                    claim(EngagementMilestone::DailyLoginStreak, 10),
+                    claim(EngagementMilestone::FirstPost, 100),
+                    claim(EngagementMilestone::DailyPostsWatched, 10),
+                ],
+                now
+            ),
+            vec![
+                claim(EngagementMilestone::DailyLoginStreak, 10),
+                claim(EngagementMilestone::FirstPost, 50),
+                claim(EngagementMilestone::DailyPostsWatched, 0),
+            ]
+        );
+        assert_eq!(
+            canister_data.reserve_engagement_rewards(
+                vec![claim(EngagementMilestone::DailyLoginStreak, 10)],
+                now
+            ),
+            vec![claim(EngagementMilestone::DailyLoginStreak, 5)]
+        );
+        assert_eq!(
+            canister_data.engagement_rewards_granted_by_day[&1][&EngagementMilestone::FirstPost],
+            150
+        );
+
+        // * the caps start over the next day and days before yesterday are dropped
+        assert_eq!(
+            canister_data.reserve_engagement_rewards(
+                vec![
+                    claim_on_day(EngagementMilestone::DailyLoginStreak, 3, 10),
+                    claim_on_day(EngagementMilestone::DailyLoginStreak, 1, 10),
+                ],
+                get_time_on_day(3)
+            ),
+            vec![
+                claim_on_day(EngagementMilestone::DailyLoginStreak, 3, 10),
+                claim_on_day(EngagementMilestone::DailyLoginStreak, 1, 0),
+            ]
+        );
+        assert_eq!(
+            canister_data
+                .engagement_rewards_granted_by_day
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&3]
+        );
+    }
+
+    #[test]
+    fn test_release_engagement_rewards() {
+        let mut canister_data = CanisterData::default();
+        let now = get_time_on_day(1);
+        canister_data.engagement_reward_caps = Some(EngagementRewardCaps {
+            daily_login_streak: 15,
+            first_post: 150,
+            daily_posts_watched: 0,
+            first_bet: 50,
+        });
+
+        canister_data
+            .reserve_engagement_rewards(vec![claim(EngagementMilestone::FirstPost, 150)], now);
+        canister_data.release_engagement_rewards(vec![claim(EngagementMilestone::FirstPost, 100)]);
+
+        assert_eq!(
+            canister_data
+                .reserve_engagement_rewards(vec![claim(EngagementMilestone::FirstPost, 150)], now),
+            vec![claim(EngagementMilestone::FirstPost, 100)]
+        );
+    }
+}
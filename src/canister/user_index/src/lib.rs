@@ -10,13 +10,22 @@ use ic_cdk::api::{
 };
 use ic_cdk_macros::export_candid;
 use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        cdao::CreatorDaoTokenStatus,
+        creator_token_quota::CreatorTokenQuotaConfig,
+        engagement_reward::{EngagementRewardCaps, EngagementRewardClaim},
+        referral::ReferralConfig,
+    },
     canister_specific::user_index::types::{
         args::UserIndexInitArgs, BroadcastCallStatus, RecycleStatus, UpgradeStatus,
     },
     common::types::http::{HttpRequest, HttpResponse},
     common::types::known_principal::KnownPrincipalType,
-    types::canister_specific::user_index::error_types::SetUniqueUsernameError,
+    types::canister_specific::user_index::error_types::{
+        ChangeUniqueUsernameError, SetUniqueUsernameError,
+    },
     types::creator_dao_stats::IndividualUserCreatorDaoEntry,
+    types::utility_token_supply_stats::UtilityTokenSupplyStats,
 };
 
 mod api;
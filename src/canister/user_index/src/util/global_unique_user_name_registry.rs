@@ -0,0 +1,190 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::Duration,
+};
+
+use candid::Principal;
+use ic_cdk_timers::TimerId;
+use shared_utils::{
+    canister_specific::platform_orchestrator::types::GlobalUniqueUserNameRegistryError,
+    common::types::known_principal::KnownPrincipalType,
+};
+
+use crate::{data_model::PendingGlobalUniqueUserNameRevert, CANISTER_DATA};
+
+const PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+thread_local! {
+    static PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_TIMER: RefCell<Option<TimerId>> = const { RefCell::new(None) };
+    static PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_IN_PROGRESS: Cell<bool> = const { Cell::new(false) };
+}
+
+pub enum GlobalUniqueUserNameRegistryCallError {
+    Unavailable,
+    Rejected(GlobalUniqueUserNameRegistryError),
+}
+
+fn get_platform_orchestrator_canister_id() -> Option<Principal> {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        canister_data
+            .configuration
+            .known_principal_ids
+            .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+            .copied()
+    })
+}
+
+/// Claims the name in the platform orchestrator's registry so that it stays unique across subnets.
+/// Subnets without a platform orchestrator configured only get subnet-local uniqueness.
+pub async fn register_unique_user_name_globally(
+    unique_user_name: &str,
+    user_principal_id: Principal,
+    user_canister_id: Principal,
+    old_unique_user_name: Option<String>,
+) -> Result<(), GlobalUniqueUserNameRegistryCallError> {
+    let Some(platform_orchestrator_canister_id) = get_platform_orchestrator_canister_id() else {
+        return Ok(());
+    };
+
+    let (response,): (Result<(), GlobalUniqueUserNameRegistryError>,) = ic_cdk::call(
+        platform_orchestrator_canister_id,
+        "register_global_unique_user_name",
+        (
+            unique_user_name,
+            user_principal_id,
+            user_canister_id,
+            old_unique_user_name,
+        ),
+    )
+    .await
+    .map_err(|e| {
+        ic_cdk::println!("Error registering unique user name globally {:?}", e);
+        GlobalUniqueUserNameRegistryCallError::Unavailable
+    })?;
+
+    response.map_err(GlobalUniqueUserNameRegistryCallError::Rejected)
+}
+
+/// Undoes `register_unique_user_name_globally` when the change could not be applied locally. A
+/// revert that could not reach the platform orchestrator is queued and retried by a timer.
+pub async fn revert_unique_user_name_registration_globally(
+    unique_user_name: &str,
+    user_principal_id: Principal,
+    old_unique_user_name: Option<String>,
+) -> Result<(), GlobalUniqueUserNameRegistryCallError> {
+    let pending_revert = PendingGlobalUniqueUserNameRevert {
+        unique_user_name: unique_user_name.to_string(),
+        user_principal_id,
+        old_unique_user_name,
+    };
+
+    let result = call_revert_global_unique_user_name_registration(&pending_revert).await;
+
+    if let Err(GlobalUniqueUserNameRegistryCallError::Unavailable) = result {
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data
+                .pending_global_unique_user_name_reverts
+                .insert(pending_revert)
+        });
+        start_pending_global_unique_user_name_revert_retry_timer_if_needed();
+    }
+
+    result
+}
+
+async fn call_revert_global_unique_user_name_registration(
+    pending_revert: &PendingGlobalUniqueUserNameRevert,
+) -> Result<(), GlobalUniqueUserNameRegistryCallError> {
+    let Some(platform_orchestrator_canister_id) = get_platform_orchestrator_canister_id() else {
+        return Ok(());
+    };
+
+    let (response,): (Result<(), GlobalUniqueUserNameRegistryError>,) = ic_cdk::call(
+        platform_orchestrator_canister_id,
+        "revert_global_unique_user_name_registration",
+        (
+            &pending_revert.unique_user_name,
+            pending_revert.user_principal_id,
+            &pending_revert.old_unique_user_name,
+        ),
+    )
+    .await
+    .map_err(|e| {
+        ic_cdk::println!(
+            "Error reverting global unique user name registration {:?}",
+            e
+        );
+        GlobalUniqueUserNameRegistryCallError::Unavailable
+    })?;
+
+    response.map_err(GlobalUniqueUserNameRegistryCallError::Rejected)
+}
+
+/// Starts the retry timer when reverts are pending and it is not running yet. Timers do not
+/// survive upgrades, so this is also called from `post_upgrade`.
+pub fn start_pending_global_unique_user_name_revert_retry_timer_if_needed() {
+    let is_running =
+        PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_TIMER.with_borrow(Option::is_some);
+    if is_running || !has_pending_global_unique_user_name_reverts() {
+        return;
+    }
+
+    let timer_id = ic_cdk_timers::set_timer_interval(
+        PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_INTERVAL,
+        || ic_cdk::spawn(retry_pending_global_unique_user_name_reverts()),
+    );
+
+    PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_TIMER
+        .with_borrow_mut(|timer| *timer = Some(timer_id));
+}
+
+fn has_pending_global_unique_user_name_reverts() -> bool {
+    CANISTER_DATA.with_borrow(|canister_data| {
+        !canister_data
+            .pending_global_unique_user_name_reverts
+            .is_empty()
+    })
+}
+
+/// Reverts that the platform orchestrator rejected are dropped since retrying them cannot succeed.
+/// The timer is stopped once nothing is pending.
+async fn retry_pending_global_unique_user_name_reverts() {
+    if PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_IN_PROGRESS.replace(true) {
+        return;
+    }
+
+    let pending_reverts: Vec<PendingGlobalUniqueUserNameRevert> =
+        CANISTER_DATA.with_borrow(|canister_data| {
+            canister_data
+                .pending_global_unique_user_name_reverts
+                .iter()
+                .cloned()
+                .collect()
+        });
+
+    for pending_revert in pending_reverts {
+        match call_revert_global_unique_user_name_registration(&pending_revert).await {
+            Err(GlobalUniqueUserNameRegistryCallError::Unavailable) => continue,
+            Err(GlobalUniqueUserNameRegistryCallError::Rejected(e)) => {
+                ic_cdk::println!("Dropping rejected global unique user name revert {:?}", e)
+            }
+            Ok(()) => {}
+        }
+
+        CANISTER_DATA.with_borrow_mut(|canister_data| {
+            canister_data
+                .pending_global_unique_user_name_reverts
+                .remove(&pending_revert)
+        });
+    }
+
+    PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_IN_PROGRESS.set(false);
+
+    if !has_pending_global_unique_user_name_reverts() {
+        if let Some(timer_id) =
+            PENDING_GLOBAL_UNIQUE_USER_NAME_REVERT_RETRY_TIMER.with_borrow_mut(Option::take)
+        {
+            ic_cdk_timers::clear_timer(timer_id);
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod canister_management;
+pub mod global_unique_user_name_registry;
 pub mod types;
@@ -134,6 +134,19 @@ impl IndividualUserCanister {
             .map_err(|e| e.1)
     }
 
+    pub async fn update_creator_token_quota_override(
+        &self,
+        quota_override: Option<u64>,
+    ) -> Result<(), String> {
+        ic_cdk::call::<_, ()>(
+            self.canister_id,
+            "update_creator_token_quota_override",
+            (quota_override,),
+        )
+        .await
+        .map_err(|e| e.1)
+    }
+
     pub fn notify_to_upgrade_creator_dao_governance_canisters(
         &self,
         wasm_module: Vec<u8>,
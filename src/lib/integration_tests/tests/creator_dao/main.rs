@@ -1,6 +1,12 @@
+#[cfg(feature = "cdao_deployment_rollback_test")]
+pub mod test_cdao_deployment_rollback;
+pub mod test_creator_dao_token_status;
+pub mod test_creator_token_quota;
+pub mod test_creator_token_swap_participation;
 pub mod test_custom_governance_upgrade;
 pub mod test_deletion_of_creator_tokens;
 pub mod test_number_of_creator_tokens;
+pub mod test_sns_neuron_staking;
 pub mod types;
 pub mod utils;
 
@@ -21,7 +27,14 @@ use ic_sns_swap::pb::v1::{
 };
 use sha2::{Digest, Sha256};
 use shared_utils::canister_specific::individual_user_template::types::arg::IndividualUserTemplateInitArgs;
+use shared_utils::canister_specific::individual_user_template::types::cdao::{
+    AirdropCampaignConfig, AirdropCampaignDetails, AirdropClaimRecord, AirdropClaimant,
+    AirdropEligibility, AirdropVesting, ClaimStatus,
+};
 use shared_utils::canister_specific::individual_user_template::types::error::AirdropError;
+use shared_utils::canister_specific::individual_user_template::types::wallet::{
+    WalletOverview, WalletOverviewError,
+};
 use shared_utils::constant::{
     SNS_TOKEN_ARCHIVE_MODULE_HASH, SNS_TOKEN_GOVERNANCE_MODULE_HASH, SNS_TOKEN_INDEX_MODULE_HASH,
     SNS_TOKEN_LEDGER_MODULE_HASH, SNS_TOKEN_ROOT_MODULE_HASH, SNS_TOKEN_SWAP_MODULE_HASH,
@@ -39,11 +52,9 @@ use icp_ledger::Subaccount;
 use pocket_ic::WasmResult;
 use serde::{Deserialize, Serialize};
 use shared_utils::{
-    canister_specific::individual_user_template::{
-        types::cdao::DeployedCdaoCanisters, types::error::CdaoDeployError,
-    },
-    common::types::known_principal::KnownPrincipalType,
-    constant::SNS_WASM_W_PRINCIPAL_ID,
+    canister_specific::individual_user_template::types::error::CdaoDeployError,
+    common::types::known_principal::KnownPrincipalType, constant::SNS_WASM_W_PRINCIPAL_ID,
+    pagination::PaginationError,
 };
 use test_utils::setup::{
     env::pocket_ic_env::get_new_pocket_ic_env,
@@ -323,6 +334,59 @@ fn creator_dao_tests() {
         })
         .unwrap();
 
+    let wallet_overview = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "get_wallet_overview",
+            encode_args((0u64, 10u64)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<WalletOverview, WalletOverviewError>).unwrap()
+            }
+            _ => panic!("\n🛑 get wallet overview failed\n"),
+        })
+        .unwrap()
+        .unwrap();
+    ic_cdk::println!("🧪 Wallet overview: {:?}", wallet_overview);
+
+    let others_wallet_overview = pocket_ic
+        .update_call(
+            alice_canister_id,
+            bob,
+            "get_wallet_overview",
+            encode_args((0u64, 10u64)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<WalletOverview, WalletOverviewError>).unwrap()
+            }
+            _ => panic!("\n🛑 get wallet overview failed\n"),
+        })
+        .unwrap();
+    assert_eq!(
+        others_wallet_overview,
+        Err(WalletOverviewError::Unauthorized)
+    );
+    let wallet_token = wallet_overview
+        .tokens
+        .iter()
+        .find(|token| token.token_root == root_canister)
+        .unwrap();
+    assert_eq!(wallet_token.ledger, Some(ledger_canister));
+    assert!(wallet_token
+        .balance
+        .as_ref()
+        .is_some_and(|balance| *balance > 0u64));
+    assert_eq!(
+        wallet_token
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.decimals),
+        Some(decimals)
+    );
+
     let transfer_args = types::TransferArg {
         from_subaccount: None,
         to: types::Account {
@@ -427,26 +491,49 @@ fn creator_dao_tests() {
     ic_cdk::println!("🧪 Result: {:?}", res);
     assert!(res.unwrap().is_err());
 
-    let deployed_cdao = pocket_ic
+    let bob_claim = pocket_ic
         .query_call(
             alice_canister_id,
             alice_principal,
-            "deployed_cdao_canisters",
-            candid::encode_one(()).unwrap(),
+            "get_airdrop_claim",
+            encode_args((root_canister, bob)).unwrap(),
         )
         .map(|res| {
-            let response: Vec<DeployedCdaoCanisters> = match res {
+            let response: Option<AirdropClaimRecord> = match res {
                 WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
-                _ => panic!("\n🛑 get requester principals canister id failed\n"),
+                _ => panic!("\n🛑 get airdrop claim failed\n"),
+            };
+            response
+        })
+        .unwrap();
+    ic_cdk::println!("🧪 Result: {:?}", bob_claim);
+    let bob_claim = bob_claim.unwrap();
+    assert_eq!(bob_claim.status, ClaimStatus::Claimed);
+    assert_eq!(
+        Nat::from(bob_claim.amount),
+        Nat::from(100u64) * 10u64.pow(decimals.into())
+    );
+
+    let claimants = pocket_ic
+        .query_call(
+            alice_canister_id,
+            alice_principal,
+            "get_airdrop_claimants",
+            encode_args((root_canister, None::<Principal>, 10u64)).unwrap(),
+        )
+        .map(|res| {
+            let response: Result<Vec<AirdropClaimant>, PaginationError> = match res {
+                WasmResult::Reply(payload) => {
+                    Decode!(&payload, Result<Vec<AirdropClaimant>, PaginationError>).unwrap()
+                }
+                _ => panic!("\n🛑 get airdrop claimants failed\n"),
             };
             response
         })
+        .unwrap()
         .unwrap();
-    ic_cdk::println!("🧪 Result: {:?}", deployed_cdao);
-    assert!(deployed_cdao[0]
-        .airdrop_info
-        .is_airdrop_claimed(&bob)
-        .unwrap());
+    assert_eq!(claimants.len(), 1);
+    assert_eq!(claimants[0].principal_id, bob);
 
     let bob_bal = pocket_ic
         .query_call(
@@ -486,6 +573,94 @@ fn creator_dao_tests() {
 
     assert!(bob_bal == Nat::from(100u64) * 10u64.pow(decimals.into()));
 
+    // airdrop campaign of the remaining tokens, vested in two tranches
+    let token_unit = 10u64.pow(decimals.into());
+    let campaign_config = AirdropCampaignConfig {
+        token_root: root_canister,
+        total_budget: 1000 * token_unit,
+        amount_per_claim: 50 * token_unit,
+        eligibility: AirdropEligibility::Allowlist(vec![bob]),
+        start_time: pocket_ic.get_time(),
+        end_time: pocket_ic.get_time() + Duration::from_secs(1000),
+        vesting: Some(AirdropVesting {
+            number_of_tranches: 2,
+            vesting_duration_in_seconds: 100,
+        }),
+    };
+
+    let create_airdrop_campaign = |config: &AirdropCampaignConfig| {
+        pocket_ic
+            .update_call(
+                alice_canister_id,
+                alice_principal,
+                "create_airdrop_campaign",
+                candid::encode_one(config).unwrap(),
+            )
+            .map(|res| match res {
+                WasmResult::Reply(payload) => Decode!(&payload, Result<u64, AirdropError>).unwrap(),
+                _ => panic!("\n🛑 create_airdrop_campaign failed\n"),
+            })
+            .unwrap()
+    };
+    assert_eq!(
+        create_airdrop_campaign(&campaign_config),
+        Err(AirdropError::NoBalance)
+    );
+
+    let campaign_id = create_airdrop_campaign(&AirdropCampaignConfig {
+        total_budget: 100 * token_unit,
+        ..campaign_config
+    })
+    .unwrap();
+
+    let claim_airdrop = |claimant: Principal, claimant_canister_id: Principal| {
+        pocket_ic
+            .update_call(
+                alice_canister_id,
+                claimant,
+                "claim_airdrop",
+                encode_args((campaign_id, None::<Memo>, claimant_canister_id)).unwrap(),
+            )
+            .map(|res| match res {
+                WasmResult::Reply(payload) => Decode!(&payload, Result<u64, AirdropError>).unwrap(),
+                _ => panic!("\n🛑 claim_airdrop failed\n"),
+            })
+            .unwrap()
+    };
+    assert_eq!(
+        claim_airdrop(alice_principal, alice_canister_id),
+        Err(AirdropError::NotEligible)
+    );
+    assert!(claim_airdrop(bob, Principal::anonymous()).is_err());
+    assert_eq!(claim_airdrop(bob, bob_canister_id), Ok(25 * token_unit));
+    assert_eq!(
+        claim_airdrop(bob, bob_canister_id),
+        Err(AirdropError::NothingToClaim)
+    );
+
+    pocket_ic.advance_time(Duration::from_secs(100));
+    assert_eq!(claim_airdrop(bob, bob_canister_id), Ok(25 * token_unit));
+    assert_eq!(
+        claim_airdrop(bob, bob_canister_id),
+        Err(AirdropError::AlreadyClaimedAirdrop)
+    );
+
+    let airdrop_campaigns: Vec<AirdropCampaignDetails> = pocket_ic
+        .query_call(
+            alice_canister_id,
+            bob,
+            "get_airdrop_campaigns",
+            candid::encode_one(root_canister).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+            _ => panic!("\n🛑 get_airdrop_campaigns failed\n"),
+        })
+        .unwrap();
+    assert_eq!(airdrop_campaigns.len(), 1);
+    assert_eq!(airdrop_campaigns[0].distributed_amount, 50 * token_unit);
+    assert_eq!(airdrop_campaigns[0].number_of_claimants, 1);
+
     for _ in 0..5 {
         pocket_ic.tick();
     }
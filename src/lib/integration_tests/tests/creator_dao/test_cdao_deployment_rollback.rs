@@ -0,0 +1,507 @@
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use candid::{Decode, Principal};
+use ic_base_types::PrincipalId;
+use ic_sns_init::pb::v1::{
+    sns_init_payload::InitialTokenDistribution, AirdropDistribution, DeveloperDistribution,
+    FractionalDeveloperVotingPower, NeuronDistribution, SnsInitPayload, SwapDistribution,
+    TreasuryDistribution,
+};
+use ic_sns_swap::pb::v1::NeuronBasketConstructionParameters;
+use pocket_ic::{PocketIc, WasmResult};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        arg::IndividualUserTemplateInitArgs,
+        cdao::{
+            CdaoDeploymentDetails, CdaoDeploymentStatus, CdaoDeploymentStep, DeployedCdaoCanisters,
+        },
+        error::CdaoDeployError,
+    },
+    common::types::known_principal::KnownPrincipalType,
+    constant::SNS_WASM_W_PRINCIPAL_ID,
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+        get_mock_user_charlie_principal_id,
+    },
+};
+
+use crate::utils::setup_sns_w_canister_for_creator_dao;
+
+/// Built with the `cdao_deployment_failure_injection` feature, apart from the release wasm
+const INDIVIDUAL_TEMPLATE_WASM_PATH: &str =
+    "../../../target/wasm32-unknown-unknown/release/individual_user_template_cdao_failure_injection.wasm.gz";
+
+fn individual_template_canister_wasm() -> Vec<u8> {
+    std::fs::read(INDIVIDUAL_TEMPLATE_WASM_PATH).unwrap()
+}
+
+fn get_sns_init_payload(super_admin: Principal, user_principal: Principal) -> SnsInitPayload {
+    let start = SystemTime::now();
+
+    SnsInitPayload {
+        confirmation_text: Some("GET RICH QUICK".to_string()),
+        transaction_fee_e8s: Some(1u64),
+        token_name: Some("Simulation Governance".to_string()),
+        token_symbol: Some("SIMG".to_string()),
+        proposal_reject_cost_e8s: Some(1u64),
+        neuron_minimum_stake_e8s: Some(2u64),
+        fallback_controller_principal_ids: vec![super_admin.to_string()],
+        logo: Some("data:image/png;base64,iVBORw0".to_string()),
+        url: Some("https://google.com".to_string()),
+        name: Some("Simulation Gov".to_string()),
+        description: Some("Simulation gov desc".to_string()),
+        neuron_minimum_dissolve_delay_to_vote_seconds: Some(1),
+        initial_reward_rate_basis_points: Some(30u64),
+        final_reward_rate_basis_points: Some(20u64),
+        reward_rate_transition_duration_seconds: Some(1u64),
+        max_dissolve_delay_seconds: Some(5u64),
+        max_neuron_age_seconds_for_age_bonus: Some(1u64),
+        max_dissolve_delay_bonus_percentage: Some(10u64),
+        max_age_bonus_percentage: Some(10u64),
+        initial_voting_period_seconds: Some(86401u64),
+        wait_for_quiet_deadline_increase_seconds: Some(1u64),
+        restricted_countries: None,
+        dapp_canisters: None,
+        min_participants: Some(1),
+        min_icp_e8s: None,
+        max_icp_e8s: None,
+        min_direct_participation_icp_e8s: Some(15u64),
+        min_participant_icp_e8s: Some(2000u64),
+        max_direct_participation_icp_e8s: Some(100_000_000u64),
+        max_participant_icp_e8s: Some(100_000_000u64),
+        swap_start_timestamp_seconds: Some(start.duration_since(UNIX_EPOCH).unwrap().as_secs()),
+        swap_due_timestamp_seconds: Some(start.duration_since(UNIX_EPOCH).unwrap().as_secs() + 300),
+        neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
+            count: 2,
+            dissolve_delay_interval_seconds: 2,
+        }),
+        nns_proposal_id: Some(1),
+        neurons_fund_participation: Some(false),
+        token_logo: Some("data:image/png;base64,iVBORw0".to_string()),
+        neurons_fund_participation_constraints: None,
+        initial_token_distribution: Some(InitialTokenDistribution::FractionalDeveloperVotingPower(
+            FractionalDeveloperVotingPower {
+                airdrop_distribution: Some(AirdropDistribution {
+                    airdrop_neurons: vec![],
+                }),
+                developer_distribution: Some(DeveloperDistribution {
+                    developer_neurons: vec![NeuronDistribution {
+                        controller: Some(
+                            PrincipalId::from_str(&user_principal.to_string()).unwrap(),
+                        ),
+                        stake_e8s: 60_000_000_000,
+                        memo: 0,
+                        dissolve_delay_seconds: 0,
+                        vesting_period_seconds: None,
+                    }],
+                }),
+                treasury_distribution: Some(TreasuryDistribution {
+                    total_e8s: 10_000_000,
+                }),
+                swap_distribution: Some(SwapDistribution {
+                    total_e8s: 65_000_000_000,
+                    initial_swap_amount_e8s: 5_000_000,
+                }),
+            },
+        )),
+    }
+}
+
+fn deploy_cdao_sns(
+    pocket_ic: &PocketIc,
+    user_canister_id: Principal,
+    user_principal: Principal,
+    sns_init_payload: &SnsInitPayload,
+) -> Result<DeployedCdaoCanisters, CdaoDeployError> {
+    pocket_ic
+        .update_call(
+            user_canister_id,
+            user_principal,
+            "deploy_cdao_sns",
+            candid::encode_args((sns_init_payload.clone(), 300 as u64)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<DeployedCdaoCanisters, CdaoDeployError>).unwrap()
+            }
+            _ => panic!("\n🛑 deploy cdao failed with {:?}", res),
+        })
+        .unwrap()
+}
+
+fn get_subnet_backup_capacity(
+    pocket_ic: &PocketIc,
+    subnet_orchestrator_canister_id: Principal,
+) -> u64 {
+    pocket_ic
+        .query_call(
+            subnet_orchestrator_canister_id,
+            Principal::anonymous(),
+            "get_subnet_backup_capacity",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+            _ => panic!("\n🛑 get_subnet_backup_capacity failed\n"),
+        })
+        .unwrap()
+}
+
+/// Returns the environment with alice's canister, the subnet orchestrator controlling it and the
+/// super admin
+fn setup_alice_canister_for_cdao_deployment() -> (PocketIc, Principal, Principal, Principal) {
+    let (pocket_ic, known_principal) = get_new_pocket_ic_env();
+    let platform_canister_id = known_principal
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .cloned()
+        .unwrap();
+
+    let super_admin = get_global_super_admin_principal_id();
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+    let charlie_global_admin = get_mock_user_charlie_principal_id();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "add_principal_as_global_admin",
+            candid::encode_one(charlie_global_admin).unwrap(),
+        )
+        .unwrap();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "update_global_known_principal",
+            candid::encode_args((
+                KnownPrincipalType::CanisterIdSnsWasm,
+                Principal::from_text(SNS_WASM_W_PRINCIPAL_ID).unwrap(),
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+
+    let subnet_orchestrator_canister_id: Principal = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[1]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..150 {
+        pocket_ic.tick();
+    }
+
+    let alice_principal = get_mock_user_alice_principal_id();
+    let alice_canister_id: Principal = pocket_ic
+        .update_call(
+            subnet_orchestrator_canister_id,
+            alice_principal,
+            "get_requester_principals_canister_id_create_if_not_exists",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| {
+            let response: Result<Principal, String> = match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("\n🛑 get requester principals canister id failed\n"),
+            };
+            response
+        })
+        .unwrap()
+        .unwrap();
+
+    setup_sns_w_canister_for_creator_dao(&pocket_ic, super_admin);
+
+    (
+        pocket_ic,
+        subnet_orchestrator_canister_id,
+        alice_canister_id,
+        super_admin,
+    )
+}
+
+fn get_cdao_deployments(
+    pocket_ic: &PocketIc,
+    user_canister_id: Principal,
+    caller: Principal,
+) -> Vec<CdaoDeploymentDetails> {
+    pocket_ic
+        .query_call(
+            user_canister_id,
+            caller,
+            "get_cdao_deployments",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+            _ => panic!("\n🛑 get_cdao_deployments failed\n"),
+        })
+        .unwrap()
+}
+
+fn set_failure_injection(
+    pocket_ic: &PocketIc,
+    user_canister_id: Principal,
+    subnet_orchestrator_canister_id: Principal,
+    method_name: &str,
+    step: Option<CdaoDeploymentStep>,
+) {
+    // * the subnet orchestrator controls the user canister
+    pocket_ic
+        .update_call(
+            user_canister_id,
+            subnet_orchestrator_canister_id,
+            method_name,
+            candid::encode_one(step).unwrap(),
+        )
+        .unwrap();
+}
+
+#[test]
+pub fn test_cdao_deployment_rollback_at_every_step() {
+    let (pocket_ic, subnet_orchestrator_canister_id, alice_canister_id, super_admin) =
+        setup_alice_canister_for_cdao_deployment();
+    let alice_principal = get_mock_user_alice_principal_id();
+
+    let sns_init_payload = get_sns_init_payload(super_admin, alice_principal);
+
+    let failing_steps = [
+        CdaoDeploymentStep::AllotCanisters,
+        CdaoDeploymentStep::DepositCycles,
+        CdaoDeploymentStep::InstallWasms,
+        CdaoDeploymentStep::UpdateControllers,
+        CdaoDeploymentStep::Register,
+    ];
+
+    for (number_of_failed_deployments, failing_step) in failing_steps.into_iter().enumerate() {
+        set_failure_injection(
+            &pocket_ic,
+            alice_canister_id,
+            subnet_orchestrator_canister_id,
+            "set_cdao_deployment_failure_injection",
+            Some(failing_step),
+        );
+
+        let backup_capacity_before_deployment =
+            get_subnet_backup_capacity(&pocket_ic, subnet_orchestrator_canister_id);
+
+        let deploy_result = deploy_cdao_sns(
+            &pocket_ic,
+            alice_canister_id,
+            alice_principal,
+            &sns_init_payload,
+        );
+        assert!(deploy_result.is_err(), "step {:?}", failing_step);
+
+        let cdao_deployments = get_cdao_deployments(&pocket_ic, alice_canister_id, super_admin);
+
+        assert_eq!(cdao_deployments.len(), number_of_failed_deployments + 1);
+        let failed_deployment = cdao_deployments.last().unwrap();
+        assert_eq!(failed_deployment.status, CdaoDeploymentStatus::Failed);
+        assert_eq!(failed_deployment.step, CdaoDeploymentStep::AllotCanisters);
+        assert!(failed_deployment
+            .error
+            .as_ref()
+            .unwrap()
+            .contains(&format!("{:?}", failing_step)));
+
+        // * every allotted canister was emptied and returned to the subnet orchestrator
+        let expected_number_of_canisters = if failing_step == CdaoDeploymentStep::AllotCanisters {
+            0
+        } else {
+            5
+        };
+        assert_eq!(
+            failed_deployment.canister_ids.len(),
+            expected_number_of_canisters
+        );
+        for canister_id in &failed_deployment.canister_ids {
+            assert_eq!(
+                pocket_ic.get_controllers(*canister_id),
+                vec![subnet_orchestrator_canister_id]
+            );
+        }
+        assert!(
+            get_subnet_backup_capacity(&pocket_ic, subnet_orchestrator_canister_id)
+                >= backup_capacity_before_deployment
+        );
+
+        let deployed_cdao_canisters: Vec<DeployedCdaoCanisters> = pocket_ic
+            .query_call(
+                alice_canister_id,
+                alice_principal,
+                "deployed_cdao_canisters",
+                candid::encode_one(()).unwrap(),
+            )
+            .map(|res| match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("\n🛑 deployed_cdao_canisters failed\n"),
+            })
+            .unwrap();
+        assert!(deployed_cdao_canisters.is_empty());
+    }
+
+    set_failure_injection(
+        &pocket_ic,
+        alice_canister_id,
+        subnet_orchestrator_canister_id,
+        "set_cdao_deployment_failure_injection",
+        None,
+    );
+
+    let deploy_result = deploy_cdao_sns(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        &sns_init_payload,
+    );
+    assert!(deploy_result.is_ok());
+}
+
+#[test]
+pub fn test_cdao_deployment_rollback_is_resumed_after_a_failed_compensation() {
+    let (pocket_ic, subnet_orchestrator_canister_id, alice_canister_id, super_admin) =
+        setup_alice_canister_for_cdao_deployment();
+    let alice_principal = get_mock_user_alice_principal_id();
+    let sns_init_payload = get_sns_init_payload(super_admin, alice_principal);
+
+    set_failure_injection(
+        &pocket_ic,
+        alice_canister_id,
+        subnet_orchestrator_canister_id,
+        "set_cdao_deployment_failure_injection",
+        Some(CdaoDeploymentStep::UpdateControllers),
+    );
+    set_failure_injection(
+        &pocket_ic,
+        alice_canister_id,
+        subnet_orchestrator_canister_id,
+        "set_cdao_compensation_failure_injection",
+        Some(CdaoDeploymentStep::InstallWasms),
+    );
+
+    let deploy_result = deploy_cdao_sns(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        &sns_init_payload,
+    );
+    assert!(deploy_result.is_err());
+
+    // * the rollback stops at the compensation that failed instead of marking the deployment failed
+    let cdao_deployments = get_cdao_deployments(&pocket_ic, alice_canister_id, super_admin);
+    assert_eq!(cdao_deployments.len(), 1);
+    assert_eq!(
+        cdao_deployments[0].status,
+        CdaoDeploymentStatus::RollingBack
+    );
+    assert_eq!(cdao_deployments[0].step, CdaoDeploymentStep::InstallWasms);
+    assert!(cdao_deployments[0].compensation_error.is_some());
+
+    // * the failure injections are not kept across the upgrade, which resumes the rollback
+    let individual_template_args = IndividualUserTemplateInitArgs {
+        known_principal_ids: None,
+        profile_owner: None,
+        upgrade_version_number: None,
+        url_to_send_canister_metrics_to: None,
+        version: "v1.0.0".into(),
+    };
+    pocket_ic
+        .upgrade_canister(
+            alice_canister_id,
+            individual_template_canister_wasm(),
+            candid::encode_one(individual_template_args).unwrap(),
+            Some(subnet_orchestrator_canister_id),
+        )
+        .unwrap();
+
+    for _ in 0..20 {
+        pocket_ic.tick();
+    }
+
+    let cdao_deployments = get_cdao_deployments(&pocket_ic, alice_canister_id, super_admin);
+    assert_eq!(cdao_deployments[0].status, CdaoDeploymentStatus::Failed);
+    assert_eq!(cdao_deployments[0].step, CdaoDeploymentStep::AllotCanisters);
+    assert_eq!(cdao_deployments[0].compensation_error, None);
+    assert_eq!(cdao_deployments[0].canister_ids.len(), 5);
+    for canister_id in &cdao_deployments[0].canister_ids {
+        assert_eq!(
+            pocket_ic.get_controllers(*canister_id),
+            vec![subnet_orchestrator_canister_id]
+        );
+    }
+
+    // * a rollback that stopped is also resumed by an admin
+    set_failure_injection(
+        &pocket_ic,
+        alice_canister_id,
+        subnet_orchestrator_canister_id,
+        "set_cdao_deployment_failure_injection",
+        Some(CdaoDeploymentStep::Register),
+    );
+    set_failure_injection(
+        &pocket_ic,
+        alice_canister_id,
+        subnet_orchestrator_canister_id,
+        "set_cdao_compensation_failure_injection",
+        Some(CdaoDeploymentStep::UpdateControllers),
+    );
+
+    let deploy_result = deploy_cdao_sns(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        &sns_init_payload,
+    );
+    assert!(deploy_result.is_err());
+
+    let cdao_deployments = get_cdao_deployments(&pocket_ic, alice_canister_id, super_admin);
+    assert_eq!(
+        cdao_deployments[1].status,
+        CdaoDeploymentStatus::RollingBack
+    );
+    assert_eq!(
+        cdao_deployments[1].step,
+        CdaoDeploymentStep::UpdateControllers
+    );
+
+    set_failure_injection(
+        &pocket_ic,
+        alice_canister_id,
+        subnet_orchestrator_canister_id,
+        "set_cdao_compensation_failure_injection",
+        None,
+    );
+    pocket_ic
+        .update_call(
+            alice_canister_id,
+            super_admin,
+            "resume_cdao_deployments",
+            candid::encode_one(()).unwrap(),
+        )
+        .unwrap();
+
+    for _ in 0..20 {
+        pocket_ic.tick();
+    }
+
+    let cdao_deployments = get_cdao_deployments(&pocket_ic, alice_canister_id, super_admin);
+    assert_eq!(cdao_deployments[1].status, CdaoDeploymentStatus::Failed);
+    assert_eq!(cdao_deployments[1].step, CdaoDeploymentStep::AllotCanisters);
+}
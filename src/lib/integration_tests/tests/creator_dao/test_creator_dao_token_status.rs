@@ -0,0 +1,171 @@
+use candid::{Decode, Nat, Principal};
+use pocket_ic::{PocketIc, WasmResult};
+use shared_utils::{
+    canister_specific::individual_user_template::types::cdao::{
+        CreatorDaoTokenStatus, CreatorDaoTokenStatusError,
+    },
+    common::types::known_principal::KnownPrincipalType,
+    constant::SNS_WASM_W_PRINCIPAL_ID,
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_principal_id,
+    },
+};
+
+use crate::utils::{setup_default_sns_creator_token, setup_sns_w_canister_for_creator_dao};
+
+fn get_creator_dao_token_status(
+    pocket_ic: &PocketIc,
+    user_canister_id: Principal,
+    caller: Principal,
+    token_root: Principal,
+) -> Result<CreatorDaoTokenStatus, CreatorDaoTokenStatusError> {
+    pocket_ic
+        .update_call(
+            user_canister_id,
+            caller,
+            "get_creator_dao_token_status",
+            candid::encode_one(token_root).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(
+                &payload,
+                Result<CreatorDaoTokenStatus, CreatorDaoTokenStatusError>
+            )
+            .unwrap(),
+            _ => panic!("\n🛑 get_creator_dao_token_status failed\n"),
+        })
+        .unwrap()
+}
+
+#[test]
+pub fn test_creator_dao_token_status_is_refreshed_only_by_the_owner() {
+    let (pocket_ic, known_principal) = get_new_pocket_ic_env();
+    let platform_canister_id = known_principal
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .cloned()
+        .unwrap();
+
+    let super_admin = get_global_super_admin_principal_id();
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+    let charlie_global_admin = get_mock_user_charlie_principal_id();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "add_principal_as_global_admin",
+            candid::encode_one(charlie_global_admin).unwrap(),
+        )
+        .unwrap();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "update_global_known_principal",
+            candid::encode_args((
+                KnownPrincipalType::CanisterIdSnsWasm,
+                Principal::from_text(SNS_WASM_W_PRINCIPAL_ID).unwrap(),
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+
+    let subnet_orchestrator_canister_id: Principal = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[1]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..150 {
+        pocket_ic.tick();
+    }
+
+    let alice_principal = get_mock_user_alice_principal_id();
+    let alice_canister_id: Principal = pocket_ic
+        .update_call(
+            subnet_orchestrator_canister_id,
+            alice_principal,
+            "get_requester_principals_canister_id_create_if_not_exists",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| {
+            let response: Result<Principal, String> = match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("\n🛑 get requester principals canister id failed\n"),
+            };
+            response
+        })
+        .unwrap()
+        .unwrap();
+
+    setup_sns_w_canister_for_creator_dao(&pocket_ic, super_admin);
+
+    let deployed_canisters = setup_default_sns_creator_token(
+        &pocket_ic,
+        super_admin,
+        alice_principal,
+        alice_canister_id,
+    );
+    let root_canister = deployed_canisters.root;
+    let bob_principal = get_mock_user_bob_principal_id();
+
+    // * anyone else than the owner is only served a status that was already fetched
+    assert_eq!(
+        get_creator_dao_token_status(&pocket_ic, alice_canister_id, bob_principal, root_canister),
+        Err(CreatorDaoTokenStatusError::StatusNotFetched)
+    );
+    assert_eq!(
+        get_creator_dao_token_status(
+            &pocket_ic,
+            alice_canister_id,
+            bob_principal,
+            Principal::anonymous()
+        ),
+        Err(CreatorDaoTokenStatusError::TokenNotFound)
+    );
+
+    let status = get_creator_dao_token_status(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        root_canister,
+    )
+    .unwrap();
+    assert_eq!(status.root, root_canister);
+    assert!(status.total_supply > Nat::from(0_u64));
+    assert!(status.number_of_neurons > 0);
+
+    assert_eq!(
+        get_creator_dao_token_status(&pocket_ic, alice_canister_id, bob_principal, root_canister),
+        Ok(status.clone())
+    );
+
+    let cached_statuses = pocket_ic
+        .query_call(
+            alice_canister_id,
+            bob_principal,
+            "get_cached_creator_dao_token_statuses",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, Vec<CreatorDaoTokenStatus>).unwrap(),
+            _ => panic!("\n🛑 get_cached_creator_dao_token_statuses failed\n"),
+        })
+        .unwrap();
+    assert_eq!(cached_statuses, vec![status]);
+}
@@ -0,0 +1,229 @@
+use candid::{Decode, Principal};
+use ic_sns_init::pb::v1::SnsInitPayload;
+use pocket_ic::{PocketIc, WasmResult};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        cdao::DeployedCdaoCanisters,
+        creator_token_quota::{CreatorTokenQuota, CreatorTokenQuotaConfig},
+        error::CdaoDeployError,
+    },
+    common::types::known_principal::KnownPrincipalType,
+    constant::MAX_LIMIT_FOR_CREATOR_DAO_SNS_TOKEN,
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_principal_id,
+    },
+};
+
+fn get_creator_token_quota(
+    pocket_ic: &PocketIc,
+    user_canister_id: Principal,
+    user_principal: Principal,
+) -> CreatorTokenQuota {
+    pocket_ic
+        .query_call(
+            user_canister_id,
+            user_principal,
+            "get_creator_token_quota",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+            _ => panic!("\n🛑 get_creator_token_quota failed\n"),
+        })
+        .unwrap()
+}
+
+#[test]
+pub fn test_creator_token_quota_configured_by_platform_orchestrator() {
+    let (pocket_ic, known_principal) = get_new_pocket_ic_env();
+    let platform_canister_id = known_principal
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .cloned()
+        .unwrap();
+
+    let super_admin = get_global_super_admin_principal_id();
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+    let charlie_global_admin = get_mock_user_charlie_principal_id();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "add_principal_as_global_admin",
+            candid::encode_one(charlie_global_admin).unwrap(),
+        )
+        .unwrap();
+
+    let subnet_orchestrator_canister_id: Principal = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[1]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..150 {
+        pocket_ic.tick();
+    }
+
+    let alice_principal = get_mock_user_alice_principal_id();
+    let alice_canister_id: Principal = pocket_ic
+        .update_call(
+            subnet_orchestrator_canister_id,
+            alice_principal,
+            "get_requester_principals_canister_id_create_if_not_exists",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| {
+            let response: Result<Principal, String> = match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("\n🛑 get requester principals canister id failed\n"),
+            };
+            response
+        })
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        get_creator_token_quota(&pocket_ic, alice_canister_id, alice_principal),
+        CreatorTokenQuota {
+            quota: MAX_LIMIT_FOR_CREATOR_DAO_SNS_TOKEN,
+            used: 0,
+            remaining: MAX_LIMIT_FOR_CREATOR_DAO_SNS_TOKEN,
+            quota_override: None,
+        }
+    );
+
+    let update_override_result = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "update_creator_token_quota_override_for_individual_canister",
+            candid::encode_args((alice_canister_id, Some(0_u64))).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, Result<(), String>).unwrap(),
+            _ => panic!("\n🛑 update creator token quota override failed\n"),
+        })
+        .unwrap();
+    assert!(update_override_result.is_ok());
+
+    // * the quota is checked before anything is deployed
+    let deploy_result = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "deploy_cdao_sns",
+            candid::encode_args((SnsInitPayload::default(), 300_u64)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<DeployedCdaoCanisters, CdaoDeployError>).unwrap()
+            }
+            _ => panic!("\n🛑 deploy cdao failed with {:?}", res),
+        })
+        .unwrap();
+    assert_eq!(deploy_result, Err(CdaoDeployError::TokenLimit(0)));
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "update_creator_token_quota_override_for_individual_canister",
+            candid::encode_args((alice_canister_id, None::<u64>)).unwrap(),
+        )
+        .unwrap();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "update_creator_token_quota_config_in_the_network",
+            candid::encode_one(CreatorTokenQuotaConfig {
+                default_quota: 5,
+                require_registered_session: true,
+                min_account_age_in_seconds: 0,
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+    for _ in 0..10 {
+        pocket_ic.tick();
+    }
+
+    // * alice has not registered yet
+    assert_eq!(
+        get_creator_token_quota(&pocket_ic, alice_canister_id, alice_principal).quota,
+        0
+    );
+
+    let update_config_result = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "update_creator_token_quota_config_in_the_network",
+            candid::encode_one(CreatorTokenQuotaConfig {
+                default_quota: 5,
+                require_registered_session: false,
+                min_account_age_in_seconds: 0,
+            })
+            .unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, Result<String, String>).unwrap(),
+            _ => panic!("\n🛑 update creator token quota config failed\n"),
+        })
+        .unwrap();
+    assert!(update_config_result.is_ok());
+
+    for _ in 0..10 {
+        pocket_ic.tick();
+    }
+
+    assert_eq!(
+        get_creator_token_quota(&pocket_ic, alice_canister_id, alice_principal),
+        CreatorTokenQuota {
+            quota: 5,
+            used: 0,
+            remaining: 5,
+            quota_override: None,
+        }
+    );
+
+    // * a user signing up later gets the config stored in the user index
+    let bob_principal = get_mock_user_bob_principal_id();
+    let bob_canister_id: Principal = pocket_ic
+        .update_call(
+            subnet_orchestrator_canister_id,
+            bob_principal,
+            "get_requester_principals_canister_id_create_if_not_exists",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| {
+            let response: Result<Principal, String> = match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("\n🛑 get requester principals canister id failed\n"),
+            };
+            response
+        })
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        get_creator_token_quota(&pocket_ic, bob_canister_id, bob_principal).quota,
+        5
+    );
+}
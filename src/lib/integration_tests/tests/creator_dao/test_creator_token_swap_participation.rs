@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use candid::{encode_args, Decode, Encode, Nat, Principal};
+use pocket_ic::{PocketIc, WasmResult};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        error::{SnsNeuronError, SwapParticipationError},
+        sns_neuron::SnsNeuronDetails,
+        swap_participation::{SwapCommitment, SwapCommitmentStatus},
+    },
+    common::types::known_principal::KnownPrincipalType,
+    constant::SNS_WASM_W_PRINCIPAL_ID,
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_principal_id,
+    },
+};
+
+use crate::{
+    types,
+    utils::{deploy_default_sns_creator_token, setup_sns_w_canister_for_creator_dao},
+    ICP_LEDGER_CANISTER_ID,
+};
+
+fn claim_creator_token_swap(
+    pocket_ic: &PocketIc,
+    user_canister_id: Principal,
+    user_principal: Principal,
+    token_root: Principal,
+) -> Result<SwapCommitment, SwapParticipationError> {
+    pocket_ic
+        .update_call(
+            user_canister_id,
+            user_principal,
+            "claim_creator_token_swap",
+            candid::encode_one(token_root).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<SwapCommitment, SwapParticipationError>).unwrap()
+            }
+            _ => panic!("\n🛑 claim_creator_token_swap failed\n"),
+        })
+        .unwrap()
+}
+
+#[test]
+pub fn test_participate_in_creator_token_swap_from_user_canister() {
+    let (pocket_ic, known_principal) = get_new_pocket_ic_env();
+    let platform_canister_id = known_principal
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .cloned()
+        .unwrap();
+
+    let super_admin = get_global_super_admin_principal_id();
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+    let charlie_global_admin = get_mock_user_charlie_principal_id();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "add_principal_as_global_admin",
+            candid::encode_one(charlie_global_admin).unwrap(),
+        )
+        .unwrap();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "update_global_known_principal",
+            candid::encode_args((
+                KnownPrincipalType::CanisterIdSnsWasm,
+                Principal::from_text(SNS_WASM_W_PRINCIPAL_ID).unwrap(),
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+
+    let subnet_orchestrator_canister_id: Principal = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[1]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..150 {
+        pocket_ic.tick();
+    }
+
+    let alice_principal = get_mock_user_alice_principal_id();
+    let alice_canister_id: Principal = pocket_ic
+        .update_call(
+            subnet_orchestrator_canister_id,
+            alice_principal,
+            "get_requester_principals_canister_id_create_if_not_exists",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| {
+            let response: Result<Principal, String> = match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("\n🛑 get requester principals canister id failed\n"),
+            };
+            response
+        })
+        .unwrap()
+        .unwrap();
+
+    setup_sns_w_canister_for_creator_dao(&pocket_ic, super_admin);
+
+    let deployed_canisters = deploy_default_sns_creator_token(
+        &pocket_ic,
+        super_admin,
+        alice_principal,
+        alice_canister_id,
+    );
+    let root_canister = deployed_canisters.root;
+
+    pocket_ic.advance_time(Duration::from_secs(200));
+    pocket_ic.tick();
+
+    // * the super admin is the minting account of the ICP ledger
+    let transfer_args = types::TransferArg {
+        from_subaccount: None,
+        to: types::Account {
+            owner: alice_canister_id,
+            subaccount: None,
+        },
+        fee: None,
+        created_at_time: None,
+        memo: None,
+        amount: Nat::from(2_000_000u64),
+    };
+    pocket_ic
+        .update_call(
+            Principal::from_text(ICP_LEDGER_CANISTER_ID).unwrap(),
+            super_admin,
+            "icrc1_transfer",
+            Encode!(&transfer_args).unwrap(),
+        )
+        .unwrap();
+
+    // * only the owner of the canister can participate
+    let participation_result = pocket_ic
+        .update_call(
+            alice_canister_id,
+            get_mock_user_bob_principal_id(),
+            "participate_in_creator_token_swap",
+            encode_args((
+                root_canister,
+                1_000_000u64,
+                Some("GET RICH QUICK".to_string()),
+            ))
+            .unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<SwapCommitment, SwapParticipationError>).unwrap()
+            }
+            _ => panic!("\n🛑 participate_in_creator_token_swap failed\n"),
+        })
+        .unwrap();
+    assert_eq!(
+        participation_result,
+        Err(SwapParticipationError::Unauthenticated)
+    );
+
+    let commitment = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "participate_in_creator_token_swap",
+            encode_args((
+                root_canister,
+                1_000_000u64,
+                Some("GET RICH QUICK".to_string()),
+            ))
+            .unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<SwapCommitment, SwapParticipationError>).unwrap()
+            }
+            _ => panic!("\n🛑 participate_in_creator_token_swap failed\n"),
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(commitment.token_root, root_canister);
+    assert_eq!(commitment.swap_canister, deployed_canisters.swap);
+    assert_eq!(commitment.amount_icp_e8s, 1_000_000);
+    assert_eq!(commitment.status, SwapCommitmentStatus::Committed);
+
+    // * refreshing again does not change the accepted amount
+    let refreshed_commitment = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "refresh_creator_token_swap_commitment",
+            encode_args((root_canister, Some("GET RICH QUICK".to_string()))).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<SwapCommitment, SwapParticipationError>).unwrap()
+            }
+            _ => panic!("\n🛑 refresh_creator_token_swap_commitment failed\n"),
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(refreshed_commitment.amount_icp_e8s, 1_000_000);
+    assert_eq!(refreshed_commitment.status, SwapCommitmentStatus::Committed);
+
+    let claim_result = claim_creator_token_swap(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        root_canister,
+    );
+    assert_eq!(claim_result, Err(SwapParticipationError::SwapNotFinalized));
+
+    pocket_ic.advance_time(Duration::from_secs(301));
+    for _ in 0..500 {
+        pocket_ic.tick();
+    }
+
+    let commitment = claim_creator_token_swap(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        root_canister,
+    )
+    .unwrap();
+    assert_eq!(commitment.amount_icp_e8s, 1_000_000);
+    assert_eq!(commitment.status, SwapCommitmentStatus::Claimed);
+
+    let commitments = pocket_ic
+        .query_call(
+            alice_canister_id,
+            alice_principal,
+            "get_creator_token_swap_commitments",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, Vec<SwapCommitment>).unwrap(),
+            _ => panic!("\n🛑 get_creator_token_swap_commitments failed\n"),
+        })
+        .unwrap();
+    assert_eq!(commitments, vec![commitment]);
+
+    let claim_result = claim_creator_token_swap(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        root_canister,
+    );
+    assert_eq!(claim_result, Err(SwapParticipationError::NothingToClaim));
+
+    // * the neuron basket of the swap is controlled by the user canister
+    let neurons = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "list_sns_neurons",
+            candid::encode_one(root_canister).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<Vec<SnsNeuronDetails>, SnsNeuronError>).unwrap()
+            }
+            _ => panic!("\n🛑 list_sns_neurons failed\n"),
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(neurons.len(), 2);
+}
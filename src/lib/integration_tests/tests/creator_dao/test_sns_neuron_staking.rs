@@ -0,0 +1,325 @@
+use candid::{encode_args, Decode, Encode, Nat, Principal};
+use ic_base_types::PrincipalId;
+use ic_sns_governance::pb::v1::{
+    manage_neuron, manage_neuron_response, neuron, proposal, ListNeurons, ListNeuronsResponse,
+    ManageNeuron, ManageNeuronResponse, Motion, Proposal,
+};
+use pocket_ic::{PocketIc, WasmResult};
+use shared_utils::{
+    canister_specific::individual_user_template::types::{
+        error::SnsNeuronError,
+        sns_neuron::{PendingSnsNeuronStake, SnsNeuronDetails, SnsVote},
+    },
+    common::types::known_principal::KnownPrincipalType,
+    constant::SNS_WASM_W_PRINCIPAL_ID,
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{
+        get_global_super_admin_principal_id, get_mock_user_alice_principal_id,
+        get_mock_user_bob_principal_id, get_mock_user_charlie_principal_id,
+    },
+};
+
+use crate::{
+    types,
+    utils::{setup_default_sns_creator_token, setup_sns_w_canister_for_creator_dao},
+};
+
+fn list_sns_neurons(
+    pocket_ic: &PocketIc,
+    user_canister_id: Principal,
+    user_principal: Principal,
+    token_root: Principal,
+) -> Vec<SnsNeuronDetails> {
+    pocket_ic
+        .update_call(
+            user_canister_id,
+            user_principal,
+            "list_sns_neurons",
+            candid::encode_one(token_root).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<Vec<SnsNeuronDetails>, SnsNeuronError>).unwrap()
+            }
+            _ => panic!("\n🛑 list_sns_neurons failed\n"),
+        })
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+pub fn test_stake_creator_token_and_vote_from_user_canister() {
+    let (pocket_ic, known_principal) = get_new_pocket_ic_env();
+    let platform_canister_id = known_principal
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .cloned()
+        .unwrap();
+
+    let super_admin = get_global_super_admin_principal_id();
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+    let charlie_global_admin = get_mock_user_charlie_principal_id();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "add_principal_as_global_admin",
+            candid::encode_one(charlie_global_admin).unwrap(),
+        )
+        .unwrap();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "update_global_known_principal",
+            candid::encode_args((
+                KnownPrincipalType::CanisterIdSnsWasm,
+                Principal::from_text(SNS_WASM_W_PRINCIPAL_ID).unwrap(),
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+
+    let subnet_orchestrator_canister_id: Principal = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[1]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..150 {
+        pocket_ic.tick();
+    }
+
+    let alice_principal = get_mock_user_alice_principal_id();
+    let alice_canister_id: Principal = pocket_ic
+        .update_call(
+            subnet_orchestrator_canister_id,
+            alice_principal,
+            "get_requester_principals_canister_id_create_if_not_exists",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| {
+            let response: Result<Principal, String> = match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("\n🛑 get requester principals canister id failed\n"),
+            };
+            response
+        })
+        .unwrap()
+        .unwrap();
+
+    setup_sns_w_canister_for_creator_dao(&pocket_ic, super_admin);
+
+    let deployed_canisters = setup_default_sns_creator_token(
+        &pocket_ic,
+        super_admin,
+        alice_principal,
+        alice_canister_id,
+    );
+    let root_canister = deployed_canisters.root;
+    let ledger_canister = deployed_canisters.ledger;
+    let gov_canister = deployed_canisters.governance;
+
+    let transfer_args = types::TransferArg {
+        from_subaccount: None,
+        to: types::Account {
+            owner: alice_canister_id,
+            subaccount: None,
+        },
+        fee: None,
+        created_at_time: None,
+        memo: None,
+        amount: Nat::from(10_000u64),
+    };
+    pocket_ic
+        .update_call(
+            ledger_canister,
+            alice_principal,
+            "icrc1_transfer",
+            Encode!(&transfer_args).unwrap(),
+        )
+        .unwrap();
+
+    // * only the owner of the canister can stake
+    let stake_result = pocket_ic
+        .update_call(
+            alice_canister_id,
+            get_mock_user_bob_principal_id(),
+            "stake_creator_token",
+            encode_args((root_canister, 1_000u64, 2u32)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<Vec<u8>, SnsNeuronError>).unwrap()
+            }
+            _ => panic!("\n🛑 stake_creator_token failed\n"),
+        })
+        .unwrap();
+    assert_eq!(stake_result, Err(SnsNeuronError::Unauthenticated));
+
+    let neuron_id = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "stake_creator_token",
+            encode_args((root_canister, 1_000u64, 2u32)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<Vec<u8>, SnsNeuronError>).unwrap()
+            }
+            _ => panic!("\n🛑 stake_creator_token failed\n"),
+        })
+        .unwrap()
+        .unwrap();
+
+    let neurons = list_sns_neurons(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        root_canister,
+    );
+    assert_eq!(neurons.len(), 1);
+    assert_eq!(neurons[0].neuron_id, neuron_id);
+    assert_eq!(neurons[0].stake_e8s, 1_000);
+    assert_eq!(neurons[0].dissolve_delay_seconds, Some(2));
+
+    // * the stake is no longer pending once its neuron is claimed and configured
+    let pending_stakes = pocket_ic
+        .query_call(
+            alice_canister_id,
+            alice_principal,
+            "get_pending_sns_neuron_stakes",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, Vec<PendingSnsNeuronStake>).unwrap(),
+            _ => panic!("\n🛑 get_pending_sns_neuron_stakes failed\n"),
+        })
+        .unwrap();
+    assert!(pending_stakes.is_empty());
+    let claim_result = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "claim_or_refresh_sns_neuron",
+            candid::encode_one(0u64).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => {
+                Decode!(&payload, Result<Vec<u8>, SnsNeuronError>).unwrap()
+            }
+            _ => panic!("\n🛑 claim_or_refresh_sns_neuron failed\n"),
+        })
+        .unwrap();
+    assert_eq!(claim_result, Err(SnsNeuronError::PendingStakeNotFound));
+
+    let increase_result = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "increase_sns_neuron_dissolve_delay",
+            encode_args((root_canister, neuron_id.clone(), 1u32)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, Result<(), SnsNeuronError>).unwrap(),
+            _ => panic!("\n🛑 increase_sns_neuron_dissolve_delay failed\n"),
+        })
+        .unwrap();
+    assert_eq!(increase_result, Ok(()));
+
+    let neurons = list_sns_neurons(
+        &pocket_ic,
+        alice_canister_id,
+        alice_principal,
+        root_canister,
+    );
+    assert_eq!(neurons[0].dissolve_delay_seconds, Some(3));
+
+    // * alice makes a proposal with her remaining developer neuron
+    let alice_neurons = pocket_ic
+        .update_call(
+            gov_canister,
+            alice_principal,
+            "list_neurons",
+            candid::encode_one(ListNeurons {
+                of_principal: Some(PrincipalId(alice_principal)),
+                limit: 10,
+                start_page_at: None,
+            })
+            .unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, ListNeuronsResponse).unwrap(),
+            _ => panic!("\n🛑 list_neurons failed\n"),
+        })
+        .unwrap()
+        .neurons;
+    let proposing_neuron = alice_neurons
+        .iter()
+        .find(|neuron| {
+            neuron.cached_neuron_stake_e8s > 0
+                && matches!(
+                    neuron.dissolve_state,
+                    Some(neuron::DissolveState::DissolveDelaySeconds(delay)) if delay > 0
+                )
+        })
+        .unwrap();
+
+    let make_proposal_response = pocket_ic
+        .update_call(
+            gov_canister,
+            alice_principal,
+            "manage_neuron",
+            candid::encode_one(ManageNeuron {
+                subaccount: proposing_neuron.id.as_ref().unwrap().id.clone(),
+                command: Some(manage_neuron::Command::MakeProposal(Proposal {
+                    title: "Motion from the creator".to_string(),
+                    summary: "Testing votes from the user canister".to_string(),
+                    url: "https://google.com".to_string(),
+                    action: Some(proposal::Action::Motion(Motion {
+                        motion_text: "Vote from the user canister".to_string(),
+                    })),
+                })),
+            })
+            .unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, ManageNeuronResponse).unwrap(),
+            _ => panic!("\n🛑 manage_neuron failed\n"),
+        })
+        .unwrap();
+    let Some(manage_neuron_response::Command::MakeProposal(make_proposal)) =
+        make_proposal_response.command
+    else {
+        panic!("\n🛑 make proposal failed\n");
+    };
+    let proposal_id = make_proposal.proposal_id.unwrap().id;
+
+    let vote_result = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "vote_on_sns_proposal",
+            encode_args((root_canister, neuron_id, proposal_id, SnsVote::Yes)).unwrap(),
+        )
+        .map(|res| match res {
+            WasmResult::Reply(payload) => Decode!(&payload, Result<(), SnsNeuronError>).unwrap(),
+            _ => panic!("\n🛑 vote_on_sns_proposal failed\n"),
+        })
+        .unwrap();
+    assert_eq!(vote_result, Ok(()));
+}
@@ -185,7 +185,9 @@ pub fn setup_sns_w_canister_for_creator_dao(pocket_ic: &PocketIc, super_admin: P
     }
 }
 
-pub fn setup_default_sns_creator_token(
+/// Deploys the default creator token of the user canister. Its swap opens right away and is due
+/// 300 seconds later.
+pub fn deploy_default_sns_creator_token(
     pocket_ic: &PocketIc,
     super_admin: Principal,
     user_principal: Principal,
@@ -319,11 +321,23 @@ pub fn setup_default_sns_creator_token(
     }
 
     assert!(deployed_canisters.len() == 1);
-    let res = deployed_canisters[0].clone();
-    let root_canister = res.root;
-    let swap_canister = res.swap;
-    let gov_canister = res.governance;
-    let ledger_canister = res.ledger;
+    deployed_canisters[0].clone()
+}
+
+pub fn setup_default_sns_creator_token(
+    pocket_ic: &PocketIc,
+    super_admin: Principal,
+    user_principal: Principal,
+    user_canister_id: Principal,
+) -> DeployedCdaoCanisters {
+    let tx_fee = 1u64;
+
+    let deployed_canisters =
+        deploy_default_sns_creator_token(pocket_ic, super_admin, user_principal, user_canister_id);
+    let root_canister = deployed_canisters.root;
+    let swap_canister = deployed_canisters.swap;
+    let gov_canister = deployed_canisters.governance;
+    let ledger_canister = deployed_canisters.ledger;
 
     ic_cdk::println!("🧪🧪🧪 Swap Canister ID: {:?}", swap_canister.to_string());
 
@@ -557,5 +571,5 @@ pub fn setup_default_sns_creator_token(
     let expected_balance = Nat::from(60_000_000_000 - tx_fee);
     ic_cdk::println!("🧪 Expected Balance: {:?}", expected_balance);
 
-    deployed_canisters[0].clone()
+    deployed_canisters
 }
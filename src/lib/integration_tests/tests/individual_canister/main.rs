@@ -1 +1,3 @@
-pub mod platform_orchestrator_on_receiving_call_from_global_admin_can_make_individual_canister_logs_public_test;
\ No newline at end of file
+pub mod platform_orchestrator_on_receiving_call_from_global_admin_can_make_individual_canister_logs_public_test;
+pub mod utility_token_ledger_mirror_test;
+pub mod utility_token_transfer_test;
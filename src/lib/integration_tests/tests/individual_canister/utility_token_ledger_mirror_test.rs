@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use candid::{Nat, Principal};
+use ic_icrc1_ledger::{InitArgsBuilder, LedgerArgument};
+use icrc_ledger_types::icrc1::account::Account;
+use pocket_ic::WasmResult;
+use shared_utils::{
+    canister_specific::individual_user_template::types::token::{
+        UtilityTokenLedgerMirrorStatus, UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT, UTILITY_TOKEN_NAME,
+        UTILITY_TOKEN_SYMBOL,
+    },
+    common::types::known_principal::KnownPrincipalType,
+    types::canister_specific::individual_user_template::error_types::UtilityTokenTransferError,
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{get_mock_user_alice_principal_id, get_mock_user_bob_principal_id},
+};
+
+#[test]
+fn when_utility_token_ledger_mirror_is_enabled_then_balance_changes_are_mirrored_to_the_ledger() {
+    let (pocket_ic, known_principal_map) = get_new_pocket_ic_env();
+
+    let alice_principal = get_mock_user_alice_principal_id();
+    let bob_principal = get_mock_user_bob_principal_id();
+
+    let platform_orchestrator_canister_id = known_principal_map
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .copied()
+        .unwrap();
+
+    let global_admin_principal = known_principal_map
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .copied()
+        .unwrap();
+
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+
+    let subnet_orchestrator_canister_id = pocket_ic
+        .update_call(
+            platform_orchestrator_canister_id,
+            global_admin_principal,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[0]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..50 {
+        pocket_ic.tick();
+    }
+
+    let get_user_canister_id = |user_principal: Principal| {
+        pocket_ic
+            .update_call(
+                subnet_orchestrator_canister_id,
+                user_principal,
+                "get_requester_principals_canister_id_create_if_not_exists",
+                candid::encode_one(()).unwrap(),
+            )
+            .map(|reply_payload| {
+                let canister_id_res: Result<Principal, String> = match reply_payload {
+                    WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                    _ => panic!(
+                        "\n🛑 get_requester_principals_canister_id_create_if_not_exists failed\n"
+                    ),
+                };
+                canister_id_res
+            })
+            .unwrap()
+            .unwrap()
+    };
+
+    let alice_canister_id = get_user_canister_id(alice_principal);
+    let bob_canister_id = get_user_canister_id(bob_principal);
+
+    for _ in 0..5 {
+        pocket_ic.tick();
+    }
+
+    let icrc1_balance_of = |canister_id: Principal, account: Account| {
+        pocket_ic
+            .query_call(
+                canister_id,
+                Principal::anonymous(),
+                "icrc1_balance_of",
+                candid::encode_one(account).unwrap(),
+            )
+            .map(|reply_payload| match reply_payload {
+                WasmResult::Reply(payload) => candid::decode_one::<Nat>(&payload).unwrap(),
+                _ => panic!("\n🛑 icrc1_balance_of failed\n"),
+            })
+            .unwrap()
+    };
+
+    let alice_account = Account {
+        owner: alice_principal,
+        subaccount: None,
+    };
+
+    // * the individual canister answers ICRC-1 reads for its own scope
+    assert_eq!(
+        icrc1_balance_of(alice_canister_id, alice_account),
+        Nat::from(1000_u64)
+    );
+    assert_eq!(
+        icrc1_balance_of(
+            alice_canister_id,
+            Account {
+                owner: bob_principal,
+                subaccount: None,
+            }
+        ),
+        Nat::from(0_u64)
+    );
+
+    let alice_token_symbol = pocket_ic
+        .query_call(
+            alice_canister_id,
+            Principal::anonymous(),
+            "icrc1_symbol",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| match reply_payload {
+            WasmResult::Reply(payload) => candid::decode_one::<String>(&payload).unwrap(),
+            _ => panic!("\n🛑 icrc1_symbol failed\n"),
+        })
+        .unwrap();
+    assert_eq!(alice_token_symbol, UTILITY_TOKEN_SYMBOL);
+
+    // * a local ledger with alice's canister as the minting account
+    let ledger_canister_id =
+        pocket_ic.create_canister_with_settings(Some(global_admin_principal), None);
+    pocket_ic.add_cycles(ledger_canister_id, 2_000_000_000_000);
+    let ledger_init_args = LedgerArgument::Init(
+        InitArgsBuilder::for_tests()
+            .with_minting_account(alice_canister_id)
+            .with_transfer_fee(0_u64)
+            .with_decimals(0)
+            .with_token_symbol(UTILITY_TOKEN_SYMBOL)
+            .with_token_name(UTILITY_TOKEN_NAME)
+            .build(),
+    );
+    pocket_ic.install_canister(
+        ledger_canister_id,
+        include_bytes!("../../../../../wasms/ledger.wasm.gz").to_vec(),
+        candid::encode_one(ledger_init_args).unwrap(),
+        Some(global_admin_principal),
+    );
+
+    pocket_ic
+        .update_call(
+            alice_canister_id,
+            global_admin_principal,
+            "set_utility_token_ledger_mirror",
+            candid::encode_one(Some(ledger_canister_id)).unwrap(),
+        )
+        .unwrap();
+
+    let transfer_result = pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal,
+            "transfer_utility_tokens",
+            candid::encode_args((0_u64, bob_canister_id, 100_u64, None::<String>)).unwrap(),
+        )
+        .map(|reply_payload| match reply_payload {
+            WasmResult::Reply(payload) => {
+                candid::decode_one::<Result<u64, UtilityTokenTransferError>>(&payload).unwrap()
+            }
+            _ => panic!("\n🛑 transfer_utility_tokens failed\n"),
+        })
+        .unwrap();
+    assert!(transfer_result.is_ok());
+
+    pocket_ic.advance_time(Duration::from_secs(61));
+    for _ in 0..10 {
+        pocket_ic.tick();
+    }
+
+    let mirror_status = pocket_ic
+        .query_call(
+            alice_canister_id,
+            Principal::anonymous(),
+            "get_utility_token_ledger_mirror_status",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| match reply_payload {
+            WasmResult::Reply(payload) => {
+                candid::decode_one::<UtilityTokenLedgerMirrorStatus>(&payload).unwrap()
+            }
+            _ => panic!("\n🛑 get_utility_token_ledger_mirror_status failed\n"),
+        })
+        .unwrap();
+    assert_eq!(
+        mirror_status,
+        UtilityTokenLedgerMirrorStatus {
+            ledger_canister_id: Some(ledger_canister_id),
+            number_of_pending_operations: 0,
+        }
+    );
+
+    // * the opening balance was minted and the transfer burned on the ledger
+    assert_eq!(
+        icrc1_balance_of(
+            ledger_canister_id,
+            Account {
+                owner: alice_canister_id,
+                subaccount: Some(UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT),
+            }
+        ),
+        Nat::from(900_u64)
+    );
+    assert_eq!(
+        icrc1_balance_of(alice_canister_id, alice_account),
+        Nat::from(900_u64)
+    );
+
+    let ledger_total_supply = pocket_ic
+        .query_call(
+            ledger_canister_id,
+            Principal::anonymous(),
+            "icrc1_total_supply",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| match reply_payload {
+            WasmResult::Reply(payload) => candid::decode_one::<Nat>(&payload).unwrap(),
+            _ => panic!("\n🛑 icrc1_total_supply failed\n"),
+        })
+        .unwrap();
+    assert_eq!(ledger_total_supply, Nat::from(900_u64));
+}
@@ -0,0 +1,225 @@
+use candid::Principal;
+use pocket_ic::{PocketIc, WasmResult};
+use shared_utils::{
+    common::types::known_principal::KnownPrincipalType,
+    types::canister_specific::individual_user_template::error_types::UtilityTokenTransferError,
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{get_mock_user_alice_principal_id, get_mock_user_bob_principal_id},
+};
+
+fn transfer_utility_tokens(
+    pocket_ic: &PocketIc,
+    from_canister_id: Principal,
+    from_principal: Principal,
+    transfer_id: u64,
+    to_canister_id: Principal,
+    amount: u64,
+) -> Result<u64, UtilityTokenTransferError> {
+    pocket_ic
+        .update_call(
+            from_canister_id,
+            from_principal,
+            "transfer_utility_tokens",
+            candid::encode_args((transfer_id, to_canister_id, amount, None::<String>)).unwrap(),
+        )
+        .map(|reply_payload| match reply_payload {
+            WasmResult::Reply(payload) => {
+                candid::decode_one::<Result<u64, UtilityTokenTransferError>>(&payload).unwrap()
+            }
+            _ => panic!("\n🛑 transfer_utility_tokens failed\n"),
+        })
+        .unwrap()
+}
+
+fn get_utility_token_balance(pocket_ic: &PocketIc, canister_id: Principal) -> u64 {
+    pocket_ic
+        .query_call(
+            canister_id,
+            Principal::anonymous(),
+            "get_utility_token_balance",
+            candid::encode_one(()).unwrap(),
+        )
+        .map(|reply_payload| match reply_payload {
+            WasmResult::Reply(payload) => candid::decode_one::<u64>(&payload).unwrap(),
+            _ => panic!("\n🛑 get_utility_token_balance failed\n"),
+        })
+        .unwrap()
+}
+
+#[test]
+fn when_a_user_transfers_utility_tokens_then_the_receiver_is_credited_once_per_transfer_id() {
+    let (pocket_ic, known_principal_map) = get_new_pocket_ic_env();
+
+    let alice_principal = get_mock_user_alice_principal_id();
+    let bob_principal = get_mock_user_bob_principal_id();
+
+    let platform_orchestrator_canister_id = known_principal_map
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .copied()
+        .unwrap();
+
+    let global_admin_principal = known_principal_map
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .copied()
+        .unwrap();
+
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+
+    let subnet_orchestrator_canister_id = pocket_ic
+        .update_call(
+            platform_orchestrator_canister_id,
+            global_admin_principal,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[0]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..50 {
+        pocket_ic.tick();
+    }
+
+    let get_user_canister_id = |user_principal: Principal| {
+        pocket_ic
+            .update_call(
+                subnet_orchestrator_canister_id,
+                user_principal,
+                "get_requester_principals_canister_id_create_if_not_exists",
+                candid::encode_one(()).unwrap(),
+            )
+            .map(|reply_payload| {
+                let canister_id_res: Result<Principal, String> = match reply_payload {
+                    WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                    _ => panic!(
+                        "\n🛑 get_requester_principals_canister_id_create_if_not_exists failed\n"
+                    ),
+                };
+                canister_id_res
+            })
+            .unwrap()
+            .unwrap()
+    };
+
+    let alice_canister_id = get_user_canister_id(alice_principal);
+    let bob_canister_id = get_user_canister_id(bob_principal);
+
+    for _ in 0..5 {
+        pocket_ic.tick();
+    }
+
+    let alice_starting_balance = get_utility_token_balance(&pocket_ic, alice_canister_id);
+    let bob_starting_balance = get_utility_token_balance(&pocket_ic, bob_canister_id);
+
+    assert_eq!(
+        transfer_utility_tokens(
+            &pocket_ic,
+            alice_canister_id,
+            bob_principal,
+            0,
+            bob_canister_id,
+            100
+        ),
+        Err(UtilityTokenTransferError::Unauthorized)
+    );
+
+    assert_eq!(
+        transfer_utility_tokens(
+            &pocket_ic,
+            alice_canister_id,
+            alice_principal,
+            0,
+            bob_canister_id,
+            100
+        ),
+        Ok(0)
+    );
+    assert_eq!(
+        get_utility_token_balance(&pocket_ic, alice_canister_id),
+        alice_starting_balance - 100
+    );
+    assert_eq!(
+        get_utility_token_balance(&pocket_ic, bob_canister_id),
+        bob_starting_balance + 100
+    );
+
+    // * a retried call with the same transfer id neither debits nor credits again
+    assert_eq!(
+        transfer_utility_tokens(
+            &pocket_ic,
+            alice_canister_id,
+            alice_principal,
+            0,
+            bob_canister_id,
+            100
+        ),
+        Ok(0)
+    );
+    assert_eq!(
+        get_utility_token_balance(&pocket_ic, alice_canister_id),
+        alice_starting_balance - 100
+    );
+    assert_eq!(
+        get_utility_token_balance(&pocket_ic, bob_canister_id),
+        bob_starting_balance + 100
+    );
+
+    assert_eq!(
+        transfer_utility_tokens(
+            &pocket_ic,
+            alice_canister_id,
+            alice_principal,
+            0,
+            bob_canister_id,
+            50
+        ),
+        Err(UtilityTokenTransferError::TransferIdAlreadyUsed)
+    );
+
+    // * a canister outside the network is rejected before anything is debited
+    let outside_canister_id =
+        pocket_ic.create_canister_with_settings(Some(global_admin_principal), None);
+    assert_eq!(
+        transfer_utility_tokens(
+            &pocket_ic,
+            alice_canister_id,
+            alice_principal,
+            1,
+            outside_canister_id,
+            100
+        ),
+        Err(UtilityTokenTransferError::ReceiverIsNotAUserCanisterOnNetwork)
+    );
+    assert_eq!(
+        get_utility_token_balance(&pocket_ic, alice_canister_id),
+        alice_starting_balance - 100
+    );
+
+    // * transfer ids are scoped to the sending canister, so bob can also use id 0
+    assert_eq!(
+        transfer_utility_tokens(
+            &pocket_ic,
+            bob_canister_id,
+            bob_principal,
+            0,
+            alice_canister_id,
+            40
+        ),
+        Ok(0)
+    );
+    assert_eq!(
+        get_utility_token_balance(&pocket_ic, alice_canister_id),
+        alice_starting_balance - 60
+    );
+    assert_eq!(
+        get_utility_token_balance(&pocket_ic, bob_canister_id),
+        bob_starting_balance + 60
+    );
+}
@@ -4,7 +4,7 @@ use candid::Principal;
 use pocket_ic::WasmResult;
 use shared_utils::{
     canister_specific::individual_user_template::types::kv_storage::{
-        NamespaceErrors, NamespaceForFrontend,
+        NamespaceErrors, NamespaceForFrontend, NamespacePermission,
     },
     common::types::known_principal::KnownPrincipalType,
 };
@@ -151,3 +151,187 @@ fn create_new_namespace() {
 
     assert_eq!(deleted_value, Some("10.00".into()))
 }
+
+#[test]
+fn namespace_permissions_and_deletion() {
+    let (pocket_ic, known_principal) = get_new_pocket_ic_env();
+
+    let platform_canister_id = known_principal
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .cloned()
+        .unwrap();
+
+    let super_admin = known_principal
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .cloned()
+        .unwrap();
+
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+
+    let charlie_global_admin = get_mock_user_charlie_principal_id();
+
+    pocket_ic
+        .update_call(
+            platform_canister_id,
+            super_admin,
+            "add_principal_as_global_admin",
+            candid::encode_one(charlie_global_admin).unwrap(),
+        )
+        .unwrap();
+
+    let subnet_orchestrator_canister_id: Principal = pocket_ic
+        .update_call(
+            platform_canister_id,
+            charlie_global_admin,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[1]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..50 {
+        pocket_ic.tick();
+    }
+
+    let alice_principal_id = get_mock_user_alice_principal_id();
+
+    let alice_canister_id = pocket_ic.update_call(subnet_orchestrator_canister_id, alice_principal_id, "get_requester_principals_canister_id_create_if_not_exists_and_optionally_allow_referrer", candid::encode_one(()).unwrap())
+    .map(|res| {
+        let canister_id: Principal = match res {
+            WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+            _ => panic!("Canister call failed")
+        };
+        canister_id
+    })
+    .unwrap();
+
+    let app_proxy_canister = pocket_ic.create_canister();
+    let other_app_canister = pocket_ic.create_canister();
+
+    let namespace = pocket_ic
+        .update_call(
+            alice_canister_id,
+            app_proxy_canister,
+            "create_a_namespace",
+            candid::encode_one("third_party_app".to_string()).unwrap(),
+        )
+        .map(|res| {
+            let namespace: Result<NamespaceForFrontend, NamespaceErrors> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            namespace
+        })
+        .unwrap()
+        .unwrap();
+
+    pocket_ic
+        .update_call(
+            alice_canister_id,
+            app_proxy_canister,
+            "write_key_value_pair",
+            candid::encode_args((namespace.id, "username".to_owned(), "alice".to_owned())).unwrap(),
+        )
+        .map(|res| {
+            let res: Result<Option<String>, NamespaceErrors> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            res
+        })
+        .unwrap()
+        .unwrap();
+
+    let read_value = |caller: Principal| {
+        pocket_ic
+            .query_call(
+                alice_canister_id,
+                caller,
+                "read_key_value_pair",
+                candid::encode_args((namespace.id, "username".to_owned())).unwrap(),
+            )
+            .map(|res| {
+                let res: Result<Option<String>, NamespaceErrors> = match res {
+                    WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                    _ => panic!("Canister call failed"),
+                };
+                res
+            })
+            .unwrap()
+    };
+
+    assert!(matches!(
+        read_value(other_app_canister),
+        Err(NamespaceErrors::Unauthorized)
+    ));
+
+    pocket_ic
+        .update_call(
+            alice_canister_id,
+            app_proxy_canister,
+            "grant_namespace_permission",
+            candid::encode_args((namespace.id, other_app_canister, NamespacePermission::Read))
+                .unwrap(),
+        )
+        .map(|res| {
+            let res: Result<(), NamespaceErrors> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            res
+        })
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        read_value(other_app_canister).unwrap(),
+        Some("alice".into())
+    );
+
+    // read permission does not allow writes
+    let write_res = pocket_ic
+        .update_call(
+            alice_canister_id,
+            other_app_canister,
+            "write_key_value_pair",
+            candid::encode_args((namespace.id, "username".to_owned(), "bob".to_owned())).unwrap(),
+        )
+        .map(|res| {
+            let res: Result<Option<String>, NamespaceErrors> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            res
+        })
+        .unwrap();
+    assert!(matches!(write_res, Err(NamespaceErrors::Unauthorized)));
+
+    //profile owners can delete the namespace
+    pocket_ic
+        .update_call(
+            alice_canister_id,
+            alice_principal_id,
+            "delete_namespace",
+            candid::encode_one(namespace.id).unwrap(),
+        )
+        .map(|res| {
+            let res: Result<(), NamespaceErrors> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            res
+        })
+        .unwrap()
+        .unwrap();
+
+    assert!(matches!(
+        read_value(app_proxy_canister),
+        Err(NamespaceErrors::NamespaceNotFound)
+    ));
+}
@@ -5,3 +5,4 @@ pub mod recharge_subnet_orchestrator_test;
 pub mod register_and_deregister_new_subnet_orchestrator_test;
 pub mod reset_ml_feed_cache_test;
 pub mod update_canisters_last_access_time_test;
+pub mod utility_token_supply_stats_test;
@@ -0,0 +1,133 @@
+use candid::Principal;
+use pocket_ic::WasmResult;
+use shared_utils::{
+    common::types::known_principal::KnownPrincipalType,
+    types::utility_token_supply_stats::{UtilityTokenSupplySnapshot, UtilityTokenSupplyStats},
+};
+use test_utils::setup::{
+    env::pocket_ic_env::get_new_pocket_ic_env,
+    test_constants::{get_mock_user_alice_principal_id, get_mock_user_bob_principal_id},
+};
+
+#[test]
+fn when_utility_token_supply_stats_are_collected_then_a_daily_snapshot_of_the_network_is_recorded()
+{
+    let (pocket_ic, known_principal_map) = get_new_pocket_ic_env();
+
+    let platform_orchestrator_canister_id = known_principal_map
+        .get(&KnownPrincipalType::CanisterIdPlatformOrchestrator)
+        .copied()
+        .unwrap();
+
+    let global_admin_principal = known_principal_map
+        .get(&KnownPrincipalType::UserIdGlobalSuperAdmin)
+        .copied()
+        .unwrap();
+
+    let application_subnets = pocket_ic.topology().get_app_subnets();
+
+    let subnet_orchestrator_canister_id = pocket_ic
+        .update_call(
+            platform_orchestrator_canister_id,
+            global_admin_principal,
+            "provision_subnet_orchestrator_canister",
+            candid::encode_one(application_subnets[0]).unwrap(),
+        )
+        .map(|res| {
+            let canister_id_result: Result<Principal, String> = match res {
+                WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                _ => panic!("Canister call failed"),
+            };
+            canister_id_result.unwrap()
+        })
+        .unwrap();
+
+    for _ in 0..50 {
+        pocket_ic.tick();
+    }
+
+    for user_principal in [
+        get_mock_user_alice_principal_id(),
+        get_mock_user_bob_principal_id(),
+    ] {
+        pocket_ic
+            .update_call(
+                subnet_orchestrator_canister_id,
+                user_principal,
+                "get_requester_principals_canister_id_create_if_not_exists",
+                candid::encode_one(()).unwrap(),
+            )
+            .map(|reply_payload| {
+                let canister_id_res: Result<Principal, String> = match reply_payload {
+                    WasmResult::Reply(payload) => candid::decode_one(&payload).unwrap(),
+                    _ => panic!(
+                        "\n🛑 get_requester_principals_canister_id_create_if_not_exists failed\n"
+                    ),
+                };
+                canister_id_res
+            })
+            .unwrap()
+            .unwrap();
+    }
+
+    for _ in 0..5 {
+        pocket_ic.tick();
+    }
+
+    pocket_ic
+        .update_call(
+            platform_orchestrator_canister_id,
+            global_admin_principal,
+            "collect_utility_token_supply_stats_in_the_network",
+            candid::encode_one(()).unwrap(),
+        )
+        .unwrap();
+
+    for _ in 0..10 {
+        pocket_ic.tick();
+    }
+
+    let snapshots = pocket_ic
+        .query_call(
+            platform_orchestrator_canister_id,
+            global_admin_principal,
+            "get_utility_token_supply_snapshots",
+            candid::encode_args((0_u64, u64::MAX)).unwrap(),
+        )
+        .map(|reply_payload| match reply_payload {
+            WasmResult::Reply(payload) => {
+                candid::decode_one::<Vec<UtilityTokenSupplySnapshot>>(&payload).unwrap()
+            }
+            _ => panic!("\n🛑 get_utility_token_supply_snapshots failed\n"),
+        })
+        .unwrap();
+
+    let expected_stats = UtilityTokenSupplyStats {
+        minted: 2000,
+        burned: 0,
+        staked: 0,
+        staked_in_open_bets: 0,
+        paid_out: 0,
+        circulating: 2000,
+        number_of_individual_canisters: 2,
+    };
+
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].network_stats, expected_stats);
+    assert_eq!(
+        snapshots[0]
+            .stats_by_subnet
+            .get(&subnet_orchestrator_canister_id),
+        Some(&expected_stats)
+    );
+    assert!(snapshots[0].stale_subnets.is_empty());
+
+    // * only admins can read the snapshots
+    let unauthorized_result = pocket_ic.query_call(
+        platform_orchestrator_canister_id,
+        get_mock_user_alice_principal_id(),
+        "get_utility_token_supply_snapshots",
+        candid::encode_args((0_u64, u64::MAX)).unwrap(),
+    );
+    assert!(unauthorized_result.is_err());
+}
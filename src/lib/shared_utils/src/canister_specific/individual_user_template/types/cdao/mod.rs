@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap, time::SystemTime};
 
-use candid::{CandidType, Principal};
+use candid::{CandidType, Decode, Encode, Nat, Principal};
+use ic_stable_structures::{storable::Bound, Storable};
+use icrc_ledger_types::icrc1::transfer::Memo;
 use serde::{Deserialize, Serialize};
 
 #[derive(CandidType, PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
@@ -11,6 +13,8 @@ pub struct DeployedCdaoCanisters {
     pub swap: Principal,
     pub index: Principal,
 
+    /// No longer updated, claims of the token's airdrop are kept in stable memory and looked up
+    /// with `get_airdrop_claim`
     #[serde(default)]
     pub airdrop_info: AirdropInfo,
 }
@@ -32,6 +36,9 @@ pub struct AirdropInfo {
     /// Maps each principal to their claim status
     #[serde(default)]
     pub principals_who_successfully_claimed: HashMap<Principal, ClaimStatus>,
+    /// Transfers made to each principal, a vesting airdrop is claimed in several tranches
+    #[serde(default)]
+    pub claim_history: HashMap<Principal, Vec<AirdropClaim>>,
 }
 
 impl AirdropInfo {
@@ -103,6 +110,33 @@ impl AirdropInfo {
             ClaimStatus::Unclaimed,
         )
     }
+
+    pub fn get_claim_history(&self, user_principal_id: &Principal) -> &[AirdropClaim] {
+        self.claim_history
+            .get(user_principal_id)
+            .map(|claims| claims.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn get_claimed_amount(&self, user_principal_id: &Principal) -> u64 {
+        self.get_claim_history(user_principal_id)
+            .iter()
+            .map(|claim| claim.amount)
+            .sum()
+    }
+
+    pub fn add_claim(&mut self, user_principal_id: Principal, claim: AirdropClaim) {
+        self.claim_history
+            .entry(user_principal_id)
+            .or_default()
+            .push(claim);
+    }
+}
+
+#[derive(CandidType, PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct AirdropClaim {
+    pub amount: u64,
+    pub claimed_at: SystemTime,
 }
 
 #[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq, Default, Hash)]
@@ -112,3 +146,356 @@ pub enum ClaimStatus {
     Claimed,
     Claiming,
 }
+
+/// Seconds after which a claim stuck in `Claiming`, e.g. after a trap while transferring, can be
+/// retried
+pub const AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS: u64 = 10 * 60;
+
+impl ClaimStatus {
+    /// Whether a claim that has been claiming since `updated_at` can be retried
+    pub fn is_claiming_timed_out(&self, updated_at: SystemTime, current_time: SystemTime) -> bool {
+        *self == ClaimStatus::Claiming
+            && current_time
+                .duration_since(updated_at)
+                .is_ok_and(|elapsed| elapsed.as_secs() >= AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS)
+    }
+}
+
+/// Claim of a principal in the airdrop of a creator token
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropClaimRecord {
+    pub status: ClaimStatus,
+    pub amount: u64,
+    /// While claiming, this is also the `created_at_time` of the transfer, so the ledger
+    /// deduplicates a retried transfer that had gone through
+    pub updated_at: SystemTime,
+    /// Memo of the transfer while claiming
+    pub memo: Option<Memo>,
+    /// While claiming, the length of the ledger's transaction log before the transfer. A retry
+    /// past the ledger's deduplication window looks the transfer up from there. Unknown for
+    /// claims started by earlier versions.
+    #[serde(default)]
+    pub ledger_start_index: Option<u64>,
+}
+
+impl AirdropClaimRecord {
+    pub fn is_claiming_timed_out(&self, current_time: SystemTime) -> bool {
+        self.status
+            .is_claiming_timed_out(self.updated_at, current_time)
+    }
+}
+
+impl Storable for AirdropClaimRecord {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).unwrap()
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropClaimant {
+    pub principal_id: Principal,
+    pub claim: AirdropClaimRecord,
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub enum AirdropEligibility {
+    Everyone,
+    /// Principals following the creator
+    Followers,
+    /// Principals who bet on any post of the creator
+    Bettors,
+    Allowlist(Vec<Principal>),
+}
+
+/// Releases the amount of a claim in equal tranches, the first one on the first claim and the
+/// rest spread evenly until the vesting duration has passed.
+#[derive(Serialize, Deserialize, CandidType, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AirdropVesting {
+    pub number_of_tranches: u64,
+    pub vesting_duration_in_seconds: u64,
+}
+
+impl AirdropVesting {
+    pub fn get_vested_amount(
+        &self,
+        amount: u64,
+        first_claimed_at: SystemTime,
+        current_time: SystemTime,
+    ) -> u64 {
+        let elapsed_seconds = current_time
+            .duration_since(first_claimed_at)
+            .unwrap_or_default()
+            .as_secs();
+        let unlocked_tranches = if self.vesting_duration_in_seconds == 0 {
+            self.number_of_tranches
+        } else {
+            (1 + (elapsed_seconds as u128 * self.number_of_tranches.saturating_sub(1) as u128
+                / self.vesting_duration_in_seconds as u128) as u64)
+                .min(self.number_of_tranches)
+        };
+
+        if unlocked_tranches == self.number_of_tranches {
+            amount
+        } else {
+            (amount as u128 * unlocked_tranches as u128 / self.number_of_tranches as u128) as u64
+        }
+    }
+}
+
+/// Amounts are in the smallest unit of the token
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropCampaignConfig {
+    pub token_root: Principal,
+    pub total_budget: u64,
+    pub amount_per_claim: u64,
+    pub eligibility: AirdropEligibility,
+    pub start_time: SystemTime,
+    pub end_time: SystemTime,
+    pub vesting: Option<AirdropVesting>,
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropCampaignDetails {
+    pub campaign_id: u64,
+    pub config: AirdropCampaignConfig,
+    /// Budget promised to the claimants so far, including tranches that are not vested yet
+    pub allocated_budget: u64,
+    pub distributed_amount: u64,
+    pub number_of_claimants: u64,
+    pub created_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropClaimDetails {
+    pub status: ClaimStatus,
+    pub claimed_amount: u64,
+    /// Vested and not claimed yet
+    pub claimable_amount: u64,
+    pub claims: Vec<AirdropClaim>,
+}
+
+/// Seconds a fetched creator token status is served from the cache before it is fetched again
+pub const CREATOR_DAO_TOKEN_STATUS_TTL_IN_SECONDS: u64 = 5 * 60;
+
+/// Mirrors the `Lifecycle` enum of the SNS swap canister
+#[derive(Serialize, Deserialize, CandidType, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CreatorDaoSwapLifecycle {
+    Unspecified,
+    Pending,
+    Open,
+    Committed,
+    Aborted,
+    Adopted,
+}
+
+impl From<i32> for CreatorDaoSwapLifecycle {
+    fn from(lifecycle: i32) -> Self {
+        match lifecycle {
+            1 => CreatorDaoSwapLifecycle::Pending,
+            2 => CreatorDaoSwapLifecycle::Open,
+            3 => CreatorDaoSwapLifecycle::Committed,
+            4 => CreatorDaoSwapLifecycle::Aborted,
+            5 => CreatorDaoSwapLifecycle::Adopted,
+            _ => CreatorDaoSwapLifecycle::Unspecified,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq)]
+pub struct CreatorDaoTokenStatus {
+    pub root: Principal,
+    pub swap_lifecycle: CreatorDaoSwapLifecycle,
+    pub swap_open_timestamp_seconds: Option<u64>,
+    pub swap_termination_timestamp_seconds: Option<u64>,
+    /// Direct and neurons' fund participants
+    pub number_of_participants: u64,
+    pub raised_icp_e8s: u64,
+    pub total_supply: Nat,
+    pub number_of_neurons: u64,
+    pub fetched_at: SystemTime,
+}
+
+impl CreatorDaoTokenStatus {
+    pub fn is_stale(&self, current_time: SystemTime) -> bool {
+        current_time
+            .duration_since(self.fetched_at)
+            .map(|age| age.as_secs() >= CREATOR_DAO_TOKEN_STATUS_TTL_IN_SECONDS)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub enum CreatorDaoTokenStatusError {
+    TokenNotFound,
+    /// The status was never fetched and the caller can't trigger a fetch
+    StatusNotFetched,
+    SwapCallFailed(String),
+    GovernanceCallFailed(String),
+    LedgerCallFailed(String),
+}
+
+/// Steps of a creator token deployment, in the order they are run. A failed deployment runs the
+/// compensation of every step it reached, in reverse order.
+#[derive(
+    Serialize, Deserialize, CandidType, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum CdaoDeploymentStep {
+    AllotCanisters,
+    DepositCycles,
+    InstallWasms,
+    UpdateControllers,
+    Register,
+}
+
+impl CdaoDeploymentStep {
+    pub fn next(&self) -> Option<CdaoDeploymentStep> {
+        match self {
+            CdaoDeploymentStep::AllotCanisters => Some(CdaoDeploymentStep::DepositCycles),
+            CdaoDeploymentStep::DepositCycles => Some(CdaoDeploymentStep::InstallWasms),
+            CdaoDeploymentStep::InstallWasms => Some(CdaoDeploymentStep::UpdateControllers),
+            CdaoDeploymentStep::UpdateControllers => Some(CdaoDeploymentStep::Register),
+            CdaoDeploymentStep::Register => None,
+        }
+    }
+
+    pub fn previous(&self) -> Option<CdaoDeploymentStep> {
+        match self {
+            CdaoDeploymentStep::AllotCanisters => None,
+            CdaoDeploymentStep::DepositCycles => Some(CdaoDeploymentStep::AllotCanisters),
+            CdaoDeploymentStep::InstallWasms => Some(CdaoDeploymentStep::DepositCycles),
+            CdaoDeploymentStep::UpdateControllers => Some(CdaoDeploymentStep::InstallWasms),
+            CdaoDeploymentStep::Register => Some(CdaoDeploymentStep::UpdateControllers),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CdaoDeploymentStatus {
+    /// `step` is the step being run
+    InProgress,
+    /// `step` is the step being compensated
+    RollingBack,
+    /// Every step was compensated and the canisters were returned
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct CdaoDeploymentDetails {
+    pub deployment_id: u64,
+    pub status: CdaoDeploymentStatus,
+    pub step: CdaoDeploymentStep,
+    pub canister_ids: Vec<Principal>,
+    pub error: Option<String>,
+    pub compensation_error: Option<String>,
+    pub started_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn test_creator_dao_token_status_is_stale() {
+        let status = CreatorDaoTokenStatus {
+            root: Principal::anonymous(),
+            swap_lifecycle: CreatorDaoSwapLifecycle::from(3),
+            swap_open_timestamp_seconds: None,
+            swap_termination_timestamp_seconds: None,
+            number_of_participants: 0,
+            raised_icp_e8s: 0,
+            total_supply: Nat::from(0_u64),
+            number_of_neurons: 0,
+            fetched_at: UNIX_EPOCH + Duration::from_secs(100),
+        };
+
+        assert_eq!(status.swap_lifecycle, CreatorDaoSwapLifecycle::Committed);
+        assert!(!status.is_stale(UNIX_EPOCH + Duration::from_secs(100)));
+        assert!(!status.is_stale(UNIX_EPOCH));
+        assert!(status.is_stale(
+            UNIX_EPOCH + Duration::from_secs(100 + CREATOR_DAO_TOKEN_STATUS_TTL_IN_SECONDS)
+        ));
+    }
+
+    #[test]
+    fn test_airdrop_claim_record_is_claiming_timed_out() {
+        let mut claim = AirdropClaimRecord {
+            status: ClaimStatus::Claiming,
+            amount: 100,
+            updated_at: UNIX_EPOCH + Duration::from_secs(100),
+            memo: None,
+            ledger_start_index: Some(0),
+        };
+
+        assert!(!claim.is_claiming_timed_out(UNIX_EPOCH));
+        assert!(!claim.is_claiming_timed_out(
+            UNIX_EPOCH + Duration::from_secs(99 + AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS)
+        ));
+        assert!(claim.is_claiming_timed_out(
+            UNIX_EPOCH + Duration::from_secs(100 + AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS)
+        ));
+
+        claim.status = ClaimStatus::Claimed;
+        assert!(!claim.is_claiming_timed_out(
+            UNIX_EPOCH + Duration::from_secs(100 + AIRDROP_CLAIMING_TIMEOUT_IN_SECONDS)
+        ));
+    }
+
+    #[test]
+    fn test_airdrop_vesting_releases_tranches_over_time() {
+        let vesting = AirdropVesting {
+            number_of_tranches: 3,
+            vesting_duration_in_seconds: 300,
+        };
+        let first_claimed_at = UNIX_EPOCH + Duration::from_secs(1000);
+
+        assert_eq!(
+            vesting.get_vested_amount(100, first_claimed_at, first_claimed_at),
+            33
+        );
+        assert_eq!(
+            vesting.get_vested_amount(100, first_claimed_at, UNIX_EPOCH),
+            33
+        );
+        assert_eq!(
+            vesting.get_vested_amount(
+                100,
+                first_claimed_at,
+                first_claimed_at + Duration::from_secs(150)
+            ),
+            66
+        );
+        assert_eq!(
+            vesting.get_vested_amount(
+                100,
+                first_claimed_at,
+                first_claimed_at + Duration::from_secs(299)
+            ),
+            66
+        );
+        assert_eq!(
+            vesting.get_vested_amount(
+                100,
+                first_claimed_at,
+                first_claimed_at + Duration::from_secs(300)
+            ),
+            100
+        );
+        assert_eq!(
+            vesting.get_vested_amount(
+                100,
+                first_claimed_at,
+                first_claimed_at + Duration::from_secs(10_000)
+            ),
+            100
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+use crate::constant::MAX_LIMIT_FOR_CREATOR_DAO_SNS_TOKEN;
+
+/// Network wide creator token quota, pushed to every individual canister by the platform
+/// orchestrator.
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreatorTokenQuotaConfig {
+    /// Creator tokens an eligible user may create
+    pub default_quota: u64,
+    pub require_registered_session: bool,
+    /// Minimum age of the account before it may create a creator token
+    pub min_account_age_in_seconds: u64,
+}
+
+impl Default for CreatorTokenQuotaConfig {
+    fn default() -> Self {
+        Self {
+            default_quota: MAX_LIMIT_FOR_CREATOR_DAO_SNS_TOKEN,
+            require_registered_session: false,
+            min_account_age_in_seconds: 0,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreatorTokenQuota {
+    /// Zero while the account does not meet the criteria of the config, unless overridden
+    pub quota: u64,
+    /// Deployed creator tokens, including deployments that are still running
+    pub used: u64,
+    pub remaining: u64,
+    /// Set when the quota of this user was overridden by the platform
+    pub quota_override: Option<u64>,
+}
@@ -0,0 +1,178 @@
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+pub const DEFAULT_DAILY_LOGIN_STREAK_REWARD_AMOUNT: u64 = 10;
+pub const DEFAULT_FIRST_POST_REWARD_AMOUNT: u64 = 100;
+pub const DEFAULT_DAILY_POSTS_WATCHED_REWARD_AMOUNT: u64 = 10;
+pub const DEFAULT_FIRST_BET_REWARD_AMOUNT: u64 = 50;
+
+#[derive(
+    CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum EngagementMilestone {
+    DailyLoginStreak,
+    FirstPost,
+    DailyPostsWatched,
+    FirstBet,
+}
+
+impl EngagementMilestone {
+    pub const ALL: [EngagementMilestone; 4] = [
+        EngagementMilestone::DailyLoginStreak,
+        EngagementMilestone::FirstPost,
+        EngagementMilestone::DailyPostsWatched,
+        EngagementMilestone::FirstBet,
+    ];
+
+    /// Daily milestones can be claimed once per day, the others only once.
+    pub fn is_daily(&self) -> bool {
+        matches!(
+            self,
+            EngagementMilestone::DailyLoginStreak | EngagementMilestone::DailyPostsWatched
+        )
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EngagementRewardRule {
+    pub reward_amount: u64,
+    /// Total that is ever minted for the milestone, over all claims
+    pub max_total_reward_amount: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EngagementRewardsConfig {
+    pub daily_login_streak: EngagementRewardRule,
+    /// Consecutive days of access needed before the streak reward is paid out each day
+    pub min_login_streak_days: u64,
+    pub first_post: EngagementRewardRule,
+    pub daily_posts_watched: EngagementRewardRule,
+    pub min_posts_watched_per_day: u64,
+    pub first_bet: EngagementRewardRule,
+}
+
+impl Default for EngagementRewardsConfig {
+    fn default() -> Self {
+        Self {
+            daily_login_streak: EngagementRewardRule {
+                reward_amount: DEFAULT_DAILY_LOGIN_STREAK_REWARD_AMOUNT,
+                max_total_reward_amount: 100 * DEFAULT_DAILY_LOGIN_STREAK_REWARD_AMOUNT,
+            },
+            min_login_streak_days: 3,
+            first_post: EngagementRewardRule {
+                reward_amount: DEFAULT_FIRST_POST_REWARD_AMOUNT,
+                max_total_reward_amount: DEFAULT_FIRST_POST_REWARD_AMOUNT,
+            },
+            daily_posts_watched: EngagementRewardRule {
+                reward_amount: DEFAULT_DAILY_POSTS_WATCHED_REWARD_AMOUNT,
+                max_total_reward_amount: 100 * DEFAULT_DAILY_POSTS_WATCHED_REWARD_AMOUNT,
+            },
+            min_posts_watched_per_day: 10,
+            first_bet: EngagementRewardRule {
+                reward_amount: DEFAULT_FIRST_BET_REWARD_AMOUNT,
+                max_total_reward_amount: DEFAULT_FIRST_BET_REWARD_AMOUNT,
+            },
+        }
+    }
+}
+
+impl EngagementRewardsConfig {
+    pub fn get_rule(&self, milestone: EngagementMilestone) -> EngagementRewardRule {
+        match milestone {
+            EngagementMilestone::DailyLoginStreak => self.daily_login_streak,
+            EngagementMilestone::FirstPost => self.first_post,
+            EngagementMilestone::DailyPostsWatched => self.daily_posts_watched,
+            EngagementMilestone::FirstBet => self.first_bet,
+        }
+    }
+}
+
+/// Total amount that is ever minted for each milestone over all users. The user index of each
+/// subnet enforces its share before an individual canister mints a reward.
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EngagementRewardCaps {
+    pub daily_login_streak: u64,
+    pub first_post: u64,
+    pub daily_posts_watched: u64,
+    pub first_bet: u64,
+}
+
+impl EngagementRewardCaps {
+    pub fn get_cap(&self, milestone: EngagementMilestone) -> u64 {
+        match milestone {
+            EngagementMilestone::DailyLoginStreak => self.daily_login_streak,
+            EngagementMilestone::FirstPost => self.first_post,
+            EngagementMilestone::DailyPostsWatched => self.daily_posts_watched,
+            EngagementMilestone::FirstBet => self.first_bet,
+        }
+    }
+
+    /// Share of the caps for each of `number_of_subnets` subnets, rounded down so the shares
+    /// never add up to more than the caps.
+    pub fn get_share_per_subnet(&self, number_of_subnets: u64) -> Self {
+        let number_of_subnets = number_of_subnets.max(1);
+
+        Self {
+            daily_login_streak: self.daily_login_streak / number_of_subnets,
+            first_post: self.first_post / number_of_subnets,
+            daily_posts_watched: self.daily_posts_watched / number_of_subnets,
+            first_bet: self.first_bet / number_of_subnets,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EngagementRewardClaim {
+    pub milestone: EngagementMilestone,
+    /// Days since the unix epoch the claim was made on
+    pub day: u64,
+    pub amount: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EngagementMilestoneStatus {
+    pub milestone: EngagementMilestone,
+    pub total_rewarded_amount: u64,
+    pub last_claimed_day: Option<u64>,
+    pub claimable_amount: u64,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EngagementRewardsStatus {
+    pub login_streak_days: u64,
+    pub milestones: Vec<EngagementMilestoneStatus>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum EngagementRewardError {
+    Unauthorized,
+    UserNotSignedUp,
+    ClaimInProgress,
+    UserIndexCrossCanisterCallFailed(String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_share_per_subnet() {
+        let caps = EngagementRewardCaps {
+            daily_login_streak: 1000,
+            first_post: 10,
+            daily_posts_watched: 0,
+            first_bet: 7,
+        };
+
+        assert_eq!(
+            caps.get_share_per_subnet(3),
+            EngagementRewardCaps {
+                daily_login_streak: 333,
+                first_post: 3,
+                daily_posts_watched: 0,
+                first_bet: 2,
+            }
+        );
+        assert_eq!(caps.get_share_per_subnet(0), caps);
+    }
+}
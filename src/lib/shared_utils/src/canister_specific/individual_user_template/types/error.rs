@@ -31,6 +31,18 @@ pub enum BetOnCurrentlyViewingPostError {
     UserNotLoggedIn,
     UserPrincipalNotSet,
     PostCreatorCanisterCallFailed,
+    BalanceOverflow,
+}
+
+impl From<TokenError> for BetOnCurrentlyViewingPostError {
+    fn from(value: TokenError) -> Self {
+        match value {
+            TokenError::InsufficientBalance { .. } => {
+                BetOnCurrentlyViewingPostError::InsufficientBalance
+            }
+            TokenError::BalanceOverflow => BetOnCurrentlyViewingPostError::BalanceOverflow,
+        }
+    }
 }
 
 #[derive(CandidType, Deserialize, PartialEq, Eq, Debug)]
@@ -40,12 +52,15 @@ pub enum FollowAnotherUserProfileError {
     UsersICanFollowListIsFull,
     UserITriedToFollowCrossCanisterCallFailed,
     UserITriedToFollowHasTheirFollowersListFull,
+    /// More principals were passed than can be checked in one request
+    TooManyPrincipalsToCheck,
 }
 
 #[derive(CandidType, Deserialize, PartialEq, Eq, Debug)]
 pub enum CdaoDeployError {
     Unregistered,
-    TokenLimit(usize),
+    /// Carries the user's effective creator token quota
+    TokenLimit(u64),
     CallError(RejectionCode, String),
     InvalidInitPayload(String),
     Unauthenticated,
@@ -82,6 +97,15 @@ pub enum AirdropError {
     InvalidRoot,
     CallError(RejectionCode, String),
     Transfer(TransferError),
+    UserCanisterNotInTheNetwork,
+    Unauthenticated,
+    InvalidCampaignConfig(String),
+    CampaignNotFound,
+    CampaignNotActive,
+    NotEligible,
+    BudgetExhausted,
+    NothingToClaim,
+    ClaimInProgress,
 }
 
 impl From<(RejectionCode, String)> for AirdropError {
@@ -89,3 +113,62 @@ impl From<(RejectionCode, String)> for AirdropError {
         AirdropError::CallError(value.0, value.1)
     }
 }
+
+#[derive(CandidType, Deserialize, PartialEq, Eq, Debug)]
+pub enum SnsNeuronError {
+    Unauthenticated,
+    InvalidRoot,
+    NoBalance,
+    CallError(RejectionCode, String),
+    Transfer(TransferError),
+    /// Error message returned by the governance canister of the token
+    Governance(String),
+    PendingStakeNotFound,
+    /// The neuron of the stake is being claimed
+    StakeInProgress,
+}
+
+impl From<(RejectionCode, String)> for SnsNeuronError {
+    fn from(value: (RejectionCode, String)) -> Self {
+        SnsNeuronError::CallError(value.0, value.1)
+    }
+}
+
+#[derive(CandidType, Deserialize, PartialEq, Eq, Debug)]
+pub enum SwapParticipationError {
+    Unauthenticated,
+    InvalidRoot,
+    CallError(RejectionCode, String),
+    Transfer(TransferError),
+    SwapNotOpen,
+    InvalidAmount {
+        min_amount_icp_e8s: u64,
+        max_amount_icp_e8s: u64,
+    },
+    SwapNotFinalized,
+    NothingToClaim,
+    CommitmentNotFound,
+    ParticipationInProgress,
+    /// Error reported by the swap canister that has no variant of its own
+    Swap(String),
+}
+
+impl From<(RejectionCode, String)> for SwapParticipationError {
+    fn from(value: (RejectionCode, String)) -> Self {
+        SwapParticipationError::CallError(value.0, value.1)
+    }
+}
+
+#[derive(CandidType, Deserialize, PartialEq, Eq, Debug)]
+pub enum NotificationError {
+    Unauthorized,
+    ReachedEndOfItemsList,
+    ExceededMaxNumberOfItemsAllowedInOneRequest,
+    PostNotFound,
+}
+
+#[derive(CandidType, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub enum TokenError {
+    InsufficientBalance { balance: u64, required: u64 },
+    BalanceOverflow,
+}
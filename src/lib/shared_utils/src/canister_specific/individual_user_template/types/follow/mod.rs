@@ -62,6 +62,14 @@ pub struct FollowEntryDetail {
     pub canister_id: Principal,
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, CandidType, Debug)]
+pub struct SuggestedCreator {
+    pub principal_id: Principal,
+    pub canister_id: Principal,
+    /// Number of profiles I follow that also follow this creator
+    pub followed_by_count: u64,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -16,9 +16,8 @@ use crate::common::types::{
 };
 
 use super::{
-    error::BetOnCurrentlyViewingPostError,
+    error::{BetOnCurrentlyViewingPostError, TokenError},
     post::{FeedScore, Post},
-    token::TokenBalance,
 };
 
 #[derive(CandidType, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -527,11 +526,12 @@ impl Post {
         }
     }
 
+    /// Returns the commission payouts earned by the post creator. The caller records them.
+    /// Nothing is written when a payout does not fit in a `u64`.
     pub fn tabulate_hot_or_not_outcome_for_slot_v1(
         &mut self,
         post_canister_id: &CanisterId,
         slot_id: &u8,
-        token_balance: &mut TokenBalance,
         current_time: &SystemTime,
         room_details_map: &mut ic_stable_structures::btreemap::BTreeMap<
             GlobalRoomId,
@@ -543,14 +543,19 @@ impl Post {
             BetDetails,
             VirtualMemory<DefaultMemoryImpl>,
         >,
-    ) {
+    ) -> Result<Vec<TokenEvent>, TokenError> {
+        let mut commission_token_events = vec![];
+        let mut tabulated_room_details = vec![];
+        let mut tabulated_bet_details = vec![];
+
         let start_global_room_id = GlobalRoomId(self.id, *slot_id, 1);
         let end_global_room_id = GlobalRoomId(self.id, *slot_id + 1, 1);
 
         let room_details = room_details_map
             .range(start_global_room_id..end_global_room_id)
             .collect::<Vec<_>>();
-        room_details.iter().for_each(|(groomid, room_detail)| {
+
+        for (groomid, room_detail) in room_details {
             let mut room_detail = room_detail.clone();
             let room_id = groomid.2;
 
@@ -567,10 +572,12 @@ impl Post {
                 }
 
                 // * Reward creator with commission. Commission is 10% of total pot
-                token_balance.handle_token_event(TokenEvent::HotOrNotOutcomePayout {
-                    amount: room_detail.room_bets_total_pot
-                        * HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE
-                        / 100,
+                commission_token_events.push(TokenEvent::HotOrNotOutcomePayout {
+                    amount: get_percentage_of_amount(
+                        room_detail.room_bets_total_pot,
+                        1,
+                        HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE,
+                    )?,
                     details: HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet {
                         post_canister_id: *post_canister_id,
                         post_id: self.id,
@@ -581,58 +588,73 @@ impl Post {
                     timestamp: *current_time,
                 });
 
-                room_details_map.insert(*groomid, room_detail.clone());
+                tabulated_room_details.push((groomid, room_detail.clone()));
             }
 
             // * Reward individual participants
 
             let bets_map: Vec<(GlobalBetId, BetDetails)> = bet_details_map
                 .iter()
-                .filter(|(global_bet_id, _)| global_bet_id.0 == *groomid)
+                .filter(|(global_bet_id, _)| global_bet_id.0 == groomid)
                 .collect();
 
-            bets_map.into_iter().for_each(|(gbetid, mut bet_detail)| {
-                match &room_detail.bet_outcome {
-                    RoomBetPossibleOutcomes::HotWon => {
-                        if bet_detail.bet_direction == BetDirection::Hot {
-                            bet_detail.payout = BetPayout::Calculated(
-                                bet_detail.amount
-                                    * HOT_OR_NOT_BET_WINNINGS_MULTIPLIER
-                                    * (100 - HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE)
-                                    / 100,
-                            );
-                        } else {
-                            bet_detail.payout = BetPayout::Calculated(0);
-                        }
-                    }
-                    RoomBetPossibleOutcomes::NotWon => {
-                        if bet_detail.bet_direction == BetDirection::Not {
-                            bet_detail.payout = BetPayout::Calculated(
-                                bet_detail.amount
-                                    * HOT_OR_NOT_BET_WINNINGS_MULTIPLIER
-                                    * (100 - HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE)
-                                    / 100,
-                            );
-                        } else {
-                            bet_detail.payout = BetPayout::Calculated(0);
-                        }
+            for (gbetid, mut bet_detail) in bets_map {
+                // * winners get their bet multiplied, a draw returns the bet, both less commission
+                let payout_multiplier = match &room_detail.bet_outcome {
+                    RoomBetPossibleOutcomes::BetOngoing => None,
+                    RoomBetPossibleOutcomes::Draw => Some(1),
+                    RoomBetPossibleOutcomes::HotWon
+                        if bet_detail.bet_direction == BetDirection::Hot =>
+                    {
+                        Some(HOT_OR_NOT_BET_WINNINGS_MULTIPLIER)
                     }
-                    RoomBetPossibleOutcomes::Draw => {
-                        bet_detail.payout = BetPayout::Calculated(
-                            bet_detail.amount
-                                * (100 - HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE)
-                                / 100,
-                        );
+                    RoomBetPossibleOutcomes::NotWon
+                        if bet_detail.bet_direction == BetDirection::Not =>
+                    {
+                        Some(HOT_OR_NOT_BET_WINNINGS_MULTIPLIER)
                     }
-                    RoomBetPossibleOutcomes::BetOngoing => {}
+                    RoomBetPossibleOutcomes::HotWon | RoomBetPossibleOutcomes::NotWon => Some(0),
                 };
 
+                if let Some(payout_multiplier) = payout_multiplier {
+                    bet_detail.payout = BetPayout::Calculated(get_percentage_of_amount(
+                        bet_detail.amount,
+                        payout_multiplier,
+                        100 - HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE,
+                    )?);
+                }
+
+                tabulated_bet_details.push((gbetid, bet_detail));
+            }
+        }
+
+        tabulated_room_details
+            .into_iter()
+            .for_each(|(groomid, room_detail)| {
+                room_details_map.insert(groomid, room_detail);
+            });
+        tabulated_bet_details
+            .into_iter()
+            .for_each(|(gbetid, bet_detail)| {
                 bet_details_map.insert(gbetid, bet_detail);
             });
-        });
+
+        Ok(commission_token_events)
     }
 }
 
+/// `amount * multiplier * percentage / 100`, computed in `u128` so that only a result that does not
+/// fit in a `u64` fails.
+fn get_percentage_of_amount(
+    amount: u64,
+    multiplier: u64,
+    percentage: u64,
+) -> Result<u64, TokenError> {
+    let result = amount as u128 * multiplier as u128 * percentage as u128 / 100;
+
+    u64::try_from(result).map_err(|_| TokenError::BalanceOverflow)
+}
+
 #[cfg(test)]
 pub mod test_hot_or_not {
     use std::{cell::RefCell, time::Duration};
@@ -642,7 +664,9 @@ pub mod test_hot_or_not {
         get_mock_user_alice_canister_id, get_mock_user_alice_principal_id,
     };
 
-    use crate::canister_specific::individual_user_template::types::post::PostDetailsFromFrontend;
+    use crate::canister_specific::individual_user_template::types::{
+        post::PostDetailsFromFrontend, token::TokenBalance,
+    };
 
     use super::*;
     pub type Memory = VirtualMemory<DefaultMemoryImpl>;
@@ -2271,6 +2295,7 @@ pub mod test_hot_or_not {
             &post_creation_time,
         );
         let mut token_balance = TokenBalance::default();
+        let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
         let tabulation_canister_id = get_mock_user_alice_canister_id();
 
         assert!(post.hot_or_not_details.is_some());
@@ -2377,13 +2402,19 @@ pub mod test_hot_or_not {
         post.tabulate_hot_or_not_outcome_for_slot_v1(
             &tabulation_canister_id,
             &1,
-            &mut token_balance,
             &score_tabulation_time,
             &mut room_details_map,
             &mut bet_details_map,
-        );
+        )
+        .unwrap()
+        .into_iter()
+        .for_each(|token_event| {
+            token_balance
+                .apply_token_event(token_event, &mut token_transaction_history)
+                .unwrap();
+        });
 
-        assert_eq!(token_balance.utility_token_transaction_history.len(), 1);
+        assert_eq!(token_transaction_history.len(), 1);
         assert_eq!(token_balance.utility_token_balance, 355);
 
         let global_room_id = GlobalRoomId(0, 1, 1);
@@ -2526,13 +2557,19 @@ pub mod test_hot_or_not {
         post.tabulate_hot_or_not_outcome_for_slot_v1(
             &get_mock_user_alice_canister_id(),
             &2,
-            &mut token_balance,
             &score_tabulation_time,
             &mut room_details_map,
             &mut bet_details_map,
-        );
+        )
+        .unwrap()
+        .into_iter()
+        .for_each(|token_event| {
+            token_balance
+                .apply_token_event(token_event, &mut token_transaction_history)
+                .unwrap();
+        });
 
-        assert_eq!(token_balance.utility_token_transaction_history.len(), 2);
+        assert_eq!(token_transaction_history.len(), 2);
         assert_eq!(token_balance.utility_token_balance, 355 + 458);
 
         let global_room_id = GlobalRoomId(0, 2, 1);
@@ -2591,6 +2628,7 @@ pub mod test_hot_or_not {
             &post_creation_time,
         );
         let mut token_balance = TokenBalance::default();
+        let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
 
         assert!(post.hot_or_not_details.is_some());
 
@@ -2771,13 +2809,19 @@ pub mod test_hot_or_not {
         post.tabulate_hot_or_not_outcome_for_slot_v1(
             &get_mock_user_alice_canister_id(),
             &1,
-            &mut token_balance,
             &score_tabulation_time,
             &mut room_details_map,
             &mut bet_details_map,
-        );
+        )
+        .unwrap()
+        .into_iter()
+        .for_each(|token_event| {
+            token_balance
+                .apply_token_event(token_event, &mut token_transaction_history)
+                .unwrap();
+        });
 
-        assert_eq!(token_balance.utility_token_transaction_history.len(), 2);
+        assert_eq!(token_transaction_history.len(), 2);
         assert_eq!(token_balance.utility_token_balance, 487 + 321);
 
         // * Room 1
@@ -2869,6 +2913,7 @@ pub mod test_hot_or_not {
             &post_creation_time,
         );
         let mut token_balance = TokenBalance::default();
+        let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
 
         assert!(post.hot_or_not_details.is_some());
 
@@ -2979,13 +3024,19 @@ pub mod test_hot_or_not {
         post.tabulate_hot_or_not_outcome_for_slot_v1(
             &get_mock_user_alice_canister_id(),
             &1,
-            &mut token_balance,
             &score_tabulation_time,
             &mut room_details_map,
             &mut bet_details_map,
-        );
+        )
+        .unwrap()
+        .into_iter()
+        .for_each(|token_event| {
+            token_balance
+                .apply_token_event(token_event, &mut token_transaction_history)
+                .unwrap();
+        });
 
-        assert_eq!(token_balance.utility_token_transaction_history.len(), 1);
+        assert_eq!(token_transaction_history.len(), 1);
         assert_eq!(token_balance.utility_token_balance, 390);
 
         let global_room_id = GlobalRoomId(0, 1, 1);
@@ -3020,4 +3071,14 @@ pub mod test_hot_or_not {
                 );
             });
     }
+
+    #[test]
+    fn test_get_percentage_of_amount() {
+        assert_eq!(get_percentage_of_amount(u64::MAX, 1, 10), Ok(u64::MAX / 10));
+        assert_eq!(get_percentage_of_amount(100, 2, 90), Ok(180));
+        assert_eq!(
+            get_percentage_of_amount(u64::MAX, 2, 90),
+            Err(TokenError::BalanceOverflow)
+        );
+    }
 }
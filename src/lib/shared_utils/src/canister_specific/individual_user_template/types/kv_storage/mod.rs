@@ -1,12 +1,32 @@
+use std::time::SystemTime;
+
 use candid::{CandidType, Principal};
 use serde::{Deserialize, Serialize};
 
+pub const DEFAULT_MAX_VALUE_SIZE_IN_BYTES: u64 = 400;
+// Values are returned in a single reply, so they stay well under the 2 MiB reply size limit
+pub const MAX_VALUE_SIZE_IN_BYTES: u64 = 1024 * 1024;
+pub const MAX_NAMESPACE_KEYS_PAGE_SIZE: u64 = 100;
+pub const MAX_KEY_VALUE_TRANSACTION_OPERATIONS: usize = 100;
+pub const MAX_PENDING_UPLOADS_PER_NAMESPACE: usize = 10;
+pub const KEY_VALUE_UPLOAD_TIMEOUT_IN_SECONDS: u64 = 60 * 60;
+
 #[derive(CandidType, Copy, Clone, Serialize, Deserialize, Debug)]
 pub enum NamespaceErrors {
     ValueTooBig,
     Unauthorized,
     NamespaceNotFound,
     UserNotSignedUp,
+    KeyLimitReached,
+    StorageLimitReached,
+    InvalidLimits,
+    VersionMismatch,
+    InvalidExpiry,
+    InvalidTransaction,
+    ValueNotText,
+    UploadNotFound,
+    InvalidChunk,
+    UploadIncomplete,
 }
 
 #[derive(CandidType, Serialize, Deserialize)]
@@ -15,3 +35,112 @@ pub struct NamespaceForFrontend {
     pub title: String,
     pub owner_id: Principal,
 }
+
+/// Access granted to a principal other than the namespace owner and the profile owner, who can
+/// always read, write and manage the namespace.
+#[derive(CandidType, Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum NamespacePermission {
+    Read,
+    ReadWrite,
+}
+
+impl NamespacePermission {
+    pub fn allows(&self, required_permission: NamespacePermission) -> bool {
+        match self {
+            NamespacePermission::ReadWrite => true,
+            NamespacePermission::Read => required_permission == NamespacePermission::Read,
+        }
+    }
+}
+
+/// Sizes are counted in bytes of both keys and values.
+#[derive(CandidType, Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct NamespaceLimits {
+    pub max_value_size_in_bytes: u64,
+    pub max_number_of_keys: Option<u64>,
+    pub max_total_size_in_bytes: Option<u64>,
+}
+
+impl NamespaceLimits {
+    /// Limits set before the maximum value size was lowered may allow bigger values
+    pub fn max_value_size(&self) -> u64 {
+        self.max_value_size_in_bytes.min(MAX_VALUE_SIZE_IN_BYTES)
+    }
+}
+
+impl Default for NamespaceLimits {
+    fn default() -> Self {
+        Self {
+            max_value_size_in_bytes: DEFAULT_MAX_VALUE_SIZE_IN_BYTES,
+            max_number_of_keys: None,
+            max_total_size_in_bytes: None,
+        }
+    }
+}
+
+#[derive(CandidType, Copy, Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct NamespaceUsage {
+    pub number_of_keys: u64,
+    pub total_size_in_bytes: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct NamespaceSettingsForFrontend {
+    pub permissions: Vec<(Principal, NamespacePermission)>,
+    pub limits: NamespaceLimits,
+    pub usage: NamespaceUsage,
+}
+
+/// A page of keys in ascending order. `next_cursor` is the last key of the page and is set only
+/// when more keys follow it.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct NamespaceKeysPage {
+    pub keys: Vec<String>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct KeyValueEntryForFrontend {
+    pub value: String,
+    pub version: u64,
+    pub expires_at: Option<SystemTime>,
+}
+
+/// The state a key must be in for an operation to apply. Expired keys count as absent.
+#[derive(CandidType, Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum KeyValueCondition {
+    Any,
+    Absent,
+    Version(u64),
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct KeyValueBlobForFrontend {
+    #[serde(with = "serde_bytes")]
+    pub value: Vec<u8>,
+    pub content_type: Option<String>,
+    pub version: u64,
+    pub expires_at: Option<SystemTime>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum KeyValueAction {
+    Write {
+        value: String,
+        expires_at: Option<SystemTime>,
+    },
+    WriteBlob {
+        #[serde(with = "serde_bytes")]
+        value: Vec<u8>,
+        content_type: Option<String>,
+        expires_at: Option<SystemTime>,
+    },
+    Delete,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct KeyValueOperation {
+    pub key: String,
+    pub condition: KeyValueCondition,
+    pub action: KeyValueAction,
+}
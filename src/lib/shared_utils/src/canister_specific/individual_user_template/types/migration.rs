@@ -27,4 +27,6 @@ pub enum MigrationErrors {
     HotOrNotSubnetCanisterIdNotFound,
     UserIndexCanisterIdNotFound,
     RequestCycleFromUserIndexFailed(String),
+    TokenBalanceOverflow,
+    InsufficientTokenBalance,
 }
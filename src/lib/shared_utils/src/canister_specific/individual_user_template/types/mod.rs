@@ -1,14 +1,21 @@
 pub mod arg;
 pub mod configuration;
+pub mod creator_token_quota;
+pub mod engagement_reward;
 pub mod error;
 pub mod follow;
 pub mod hot_or_not;
 pub mod kv_storage;
 pub mod migration;
 pub mod ml_data;
+pub mod notification;
 pub mod post;
 pub mod profile;
+pub mod referral;
 pub mod session;
+pub mod sns_neuron;
+pub mod swap_participation;
 pub mod token;
+pub mod wallet;
 pub mod cdao;
 pub mod device_id;
@@ -0,0 +1,85 @@
+use std::{borrow::Cow, time::SystemTime};
+
+use candid::{CandidType, Decode, Deserialize, Encode, Nat, Principal};
+use ic_stable_structures::{storable::Bound, Storable};
+use serde::Serialize;
+
+use crate::common::types::app_primitive_type::PostId;
+
+use super::engagement_reward::EngagementMilestone;
+
+pub type NotificationId = u64;
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum NotificationType {
+    NewFollower {
+        follower_principal_id: Principal,
+        follower_canister_id: Principal,
+    },
+    PostLiked {
+        post_id: PostId,
+        liked_by_principal_id: Principal,
+    },
+    PostCommented {
+        post_id: PostId,
+        commented_by_principal_id: Principal,
+    },
+    BetWon {
+        post_canister_id: Principal,
+        post_id: PostId,
+        winnings_amount: u64,
+    },
+    BetLost {
+        post_canister_id: Principal,
+        post_id: PostId,
+    },
+    BetDraw {
+        post_canister_id: Principal,
+        post_id: PostId,
+        refund_amount: u64,
+    },
+    CommissionEarned {
+        post_id: PostId,
+        slot_id: u8,
+        amount: u64,
+    },
+    ReferralRewardCredited {
+        referrer_user_principal_id: Principal,
+        referee_user_principal_id: Principal,
+        amount: u64,
+    },
+    EngagementRewardCredited {
+        milestone: EngagementMilestone,
+        amount: u64,
+    },
+    /// Recorded in the creator's inbox before airdrops were notified to the claimant
+    TokenAirdropClaimed {
+        token_root: Principal,
+        claimed_by_principal_id: Principal,
+        amount: Nat,
+    },
+    TokenAirdropReceived {
+        token_root: Principal,
+        creator_canister_id: Principal,
+        amount: Nat,
+    },
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub notification_type: NotificationType,
+    pub created_at: SystemTime,
+    pub read: bool,
+}
+
+impl Storable for Notification {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).unwrap()
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
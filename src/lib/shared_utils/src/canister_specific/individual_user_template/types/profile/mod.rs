@@ -1,3 +1,5 @@
+use std::time::SystemTime;
+
 use candid::{CandidType, Deserialize, Principal};
 use serde::Serialize;
 
@@ -12,6 +14,23 @@ pub struct UserProfile {
     pub profile_stats: UserProfileGlobalStats,
     #[serde(default)]
     pub referrer_details: Option<UserCanisterDetails>,
+    #[serde(default)]
+    pub unique_user_name_history: Vec<UniqueUserNameHistoryEntry>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub external_links: Vec<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub banner_picture_url: Option<String>,
+}
+
+/// A unique user name this profile used to have, oldest first.
+#[derive(Clone, CandidType, Deserialize, Debug, Serialize, PartialEq, Eq)]
+pub struct UniqueUserNameHistoryEntry {
+    pub unique_user_name: String,
+    pub changed_at: SystemTime,
 }
 
 #[derive(Clone, CandidType, Deserialize, Debug, Serialize, PartialEq, Eq)]
@@ -47,6 +66,24 @@ pub struct UserProfileDetailsForFrontendV2 {
     pub migration_info: MigrationInfo
 }
 
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct UserProfileDetailsForFrontendV3 {
+    pub display_name: Option<String>,
+    pub followers_count: u64,
+    pub following_count: u64,
+    pub principal_id: Principal,
+    pub profile_picture_url: Option<String>,
+    pub profile_stats: UserProfileGlobalStats,
+    pub lifetime_earnings: u64,
+    pub unique_user_name: Option<String>,
+    pub referrer_details: Option<UserCanisterDetails>,
+    pub migration_info: MigrationInfo,
+    pub bio: Option<String>,
+    pub external_links: Vec<String>,
+    pub location: Option<String>,
+    pub banner_picture_url: Option<String>,
+}
+
 #[derive(CandidType, Deserialize, Clone, Copy, Debug, Default, Serialize, PartialEq, Eq)]
 pub struct UserProfileGlobalStats {
     pub hot_bets_received: u64,
@@ -58,3 +95,13 @@ pub struct UserProfileUpdateDetailsFromFrontend {
     pub display_name: Option<String>,
     pub profile_picture_url: Option<String>,
 }
+
+#[derive(Deserialize, CandidType)]
+pub struct UserProfileUpdateDetailsFromFrontendV2 {
+    pub display_name: Option<String>,
+    pub profile_picture_url: Option<String>,
+    pub bio: Option<String>,
+    pub external_links: Vec<String>,
+    pub location: Option<String>,
+    pub banner_picture_url: Option<String>,
+}
@@ -0,0 +1,124 @@
+use std::time::SystemTime;
+
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_REFERRAL_REWARD_AMOUNT: u64 = 500;
+pub const DEFAULT_MAX_REWARDED_REFERRALS_PER_REFERRER: u64 = 100;
+
+const PRINCIPAL_TEXT_GROUP_LENGTH: usize = 5;
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReferralConfig {
+    pub referrer_reward_amount: u64,
+    pub referee_reward_amount: u64,
+    pub max_rewarded_referrals_per_referrer: u64,
+}
+
+impl Default for ReferralConfig {
+    fn default() -> Self {
+        Self {
+            referrer_reward_amount: DEFAULT_REFERRAL_REWARD_AMOUNT,
+            referee_reward_amount: DEFAULT_REFERRAL_REWARD_AMOUNT,
+            max_rewarded_referrals_per_referrer: DEFAULT_MAX_REWARDED_REFERRALS_PER_REFERRER,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferralRejectionReason {
+    /// The referee is the referrer or signs in from one of the referrer's devices
+    SelfReferral,
+    /// The referee referred the referrer, or shares a device with another of the referrer's
+    /// referees
+    ReferralRing,
+    ReferrerRewardCapReached,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferralStatus {
+    Pending,
+    Rewarded { amount: u64 },
+    Rejected { reason: ReferralRejectionReason },
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReferralDetails {
+    pub referee_principal_id: Principal,
+    pub referee_canister_id: Principal,
+    pub status: ReferralStatus,
+    pub referred_at: SystemTime,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ReferralError {
+    Unauthorized,
+    InvalidReferralCode,
+    ReferrerAlreadySet,
+    ReferrerNotSignedUp,
+    ReferrerCrossCanisterCallFailed(String),
+    UserIndexCanisterIdNotFound,
+    UserIndexCrossCanisterCallFailed,
+    SenderIsNotAUserCanisterOnNetwork,
+    Rejected(ReferralRejectionReason),
+}
+
+/// A referral code is the user's canister id without the dashes, so it resolves without a
+/// lookup and its checksum catches typos.
+pub fn get_referral_code_for_user_canister(user_canister_id: Principal) -> String {
+    user_canister_id
+        .to_text()
+        .replace('-', "")
+        .to_ascii_uppercase()
+}
+
+pub fn get_user_canister_id_from_referral_code(referral_code: &str) -> Option<Principal> {
+    let referral_code: Vec<char> = referral_code
+        .trim()
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    let principal_text = referral_code
+        .chunks(PRINCIPAL_TEXT_GROUP_LENGTH)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("-");
+
+    Principal::from_text(principal_text)
+        .ok()
+        .filter(|user_canister_id| {
+            *user_canister_id != Principal::anonymous()
+                && *user_canister_id != Principal::management_canister()
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_referral_code_round_trip() {
+        let user_canister_id = Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
+
+        let referral_code = get_referral_code_for_user_canister(user_canister_id);
+        assert_eq!(referral_code, "RRKAHFQAAAAAAAAAAAAQCAI");
+
+        assert_eq!(
+            get_user_canister_id_from_referral_code(&referral_code),
+            Some(user_canister_id)
+        );
+        assert_eq!(
+            get_user_canister_id_from_referral_code(" rrkah-fqaaa-aaaaa-aaaaq-cai "),
+            Some(user_canister_id)
+        );
+
+        // * the checksum rejects a mistyped code
+        assert_eq!(
+            get_user_canister_id_from_referral_code("RRKAHFQAAAAAAAAAAAABCAI"),
+            None
+        );
+        assert_eq!(get_user_canister_id_from_referral_code(""), None);
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::SystemTime;
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnsVote {
+    Yes,
+    No,
+}
+
+impl SnsVote {
+    /// Value of the vote in the governance canister's `RegisterVote` command
+    pub fn to_governance_vote(self) -> i32 {
+        match self {
+            SnsVote::Yes => 1,
+            SnsVote::No => 2,
+        }
+    }
+}
+
+/// Neuron of a creator token staked from the user canister. The neuron id is also the
+/// subaccount of the neuron on the governance canister.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SnsNeuronDetails {
+    pub neuron_id: Vec<u8>,
+    pub stake_e8s: u64,
+    pub maturity_e8s_equivalent: u64,
+    /// Set while the neuron is not dissolving
+    pub dissolve_delay_seconds: Option<u64>,
+    /// Set while the neuron is dissolving
+    pub when_dissolved_timestamp_seconds: Option<u64>,
+    pub created_timestamp_seconds: u64,
+}
+
+/// Stake sent to the governance canister of a creator token whose neuron is not claimed and
+/// configured yet. It is recorded before the transfer, so the neuron can be claimed again with
+/// `claim_or_refresh_sns_neuron` if staking is interrupted.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingSnsNeuronStake {
+    pub token_root: Principal,
+    /// Memo of the transfer, the neuron is claimed with it
+    pub memo: u64,
+    pub neuron_id: Vec<u8>,
+    pub amount_e8s: u64,
+    pub additional_dissolve_delay_seconds: u32,
+    pub created_at: SystemTime,
+}
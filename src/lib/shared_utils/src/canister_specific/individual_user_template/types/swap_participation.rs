@@ -0,0 +1,29 @@
+use std::time::SystemTime;
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapCommitmentStatus {
+    /// ICP is being sent to the swap and has not been accepted yet. Recorded before the transfer
+    /// so that ICP stranded by an interrupted participation can still be refreshed or refunded.
+    Pending,
+    /// ICP has been accepted by the swap, the tokens are distributed once it is finalized
+    Committed,
+    /// The swap was finalized and the neurons of the canister were created
+    Claimed,
+    /// The swap was aborted and the committed ICP was sent back to the canister
+    Refunded,
+}
+
+/// Participation of the user canister in the swap of a creator token
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapCommitment {
+    pub token_root: Principal,
+    pub swap_canister: Principal,
+    /// Total ICP accepted by the swap from this canister
+    pub amount_icp_e8s: u64,
+    pub status: SwapCommitmentStatus,
+    pub committed_at: SystemTime,
+    pub updated_at: SystemTime,
+}
@@ -1,17 +1,86 @@
 use std::collections::BTreeMap;
 
-use candid::{CandidType, Deserialize};
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::Memory;
 use serde::Serialize;
 use serde_json_any_key::*;
 
 use crate::common::types::utility_token::token_event::{
-    HotOrNotOutcomePayoutEvent, MintEvent, StakeEvent, TokenEvent,
-    HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE, HOT_OR_NOT_BET_WINNINGS_MULTIPLIER,
+    HotOrNotOutcomePayoutEvent, TokenEvent, HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE,
+    HOT_OR_NOT_BET_WINNINGS_MULTIPLIER,
 };
 
+use super::error::TokenError;
+
+pub const UTILITY_TOKEN_NAME: &str = "HotOrNot Utility Token";
+pub const UTILITY_TOKEN_SYMBOL: &str = "HOT";
+pub const UTILITY_TOKEN_DECIMALS: u8 = 0;
+pub const UTILITY_TOKEN_FEE: u64 = 0;
+/// Subaccount of the individual canister holding the mirrored balance on the ledger. The
+/// canister's default account is the minting account of that ledger.
+pub const UTILITY_TOKEN_LEDGER_MIRROR_SUBACCOUNT: [u8; 32] = [1; 32];
+
+/// Append only log of token events. Event ids start at 1 and increase by one per event.
+pub trait TokenTransactionHistory {
+    fn append_token_event(&mut self, token_event: TokenEvent) -> u64;
+}
+
+impl TokenTransactionHistory for BTreeMap<u64, TokenEvent> {
+    fn append_token_event(&mut self, token_event: TokenEvent) -> u64 {
+        let token_event_id = self.last_key_value().map_or(0, |(id, _)| *id) + 1;
+        self.insert(token_event_id, token_event);
+        token_event_id
+    }
+}
+
+impl<M: Memory> TokenTransactionHistory
+    for ic_stable_structures::btreemap::BTreeMap<u64, TokenEvent, M>
+{
+    fn append_token_event(&mut self, token_event: TokenEvent) -> u64 {
+        let token_event_id = self.last_key_value().map_or(0, |(id, _)| id) + 1;
+        self.insert(token_event_id, token_event);
+        token_event_id
+    }
+}
+
+#[derive(Clone, Deserialize, CandidType, Debug, PartialEq, Eq, Serialize)]
+pub struct TokenBalanceReconciliation {
+    pub recorded_balance: u64,
+    pub recomputed_balance: i128,
+    /// `recorded_balance - recomputed_balance`
+    pub drift: i128,
+    pub number_of_events: u64,
+}
+
+/// Balance recomputed from the start of the event log up to an event, so that reconciling only
+/// reads the events recorded after it.
+#[derive(Default, Clone, Deserialize, Debug, PartialEq, Eq, Serialize)]
+pub struct TokenBalanceReconciliationCheckpoint {
+    /// Id of the last event folded into the checkpoint, 0 before any event
+    pub last_token_event_id: u64,
+    pub recomputed_balance: i128,
+    pub number_of_events: u64,
+}
+
+impl TokenBalanceReconciliationCheckpoint {
+    pub fn fold_token_event(&mut self, token_event_id: u64, token_event: &TokenEvent) {
+        self.last_token_event_id = token_event_id;
+        self.recomputed_balance += get_recomputed_balance_change_for_token_event(token_event);
+        self.number_of_events += 1;
+    }
+}
+
+#[derive(Clone, Deserialize, CandidType, Debug, PartialEq, Eq, Serialize)]
+pub struct UtilityTokenLedgerMirrorStatus {
+    pub ledger_canister_id: Option<Principal>,
+    pub number_of_pending_operations: u64,
+}
+
 #[derive(Default, Clone, Deserialize, CandidType, Debug, Serialize)]
 pub struct TokenBalance {
     pub utility_token_balance: u64,
+    /// Only read to move events recorded before the history lived in stable memory
+    #[serde(default)]
     pub utility_token_transaction_history: BTreeMap<u64, TokenEvent>,
     pub lifetime_earnings: u64,
 }
@@ -21,91 +90,152 @@ impl TokenBalance {
         self.utility_token_balance
     }
 
-    pub fn get_utility_token_transaction_history(&self) -> &BTreeMap<u64, TokenEvent> {
-        &self.utility_token_transaction_history
+    /// Recomputes the balance from the complete event log and reports how far the recorded
+    /// balance has drifted from it.
+    pub fn reconcile_balance(
+        &self,
+        token_events: impl Iterator<Item = TokenEvent>,
+    ) -> TokenBalanceReconciliation {
+        let mut checkpoint = TokenBalanceReconciliationCheckpoint::default();
+        token_events.enumerate().for_each(|(index, token_event)| {
+            checkpoint.fold_token_event(index as u64 + 1, &token_event);
+        });
+
+        self.reconcile_balance_with_checkpoint(&checkpoint)
     }
 
-    pub fn handle_token_event(&mut self, token_event: TokenEvent) {
-        match &token_event {
-            TokenEvent::Mint { details, .. } => match details {
-                MintEvent::NewUserSignup { .. } => {
-                    self.utility_token_balance += token_event.get_token_amount_for_token_event();
-                    self.lifetime_earnings += token_event.get_token_amount_for_token_event();
-                }
-                MintEvent::Referral { .. } => {
-                    self.utility_token_balance += token_event.get_token_amount_for_token_event();
-                    self.lifetime_earnings += token_event.get_token_amount_for_token_event();
-                }
-            },
-            TokenEvent::Burn => {}
-            TokenEvent::Transfer { amount, .. } => {
-                self.utility_token_balance -= amount;
-            }
-            TokenEvent::Receive { amount, .. } => {
-                self.utility_token_balance += amount;
-            }
-            TokenEvent::Stake { details, .. } => match details {
-                StakeEvent::BetOnHotOrNotPost { .. } => {
-                    // self.utility_token_balance -= bet_amount;
-                }
-            },
-            TokenEvent::HotOrNotOutcomePayout { details, .. } => match details {
-                HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet {
-                    room_pot_total_amount,
-                    ..
-                } => {
-                    self.utility_token_balance +=
-                        room_pot_total_amount * HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE / 100;
-                    self.lifetime_earnings +=
-                        room_pot_total_amount * HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE / 100;
-                }
-                HotOrNotOutcomePayoutEvent::WinningsEarnedFromBet {
-                    winnings_amount, ..
-                } => {
-                    self.utility_token_balance += winnings_amount;
-                    self.lifetime_earnings +=
-                        get_earnings_amount_from_winnings_amount(winnings_amount);
-                }
-            },
+    /// Reports how far the recorded balance has drifted from a checkpoint that has folded the
+    /// complete event log.
+    pub fn reconcile_balance_with_checkpoint(
+        &self,
+        checkpoint: &TokenBalanceReconciliationCheckpoint,
+    ) -> TokenBalanceReconciliation {
+        TokenBalanceReconciliation {
+            recorded_balance: self.utility_token_balance,
+            recomputed_balance: checkpoint.recomputed_balance,
+            drift: self.utility_token_balance as i128 - checkpoint.recomputed_balance,
+            number_of_events: checkpoint.number_of_events,
         }
+    }
 
-        let utility_token_transaction_history = &mut self.utility_token_transaction_history;
+    /// Applies the event to the balance and appends it to the history. A failing event leaves
+    /// both the balance and the history untouched. Returns the id the event was recorded under.
+    pub fn apply_token_event(
+        &mut self,
+        token_event: TokenEvent,
+        token_transaction_history: &mut impl TokenTransactionHistory,
+    ) -> Result<u64, TokenError> {
+        let utility_token_balance = match get_balance_change_for_token_event(&token_event) {
+            BalanceChange::Credit(amount) => self
+                .utility_token_balance
+                .checked_add(amount)
+                .ok_or(TokenError::BalanceOverflow)?,
+            BalanceChange::Debit(amount) => self.checked_debit(amount)?,
+            BalanceChange::None => self.utility_token_balance,
+        };
 
-        let last_key = *utility_token_transaction_history
-            .last_key_value()
-            .unwrap_or((&0, &TokenEvent::Burn))
-            .0;
+        let lifetime_earnings = self
+            .lifetime_earnings
+            .checked_add(get_earnings_for_token_event(&token_event))
+            .ok_or(TokenError::BalanceOverflow)?;
 
-        if utility_token_transaction_history.len() > 1500 {
-            utility_token_transaction_history.retain(|key, _| *key > last_key - 1000)
-        }
+        self.utility_token_balance = utility_token_balance;
+        self.lifetime_earnings = lifetime_earnings;
 
-        self.utility_token_transaction_history
-            .insert(last_key + 1, token_event);
+        Ok(token_transaction_history.append_token_event(token_event))
     }
 
     // this is being done to handle concurrency issues inside canister
-    pub fn adjust_balance_pre_bet(&mut self, bet_amount: u64) {
-        self.utility_token_balance -= bet_amount;
+    pub fn adjust_balance_pre_bet(&mut self, bet_amount: u64) -> Result<(), TokenError> {
+        self.utility_token_balance = self.checked_debit(bet_amount)?;
+        Ok(())
+    }
+
+    pub fn adjust_balance_for_failed_bet_placement(
+        &mut self,
+        bet_amount: u64,
+    ) -> Result<(), TokenError> {
+        self.utility_token_balance = self
+            .utility_token_balance
+            .checked_add(bet_amount)
+            .ok_or(TokenError::BalanceOverflow)?;
+        Ok(())
+    }
+
+    fn checked_debit(&self, amount: u64) -> Result<u64, TokenError> {
+        self.utility_token_balance
+            .checked_sub(amount)
+            .ok_or(TokenError::InsufficientBalance {
+                balance: self.utility_token_balance,
+                required: amount,
+            })
     }
+}
+
+enum BalanceChange {
+    Credit(u64),
+    Debit(u64),
+    None,
+}
+
+/// The event `amount` is the only amount read. Bets are debited through
+/// `adjust_balance_pre_bet` before the `Stake` is recorded, so a stake leaves the balance
+/// untouched when applied.
+fn get_balance_change_for_token_event(token_event: &TokenEvent) -> BalanceChange {
+    match token_event {
+        TokenEvent::Mint { amount, .. }
+        | TokenEvent::Receive { amount, .. }
+        | TokenEvent::TransferReverted { amount, .. }
+        | TokenEvent::HotOrNotOutcomePayout { amount, .. } => BalanceChange::Credit(*amount),
+        TokenEvent::Transfer { amount, .. } => BalanceChange::Debit(*amount),
+        TokenEvent::Burn | TokenEvent::Stake { .. } => BalanceChange::None,
+    }
+}
 
-    pub fn adjust_balance_for_failed_bet_placement(&mut self, bet_amount: u64) {
-        self.utility_token_balance += bet_amount;
+/// Signed change a complete event log implies for the balance. Unlike
+/// `get_balance_change_for_token_event` a stake counts as a debit here, since the pre-bet
+/// debit is never recorded on its own.
+pub fn get_recomputed_balance_change_for_token_event(token_event: &TokenEvent) -> i128 {
+    if let TokenEvent::Stake { amount, .. } = token_event {
+        return -(*amount as i128);
+    }
+
+    match get_balance_change_for_token_event(token_event) {
+        BalanceChange::Credit(amount) => amount as i128,
+        BalanceChange::Debit(amount) => -(amount as i128),
+        BalanceChange::None => 0,
+    }
+}
+
+fn get_earnings_for_token_event(token_event: &TokenEvent) -> u64 {
+    match token_event {
+        TokenEvent::Mint { amount, .. } => *amount,
+        TokenEvent::HotOrNotOutcomePayout {
+            amount, details, ..
+        } => match details {
+            HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet { .. } => *amount,
+            HotOrNotOutcomePayoutEvent::WinningsEarnedFromBet { .. } => {
+                get_earnings_amount_from_winnings_amount(amount)
+            }
+        },
+        _ => 0,
     }
 }
 
 fn get_earnings_amount_from_winnings_amount(winnings_amount: &u64) -> u64 {
-    let comission_subtracted_bet_amount = winnings_amount / HOT_OR_NOT_BET_WINNINGS_MULTIPLIER;
+    let comission_subtracted_bet_amount =
+        (winnings_amount / HOT_OR_NOT_BET_WINNINGS_MULTIPLIER) as u128;
+    // * widened so large payouts cannot overflow, the bet is always smaller than the winnings
     let bet_amount = comission_subtracted_bet_amount * 100
-        / (100 - HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE);
-    winnings_amount - bet_amount
+        / (100 - HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE) as u128;
+    winnings_amount - bet_amount as u64
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    mod test_handle_token_event {
+    mod test_apply_token_event {
         use std::time::SystemTime;
 
         use test_utils::setup::test_constants::{
@@ -113,116 +243,403 @@ mod test {
             get_mock_user_bob_principal_id,
         };
 
-        use crate::canister_specific::individual_user_template::types::hot_or_not::BetDirection;
+        use crate::{
+            canister_specific::individual_user_template::types::hot_or_not::{
+                BetDirection, BetOutcomeForBetMaker,
+            },
+            common::types::utility_token::token_event::{MintEvent, StakeEvent},
+        };
 
         use super::*;
 
         #[test]
-        fn test_handle_token_event_truncate_overflowing_entries() {
+        fn test_apply_token_event_keeps_every_entry() {
             let mut token_balance = TokenBalance::default();
+            let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
 
-            (0..1500).for_each(|_| {
-                token_balance.handle_token_event(TokenEvent::Burn);
+            (0..2000).for_each(|_| {
+                token_balance
+                    .apply_token_event(TokenEvent::Burn, &mut token_transaction_history)
+                    .unwrap();
             });
 
-            assert_eq!(token_balance.utility_token_transaction_history.len(), 1500);
+            assert_eq!(token_transaction_history.len(), 2000);
+            assert_eq!(*token_transaction_history.first_key_value().unwrap().0, 1);
+            assert_eq!(*token_transaction_history.last_key_value().unwrap().0, 2000);
+        }
+
+        #[test]
+        fn test_apply_token_event() {
+            let mut token_balance = TokenBalance::default();
+            let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::Mint {
+                        amount: 1000,
+                        details: MintEvent::NewUserSignup {
+                            new_user_principal_id: get_mock_user_alice_principal_id(),
+                        },
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            assert_eq!(token_balance.utility_token_balance, 1000);
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::Mint {
+                        amount: 500,
+                        details: MintEvent::Referral {
+                            referee_user_principal_id: get_mock_user_alice_principal_id(),
+                            referrer_user_principal_id: get_mock_user_bob_principal_id(),
+                        },
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            assert_eq!(token_balance.utility_token_balance, 1500);
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::Stake {
+                        amount: 100,
+                        details: StakeEvent::BetOnHotOrNotPost {
+                            post_canister_id: get_mock_user_alice_canister_id(),
+                            post_id: 1,
+                            bet_amount: 100,
+                            bet_direction: BetDirection::Hot,
+                        },
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            // this event is special and does not change the balance
+            assert_eq!(token_balance.utility_token_balance, 1500);
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::Transfer {
+                        amount: 200,
+                        to_account: get_mock_user_alice_canister_id(),
+                        timestamp: SystemTime::now(),
+                        memo: Some("thanks".to_string()),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            assert_eq!(token_balance.utility_token_balance, 1300);
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::TransferReverted {
+                        amount: 200,
+                        to_account: get_mock_user_alice_canister_id(),
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            assert_eq!(token_balance.utility_token_balance, 1500);
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::Receive {
+                        amount: 50,
+                        from_account: get_mock_user_alice_canister_id(),
+                        timestamp: SystemTime::now(),
+                        memo: None,
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            assert_eq!(token_balance.utility_token_balance, 1550);
+            assert_eq!(token_balance.lifetime_earnings, 1500);
+            assert_eq!(token_transaction_history.len(), 6);
+        }
+
+        #[test]
+        fn test_reconcile_balance() {
+            let mut token_balance = TokenBalance::default();
+            let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::Mint {
+                        amount: 1000,
+                        details: MintEvent::NewUserSignup {
+                            new_user_principal_id: get_mock_user_alice_principal_id(),
+                        },
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            // * bets are debited before the stake is recorded
+            token_balance.adjust_balance_pre_bet(100).unwrap();
+            token_balance
+                .apply_token_event(
+                    TokenEvent::Stake {
+                        amount: 100,
+                        details: StakeEvent::BetOnHotOrNotPost {
+                            post_canister_id: get_mock_user_alice_canister_id(),
+                            post_id: 1,
+                            bet_amount: 100,
+                            bet_direction: BetDirection::Hot,
+                        },
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+            token_balance
+                .apply_token_event(
+                    TokenEvent::HotOrNotOutcomePayout {
+                        amount: 180,
+                        details: HotOrNotOutcomePayoutEvent::WinningsEarnedFromBet {
+                            post_canister_id: get_mock_user_alice_canister_id(),
+                            post_id: 1,
+                            slot_id: 1,
+                            room_id: 1,
+                            event_outcome: BetOutcomeForBetMaker::Won(180),
+                            winnings_amount: 180,
+                        },
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
             assert_eq!(
-                *token_balance
-                    .utility_token_transaction_history
-                    .last_key_value()
-                    .unwrap()
-                    .0,
-                1500
+                token_balance.reconcile_balance(token_transaction_history.values().cloned()),
+                TokenBalanceReconciliation {
+                    recorded_balance: 1080,
+                    recomputed_balance: 1080,
+                    drift: 0,
+                    number_of_events: 3,
+                }
             );
 
-            token_balance.handle_token_event(TokenEvent::Burn);
-            assert_eq!(token_balance.utility_token_transaction_history.len(), 1501);
+            token_balance.utility_token_balance = 1000;
+
             assert_eq!(
-                *token_balance
-                    .utility_token_transaction_history
-                    .last_key_value()
-                    .unwrap()
-                    .0,
-                1501
+                token_balance.reconcile_balance(token_transaction_history.values().cloned()),
+                TokenBalanceReconciliation {
+                    recorded_balance: 1000,
+                    recomputed_balance: 1080,
+                    drift: -80,
+                    number_of_events: 3,
+                }
             );
+        }
+
+        #[test]
+        fn test_apply_token_event_rejects_underflow_and_overflow() {
+            let mut token_balance = TokenBalance {
+                utility_token_balance: 100,
+                ..Default::default()
+            };
+            let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
 
-            token_balance.handle_token_event(TokenEvent::Burn);
-            assert_eq!(token_balance.utility_token_transaction_history.len(), 1001);
             assert_eq!(
-                *token_balance
-                    .utility_token_transaction_history
-                    .first_key_value()
-                    .unwrap()
-                    .0,
-                502
+                token_balance.apply_token_event(
+                    TokenEvent::Transfer {
+                        amount: 101,
+                        to_account: get_mock_user_alice_canister_id(),
+                        timestamp: SystemTime::now(),
+                        memo: None,
+                    },
+                    &mut token_transaction_history,
+                ),
+                Err(TokenError::InsufficientBalance {
+                    balance: 100,
+                    required: 101
+                })
             );
             assert_eq!(
-                *token_balance
-                    .utility_token_transaction_history
-                    .last_key_value()
-                    .unwrap()
-                    .0,
-                1502
+                token_balance.adjust_balance_pre_bet(101),
+                Err(TokenError::InsufficientBalance {
+                    balance: 100,
+                    required: 101
+                })
             );
-
-            token_balance.handle_token_event(TokenEvent::Burn);
-            assert_eq!(token_balance.utility_token_transaction_history.len(), 1002);
             assert_eq!(
-                *token_balance
-                    .utility_token_transaction_history
-                    .first_key_value()
-                    .unwrap()
-                    .0,
-                502
+                token_balance.apply_token_event(
+                    TokenEvent::Receive {
+                        amount: u64::MAX,
+                        from_account: get_mock_user_alice_canister_id(),
+                        timestamp: SystemTime::now(),
+                        memo: None,
+                    },
+                    &mut token_transaction_history,
+                ),
+                Err(TokenError::BalanceOverflow)
             );
             assert_eq!(
-                *token_balance
-                    .utility_token_transaction_history
-                    .last_key_value()
-                    .unwrap()
-                    .0,
-                1503
+                token_balance.adjust_balance_for_failed_bet_placement(u64::MAX),
+                Err(TokenError::BalanceOverflow)
             );
+
+            assert_eq!(token_balance.utility_token_balance, 100);
+            assert!(token_transaction_history.is_empty());
         }
 
         #[test]
-        fn test_handle_token_event() {
+        fn test_apply_token_event_credits_the_event_amount_for_payouts() {
             let mut token_balance = TokenBalance::default();
+            let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
+
+            token_balance
+                .apply_token_event(
+                    TokenEvent::HotOrNotOutcomePayout {
+                        amount: 7,
+                        details: HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet {
+                            post_canister_id: get_mock_user_alice_canister_id(),
+                            post_id: 1,
+                            slot_id: 1,
+                            room_id: 1,
+                            room_pot_total_amount: 1000,
+                        },
+                        timestamp: SystemTime::now(),
+                    },
+                    &mut token_transaction_history,
+                )
+                .unwrap();
+
+            assert_eq!(token_balance.utility_token_balance, 7);
+            assert_eq!(token_balance.lifetime_earnings, 7);
+            assert_eq!(
+                token_balance
+                    .reconcile_balance(token_transaction_history.values().cloned())
+                    .drift,
+                0
+            );
+        }
 
-            token_balance.handle_token_event(TokenEvent::Mint {
-                amount: 1000,
-                details: MintEvent::NewUserSignup {
-                    new_user_principal_id: get_mock_user_alice_principal_id(),
-                },
-                timestamp: SystemTime::now(),
-            });
+        /// Small xorshift generator so the generated event sequences are reproducible
+        struct EventGenerator(u64);
 
-            assert_eq!(token_balance.utility_token_balance, 1000);
+        impl EventGenerator {
+            fn next(&mut self) -> u64 {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                self.0
+            }
 
-            token_balance.handle_token_event(TokenEvent::Mint {
-                amount: 500,
-                details: MintEvent::Referral {
-                    referee_user_principal_id: get_mock_user_alice_principal_id(),
-                    referrer_user_principal_id: get_mock_user_bob_principal_id(),
-                },
-                timestamp: SystemTime::now(),
-            });
+            fn next_amount(&mut self) -> u64 {
+                match self.next() % 10 {
+                    0 => u64::MAX - self.next() % 1000,
+                    1 => 0,
+                    _ => self.next() % 2000,
+                }
+            }
 
-            assert_eq!(token_balance.utility_token_balance, 1500);
+            fn next_token_event(&mut self) -> TokenEvent {
+                let amount = self.next_amount();
+                let timestamp = SystemTime::UNIX_EPOCH;
+                let account = get_mock_user_alice_canister_id();
+
+                match self.next() % 7 {
+                    0 => TokenEvent::Mint {
+                        amount,
+                        details: MintEvent::NewUserSignup {
+                            new_user_principal_id: get_mock_user_alice_principal_id(),
+                        },
+                        timestamp,
+                    },
+                    1 => TokenEvent::Transfer {
+                        amount,
+                        to_account: account,
+                        timestamp,
+                        memo: None,
+                    },
+                    2 => TokenEvent::Receive {
+                        amount,
+                        from_account: account,
+                        timestamp,
+                        memo: None,
+                    },
+                    3 => TokenEvent::TransferReverted {
+                        amount,
+                        to_account: account,
+                        timestamp,
+                    },
+                    4 => TokenEvent::Stake {
+                        amount,
+                        details: StakeEvent::BetOnHotOrNotPost {
+                            post_canister_id: account,
+                            post_id: 1,
+                            bet_amount: amount,
+                            bet_direction: BetDirection::Hot,
+                        },
+                        timestamp,
+                    },
+                    5 => TokenEvent::HotOrNotOutcomePayout {
+                        amount,
+                        details: HotOrNotOutcomePayoutEvent::WinningsEarnedFromBet {
+                            post_canister_id: account,
+                            post_id: 1,
+                            slot_id: 1,
+                            room_id: 1,
+                            event_outcome: BetOutcomeForBetMaker::Won(amount),
+                            winnings_amount: amount,
+                        },
+                        timestamp,
+                    },
+                    _ => TokenEvent::Burn,
+                }
+            }
+        }
 
-            token_balance.handle_token_event(TokenEvent::Stake {
-                amount: 100,
-                details: StakeEvent::BetOnHotOrNotPost {
-                    post_canister_id: get_mock_user_alice_canister_id(),
-                    post_id: 1,
-                    bet_amount: 100,
-                    bet_direction: BetDirection::Hot,
-                },
-                timestamp: SystemTime::now(),
+        #[test]
+        fn test_balance_always_equals_the_fold_of_recorded_events() {
+            (1..=200_u64).for_each(|seed| {
+                let mut event_generator = EventGenerator(seed * 0x9E37_79B9_7F4A_7C15);
+                let mut token_balance = TokenBalance::default();
+                let mut token_transaction_history: BTreeMap<u64, TokenEvent> = BTreeMap::new();
+
+                (0..200).for_each(|_| {
+                    let token_event = event_generator.next_token_event();
+                    let balance_before = token_balance.utility_token_balance;
+                    let number_of_events_before = token_transaction_history.len();
+
+                    // * mirrors the bet flow, the stake is only recorded once the debit succeeded
+                    let result = match &token_event {
+                        TokenEvent::Stake { amount, .. } => {
+                            token_balance.adjust_balance_pre_bet(*amount).and_then(|_| {
+                                token_balance
+                                    .apply_token_event(token_event, &mut token_transaction_history)
+                            })
+                        }
+                        _ => token_balance
+                            .apply_token_event(token_event, &mut token_transaction_history),
+                    };
+
+                    if result.is_err() {
+                        assert_eq!(token_balance.utility_token_balance, balance_before);
+                        assert_eq!(token_transaction_history.len(), number_of_events_before);
+                    }
+
+                    let reconciliation = token_balance
+                        .reconcile_balance(token_transaction_history.values().cloned());
+                    assert_eq!(reconciliation.drift, 0, "seed {seed}");
+                    assert_eq!(
+                        reconciliation.recomputed_balance,
+                        token_balance.utility_token_balance as i128
+                    );
+                });
             });
-
-            // this event is special and does not change the balance
-            assert_eq!(token_balance.utility_token_balance, 1500);
         }
     }
 
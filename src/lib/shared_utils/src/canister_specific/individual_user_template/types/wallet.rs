@@ -0,0 +1,56 @@
+use std::time::SystemTime;
+
+use candid::{CandidType, Deserialize, Nat, Principal};
+use serde::Serialize;
+
+use crate::pagination::PaginationError;
+
+pub const DEFAULT_ZERO_BALANCE_GRACE_PERIOD_IN_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WalletConfig {
+    /// How long a token can stay at a zero balance before it is removed from the wallet
+    pub zero_balance_grace_period_in_seconds: u64,
+}
+
+impl Default for WalletConfig {
+    fn default() -> Self {
+        Self {
+            zero_balance_grace_period_in_seconds: DEFAULT_ZERO_BALANCE_GRACE_PERIOD_IN_SECONDS,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WalletTokenMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    pub logo: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct WalletToken {
+    pub token_root: Principal,
+    pub ledger: Option<Principal>,
+    pub balance: Option<Nat>,
+    pub metadata: Option<WalletTokenMetadata>,
+    /// Set when the root or the ledger could not be reached, the fields above are then partial
+    pub error: Option<String>,
+    /// Set while the balance is zero. The token is removed once the grace period has passed.
+    pub zero_balance_since: Option<SystemTime>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct WalletOverview {
+    pub tokens: Vec<WalletToken>,
+    pub total_number_of_tokens: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq)]
+pub enum WalletOverviewError {
+    Unauthorized,
+    /// Another overview of the wallet is being fetched
+    FetchInProgress,
+    Pagination(PaginationError),
+}
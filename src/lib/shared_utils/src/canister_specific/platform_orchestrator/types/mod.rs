@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap, time::SystemTime};
 
-use candid::{CandidType, Principal};
+use candid::{CandidType, Decode, Encode, Principal};
+use ic_stable_structures::{storable::Bound, Storable};
 use serde::{Deserialize, Serialize};
 
 use crate::canister_specific::user_index::types::UpgradeStatus;
@@ -12,3 +13,35 @@ pub mod well_known_principal;
 pub struct SubnetUpgradeReport {
     pub subnet_wise_report: HashMap<Principal, UpgradeStatus>,
 }
+
+/// Where a unique user name lives across the whole platform.
+#[derive(Clone, CandidType, Serialize, Deserialize, Debug, PartialEq)]
+pub struct GlobalUniqueUserNameEntry {
+    pub user_principal_id: Principal,
+    pub user_canister_id: Principal,
+    pub subnet_orchestrator_canister_id: Principal,
+    /// Set when the name was given up through a rename. Until then only `user_principal_id` can
+    /// claim it back.
+    #[serde(default)]
+    pub released_claimable_by_others_after: Option<SystemTime>,
+}
+
+impl Storable for GlobalUniqueUserNameEntry {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).unwrap()
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum GlobalUniqueUserNameRegistryError {
+    UsernameAlreadyTaken,
+    UsernameInCooldown,
+    UsernameNotOwnedByUser,
+    CallerIsNotASubnetOrchestrator,
+}
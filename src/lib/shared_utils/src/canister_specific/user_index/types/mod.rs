@@ -69,3 +69,11 @@ impl Default for BroadcastCallStatus {
         }
     }
 }
+
+/// A unique user name given up through a rename. It stays reserved for the previous owner until
+/// `claimable_by_others_after` so that links to the old name cannot be hijacked right away.
+#[derive(Debug, CandidType, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReleasedUniqueUserName {
+    pub released_by_user_principal_id: Principal,
+    pub claimable_by_others_after: SystemTime,
+}
@@ -1,10 +1,16 @@
-use std::time::SystemTime;
+use std::{borrow::Cow, time::SystemTime};
 
-use candid::{CandidType, Deserialize, Principal};
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_stable_structures::{storable::Bound, Storable};
 use serde::Serialize;
 
-use crate::canister_specific::individual_user_template::types::hot_or_not::{
-    BetDirection, BetOutcomeForBetMaker,
+use crate::canister_specific::individual_user_template::types::{
+    engagement_reward::{
+        DEFAULT_DAILY_LOGIN_STREAK_REWARD_AMOUNT, DEFAULT_DAILY_POSTS_WATCHED_REWARD_AMOUNT,
+        DEFAULT_FIRST_BET_REWARD_AMOUNT, DEFAULT_FIRST_POST_REWARD_AMOUNT,
+    },
+    hot_or_not::{BetDirection, BetOutcomeForBetMaker},
+    referral::DEFAULT_REFERRAL_REWARD_AMOUNT,
 };
 
 #[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq, Serialize)]
@@ -19,11 +25,21 @@ pub enum TokenEvent {
         amount: u64,
         to_account: Principal,
         timestamp: SystemTime,
+        #[serde(default)]
+        memo: Option<String>,
     },
     Receive {
         amount: u64,
         from_account: Principal,
         timestamp: SystemTime,
+        #[serde(default)]
+        memo: Option<String>,
+    },
+    /// Refund of an outgoing `Transfer` that the receiving canister did not accept
+    TransferReverted {
+        amount: u64,
+        to_account: Principal,
+        timestamp: SystemTime,
     },
     Stake {
         amount: u64,
@@ -42,11 +58,103 @@ impl TokenEvent {
         match self {
             TokenEvent::Mint { details, .. } => match details {
                 MintEvent::NewUserSignup { .. } => 1000,
-                MintEvent::Referral { .. } => 500,
+                MintEvent::Referral { .. } => DEFAULT_REFERRAL_REWARD_AMOUNT,
+                MintEvent::DailyLoginStreak { .. } => DEFAULT_DAILY_LOGIN_STREAK_REWARD_AMOUNT,
+                MintEvent::FirstPost { .. } => DEFAULT_FIRST_POST_REWARD_AMOUNT,
+                MintEvent::DailyPostsWatched { .. } => DEFAULT_DAILY_POSTS_WATCHED_REWARD_AMOUNT,
+                MintEvent::FirstBet { .. } => DEFAULT_FIRST_BET_REWARD_AMOUNT,
             },
             _ => 0,
         }
     }
+
+    pub fn get_event_type(&self) -> TokenEventType {
+        match self {
+            TokenEvent::Mint { .. } => TokenEventType::Mint,
+            TokenEvent::Burn => TokenEventType::Burn,
+            TokenEvent::Transfer { .. } => TokenEventType::Transfer,
+            TokenEvent::Receive { .. } => TokenEventType::Receive,
+            TokenEvent::TransferReverted { .. } => TokenEventType::TransferReverted,
+            TokenEvent::Stake { .. } => TokenEventType::Stake,
+            TokenEvent::HotOrNotOutcomePayout { .. } => TokenEventType::HotOrNotOutcomePayout,
+        }
+    }
+
+    pub fn get_timestamp(&self) -> Option<SystemTime> {
+        match self {
+            TokenEvent::Mint { timestamp, .. }
+            | TokenEvent::Transfer { timestamp, .. }
+            | TokenEvent::Receive { timestamp, .. }
+            | TokenEvent::TransferReverted { timestamp, .. }
+            | TokenEvent::Stake { timestamp, .. }
+            | TokenEvent::HotOrNotOutcomePayout { timestamp, .. } => Some(*timestamp),
+            TokenEvent::Burn => None,
+        }
+    }
+}
+
+impl Storable for TokenEvent {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(bytes.as_ref(), Self).unwrap()
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+#[derive(Clone, Copy, CandidType, Deserialize, Debug, PartialEq, Eq, Serialize)]
+pub enum TokenEventType {
+    Mint,
+    Burn,
+    Transfer,
+    Receive,
+    TransferReverted,
+    Stake,
+    HotOrNotOutcomePayout,
+}
+
+#[derive(Clone, CandidType, Deserialize, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TokenEventFilter {
+    /// Matches every event type when empty
+    pub event_types: Vec<TokenEventType>,
+    pub from_inclusive_timestamp: Option<SystemTime>,
+    pub to_exclusive_timestamp: Option<SystemTime>,
+}
+
+impl TokenEventFilter {
+    /// Events without a timestamp only match filters without a time range.
+    pub fn matches(&self, token_event: &TokenEvent) -> bool {
+        if !self.event_types.is_empty() && !self.event_types.contains(&token_event.get_event_type())
+        {
+            return false;
+        }
+
+        if self.from_inclusive_timestamp.is_none() && self.to_exclusive_timestamp.is_none() {
+            return true;
+        }
+
+        let Some(timestamp) = token_event.get_timestamp() else {
+            return false;
+        };
+
+        self.from_inclusive_timestamp
+            .is_none_or(|from_inclusive_timestamp| timestamp >= from_inclusive_timestamp)
+            && self
+                .to_exclusive_timestamp
+                .is_none_or(|to_exclusive_timestamp| timestamp < to_exclusive_timestamp)
+    }
+}
+
+/// Events matching a filter, newest first.
+#[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq, Serialize)]
+pub struct FilteredTokenEventPage {
+    pub events: Vec<(u64, TokenEvent)>,
+    /// Passed as `before_exclusive_id` to read the next page. None once the oldest event was
+    /// scanned. A page can be empty when no event in its scan matched.
+    pub next_before_exclusive_id: Option<u64>,
 }
 
 #[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq, Serialize)]
@@ -58,6 +166,23 @@ pub enum MintEvent {
         referee_user_principal_id: Principal,
         referrer_user_principal_id: Principal,
     },
+    DailyLoginStreak {
+        /// Days since the unix epoch
+        day: u64,
+        streak_days: u64,
+    },
+    FirstPost {
+        post_id: u64,
+    },
+    DailyPostsWatched {
+        /// Days since the unix epoch
+        day: u64,
+        posts_watched: u64,
+    },
+    FirstBet {
+        post_canister_id: Principal,
+        post_id: u64,
+    },
 }
 
 #[derive(Clone, CandidType, Deserialize, Serialize, Debug, PartialEq, Eq)]
@@ -91,3 +216,52 @@ pub enum HotOrNotOutcomePayoutEvent {
 
 pub const HOT_OR_NOT_BET_CREATOR_COMMISSION_PERCENTAGE: u64 = 10;
 pub const HOT_OR_NOT_BET_WINNINGS_MULTIPLIER: u64 = 2;
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use test_utils::setup::test_constants::get_mock_user_alice_canister_id;
+
+    use super::*;
+
+    #[test]
+    fn test_token_event_filter_matches() {
+        let transfer = TokenEvent::Transfer {
+            amount: 10,
+            to_account: get_mock_user_alice_canister_id(),
+            timestamp: UNIX_EPOCH + Duration::from_secs(100),
+            memo: None,
+        };
+
+        assert!(TokenEventFilter::default().matches(&transfer));
+        assert!(TokenEventFilter::default().matches(&TokenEvent::Burn));
+
+        let transfers_only = TokenEventFilter {
+            event_types: vec![TokenEventType::Transfer, TokenEventType::Receive],
+            ..Default::default()
+        };
+        assert!(transfers_only.matches(&transfer));
+        assert!(!transfers_only.matches(&TokenEvent::Burn));
+
+        let time_range = TokenEventFilter {
+            from_inclusive_timestamp: Some(UNIX_EPOCH + Duration::from_secs(100)),
+            to_exclusive_timestamp: Some(UNIX_EPOCH + Duration::from_secs(200)),
+            ..Default::default()
+        };
+        assert!(time_range.matches(&transfer));
+        assert!(!time_range.matches(&TokenEvent::Burn));
+
+        let after_transfer = TokenEventFilter {
+            from_inclusive_timestamp: Some(UNIX_EPOCH + Duration::from_secs(101)),
+            ..Default::default()
+        };
+        assert!(!after_transfer.matches(&transfer));
+
+        let before_transfer = TokenEventFilter {
+            to_exclusive_timestamp: Some(UNIX_EPOCH + Duration::from_secs(100)),
+            ..Default::default()
+        };
+        assert!(!before_transfer.matches(&transfer));
+    }
+}
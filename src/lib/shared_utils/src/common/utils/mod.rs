@@ -3,6 +3,7 @@ pub mod stable_memory_serializer_deserializer;
 pub mod system_time;
 pub mod task;
 pub mod upgrade_canister;
+pub mod validation;
 
 #[cfg(target_arch = "wasm32")]
 const WASM_PAGE_SIZE: u64 = 65536;
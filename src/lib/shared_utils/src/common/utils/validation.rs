@@ -0,0 +1,215 @@
+use crate::types::canister_specific::{
+    individual_user_template::error_types::ProfileFieldValidationError,
+    user_index::error_types::UsernameValidationError,
+};
+
+pub const UNIQUE_USER_NAME_MIN_LENGTH: usize = 3;
+pub const UNIQUE_USER_NAME_MAX_LENGTH: usize = 20;
+pub const DISPLAY_NAME_MAX_LENGTH: usize = 50;
+pub const BIO_MAX_LENGTH: usize = 300;
+pub const LOCATION_MAX_LENGTH: usize = 100;
+pub const URL_MAX_LENGTH: usize = 2048;
+pub const MAX_EXTERNAL_LINKS: usize = 5;
+
+/// Names that could be used to impersonate the platform. Compared case-insensitively.
+pub const RESERVED_UNIQUE_USER_NAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "anonymous",
+    "help",
+    "hotornot",
+    "moderator",
+    "official",
+    "root",
+    "support",
+    "system",
+    "yral",
+];
+
+/// Unique user names are case-insensitive, so they are stored and looked up in lowercase.
+pub fn normalize_unique_user_name(unique_user_name: &str) -> String {
+    unique_user_name.to_ascii_lowercase()
+}
+
+/// A unique user name is 3 to 20 ASCII letters, digits or underscores and is not reserved.
+pub fn validate_unique_user_name(unique_user_name: &str) -> Result<(), UsernameValidationError> {
+    if unique_user_name.len() < UNIQUE_USER_NAME_MIN_LENGTH {
+        return Err(UsernameValidationError::TooShort);
+    }
+
+    if unique_user_name.len() > UNIQUE_USER_NAME_MAX_LENGTH {
+        return Err(UsernameValidationError::TooLong);
+    }
+
+    if !unique_user_name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(UsernameValidationError::InvalidCharacters);
+    }
+
+    if RESERVED_UNIQUE_USER_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(unique_user_name))
+    {
+        return Err(UsernameValidationError::Reserved);
+    }
+
+    Ok(())
+}
+
+/// Lengths of free text fields are counted in characters, not bytes.
+fn validate_text_length(
+    text: &str,
+    max_length: usize,
+    error: impl FnOnce(u64) -> ProfileFieldValidationError,
+) -> Result<(), ProfileFieldValidationError> {
+    if text.chars().count() > max_length {
+        return Err(error(max_length as u64));
+    }
+
+    Ok(())
+}
+
+pub fn validate_display_name(display_name: &str) -> Result<(), ProfileFieldValidationError> {
+    validate_text_length(display_name, DISPLAY_NAME_MAX_LENGTH, |max_length| {
+        ProfileFieldValidationError::DisplayNameTooLong { max_length }
+    })
+}
+
+pub fn validate_bio(bio: &str) -> Result<(), ProfileFieldValidationError> {
+    validate_text_length(bio, BIO_MAX_LENGTH, |max_length| {
+        ProfileFieldValidationError::BioTooLong { max_length }
+    })
+}
+
+pub fn validate_location(location: &str) -> Result<(), ProfileFieldValidationError> {
+    validate_text_length(location, LOCATION_MAX_LENGTH, |max_length| {
+        ProfileFieldValidationError::LocationTooLong { max_length }
+    })
+}
+
+/// Only absolute `https` URLs with a dotted host and no whitespace are accepted.
+pub fn validate_url(url: &str) -> Result<(), ProfileFieldValidationError> {
+    let invalid_url = || ProfileFieldValidationError::InvalidUrl {
+        url: url.to_string(),
+    };
+
+    if url.len() > URL_MAX_LENGTH || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(invalid_url());
+    }
+
+    let host = url
+        .strip_prefix("https://")
+        .and_then(|rest| rest.split(['/', '?', '#']).next())
+        .ok_or_else(invalid_url)?;
+
+    let is_valid_host =
+        host.contains('.') && !host.starts_with('.') && !host.ends_with('.') && !host.contains('@');
+
+    if !is_valid_host {
+        return Err(invalid_url());
+    }
+
+    Ok(())
+}
+
+pub fn validate_external_links(
+    external_links: &[String],
+) -> Result<(), ProfileFieldValidationError> {
+    if external_links.len() > MAX_EXTERNAL_LINKS {
+        return Err(ProfileFieldValidationError::TooManyExternalLinks {
+            max_count: MAX_EXTERNAL_LINKS as u64,
+        });
+    }
+
+    external_links
+        .iter()
+        .try_for_each(|external_link| validate_url(external_link))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_unique_user_name() {
+        assert_eq!(validate_unique_user_name("cool_alice_1234"), Ok(()));
+        assert_eq!(
+            validate_unique_user_name("ab"),
+            Err(UsernameValidationError::TooShort)
+        );
+        assert_eq!(
+            validate_unique_user_name("a_very_long_user_name_indeed"),
+            Err(UsernameValidationError::TooLong)
+        );
+        assert_eq!(
+            validate_unique_user_name("alice bob"),
+            Err(UsernameValidationError::InvalidCharacters)
+        );
+        assert_eq!(
+            validate_unique_user_name("alicé"),
+            Err(UsernameValidationError::InvalidCharacters)
+        );
+        assert_eq!(
+            validate_unique_user_name("Admin"),
+            Err(UsernameValidationError::Reserved)
+        );
+    }
+
+    #[test]
+    fn test_validate_url() {
+        assert_eq!(validate_url("https://yral.com/profile?id=1"), Ok(()));
+        assert_eq!(validate_url("https://x.com"), Ok(()));
+
+        [
+            "http://yral.com",
+            "https://",
+            "https://localhost",
+            "https://yral .com",
+            "javascript:alert(1)",
+            "https://user@yral.com",
+        ]
+        .into_iter()
+        .for_each(|url| {
+            assert_eq!(
+                validate_url(url),
+                Err(ProfileFieldValidationError::InvalidUrl {
+                    url: url.to_string()
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn test_validate_profile_fields() {
+        assert_eq!(validate_bio(&"é".repeat(BIO_MAX_LENGTH)), Ok(()));
+        assert_eq!(
+            validate_bio(&"a".repeat(BIO_MAX_LENGTH + 1)),
+            Err(ProfileFieldValidationError::BioTooLong {
+                max_length: BIO_MAX_LENGTH as u64
+            })
+        );
+        assert_eq!(
+            validate_display_name(&"a".repeat(DISPLAY_NAME_MAX_LENGTH + 1)),
+            Err(ProfileFieldValidationError::DisplayNameTooLong {
+                max_length: DISPLAY_NAME_MAX_LENGTH as u64
+            })
+        );
+        assert_eq!(
+            validate_location(&"a".repeat(LOCATION_MAX_LENGTH + 1)),
+            Err(ProfileFieldValidationError::LocationTooLong {
+                max_length: LOCATION_MAX_LENGTH as u64
+            })
+        );
+        assert_eq!(
+            validate_external_links(&vec![
+                "https://yral.com".to_string();
+                MAX_EXTERNAL_LINKS + 1
+            ]),
+            Err(ProfileFieldValidationError::TooManyExternalLinks {
+                max_count: MAX_EXTERNAL_LINKS as u64
+            })
+        );
+    }
+}
@@ -43,7 +43,11 @@ pub const RESERVED_NUMBER_OF_INSTRUCTIONS_FOR_INSTALL_CODE: u128 = 300_000_000_0
 pub const THRESHOLD_NUMBER_OF_DAYS_TO_KEEP_CANISTER_RUNNING: u128 = 1;
 pub const MAX_NUMBER_OF_DAYS_TO_KEEP_CANISTER_RUNNING: u128 = 7;
 
-pub const MAX_LIMIT_FOR_CREATOR_DAO_SNS_TOKEN: usize = 2;
+/// Default creator token quota, until the platform orchestrator configures another one
+pub const MAX_LIMIT_FOR_CREATOR_DAO_SNS_TOKEN: u64 = 2;
+
+pub const UNIQUE_USER_NAME_RELEASE_COOLDOWN_IN_SECONDS: u64 = 30 * 24 * 60 * 60; // 30 days
+pub const MAX_UNIQUE_USER_NAME_HISTORY_ENTRIES: usize = 20;
 
 pub const SNS_TOKEN_GOVERNANCE_MODULE_HASH: &'static str =
     "51fd3d1a529f3f7bad808b19074e761ce3538282ac8189bd7067b4156360c279";
@@ -1,8 +1,8 @@
-use candid::CandidType;
+use candid::{CandidType, Deserialize};
 
 use crate::constant::MAX_POSTS_IN_ONE_REQUEST;
 
-#[derive(PartialEq, Debug, CandidType)]
+#[derive(PartialEq, Debug, CandidType, Deserialize)]
 pub enum PaginationError {
     InvalidBoundsPassed,
     ReachedEndOfItemsList,
@@ -1,5 +1,13 @@
 use candid::{CandidType, Deserialize};
 
+use crate::{
+    canister_specific::{
+        individual_user_template::types::error::TokenError,
+        platform_orchestrator::types::GlobalUniqueUserNameRegistryError,
+    },
+    types::canister_specific::user_index::error_types::UsernameValidationError,
+};
+
 #[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
 pub enum UpdateProfileSetUniqueUsernameError {
     NotAuthorized,
@@ -7,6 +15,42 @@ pub enum UpdateProfileSetUniqueUsernameError {
     SendingCanisterDoesNotMatchUserCanisterId,
     UserCanisterEntryDoesNotExist,
     UserIndexCrossCanisterCallFailed,
+    InvalidUsername(UsernameValidationError),
+    GlobalUsernameRegistryUnavailable,
+    GlobalUsernameRegistryRejected(GlobalUniqueUserNameRegistryError),
+    /// The change failed and undoing it in the platform orchestrator is still being retried.
+    GlobalUsernameRegistryRevertPending,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum UpdateProfileChangeUniqueUsernameError {
+    NotAuthorized,
+    NoUniqueUsernameSet,
+    InvalidUsername(UsernameValidationError),
+    UsernameUnchanged,
+    UsernameAlreadyTaken,
+    UsernameInCooldown,
+    UsernameNotOwnedByUser,
+    SendingCanisterDoesNotMatchUserCanisterId,
+    UserCanisterEntryDoesNotExist,
+    UserIndexCrossCanisterCallFailed,
+    GlobalUsernameRegistryUnavailable,
+    GlobalUsernameRegistryRejected(GlobalUniqueUserNameRegistryError),
+    /// The change failed and undoing it in the platform orchestrator is still being retried.
+    GlobalUsernameRegistryRevertPending,
+    /// The profile was changed by another call while waiting on the user index. The index change was reverted.
+    ProfileChangedDuringUpdate,
+    /// The profile was changed by another call while waiting on the user index and the index change could not be reverted.
+    UserIndexRevertFailed,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum ProfileFieldValidationError {
+    DisplayNameTooLong { max_length: u64 },
+    BioTooLong { max_length: u64 },
+    LocationTooLong { max_length: u64 },
+    TooManyExternalLinks { max_count: u64 },
+    InvalidUrl { url: String },
 }
 
 #[derive(CandidType, Debug, Deserialize)]
@@ -15,3 +59,35 @@ pub enum GetUserUtilityTokenTransactionHistoryError {
     ReachedEndOfItemsList,
     ExceededMaxNumberOfItemsAllowedInOneRequest,
 }
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum UtilityTokenTransferError {
+    Unauthorized,
+    InvalidAmount,
+    CannotTransferToSelf,
+    MemoTooLong { max_length: u64 },
+    InsufficientBalance { balance: u64 },
+    BalanceOverflow,
+    UserIndexCanisterIdNotFound,
+    UserIndexCrossCanisterCallFailed,
+    /// The calling canister is not the canister the user index has on record for the sending user
+    SenderIsNotAUserCanisterOnNetwork,
+    /// The receiving canister is not the canister the user index has on record for its user
+    ReceiverIsNotAUserCanisterOnNetwork,
+    ReceiverCrossCanisterCallFailed(String),
+    /// The transfer id was already used for a transfer to another receiver or of another amount
+    TransferIdAlreadyUsed,
+    /// A transfer with this id is waiting on the receiving canister
+    TransferInProgress,
+}
+
+impl From<TokenError> for UtilityTokenTransferError {
+    fn from(value: TokenError) -> Self {
+        match value {
+            TokenError::InsufficientBalance { balance, .. } => {
+                UtilityTokenTransferError::InsufficientBalance { balance }
+            }
+            TokenError::BalanceOverflow => UtilityTokenTransferError::BalanceOverflow,
+        }
+    }
+}
@@ -1,8 +1,38 @@
 use candid::{CandidType, Deserialize};
 
+use crate::canister_specific::platform_orchestrator::types::GlobalUniqueUserNameRegistryError;
+
 #[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
 pub enum SetUniqueUsernameError {
     UsernameAlreadyTaken,
     SendingCanisterDoesNotMatchUserCanisterId,
     UserCanisterEntryDoesNotExist,
+    InvalidUsername(UsernameValidationError),
+    GlobalUsernameRegistryUnavailable,
+    GlobalUsernameRegistryRejected(GlobalUniqueUserNameRegistryError),
+    /// The change failed and undoing it in the platform orchestrator is still being retried.
+    GlobalUsernameRegistryRevertPending,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum ChangeUniqueUsernameError {
+    InvalidUsername(UsernameValidationError),
+    UsernameUnchanged,
+    UsernameAlreadyTaken,
+    UsernameInCooldown,
+    UsernameNotOwnedByUser,
+    SendingCanisterDoesNotMatchUserCanisterId,
+    UserCanisterEntryDoesNotExist,
+    GlobalUsernameRegistryUnavailable,
+    GlobalUsernameRegistryRejected(GlobalUniqueUserNameRegistryError),
+    /// The change failed and undoing it in the platform orchestrator is still being retried.
+    GlobalUsernameRegistryRevertPending,
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum UsernameValidationError {
+    TooShort,
+    TooLong,
+    InvalidCharacters,
+    Reserved,
 }
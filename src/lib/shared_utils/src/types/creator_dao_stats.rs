@@ -3,6 +3,8 @@ use std::collections::{HashMap, HashSet};
 use candid::{CandidType, Principal};
 use serde::{Deserialize, Serialize};
 
+use crate::canister_specific::individual_user_template::types::cdao::CreatorDaoTokenStatus;
+
 type IndividualProfileId = Principal;
 type RootCanisterId = Principal;
 
@@ -10,6 +12,9 @@ type RootCanisterId = Principal;
 pub struct CreatorDaoTokenStats {
     creator_dao_token_sns_canisters: HashMap<IndividualProfileId, IndividualUserCreatorDaoEntry>,
     pub total_number_of_creator_dao_tokens: u64,
+    /// Last status reported for each creator token
+    #[serde(default)]
+    pub token_statuses: HashMap<RootCanisterId, CreatorDaoTokenStatus>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Debug, Clone)]
@@ -19,6 +24,10 @@ pub struct IndividualUserCreatorDaoEntry {
 }
 
 impl CreatorDaoTokenStats {
+    pub fn update_token_status(&mut self, token_status: CreatorDaoTokenStatus) {
+        self.token_statuses.insert(token_status.root, token_status);
+    }
+
     pub fn insert_new_entry(
         &mut self,
         individual_user_profile_id: IndividualProfileId,
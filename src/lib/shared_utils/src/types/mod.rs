@@ -1,3 +1,4 @@
 pub mod canister_specific;
 pub mod creator_dao_stats;
 pub mod sns_canisters;
+pub mod utility_token_supply_stats;
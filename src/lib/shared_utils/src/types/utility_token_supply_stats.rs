@@ -0,0 +1,201 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+use crate::common::types::utility_token::token_event::TokenEvent;
+
+const SECONDS_IN_A_DAY: u64 = 24 * 60 * 60;
+
+/// Utility token totals of an individual canister, or the sum over a subnet or the network.
+/// Transfers between users move tokens around without changing these.
+#[derive(Default, Debug, Serialize, Deserialize, CandidType, Clone, Copy, PartialEq, Eq)]
+pub struct UtilityTokenSupplyStats {
+    pub minted: u128,
+    /// Staked tokens that did not come back as payouts once their bets were settled. Bets pay
+    /// out to other canisters than the ones that staked, so this is only meaningful for a sum
+    /// over the whole network.
+    pub burned: u128,
+    pub staked: u128,
+    /// Part of `staked` that is still in bets waiting for their result
+    #[serde(default)]
+    pub staked_in_open_bets: u128,
+    pub paid_out: u128,
+    /// Sum of the balances currently held
+    pub circulating: u128,
+    pub number_of_individual_canisters: u64,
+}
+
+impl UtilityTokenSupplyStats {
+    pub fn add_token_event(&mut self, token_event: &TokenEvent) {
+        match token_event {
+            TokenEvent::Mint { amount, .. } => self.minted += *amount as u128,
+            TokenEvent::Stake { amount, .. } => self.staked += *amount as u128,
+            TokenEvent::HotOrNotOutcomePayout { amount, .. } => self.paid_out += *amount as u128,
+            TokenEvent::Burn
+            | TokenEvent::Transfer { .. }
+            | TokenEvent::Receive { .. }
+            | TokenEvent::TransferReverted { .. } => {}
+        }
+    }
+
+    pub fn merge(&mut self, other: &UtilityTokenSupplyStats) {
+        self.minted += other.minted;
+        self.staked += other.staked;
+        self.staked_in_open_bets += other.staked_in_open_bets;
+        self.paid_out += other.paid_out;
+        self.circulating += other.circulating;
+        self.number_of_individual_canisters += other.number_of_individual_canisters;
+        self.update_burned();
+    }
+
+    pub fn update_burned(&mut self) {
+        self.burned = self
+            .staked
+            .saturating_sub(self.staked_in_open_bets + self.paid_out);
+    }
+}
+
+/// Supply stats folded from the start of the event log up to an event, so that collecting them
+/// only reads the events recorded after it.
+#[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UtilityTokenSupplyStatsCheckpoint {
+    /// Id of the last event folded into the checkpoint, 0 before any event
+    pub last_token_event_id: u64,
+    pub stats: UtilityTokenSupplyStats,
+}
+
+impl UtilityTokenSupplyStatsCheckpoint {
+    pub fn fold_token_event(&mut self, token_event_id: u64, token_event: &TokenEvent) {
+        self.last_token_event_id = token_event_id;
+        self.stats.add_token_event(token_event);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, CandidType, Clone, PartialEq, Eq)]
+pub struct UtilityTokenSupplySnapshot {
+    /// Days since the unix epoch
+    pub day: u64,
+    pub taken_at: SystemTime,
+    pub network_stats: UtilityTokenSupplyStats,
+    // Key is the subnet orchestrator canister id
+    pub stats_by_subnet: BTreeMap<Principal, UtilityTokenSupplyStats>,
+    /// Subnets that did not report for this snapshot. Their stats are carried over from the
+    /// previous snapshot.
+    #[serde(default)]
+    pub stale_subnets: BTreeSet<Principal>,
+}
+
+impl UtilityTokenSupplySnapshot {
+    pub fn new(
+        taken_at: SystemTime,
+        stats_by_subnet: BTreeMap<Principal, UtilityTokenSupplyStats>,
+        stale_subnets: BTreeSet<Principal>,
+    ) -> Self {
+        let mut network_stats = UtilityTokenSupplyStats::default();
+        stats_by_subnet
+            .values()
+            .for_each(|subnet_stats| network_stats.merge(subnet_stats));
+
+        Self {
+            day: get_day_since_epoch(taken_at),
+            taken_at,
+            network_stats,
+            stats_by_subnet,
+            stale_subnets,
+        }
+    }
+}
+
+pub fn get_day_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECONDS_IN_A_DAY
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::common::types::utility_token::token_event::{HotOrNotOutcomePayoutEvent, MintEvent};
+
+    use super::*;
+
+    #[test]
+    fn test_add_token_event_and_merge() {
+        let mut alice_stats = UtilityTokenSupplyStats {
+            circulating: 1000,
+            number_of_individual_canisters: 1,
+            ..Default::default()
+        };
+        alice_stats.add_token_event(&TokenEvent::Mint {
+            amount: 1000,
+            details: MintEvent::NewUserSignup {
+                new_user_principal_id: Principal::anonymous(),
+            },
+            timestamp: UNIX_EPOCH,
+        });
+        alice_stats.add_token_event(&TokenEvent::Transfer {
+            amount: 10,
+            to_account: Principal::anonymous(),
+            timestamp: UNIX_EPOCH,
+            memo: None,
+        });
+        alice_stats.add_token_event(&TokenEvent::HotOrNotOutcomePayout {
+            amount: 20,
+            details: HotOrNotOutcomePayoutEvent::CommissionFromHotOrNotBet {
+                post_canister_id: Principal::anonymous(),
+                post_id: 0,
+                slot_id: 1,
+                room_id: 1,
+                room_pot_total_amount: 200,
+            },
+            timestamp: UNIX_EPOCH,
+        });
+
+        let mut subnet_stats = UtilityTokenSupplyStats {
+            minted: u64::MAX as u128,
+            staked: 500,
+            staked_in_open_bets: 100,
+            circulating: u64::MAX as u128,
+            number_of_individual_canisters: 1,
+            ..Default::default()
+        };
+        subnet_stats.merge(&alice_stats);
+
+        assert_eq!(
+            subnet_stats,
+            UtilityTokenSupplyStats {
+                minted: u64::MAX as u128 + 1000,
+                burned: 380,
+                staked: 500,
+                staked_in_open_bets: 100,
+                paid_out: 20,
+                circulating: u64::MAX as u128 + 1000,
+                number_of_individual_canisters: 2,
+            }
+        );
+
+        let snapshot = UtilityTokenSupplySnapshot::new(
+            UNIX_EPOCH + Duration::from_secs(2 * SECONDS_IN_A_DAY + 5),
+            [
+                (Principal::anonymous(), subnet_stats),
+                (Principal::management_canister(), alice_stats),
+            ]
+            .into_iter()
+            .collect(),
+            BTreeSet::from([Principal::management_canister()]),
+        );
+        assert_eq!(snapshot.day, 2);
+        assert_eq!(snapshot.network_stats.number_of_individual_canisters, 3);
+        assert_eq!(snapshot.network_stats.paid_out, 40);
+        assert_eq!(snapshot.network_stats.burned, 360);
+        assert!(snapshot
+            .stale_subnets
+            .contains(&Principal::management_canister()));
+    }
+}